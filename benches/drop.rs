@@ -0,0 +1,89 @@
+//! Measures how long it takes to drop adapted values which drop successfully, which is the hot
+//! path in tight loops dropping lots of values.
+//!
+//! Run with `cargo bench --bench drop`. Numbers measured on a Linux x86_64 machine with rustc
+//! 1.95.0, in nanoseconds per drop. Each number is the median of 8 invocations, run interleaved
+//! with the other build. Each invocation reports the best of 5 runs of 10,000,000 drops:
+//!
+//! | benchmark | before | after |
+//! |-----------|--------|-------|
+//! | pure      | 0.879  | 0.808 |
+//! | impure    | 0.811  | 0.809 |
+//!
+//! "before" is with the error handling inlined into `Drop::drop` of the drop adapter. "after" is
+//! with it split out into a cold function. The impure values go through the same drop adapter,
+//! the handlers are only reached on an error, so both are within noise of each other. Individual
+//! runs on a busy machine vary by up to 50%, so compare the builds interleaved.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use try_drop::drop_strategies::NoOpDropStrategy;
+use try_drop::{ImpureTryDrop, PureTryDrop};
+
+const DROPS: u64 = 10_000_000;
+const RUNS: usize = 5;
+
+/// Succeeds dropping, using its own drop strategies.
+struct Pure(u64);
+
+impl PureTryDrop for Pure {
+    type Error = anyhow::Error;
+    type FallbackTryDropStrategy = NoOpDropStrategy;
+    type TryDropStrategy = NoOpDropStrategy;
+
+    fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+        &NoOpDropStrategy
+    }
+
+    fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+        &NoOpDropStrategy
+    }
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        if black_box(self.0) == u64::MAX {
+            anyhow::bail!("this will never fail")
+        }
+
+        Ok(())
+    }
+}
+
+/// Succeeds dropping, using the primary and fallback handlers.
+struct Impure(u64);
+
+impl ImpureTryDrop for Impure {
+    type Error = anyhow::Error;
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        if black_box(self.0) == u64::MAX {
+            anyhow::bail!("this will never fail")
+        }
+
+        Ok(())
+    }
+}
+
+fn bench(name: &str, mut drop_one: impl FnMut(u64)) {
+    let best = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+
+            for index in 0..DROPS {
+                drop_one(black_box(index))
+            }
+
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(Duration::ZERO);
+
+    println!(
+        "{name}: {best:?} for {DROPS} drops, {:.3} ns per drop",
+        best.as_nanos() as f64 / DROPS as f64
+    )
+}
+
+fn main() {
+    bench("pure", |index| drop(black_box(Pure(index).adapt())));
+    bench("impure", |index| drop(black_box(Impure(index).adapt())));
+}
@@ -0,0 +1,112 @@
+//! Measures how long it takes `TopNReporter` to record a drop error, which finds the stats of the
+//! type it came from by the label of the type name, compared to finding them by comparing the
+//! type names themselves, like it did before it used labels.
+//!
+//! Run with `cargo bench --bench labels --features error-telemetry`. Every source is recorded
+//! once before measuring, so each record walks past the sources which were recorded before it.
+//! Numbers measured on a Linux x86_64 machine with rustc 1.95.0, in nanoseconds per record. Each
+//! number is the median of 8 invocations. Each invocation reports the best of 50 runs of 10,000
+//! records:
+//!
+//! | benchmark  | ns per record |
+//! |------------|---------------|
+//! | type names | 20.4          |
+//! | labels     | 21.2          |
+//!
+//! Most of the time goes to locking the sources either way. Comparing labels instead of type names
+//! makes up for looking up the label of the type name, but with this few sources, not for much
+//! more than that.
+
+use std::any::type_name;
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use try_drop::error_telemetry::{SourceStats, TopNReporter};
+
+const RECORDS: usize = 10_000;
+const RUNS: usize = 50;
+
+/// The reporter before it used labels, which found the stats of a type by its type name.
+#[derive(Default)]
+struct TypeNameReporter {
+    sources: Mutex<Vec<SourceStats>>,
+}
+
+impl TypeNameReporter {
+    fn record(&self, type_name: &'static str, size: usize) {
+        let mut sources = self.sources.lock().unwrap();
+
+        match sources
+            .iter_mut()
+            .find(|source| source.type_name == type_name)
+        {
+            Some(source) => {
+                source.count += 1;
+                source.bytes += size;
+            }
+            None => sources.push(SourceStats {
+                type_name,
+                count: 1,
+                bytes: size,
+            }),
+        }
+    }
+}
+
+/// The drop errors usually come from values wrapped the same way, so their type names share most
+/// of their bytes.
+type Wrapped<T> = Option<std::sync::Arc<std::sync::Mutex<std::io::BufWriter<T>>>>;
+
+fn sources() -> [&'static str; 16] {
+    [
+        type_name::<Wrapped<std::fs::File>>(),
+        type_name::<Wrapped<std::net::TcpStream>>(),
+        type_name::<Wrapped<std::process::ChildStdin>>(),
+        type_name::<Wrapped<std::io::Stdout>>(),
+        type_name::<Wrapped<std::io::Stderr>>(),
+        type_name::<Wrapped<std::io::Sink>>(),
+        type_name::<Wrapped<std::os::unix::net::UnixStream>>(),
+        type_name::<Wrapped<Vec<u8>>>(),
+        type_name::<Wrapped<std::io::LineWriter<std::fs::File>>>(),
+        type_name::<Wrapped<std::io::LineWriter<std::net::TcpStream>>>(),
+        type_name::<Wrapped<std::io::LineWriter<std::process::ChildStdin>>>(),
+        type_name::<Wrapped<std::io::LineWriter<std::io::Stdout>>>(),
+        type_name::<Wrapped<std::io::LineWriter<std::io::Stderr>>>(),
+        type_name::<Wrapped<std::io::LineWriter<std::io::Sink>>>(),
+        type_name::<Wrapped<std::io::LineWriter<std::os::unix::net::UnixStream>>>(),
+        type_name::<Wrapped<std::io::LineWriter<Vec<u8>>>>(),
+    ]
+}
+
+fn bench(name: &str, mut record: impl FnMut(&'static str, usize)) {
+    let sources = sources();
+    for source in sources {
+        record(source, 0)
+    }
+
+    let best = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            for index in 0..RECORDS {
+                record(black_box(sources[index % sources.len()]), 64)
+            }
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(Duration::ZERO);
+
+    println!(
+        "{name}: {best:?} for {RECORDS} records, {:.1} ns per record",
+        best.as_nanos() as f64 / RECORDS as f64
+    )
+}
+
+fn main() {
+    let type_names = TypeNameReporter::default();
+    bench("type names", |type_name, size| {
+        type_names.record(type_name, size)
+    });
+
+    let labels = TopNReporter::new();
+    bench("labels", |type_name, size| labels.record(type_name, size));
+}
@@ -1,4 +1,3 @@
-use std::borrow::{Borrow, BorrowMut};
 use std::cell::Cell;
 use std::rc::Rc;
 use std::thread;
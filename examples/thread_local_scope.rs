@@ -42,14 +42,36 @@ fn main() {
 
     {
         println!("create nested scope");
-        let _guard = primary::thread_local::ScopeGuard::try_new(UnreachableDropStrategy::safe());
+        let _guard = primary::thread_local::scope(AdHocDropStrategy(|error| {
+            println!("error from the outer thread local scope: {error}")
+        }));
+
+        {
+            let _guard = primary::thread_local::scope(AdHocDropStrategy(|error| {
+                println!("error from the inner thread local scope: {error}")
+            }));
+
+            let thing = ErrorsOnDrop::<Fallible, _>::not_given().adapt();
+            println!("drop test from inner thread local scope");
+            drop(thing)
+        }
+
+        let thing = ErrorsOnDrop::<Fallible, _>::not_given().adapt();
+        println!("drop test from outer thread local scope, after the inner one is dropped");
+        drop(thing)
+    }
+
+    {
+        println!("create exclusive scope");
+        let _guard =
+            primary::thread_local::ScopeGuard::try_new_exclusive(UnreachableDropStrategy::safe());
 
         {
-            println!("nested scopes aren't allowed");
+            println!("scopes can't be nested in exclusive ones");
             let error = primary::thread_local::ScopeGuard::try_new(UnreachableDropStrategy::safe())
                 .unwrap_err();
             println!(
-                "the error comes in a form of a `LockedError`: debug={error:?}, display={error}"
+                "the error comes in a form of a `NestedScopeError`: debug={error:?}, display={error}"
             )
         }
     }
@@ -25,20 +25,141 @@ mod arc_error {
     }
 
     impl Error for ArcError {}
+
+    #[cfg(feature = "serde")]
+    impl serde::Serialize for ArcError {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            super::SerializableError(&self.0).serialize(serializer)
+        }
+    }
 }
 
 #[cfg(feature = "std")]
 pub use arc_error::ArcError;
 
+#[cfg(feature = "serde")]
+mod serializable_error {
+    use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
+    use std::string::{String, ToString};
+
+    /// A wrapper around a [`crate::Error`] which serializes it as its message and the messages of
+    /// its causes, for example `{ "message": "...", "chain": ["...", "..."] }` in JSON, since
+    /// [`anyhow::Error`] doesn't implement [`Serialize`] itself. This is useful to send drop
+    /// errors across process boundaries, e.g. to a JSON logging pipeline.
+    ///
+    /// Only the [`Display`](std::fmt::Display) output of the errors is kept, so the error can't
+    /// be deserialized back into its original type. [`ArcError`](super::ArcError) is serialized
+    /// the same way.
+    #[derive(Debug, Clone, Copy)]
+    pub struct SerializableError<'a>(pub &'a crate::Error);
+
+    impl<'a> SerializableError<'a> {
+        /// The message of the error, as it's serialized.
+        pub fn message(&self) -> String {
+            self.0.to_string()
+        }
+
+        /// The messages of the causes of the error, outermost first, as they're serialized.
+        pub fn chain(&self) -> impl Iterator<Item = String> + 'a {
+            self.0.chain().skip(1).map(ToString::to_string)
+        }
+    }
+
+    /// The causes of the error, serialized lazily as a sequence of their messages.
+    struct Chain<'a>(SerializableError<'a>);
+
+    impl Serialize for Chain<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(None)?;
+
+            for cause in self.0.chain() {
+                seq.serialize_element(&cause)?;
+            }
+
+            seq.end()
+        }
+    }
+
+    impl Serialize for SerializableError<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("SerializableError", 2)?;
+            state.serialize_field("message", &self.message())?;
+            state.serialize_field("chain", &Chain(*self))?;
+            state.end()
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+pub use serializable_error::SerializableError;
+
+#[cfg(feature = "alloc")]
+mod shared_error {
+    use alloc::sync::Arc;
+    use core::error::Error;
+    use core::fmt;
+
+    /// An error which is handed to more than one try drop strategy. Its copies render and chain
+    /// like the original error, but can't be downcast to the type of the original error.
+    pub(crate) struct SharedError(Arc<crate::Error>);
+
+    impl SharedError {
+        pub(crate) fn new(error: crate::Error) -> Self {
+            Self(Arc::new(error))
+        }
+
+        /// Make a copy of the error.
+        pub(crate) fn copy(&self) -> crate::Error {
+            crate::Error::new(Self(Arc::clone(&self.0)))
+        }
+
+        /// Get the original error back, unless a copy of it is still around.
+        pub(crate) fn into_error(self) -> crate::Error {
+            Arc::try_unwrap(self.0).unwrap_or_else(|shared| crate::Error::new(Self(shared)))
+        }
+    }
+
+    impl fmt::Debug for SharedError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Debug::fmt(&**self.0, f)
+        }
+    }
+
+    impl fmt::Display for SharedError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Display::fmt(&**self.0, f)
+        }
+    }
+
+    impl Error for SharedError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            self.0.source()
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) use shared_error::SharedError;
+
 use crate::{
-    DynFallibleTryDropStrategy, FallibleTryDropStrategy, PureTryDrop, RepeatableTryDrop,
-    TryDropStrategy,
+    DynFallibleTryDropStrategy, FallibleTryDropStrategy, InfallibleStrategy, PureTryDrop,
+    RepeatableTryDrop, TryDropStrategy,
 };
 
 #[allow(unused_imports)] // JUSTIFICATION: For docs
 use crate::TryDrop;
 
+#[cfg(any(feature = "global", feature = "thread-local"))]
+use crate::ImpureTryDrop;
+
+#[cfg(not(any(feature = "global", feature = "thread-local")))]
+use crate::SafeTryDrop;
+
+#[cfg(feature = "derives")]
+use core::fmt;
 use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr;
 
 /// An adapter which makes a type which implements [`TryDropStrategy`], an infallible or try drop
 /// strategy which never fails, fallible.
@@ -71,6 +192,34 @@ impl<T: TryDropStrategy, E: Into<anyhow::Error>> InfallibleToFallibleTryDropStra
     pub fn take(this: Self) -> T {
         this.inner
     }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn inner(this: &Self) -> &T {
+        &this.inner
+    }
+
+    /// Take the inner value. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Take the inner value. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn into_inner(this: Self) -> T {
+        this.inner
+    }
 }
 
 impl<T: TryDropStrategy, E: Into<anyhow::Error>> FallibleTryDropStrategy
@@ -84,6 +233,158 @@ impl<T: TryDropStrategy, E: Into<anyhow::Error>> FallibleTryDropStrategy
     }
 }
 
+/// An adapter which makes a type which implements [`FallibleTryDropStrategy`], a try drop strategy
+/// which may fail, infallible by asserting that it never fails.
+///
+/// # Panics
+/// If the inner try drop strategy fails, the error is panicked with.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+)]
+#[cfg_attr(feature = "shrinkwraprs", derive(Shrinkwrap))]
+pub struct AssertInfallibleTryDropStrategyAdapter<T: FallibleTryDropStrategy> {
+    /// The inner value.
+    #[cfg_attr(feature = "shrinkwraprs", shrinkwrap(main_field))]
+    pub inner: T,
+}
+
+impl<T: FallibleTryDropStrategy> AssertInfallibleTryDropStrategyAdapter<T> {
+    /// Wrap the `value` in this adapter.
+    pub const fn new(value: T) -> Self {
+        Self { inner: value }
+    }
+
+    /// Take the inner value.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn take(this: Self) -> T {
+        this.inner
+    }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn inner(this: &Self) -> &T {
+        &this.inner
+    }
+
+    /// Take the inner value. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Take the inner value. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn into_inner(this: Self) -> T {
+        this.inner
+    }
+}
+
+impl<T: FallibleTryDropStrategy> TryDropStrategy for AssertInfallibleTryDropStrategyAdapter<T> {
+    fn handle_error(&self, error: anyhow::Error) {
+        if let Err(error) = self.inner.dyn_try_handle_error(error) {
+            #[cfg(feature = "std")]
+            crate::last_resort::panic_unless_unwinding(format_args!(
+                "the try drop strategy asserted to be infallible failed: {error}"
+            ));
+
+            #[cfg(not(feature = "std"))]
+            panic!("the try drop strategy asserted to be infallible failed: {error}")
+        }
+    }
+}
+
+/// An adapter which marks a try drop strategy as an [`InfallibleStrategy`], without it being
+/// audited by this crate. This is the escape hatch for strategies which can't implement the trait
+/// themselves, such as ad-hoc strategies over closures.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[cfg_attr(feature = "shrinkwraprs", derive(Shrinkwrap))]
+pub struct AssumeInfallibleAdapter<T: TryDropStrategy> {
+    // private, so that this can only be constructed through the unsafe `new` function.
+    #[cfg_attr(feature = "shrinkwraprs", shrinkwrap(main_field))]
+    inner: T,
+}
+
+impl<T: TryDropStrategy> AssumeInfallibleAdapter<T> {
+    /// Wrap the `value` in this adapter.
+    ///
+    /// # Safety
+    /// The caller must uphold the guarantees of [`InfallibleStrategy`] for `value`.
+    pub const unsafe fn new(value: T) -> Self {
+        Self { inner: value }
+    }
+
+    /// Take the inner value.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn take(this: Self) -> T {
+        this.inner
+    }
+
+    /// Take the inner value.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn take(self) -> T {
+        self.inner
+    }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn inner(this: &Self) -> &T {
+        &this.inner
+    }
+
+    /// Take the inner value. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Take the inner value. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn into_inner(this: Self) -> T {
+        this.inner
+    }
+}
+
+impl<T: TryDropStrategy> TryDropStrategy for AssumeInfallibleAdapter<T> {
+    fn handle_error(&self, error: anyhow::Error) {
+        self.inner.handle_error(error)
+    }
+
+    fn on_uninstall(&self) {
+        self.inner.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.inner.on_fork_child()
+    }
+}
+
+// SAFETY: the caller of `AssumeInfallibleAdapter::new` guarantees this.
+unsafe impl<T: TryDropStrategy> InfallibleStrategy for AssumeInfallibleAdapter<T> {}
+
 /// This type is an adapter for types which implement [`TryDrop`] which allow their
 /// [`TryDrop::try_drop`] functions to be repeated multiple times.
 #[cfg_attr(
@@ -97,8 +398,33 @@ pub struct RepeatableTryDropAdapter<T: PureTryDrop> {
     #[cfg_attr(feature = "shrinkwraprs", shrinkwrap(main_field))]
     pub inner: T,
 
-    dropped: bool,
-    panic_on_double_drop: bool,
+    // packed into a single byte, so that this adds as little as possible to the size of `T`.
+    flags: RepeatableFlags,
+}
+
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Copy, Clone)]
+struct RepeatableFlags(u8);
+
+impl RepeatableFlags {
+    const DROPPED: u8 = 1 << 0;
+    const DISARMED: u8 = 1 << 1;
+    const PANIC_ON_DOUBLE_DROP: u8 = 1 << 2;
+
+    fn get(self, flag: u8) -> bool {
+        self.0 & flag != 0
+    }
+
+    fn set(&mut self, flag: u8, value: bool) {
+        if value {
+            self.0 |= flag
+        } else {
+            self.0 &= !flag
+        }
+    }
 }
 
 impl<T: PureTryDrop + Default> Default for RepeatableTryDropAdapter<T> {
@@ -112,10 +438,37 @@ impl<T: PureTryDrop> RepeatableTryDropAdapter<T> {
     pub fn new(item: T) -> Self {
         Self {
             inner: item,
-            dropped: false,
-            panic_on_double_drop: true,
+            flags: RepeatableFlags(RepeatableFlags::PANIC_ON_DOUBLE_DROP),
         }
     }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn inner(this: &Self) -> &T {
+        &this.inner
+    }
+
+    /// Take the inner value. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Take the inner value. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn into_inner(this: Self) -> T {
+        this.inner
+    }
 }
 
 #[cfg(not(feature = "shrinkwraprs"))]
@@ -123,18 +476,40 @@ impl<T: PureTryDrop> RepeatableTryDropAdapter<T> {
     /// Choose whether or not to panic when the [`RepeatableTryDropAdapter`] is dropped twice or
     /// multiple times.
     pub fn with_panic_on_double_drop(mut self, panic_on_double_drop: bool) -> Self {
-        self.panic_on_double_drop = panic_on_double_drop;
+        self.flags
+            .set(RepeatableFlags::PANIC_ON_DOUBLE_DROP, panic_on_double_drop);
         self
     }
 
     /// Check whether or not this object has it's destructor called.
     pub fn dropped(&self) -> bool {
-        self.dropped
+        self.flags.get(RepeatableFlags::DROPPED)
     }
 
     /// Check whether or not this object will panic when dropped twice or multiple times.
     pub fn panic_on_double_drop(&self) -> bool {
-        self.panic_on_double_drop
+        self.flags.get(RepeatableFlags::PANIC_ON_DOUBLE_DROP)
+    }
+
+    /// Mark this object as already dropped without calling the destructor of the inner value.
+    /// Any later attempt to drop it does nothing.
+    ///
+    /// This is useful if ownership of the underlying resource has been transferred elsewhere.
+    pub fn disarm(&mut self) {
+        self.flags.set(RepeatableFlags::DROPPED, true);
+        self.flags.set(RepeatableFlags::DISARMED, true);
+    }
+
+    /// Mark this object as not dropped yet, and not disarmed, so that the destructor of the inner
+    /// value is called again the next time this is dropped. This is useful to reuse this object
+    /// after the underlying resource has been acquired again, e.g. in an object pool.
+    ///
+    /// # Safety
+    /// The caller must guarantee that the inner value can be dropped again, e.g. because the
+    /// underlying resource has been acquired again since its destructor was last called.
+    pub unsafe fn reset(&mut self) {
+        self.flags.set(RepeatableFlags::DROPPED, false);
+        self.flags.set(RepeatableFlags::DISARMED, false);
     }
 }
 
@@ -143,18 +518,40 @@ impl<T: PureTryDrop> RepeatableTryDropAdapter<T> {
     /// Choose whether or not to panic when the [`RepeatableTryDropAdapter`] is dropped twice or
     /// multiple times.
     pub fn with_panic_on_double_drop(mut this: Self, panic_on_double_drop: bool) -> Self {
-        this.panic_on_double_drop = panic_on_double_drop;
+        this.flags
+            .set(RepeatableFlags::PANIC_ON_DOUBLE_DROP, panic_on_double_drop);
         this
     }
 
     /// Check whether or not this object has it's destructor called.
     pub fn dropped(this: &Self) -> bool {
-        this.dropped
+        this.flags.get(RepeatableFlags::DROPPED)
     }
 
     /// Check whether or not this object will panic when dropped twice or multiple times.
     pub fn panic_on_double_drop(this: &Self) -> bool {
-        this.panic_on_double_drop
+        this.flags.get(RepeatableFlags::PANIC_ON_DOUBLE_DROP)
+    }
+
+    /// Mark this object as already dropped without calling the destructor of the inner value.
+    /// Any later attempt to drop it does nothing.
+    ///
+    /// This is useful if ownership of the underlying resource has been transferred elsewhere.
+    pub fn disarm(this: &mut Self) {
+        this.flags.set(RepeatableFlags::DROPPED, true);
+        this.flags.set(RepeatableFlags::DISARMED, true);
+    }
+
+    /// Mark this object as not dropped yet, and not disarmed, so that the destructor of the inner
+    /// value is called again the next time this is dropped. This is useful to reuse this object
+    /// after the underlying resource has been acquired again, e.g. in an object pool.
+    ///
+    /// # Safety
+    /// The caller must guarantee that the inner value can be dropped again, e.g. because the
+    /// underlying resource has been acquired again since its destructor was last called.
+    pub unsafe fn reset(this: &mut Self) {
+        this.flags.set(RepeatableFlags::DROPPED, false);
+        this.flags.set(RepeatableFlags::DISARMED, false);
     }
 
     /// Take the inner value out of the adapter.
@@ -177,11 +574,15 @@ impl<T: PureTryDrop> PureTryDrop for RepeatableTryDropAdapter<T> {
     }
 
     unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
-        if self.dropped && self.panic_on_double_drop {
+        if self.flags.get(RepeatableFlags::DISARMED) {
+            Ok(())
+        } else if self.flags.get(RepeatableFlags::DROPPED)
+            && self.flags.get(RepeatableFlags::PANIC_ON_DOUBLE_DROP)
+        {
             panic!("tried to drop object twice, this is an invalid operation")
         } else {
             self.inner.try_drop()?;
-            self.dropped = true;
+            self.flags.set(RepeatableFlags::DROPPED, true);
             Ok(())
         }
     }
@@ -200,6 +601,12 @@ unsafe impl<T: PureTryDrop> RepeatableTryDrop for RepeatableTryDropAdapter<T> {}
 /// # Implementation
 /// We call `try_drop`, which is safe because we only do it in [`Drop::drop`]. If it returns an
 /// error, we redirect the error to the fallback try drop strategy.
+///
+/// # Size
+/// This is guaranteed to be exactly as large as `TD`. Together with [`WithStrategies`] over zero
+/// sized try drop strategies, this means that a resource can be dropped with its own strategies
+/// without taking up any more memory than the resource itself, which matters on constrained
+/// targets. [`RepeatableTryDropAdapter`] adds a single byte to `TD`, before padding.
 #[cfg_attr(
     feature = "derives",
     derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
@@ -209,20 +616,215 @@ unsafe impl<T: PureTryDrop> RepeatableTryDrop for RepeatableTryDropAdapter<T> {}
 pub struct DropAdapter<TD: PureTryDrop>(pub TD);
 
 impl<TD: PureTryDrop> Drop for DropAdapter<TD> {
+    #[inline]
     fn drop(&mut self) {
         // SAFETY: we called this function inside a `Drop::drop` context.
         let result = unsafe { self.0.try_drop() };
         if let Err(error) = result {
-            let handler = FallbackTryDropStrategyHandler::new(
-                TryDropStrategyRef(self.0.fallback_try_drop_strategy()),
-                FallibleTryDropStrategyRef(self.0.try_drop_strategy()),
-            );
+            handle_drop_error(&self.0, error, core::convert::identity)
+        }
+    }
+}
+
+// Fails to compile if the guarantees in the `# Size` section of `DropAdapter` regress.
+const _: () = {
+    use core::mem::size_of;
+
+    struct Ignore;
+
+    impl TryDropStrategy for Ignore {
+        fn handle_error(&self, _error: anyhow::Error) {}
+    }
+
+    struct Resource<T>(T);
+
+    impl<T> PureTryDrop for Resource<T> {
+        type Error = anyhow::Error;
+        type FallbackTryDropStrategy = Ignore;
+        type TryDropStrategy = Ignore;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &Ignore
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &Ignore
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    assert!(size_of::<DropAdapter<Resource<[u64; 3]>>>() == size_of::<[u64; 3]>());
+    assert!(size_of::<DropAdapter<Resource<()>>>() == 0);
+    assert!(size_of::<WithStrategies<[u64; 3], Ignore, Ignore>>() == size_of::<[u64; 3]>());
+    assert!(size_of::<RepeatableTryDropAdapter<Resource<[u8; 3]>>>() == size_of::<[u8; 3]>() + 1);
+    assert!(size_of::<InfallibleToFallibleTryDropStrategyAdapter<Ignore, anyhow::Error>>() == 0);
+    assert!(size_of::<AssertInfallibleTryDropStrategyAdapter<Ignore>>() == 0);
+    assert!(size_of::<AssumeInfallibleAdapter<Ignore>>() == 0);
+};
+
+/// Hand the drop error of `value` to its try drop strategies, mapping it with `map_err` first.
+///
+/// Drops rarely fail, so this is kept out of line and cold, leaving only the check for an error in
+/// the `Drop::drop` of the adapters. See `benches/drop.rs`.
+#[cold]
+#[inline(never)]
+fn handle_drop_error<TD: PureTryDrop>(
+    value: &TD,
+    error: TD::Error,
+    map_err: impl FnOnce(anyhow::Error) -> anyhow::Error,
+) {
+    let handler = FallbackTryDropStrategyHandler::new(
+        TryDropStrategyRef(value.fallback_try_drop_strategy()),
+        FallibleTryDropStrategyRef(value.try_drop_strategy()),
+    );
+
+    let error = error.into();
+
+    #[cfg(feature = "std")]
+    if crate::suppress::is_suppressed(&error) {
+        return;
+    }
+
+    #[cfg(any(feature = "error-telemetry", test))]
+    crate::error_telemetry::record::<TD>(&error);
+
+    #[cfg(feature = "std")]
+    let _guard = crate::drop_context::enter::<TD>();
+
+    handler.handle_error(map_err(error))
+}
+
+impl<TD: PureTryDrop> DropAdapter<TD> {
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn inner(&self) -> &TD {
+        &self.0
+    }
+
+    /// Get a reference to the inner value. Unlike dereferencing, this is available regardless of
+    /// whether the `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn inner(this: &Self) -> &TD {
+        &this.0
+    }
+
+    fn defuse(this: Self) -> TD {
+        let this = ManuallyDrop::new(this);
+
+        // SAFETY: `this` is wrapped in a `ManuallyDrop`, so the inner value is never dropped twice
+        // and our `Drop` implementation is never run.
+        unsafe { ptr::read(&this.0) }
+    }
+
+    fn try_defuse(this: Self) -> Result<TD, (TD, TD::Error)>
+    where
+        TD: RepeatableTryDrop,
+    {
+        let mut inner = Self::defuse(this);
 
-            handler.handle_error(error.into())
+        match inner.safe_try_drop() {
+            Ok(()) => Ok(inner),
+            Err(error) => Err((inner, error)),
         }
     }
 }
 
+#[cfg(not(feature = "shrinkwraprs"))]
+impl<TD: PureTryDrop> DropAdapter<TD> {
+    /// Take the inner value out of the adapter without calling its [`TryDrop::try_drop`]
+    /// function.
+    pub fn into_inner(self) -> TD {
+        Self::defuse(self)
+    }
+
+    /// Suppress the destructor of the inner value without calling its [`TryDrop::try_drop`]
+    /// function, leaking it. This is useful to intentionally leak a resource which failed in a way
+    /// that dropping it could cause worse corruption.
+    ///
+    /// If the inner value is a [`RepeatableTryDropAdapter`], it isn't marked as dropped, since
+    /// nothing was dropped.
+    pub fn forget(self) {
+        core::mem::forget(Self::defuse(self))
+    }
+
+    /// Leak the inner value without calling its [`TryDrop::try_drop`] function, returning a
+    /// reference to it which lives for the rest of the program. Like [`DropAdapter::forget`], the
+    /// destructor of the inner value is never run.
+    ///
+    /// If the inner value implements [`RepeatableTryDrop`], e.g. if it is a
+    /// [`RepeatableTryDropAdapter`], it can still be finalized later through the returned reference
+    /// with [`RepeatableTryDrop::safe_try_drop`].
+    #[cfg(feature = "std")]
+    pub fn leak(self) -> &'static mut TD
+    where
+        TD: 'static,
+    {
+        std::boxed::Box::leak(std::boxed::Box::new(Self::defuse(self)))
+    }
+}
+
+#[cfg(not(feature = "shrinkwraprs"))]
+impl<RTD: RepeatableTryDrop> DropAdapter<RTD> {
+    /// Take the inner value out of the adapter after calling its [`TryDrop::try_drop`] function.
+    ///
+    /// # Errors
+    /// If the inner value fails to drop, the error is returned alongside the inner value instead
+    /// of being handled by the try drop strategies.
+    pub fn try_into_inner(self) -> Result<RTD, (RTD, RTD::Error)> {
+        Self::try_defuse(self)
+    }
+}
+
+#[cfg(feature = "shrinkwraprs")]
+impl<TD: PureTryDrop> DropAdapter<TD> {
+    /// Take the inner value out of the adapter without calling its [`TryDrop::try_drop`]
+    /// function.
+    pub fn into_inner(this: Self) -> TD {
+        Self::defuse(this)
+    }
+
+    /// Suppress the destructor of the inner value without calling its [`TryDrop::try_drop`]
+    /// function, leaking it. This is useful to intentionally leak a resource which failed in a way
+    /// that dropping it could cause worse corruption.
+    ///
+    /// If the inner value is a [`RepeatableTryDropAdapter`], it isn't marked as dropped, since
+    /// nothing was dropped.
+    pub fn forget(this: Self) {
+        core::mem::forget(Self::defuse(this))
+    }
+
+    /// Leak the inner value without calling its [`TryDrop::try_drop`] function, returning a
+    /// reference to it which lives for the rest of the program. Like [`DropAdapter::forget`], the
+    /// destructor of the inner value is never run.
+    ///
+    /// If the inner value implements [`RepeatableTryDrop`], e.g. if it is a
+    /// [`RepeatableTryDropAdapter`], it can still be finalized later through the returned reference
+    /// with [`RepeatableTryDrop::safe_try_drop`].
+    #[cfg(feature = "std")]
+    pub fn leak(this: Self) -> &'static mut TD
+    where
+        TD: 'static,
+    {
+        std::boxed::Box::leak(std::boxed::Box::new(Self::defuse(this)))
+    }
+}
+
+#[cfg(feature = "shrinkwraprs")]
+impl<RTD: RepeatableTryDrop> DropAdapter<RTD> {
+    /// Take the inner value out of the adapter after calling its [`TryDrop::try_drop`] function.
+    ///
+    /// # Errors
+    /// If the inner value fails to drop, the error is returned alongside the inner value instead
+    /// of being handled by the try drop strategies.
+    pub fn try_into_inner(this: Self) -> Result<RTD, (RTD, RTD::Error)> {
+        Self::try_defuse(this)
+    }
+}
+
 impl<RTD: RepeatableTryDrop> PureTryDrop for DropAdapter<RTD> {
     type Error = RTD::Error;
     type FallbackTryDropStrategy = RTD::FallbackTryDropStrategy;
@@ -251,36 +853,342 @@ impl<TD: PureTryDrop> From<TD> for DropAdapter<TD> {
     }
 }
 
-/// A reference to a type which implements [`FallibleTryDropStrategy`]. Used as a workaround for
-/// implementing [`FallibleTryDropStrategy`] on references.
-#[cfg_attr(
-    feature = "derives",
-    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
-)]
-#[cfg_attr(feature = "shrinkwraprs", derive(Shrinkwrap))]
-pub struct FallibleTryDropStrategyRef<'a, T: FallibleTryDropStrategy>(pub &'a T);
+/// A [`DropAdapter`] which doesn't give out access to the inner value.
+///
+/// This is the recommended adapter for types which implement [`Copy`]. Through [`DropAdapter`],
+/// the inner value can be implicitly copied out of the public field, and the copy will have its
+/// [`TryDrop::try_drop`] function called separately once it's adapted again. Since the inner value
+/// of this adapter can't be reached, it can only be taken out through
+/// [`SealedDropAdapter::into_inner`], which doesn't call [`TryDrop::try_drop`].
+///
+/// For debugging double executions of [`TryDrop::try_drop`], see the
+/// [`ExecutionTracker`](crate::debugging::ExecutionTracker).
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct SealedDropAdapter<TD: PureTryDrop>(DropAdapter<TD>);
 
-impl<'a, T: FallibleTryDropStrategy> FallibleTryDropStrategy for FallibleTryDropStrategyRef<'a, T> {
-    type Error = T::Error;
+impl<TD: PureTryDrop> SealedDropAdapter<TD> {
+    /// Create a new sealed drop adapter from the given value.
+    pub fn new(value: TD) -> Self {
+        Self(DropAdapter(value))
+    }
 
-    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
-        self.0.try_handle_error(error)
+    /// Take the inner value out of the adapter without calling its [`TryDrop::try_drop`]
+    /// function.
+    pub fn into_inner(self) -> TD {
+        DropAdapter::defuse(self.0)
     }
 }
 
-/// A reference to a type which implements [`TryDropStrategy`]. Used as a workaround for
-/// implementing [`TryDropStrategy`] on references.
-#[cfg_attr(
-    feature = "derives",
-    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
-)]
-#[cfg_attr(feature = "shrinkwraprs", derive(Shrinkwrap))]
-pub struct TryDropStrategyRef<'a, T: TryDropStrategy>(pub &'a T);
+impl<TD: PureTryDrop> From<TD> for SealedDropAdapter<TD> {
+    fn from(t: TD) -> Self {
+        t.adapt_sealed()
+    }
+}
+
+/// An adapter like [`DropAdapter`], which maps the drop error with a function before it reaches
+/// the try drop strategies, e.g. to add context to it.
+pub struct MapErrDropAdapter<TD: PureTryDrop, F: Fn(anyhow::Error) -> anyhow::Error> {
+    /// The inner value.
+    pub inner: TD,
+
+    map_err: F,
+}
+
+impl<TD: PureTryDrop, F: Fn(anyhow::Error) -> anyhow::Error> MapErrDropAdapter<TD, F> {
+    /// Create a new adapter which maps the drop error of the given value with `map_err`.
+    pub fn new(value: TD, map_err: F) -> Self {
+        Self {
+            inner: value,
+            map_err,
+        }
+    }
+}
+
+// closures don't implement `Debug`, so the function is left out
+#[cfg(feature = "derives")]
+impl<TD, F> fmt::Debug for MapErrDropAdapter<TD, F>
+where
+    TD: PureTryDrop + fmt::Debug,
+    F: Fn(anyhow::Error) -> anyhow::Error,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MapErrDropAdapter")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<TD: PureTryDrop, F: Fn(anyhow::Error) -> anyhow::Error> Drop for MapErrDropAdapter<TD, F> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: we called this function inside a `Drop::drop` context.
+        let result = unsafe { self.inner.try_drop() };
+        if let Err(error) = result {
+            handle_drop_error(&self.inner, error, &self.map_err)
+        }
+    }
+}
+
+/// An adapter like [`DropAdapter`], which attaches a snapshot of the [`Debug`] representation of
+/// the inner value to the drop error as context, e.g.
+/// `failed to drop Connection { addr: .., state: HalfClosed }: connection reset`. Created by
+/// [`PureTryDrop::adapt_with_debug_snapshot`].
+///
+/// The snapshot is taken when the drop fails, after [`TryDrop::try_drop`] was called, so it shows
+/// the state the value was left in. It's cut off after [`Self::limit`] bytes, and the rest of the
+/// [`Debug`] representation isn't formatted at all.
+///
+/// # Sensitive data
+/// The snapshot is part of the error, so anything in the [`Debug`] representation reaches every
+/// try drop strategy the error is routed to, e.g. logs or panic messages. Leave sensitive fields
+/// out of the [`Debug`] implementation, or redact the error in the strategy, e.g. with a
+/// `PanicFormatter`.
+///
+/// [`Debug`]: core::fmt::Debug
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct DebugSnapshotDropAdapter<TD: PureTryDrop + core::fmt::Debug> {
+    /// The inner value.
+    pub inner: TD,
+
+    /// The maximum length of the snapshot in bytes, before the `...` which marks it as cut off.
+    pub limit: usize,
+}
+
+#[cfg(feature = "std")]
+impl<TD: PureTryDrop + core::fmt::Debug> DebugSnapshotDropAdapter<TD> {
+    /// The default maximum length of the snapshot in bytes.
+    pub const DEFAULT_LIMIT: usize = 256;
+
+    /// Create a new adapter which attaches a snapshot of the given value to its drop error,
+    /// cut off after [`Self::DEFAULT_LIMIT`] bytes.
+    pub fn new(value: TD) -> Self {
+        Self {
+            inner: value,
+            limit: Self::DEFAULT_LIMIT,
+        }
+    }
+
+    /// Set the maximum length of the snapshot in bytes.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+impl<TD: PureTryDrop + core::fmt::Debug> Drop for DebugSnapshotDropAdapter<TD> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: we called this function inside a `Drop::drop` context.
+        let result = unsafe { self.inner.try_drop() };
+        if let Err(error) = result {
+            let snapshot = debug_snapshot(&self.inner, self.limit);
+            handle_drop_error(&self.inner, error, |error| {
+                error.context(std::format!("failed to drop {snapshot}"))
+            })
+        }
+    }
+}
+
+/// Format `value` with [`Debug`](core::fmt::Debug), cutting it off after `limit` bytes.
+#[cfg(feature = "std")]
+fn debug_snapshot(value: &impl core::fmt::Debug, limit: usize) -> std::string::String {
+    use core::fmt::{self, Write};
+
+    /// Stops the formatting with an error once the limit is reached.
+    struct Truncate {
+        snapshot: std::string::String,
+        limit: usize,
+    }
+
+    impl Write for Truncate {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let remaining = self.limit - self.snapshot.len();
+
+            if s.len() <= remaining {
+                self.snapshot.push_str(s);
+                Ok(())
+            } else {
+                let mut end = remaining;
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+
+                self.snapshot.push_str(&s[..end]);
+                Err(fmt::Error)
+            }
+        }
+    }
+
+    let mut truncate = Truncate {
+        snapshot: std::string::String::new(),
+        limit,
+    };
+
+    if write!(truncate, "{value:?}").is_err() {
+        truncate.snapshot.push_str("...");
+    }
+
+    truncate.snapshot
+}
+
+/// A value which uses the given try drop strategies instead of the primary and fallback handlers.
+/// Created by [`WithStrategies::new`], or by `with_strategies` on types which implement
+/// `ImpureTryDrop`.
+///
+/// The value has to implement `ImpureTryDrop` with the `global` or `thread-local` features, or
+/// [`SafeTryDrop`](crate::SafeTryDrop) without them.
+///
+/// Like any other type which implements [`PureTryDrop`], this still needs to be adapted in order
+/// for the strategies to be run, e.g. with `value.with_strategies(primary, fallback).adapt()`.
+/// Adapting the value itself instead would use the handlers again.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+)]
+pub struct WithStrategies<TD, S, F>
+where
+    S: FallibleTryDropStrategy,
+    F: TryDropStrategy,
+{
+    /// The inner value.
+    pub inner: TD,
+
+    /// The try drop strategy to use instead of the primary handler.
+    pub try_drop_strategy: S,
+
+    /// The try drop strategy to use instead of the fallback handler.
+    pub fallback_try_drop_strategy: F,
+}
+
+impl<TD, S, F> WithStrategies<TD, S, F>
+where
+    S: FallibleTryDropStrategy,
+    F: TryDropStrategy,
+{
+    /// Use the given try drop strategies for the value.
+    pub const fn new(value: TD, try_drop_strategy: S, fallback_try_drop_strategy: F) -> Self {
+        Self {
+            inner: value,
+            try_drop_strategy,
+            fallback_try_drop_strategy,
+        }
+    }
+}
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+impl<TD, S, F> PureTryDrop for WithStrategies<TD, S, F>
+where
+    TD: ImpureTryDrop,
+    S: FallibleTryDropStrategy,
+    F: TryDropStrategy,
+{
+    type Error = TD::Error;
+    type FallbackTryDropStrategy = F;
+    type TryDropStrategy = S;
+
+    fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+        &self.fallback_try_drop_strategy
+    }
+
+    fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+        &self.try_drop_strategy
+    }
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        self.inner.try_drop()
+    }
+}
+
+// SAFETY: since `TD` is `RepeatableTryDrop`, we know that it is safe to call `try_drop` multiple
+// times.
+#[cfg(any(feature = "global", feature = "thread-local"))]
+unsafe impl<TD, S, F> RepeatableTryDrop for WithStrategies<TD, S, F>
+where
+    TD: ImpureTryDrop + RepeatableTryDrop,
+    S: FallibleTryDropStrategy,
+    F: TryDropStrategy,
+{
+}
+
+#[cfg(not(any(feature = "global", feature = "thread-local")))]
+impl<TD, S, F> PureTryDrop for WithStrategies<TD, S, F>
+where
+    TD: SafeTryDrop,
+    S: FallibleTryDropStrategy,
+    F: TryDropStrategy,
+{
+    type Error = TD::Error;
+    type FallbackTryDropStrategy = F;
+    type TryDropStrategy = S;
+
+    fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+        &self.fallback_try_drop_strategy
+    }
+
+    fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+        &self.try_drop_strategy
+    }
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        self.inner.try_drop()
+    }
+}
+
+// SAFETY: `SafeTryDrop::try_drop` is a safe function, so calling it any number of times can't
+// cause undefined behavior.
+#[cfg(not(any(feature = "global", feature = "thread-local")))]
+unsafe impl<TD, S, F> RepeatableTryDrop for WithStrategies<TD, S, F>
+where
+    TD: SafeTryDrop,
+    S: FallibleTryDropStrategy,
+    F: TryDropStrategy,
+{
+}
+
+/// A reference to a type which implements [`FallibleTryDropStrategy`]. Used as a workaround for
+/// implementing [`FallibleTryDropStrategy`] on references.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[cfg_attr(feature = "shrinkwraprs", derive(Shrinkwrap))]
+pub struct FallibleTryDropStrategyRef<'a, T: FallibleTryDropStrategy>(pub &'a T);
+
+impl<'a, T: FallibleTryDropStrategy> FallibleTryDropStrategy for FallibleTryDropStrategyRef<'a, T> {
+    type Error = T::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        self.0.try_handle_error(error)
+    }
+
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = anyhow::Error>,
+        failed: &mut dyn FnMut(Self::Error),
+    ) {
+        self.0.try_handle_errors_batch(errors, failed)
+    }
+}
+
+/// A reference to a type which implements [`TryDropStrategy`]. Used as a workaround for
+/// implementing [`TryDropStrategy`] on references.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[cfg_attr(feature = "shrinkwraprs", derive(Shrinkwrap))]
+pub struct TryDropStrategyRef<'a, T: TryDropStrategy>(pub &'a T);
 
 impl<'a, T: TryDropStrategy> TryDropStrategy for TryDropStrategyRef<'a, T> {
     fn handle_error(&self, error: anyhow::Error) {
         self.0.handle_error(error)
     }
+
+    fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = anyhow::Error>) {
+        self.0.handle_errors_batch(errors)
+    }
 }
 
 /// A type which chains two try drop strategies together, one of which may fail and if so, will be
@@ -317,6 +1225,13 @@ where
             fallible_try_drop_strategy,
         }
     }
+
+    /// Handle many drop errors at once, e.g. the ones a sequence of try drop strategies failed to
+    /// handle. The fallible try drop strategy gets them as a batch, and every error it fails to
+    /// handle is redirected to the fallback try drop strategy.
+    pub fn handle_errors(&self, errors: impl IntoIterator<Item = anyhow::Error>) {
+        self.handle_errors_batch(&mut errors.into_iter())
+    }
 }
 
 impl<FDS, FTDS> TryDropStrategy for FallbackTryDropStrategyHandler<FDS, FTDS>
@@ -325,8 +1240,1406 @@ where
     FTDS: FallibleTryDropStrategy,
 {
     fn handle_error(&self, error: anyhow::Error) {
-        if let Err(error) = self.fallible_try_drop_strategy.dyn_try_handle_error(error) {
+        #[cfg(feature = "std")]
+        let scope = crate::sampling::enter(&error);
+
+        #[cfg(feature = "std")]
+        let guard = crate::tier::enter(&error, crate::tier::HandlingTier::Primary);
+
+        let result = self.fallible_try_drop_strategy.dyn_try_handle_error(error);
+
+        #[cfg(feature = "std")]
+        drop(guard);
+
+        if let Err(error) = result {
+            #[cfg(feature = "std")]
+            scope.carry_to(&error);
+
+            #[cfg(feature = "std")]
+            let _guard = crate::tier::enter(&error, crate::tier::HandlingTier::Fallback);
+
             self.fallback_try_drop_strategy.handle_error(error)
         }
     }
+
+    fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = anyhow::Error>) {
+        #[cfg(feature = "std")]
+        let _scope = crate::sampling::enter_batch();
+
+        self.fallible_try_drop_strategy
+            .try_handle_errors_batch(errors, &mut |error| {
+                let error = error.into();
+
+                #[cfg(feature = "std")]
+                let _guard = crate::tier::enter(&error, crate::tier::HandlingTier::Fallback);
+
+                self.fallback_try_drop_strategy.handle_error(error)
+            })
+    }
+}
+
+/// Chain any amount of try drop strategies together into nested
+/// [`FallbackTryDropStrategyHandler`]s. Each strategy is tried in order, and the errors it fails
+/// to handle cascade to the next one. The last strategy must be a [`TryDropStrategy`], so the
+/// whole chain is one too.
+///
+/// `fallback_chain![a, b, c]` expands to the equivalent of
+/// `FallbackTryDropStrategyHandler::new(FallbackTryDropStrategyHandler::new(c, b), a)`, except
+/// that the strategies are evaluated from left to right.
+///
+/// # Examples
+/// ```ignore
+/// use try_drop::drop_strategies::{PanicDropStrategy, WriteDropStrategy};
+///
+/// let strategy = try_drop::fallback_chain![
+///     WriteDropStrategy::stderr(),
+///     WriteDropStrategy::stdout(),
+///     PanicDropStrategy::DEFAULT,
+/// ];
+/// ```
+#[macro_export]
+macro_rules! fallback_chain {
+    ($last:expr $(,)?) => {
+        $last
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        match $first {
+            first => $crate::adapters::FallbackTryDropStrategyHandler::new(
+                $crate::fallback_chain![$($rest),+],
+                first,
+            ),
+        }
+    };
+}
+
+/// A type which chains two fallible try drop strategies together, handing the error to the second
+/// one only if the first one succeeded. Created by [`FallibleTryDropStrategyExt::and_then`].
+///
+/// The first try drop strategy gets a copy of the error, so that the second one can get the
+/// original error, as long as the first one didn't keep its copy around.
+///
+/// [`FallibleTryDropStrategyExt::and_then`]: crate::FallibleTryDropStrategyExt::and_then
+#[cfg(feature = "alloc")]
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+)]
+pub struct AndThenTryDropStrategyHandler<A, B>
+where
+    A: FallibleTryDropStrategy,
+    B: FallibleTryDropStrategy,
+{
+    /// The try drop strategy which will be called first.
+    pub first_try_drop_strategy: A,
+
+    /// The try drop strategy which will be called if the first one succeeded.
+    pub second_try_drop_strategy: B,
+}
+
+#[cfg(feature = "alloc")]
+impl<A, B> AndThenTryDropStrategyHandler<A, B>
+where
+    A: FallibleTryDropStrategy,
+    B: FallibleTryDropStrategy,
+{
+    /// Create a new and then try drop strategy handler.
+    pub fn new(first_try_drop_strategy: A, second_try_drop_strategy: B) -> Self {
+        Self {
+            first_try_drop_strategy,
+            second_try_drop_strategy,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, B> FallibleTryDropStrategy for AndThenTryDropStrategyHandler<A, B>
+where
+    A: FallibleTryDropStrategy,
+    B: FallibleTryDropStrategy,
+{
+    type Error = anyhow::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        let shared = SharedError::new(error);
+        self.first_try_drop_strategy
+            .dyn_try_handle_error(shared.copy())?;
+        self.second_try_drop_strategy
+            .dyn_try_handle_error(shared.into_error())
+    }
+
+    fn on_uninstall(&self) {
+        self.first_try_drop_strategy.on_uninstall();
+        self.second_try_drop_strategy.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.first_try_drop_strategy.on_fork_child();
+        self.second_try_drop_strategy.on_fork_child()
+    }
+}
+
+#[cfg(feature = "std")]
+mod catch_panic {
+    use crate::{PureTryDrop, RepeatableTryDrop};
+    use core::any::Any;
+    use std::error::Error;
+    use std::fmt;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::string::{String, ToString};
+
+    /// Get the message of a panic payload, if it's a string.
+    pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> &str {
+        match (
+            payload.downcast_ref::<&str>(),
+            payload.downcast_ref::<String>(),
+        ) {
+            (Some(message), _) => message,
+            (_, Some(message)) => message.as_str(),
+            (None, None) => "Box<dyn Any>",
+        }
+    }
+
+    /// The error used when the destructor of a value in a [`PanicToErrorAdapter`] panics.
+    #[cfg_attr(
+        feature = "derives",
+        derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+    )]
+    #[derive(Debug)]
+    pub struct DestructorPanicked {
+        /// The message of the panic.
+        pub message: String,
+    }
+
+    impl fmt::Display for DestructorPanicked {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "destructor panicked: {}", self.message)
+        }
+    }
+
+    impl Error for DestructorPanicked {}
+
+    /// The drop error of a [`PanicToErrorAdapter`]. Either the destructor panicked, or it returned
+    /// its own error, which is kept as is.
+    #[cfg_attr(
+        feature = "derives",
+        derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+    )]
+    pub enum PanicOrError<E> {
+        /// The destructor panicked.
+        Panicked(DestructorPanicked),
+
+        /// The destructor returned an error.
+        Error(E),
+    }
+
+    impl<E: Into<anyhow::Error>> From<PanicOrError<E>> for anyhow::Error {
+        fn from(error: PanicOrError<E>) -> Self {
+            match error {
+                PanicOrError::Panicked(error) => error.into(),
+                PanicOrError::Error(error) => error.into(),
+            }
+        }
+    }
+
+    /// An adapter which catches panics in the destructor of the inner value, turning them into
+    /// drop errors which go through the try drop strategies like any other, instead of unwinding
+    /// out of [`Drop::drop`]. Created by [`PureTryDrop::adapt_catching`], or with
+    /// [`PanicToErrorAdapter::new`] then adapted.
+    ///
+    /// This is useful when the destructor calls into code which panics instead of returning an
+    /// error.
+    ///
+    /// # Unwind safety
+    /// The destructor is run under [`AssertUnwindSafe`], as drop errors are expected from it
+    /// anyways. If it panics, the inner value may be left in an inconsistent state, so it
+    /// shouldn't be used any further, which adapting it already ensures. Note that panics are
+    /// still reported by the panic hook, and that this does nothing if panics abort.
+    #[cfg_attr(
+        feature = "derives",
+        derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+    )]
+    #[cfg_attr(feature = "shrinkwraprs", derive(Shrinkwrap))]
+    #[cfg_attr(feature = "shrinkwraprs", shrinkwrap(mutable))]
+    pub struct PanicToErrorAdapter<TD: PureTryDrop>(pub TD);
+
+    impl<TD: PureTryDrop> PanicToErrorAdapter<TD> {
+        /// Catch panics in the destructor of the given value.
+        pub const fn new(value: TD) -> Self {
+            Self(value)
+        }
+
+        /// Get a reference to the inner value. Unlike dereferencing, this is available regardless
+        /// of whether the `shrinkwraprs` feature is enabled.
+        #[cfg(not(feature = "shrinkwraprs"))]
+        pub fn inner(&self) -> &TD {
+            &self.0
+        }
+
+        /// Get a reference to the inner value. Unlike dereferencing, this is available regardless
+        /// of whether the `shrinkwraprs` feature is enabled.
+        #[cfg(feature = "shrinkwraprs")]
+        pub fn inner(this: &Self) -> &TD {
+            &this.0
+        }
+
+        /// Take the inner value.
+        #[cfg(not(feature = "shrinkwraprs"))]
+        pub fn into_inner(self) -> TD {
+            self.0
+        }
+
+        /// Take the inner value.
+        #[cfg(feature = "shrinkwraprs")]
+        pub fn into_inner(this: Self) -> TD {
+            this.0
+        }
+    }
+
+    impl<TD: PureTryDrop> PureTryDrop for PanicToErrorAdapter<TD> {
+        type Error = PanicOrError<TD::Error>;
+        type FallbackTryDropStrategy = TD::FallbackTryDropStrategy;
+        type TryDropStrategy = TD::TryDropStrategy;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            self.0.fallback_try_drop_strategy()
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            self.0.try_drop_strategy()
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            // SAFETY: the caller upholds the contract of `PureTryDrop::try_drop` for us.
+            match panic::catch_unwind(AssertUnwindSafe(|| unsafe { self.0.try_drop() })) {
+                Ok(result) => result.map_err(PanicOrError::Error),
+                Err(payload) => Err(PanicOrError::Panicked(DestructorPanicked {
+                    message: panic_message(&*payload).to_string(),
+                })),
+            }
+        }
+    }
+
+    // SAFETY: catching panics doesn't change how many times `try_drop` can be called.
+    unsafe impl<TD: RepeatableTryDrop> RepeatableTryDrop for PanicToErrorAdapter<TD> {}
+}
+
+#[cfg(any(feature = "global", feature = "test-utils", test))]
+pub(crate) use catch_panic::panic_message;
+
+#[cfg(feature = "std")]
+pub use catch_panic::{DestructorPanicked, PanicOrError, PanicToErrorAdapter};
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+mod check_on_drop {
+    use crate::{ImpureTryDrop, RepeatableTryDrop};
+    use std::io::{self, BufWriter, Write};
+    use std::net::{Shutdown, TcpStream};
+
+    /// A trait for types which have a fallible finalizing operation which is usually silently
+    /// ignored when dropped, such as flushing a [`BufWriter`].
+    pub trait Finalize {
+        /// Finalize this value.
+        fn finalize(&mut self) -> anyhow::Result<()>;
+    }
+
+    impl<W: Write> Finalize for BufWriter<W> {
+        fn finalize(&mut self) -> anyhow::Result<()> {
+            self.flush().map_err(Into::into)
+        }
+    }
+
+    impl Finalize for TcpStream {
+        fn finalize(&mut self) -> anyhow::Result<()> {
+            match self.shutdown(Shutdown::Both) {
+                Err(error) if error.kind() != io::ErrorKind::NotConnected => Err(error.into()),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// An adapter which calls [`Finalize::finalize`] on the inner value when dropped, redirecting
+    /// any errors to the try drop strategies instead of silently ignoring them.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// use std::io::BufWriter;
+    /// use try_drop::adapters::CheckOnDrop;
+    /// use try_drop::PureTryDrop;
+    ///
+    /// let writer = CheckOnDrop::new(BufWriter::new(writer)).adapt();
+    ///
+    /// // if flushing fails here, the error is handled by the try drop strategies.
+    /// drop(writer);
+    /// ```
+    #[cfg_attr(
+        feature = "derives",
+        derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+    )]
+    pub struct CheckOnDrop<F: Finalize> {
+        value: F,
+        finished: bool,
+    }
+
+    impl<F: Finalize> CheckOnDrop<F> {
+        /// Wrap the `value` in this adapter.
+        pub fn new(value: F) -> Self {
+            Self {
+                value,
+                finished: false,
+            }
+        }
+
+        /// Get a reference to the inner value.
+        pub fn get_ref(&self) -> &F {
+            &self.value
+        }
+
+        /// Get a mutable reference to the inner value.
+        pub fn get_mut(&mut self) -> &mut F {
+            &mut self.value
+        }
+
+        /// Take the inner value out of the adapter without finalizing it.
+        pub fn into_inner(self) -> F {
+            self.value
+        }
+
+        /// Explicitly finalize the inner value, returning the error to the caller instead of the
+        /// try drop strategies. The inner value will not be finalized again when dropped.
+        pub fn finish(&mut self) -> anyhow::Result<()> {
+            if self.finished {
+                Ok(())
+            } else {
+                self.finished = true;
+                self.value.finalize()
+            }
+        }
+
+        /// Check whether or not the inner value has been finalized.
+        pub fn finished(&self) -> bool {
+            self.finished
+        }
+    }
+
+    impl<F: Finalize> ImpureTryDrop for CheckOnDrop<F> {
+        type Error = anyhow::Error;
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            self.finish()
+        }
+    }
+
+    // SAFETY: the inner value is only ever finalized once.
+    unsafe impl<F: Finalize> RepeatableTryDrop for CheckOnDrop<F> {}
+
+    /// A resource, such as a [`File`](std::fs::File), which is closed when dropped, redirecting
+    /// the close error to the try drop strategies instead of silently ignoring it.
+    ///
+    /// By default, the resource is closed by flushing it, see [`ClosableResource::new`]. Any
+    /// other function can be used to close it with [`ClosableResource::with_close`]. Unlike
+    /// [`CheckOnDrop`], the resource doesn't have to implement [`Finalize`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::fs::File;
+    /// use std::io::{self, Write};
+    /// use try_drop::adapters::ClosableResource;
+    /// use try_drop::PureTryDrop;
+    ///
+    /// fn save(data: &[u8]) -> io::Result<()> {
+    ///     let mut file = ClosableResource::new(File::create("data.bin")?);
+    ///     file.get_mut().write_all(data)?;
+    ///     let file = file.adapt();
+    ///
+    ///     // if flushing fails here, the error is handled by the try drop strategies.
+    ///     drop(file);
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg_attr(feature = "derives", derive(Debug))]
+    pub struct ClosableResource<T, C = fn(&mut T) -> io::Result<()>>
+    where
+        C: FnMut(&mut T) -> io::Result<()>,
+    {
+        resource: T,
+        close: C,
+        closed: bool,
+    }
+
+    impl<T: Write> ClosableResource<T> {
+        /// Wrap the `resource` in this adapter, closing it by flushing it.
+        pub fn new(resource: T) -> Self {
+            Self::with_close(resource, T::flush)
+        }
+    }
+
+    impl<T, C> ClosableResource<T, C>
+    where
+        C: FnMut(&mut T) -> io::Result<()>,
+    {
+        /// Wrap the `resource` in this adapter, closing it with the given function.
+        pub fn with_close(resource: T, close: C) -> Self {
+            Self {
+                resource,
+                close,
+                closed: false,
+            }
+        }
+
+        /// Get a reference to the resource.
+        pub fn get_ref(&self) -> &T {
+            &self.resource
+        }
+
+        /// Get a mutable reference to the resource.
+        pub fn get_mut(&mut self) -> &mut T {
+            &mut self.resource
+        }
+
+        /// Take the resource out of the adapter without closing it.
+        pub fn into_inner(self) -> T {
+            self.resource
+        }
+
+        /// Explicitly close the resource, returning the error to the caller instead of the try
+        /// drop strategies. The resource will not be closed again when dropped.
+        pub fn close(&mut self) -> io::Result<()> {
+            if self.closed {
+                Ok(())
+            } else {
+                self.closed = true;
+                (self.close)(&mut self.resource)
+            }
+        }
+
+        /// Check whether or not the resource has been closed.
+        pub fn closed(&self) -> bool {
+            self.closed
+        }
+    }
+
+    impl<T, C> ImpureTryDrop for ClosableResource<T, C>
+    where
+        C: FnMut(&mut T) -> io::Result<()>,
+    {
+        type Error = io::Error;
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            self.close()
+        }
+    }
+
+    // SAFETY: the resource is only ever closed once.
+    unsafe impl<T, C> RepeatableTryDrop for ClosableResource<T, C> where
+        C: FnMut(&mut T) -> io::Result<()>
+    {
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::drop_strategies::{AdHocDropStrategy, AdHocFallibleDropStrategy};
+        use crate::PureTryDrop;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+        use std::string::{String, ToString};
+        use std::vec::Vec;
+
+        struct FailingWriter;
+
+        impl Write for FailingWriter {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("flush failed"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        fn collect_errors() -> Rc<RefCell<Vec<String>>> {
+            let errors = Rc::new(RefCell::new(Vec::new()));
+            let e = Rc::clone(&errors);
+            crate::install_thread_local_handlers(
+                AdHocFallibleDropStrategy::<_, crate::Error>(move |error| {
+                    e.borrow_mut().push(error.to_string());
+                    Ok(())
+                }),
+                AdHocDropStrategy(|_| panic!("the primary handler should not fail")),
+            );
+            errors
+        }
+
+        #[test]
+        fn test_flush_error_on_drop() {
+            let errors = collect_errors();
+            let mut writer = CheckOnDrop::new(BufWriter::new(FailingWriter)).adapt();
+            writer
+                .0
+                .get_mut()
+                .write_all(b"lost")
+                .expect("write should be buffered");
+            drop(writer);
+            assert_eq!(*errors.borrow(), ["flush failed"]);
+        }
+
+        #[test]
+        fn test_finish() {
+            let errors = collect_errors();
+            let mut writer = CheckOnDrop::new(BufWriter::new(FailingWriter));
+            writer
+                .get_mut()
+                .write_all(b"lost")
+                .expect("write should be buffered");
+            let error = writer.finish().expect_err("flushing should have failed");
+            assert_eq!(error.to_string(), "flush failed");
+            assert!(writer.finished());
+            drop(writer.adapt());
+            assert!(
+                errors.borrow().is_empty(),
+                "finished value was finalized again"
+            );
+        }
+
+        struct FailsToFlush;
+
+        impl Write for FailsToFlush {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Err(io::Error::other("disk full"))
+            }
+        }
+
+        #[test]
+        fn test_closable_resource() {
+            let errors = collect_errors();
+            drop(ClosableResource::new(FailsToFlush).adapt());
+            assert_eq!(*errors.borrow(), ["disk full"]);
+        }
+
+        #[test]
+        fn test_closable_resource_with_close() {
+            let errors = collect_errors();
+            let mut resource = ClosableResource::with_close(Vec::new(), |buffer: &mut Vec<u8>| {
+                buffer.clear();
+                Err(io::Error::other("failed to release buffer"))
+            });
+            resource.get_mut().extend_from_slice(b"data");
+            assert!(resource.close().is_err());
+            assert!(resource.closed());
+            assert!(resource.get_ref().is_empty());
+            drop(resource.adapt());
+            assert!(
+                errors.borrow().is_empty(),
+                "closed resource was closed again"
+            );
+        }
+
+        #[test]
+        fn test_into_inner() {
+            let errors = collect_errors();
+            let writer = CheckOnDrop::new(BufWriter::new(Vec::new()));
+            let mut writer = writer.into_inner();
+            writer.write_all(b"kept").expect("failed to write");
+            assert_eq!(writer.buffer(), b"kept");
+            assert!(errors.borrow().is_empty());
+        }
+    }
+}
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+pub use check_on_drop::{CheckOnDrop, ClosableResource, Finalize};
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+mod option_drop {
+    use crate::{ImpureTryDrop, RepeatableTryDrop};
+
+    /// An adapter which try drops the inner value only if there is one, doing nothing otherwise.
+    /// This saves destructors the `if let Some(..)` plumbing around optional try droppable values.
+    ///
+    /// This is only available for [`ImpureTryDrop`] values, as a [`None`] has no value to get the
+    /// try drop strategies from; the default primary and fallback handlers are used instead.
+    /// [`PureTryDrop`](crate::PureTryDrop) values which bring their own try drop strategies can't
+    /// be adapted by this, even if the strategies could be created out of nothing, since such
+    /// values may implement [`ImpureTryDrop`] as well. Use an `Option<DropAdapter<T>>` for them
+    /// instead, which already does nothing when it's [`None`].
+    #[cfg_attr(
+        feature = "derives",
+        derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+    )]
+    #[cfg_attr(feature = "shrinkwraprs", derive(Shrinkwrap))]
+    #[cfg_attr(feature = "shrinkwraprs", shrinkwrap(mutable))]
+    pub struct OptionDropAdapter<T: ImpureTryDrop>(pub Option<T>);
+
+    impl<T: ImpureTryDrop> OptionDropAdapter<T> {
+        /// Try drop the inner value, if there is one.
+        pub const fn new(value: Option<T>) -> Self {
+            Self(value)
+        }
+
+        /// Get a reference to the inner value. Unlike dereferencing, this is available regardless
+        /// of whether the `shrinkwraprs` feature is enabled.
+        #[cfg(not(feature = "shrinkwraprs"))]
+        pub fn inner(&self) -> &Option<T> {
+            &self.0
+        }
+
+        /// Get a reference to the inner value. Unlike dereferencing, this is available regardless
+        /// of whether the `shrinkwraprs` feature is enabled.
+        #[cfg(feature = "shrinkwraprs")]
+        pub fn inner(this: &Self) -> &Option<T> {
+            &this.0
+        }
+
+        /// Take the inner value.
+        #[cfg(not(feature = "shrinkwraprs"))]
+        pub fn into_inner(self) -> Option<T> {
+            self.0
+        }
+
+        /// Take the inner value.
+        #[cfg(feature = "shrinkwraprs")]
+        pub fn into_inner(this: Self) -> Option<T> {
+            this.0
+        }
+    }
+
+    impl<T: ImpureTryDrop> From<Option<T>> for OptionDropAdapter<T> {
+        fn from(value: Option<T>) -> Self {
+            Self(value)
+        }
+    }
+
+    impl<T: ImpureTryDrop> ImpureTryDrop for OptionDropAdapter<T> {
+        type Error = T::Error;
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            match &mut self.0 {
+                // SAFETY: the caller upholds the contract of `ImpureTryDrop::try_drop` for us.
+                Some(value) => unsafe { value.try_drop() },
+                None => Ok(()),
+            }
+        }
+    }
+
+    // SAFETY: skipping `None` doesn't change how many times `try_drop` can be called.
+    unsafe impl<T: ImpureTryDrop + RepeatableTryDrop> RepeatableTryDrop for OptionDropAdapter<T> {}
+
+    #[cfg(test)]
+    #[cfg(feature = "thread-local")]
+    mod tests {
+        use super::*;
+        use crate::drop_strategies::{AdHocDropStrategy, AdHocFallibleDropStrategy};
+        use crate::PureTryDrop;
+        use std::cell::{Cell, RefCell};
+        use std::rc::Rc;
+        use std::string::{String, ToString};
+        use std::vec::Vec;
+
+        struct Fails<'a>(&'a Cell<usize>);
+
+        impl ImpureTryDrop for Fails<'_> {
+            type Error = anyhow::Error;
+
+            unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+                self.0.set(self.0.get() + 1);
+                anyhow::bail!("this will always fail")
+            }
+        }
+
+        fn with_errors(f: impl FnOnce()) -> Vec<String> {
+            let errors = Rc::new(RefCell::new(Vec::new()));
+            let e = Rc::clone(&errors);
+            crate::with_thread_local_handlers(
+                AdHocFallibleDropStrategy::<_, crate::Error>(move |error| {
+                    e.borrow_mut().push(error.to_string());
+                    Ok(())
+                }),
+                AdHocDropStrategy(|_| panic!("the primary handler should not fail")),
+                f,
+            );
+            Rc::try_unwrap(errors).unwrap().into_inner()
+        }
+
+        #[test]
+        fn test_some() {
+            let times_dropped = Cell::new(0);
+            let errors =
+                with_errors(|| drop(OptionDropAdapter::new(Some(Fails(&times_dropped))).adapt()));
+            assert_eq!(times_dropped.get(), 1);
+            assert_eq!(errors, ["this will always fail"]);
+        }
+
+        #[test]
+        fn test_none() {
+            let errors = with_errors(|| drop(OptionDropAdapter::<Fails>::new(None).adapt()));
+            assert!(errors.is_empty(), "try drop was called on `None`");
+        }
+
+        #[test]
+        fn test_accessors() {
+            let times_dropped = Cell::new(0);
+            let adapter = OptionDropAdapter::new(Some(Fails(&times_dropped)));
+            assert!(OptionDropAdapter::inner(&adapter).is_some());
+            let value = OptionDropAdapter::into_inner(adapter);
+            assert!(value.is_some());
+            assert_eq!(times_dropped.get(), 0);
+        }
+    }
+}
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+pub use option_drop::OptionDropAdapter;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::NoOpDropStrategy;
+    use core::fmt;
+    use std::cell::{Cell, RefCell};
+    use std::format;
+    use std::rc::Rc;
+    use std::string::{String, ToString};
+    use std::vec::Vec;
+
+    struct CountsDrops {
+        times_dropped: Rc<Cell<usize>>,
+        fail: bool,
+    }
+
+    impl CountsDrops {
+        fn new(fail: bool) -> (Self, Rc<Cell<usize>>) {
+            let times_dropped = Rc::new(Cell::new(0));
+            let this = Self {
+                times_dropped: Rc::clone(&times_dropped),
+                fail,
+            };
+            (this, times_dropped)
+        }
+    }
+
+    impl PureTryDrop for CountsDrops {
+        type Error = anyhow::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = NoOpDropStrategy;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            self.times_dropped.set(self.times_dropped.get() + 1);
+
+            if self.fail {
+                anyhow::bail!("this will always fail")
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    unsafe impl RepeatableTryDrop for CountsDrops {}
+
+    /// Succeeds to drop once, then fails every time after that, which breaks the contract of
+    /// `RepeatableTryDrop`.
+    #[derive(Default)]
+    struct FailsAfterSuccess(bool);
+
+    impl PureTryDrop for FailsAfterSuccess {
+        type Error = anyhow::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = NoOpDropStrategy;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            if core::mem::replace(&mut self.0, true) {
+                anyhow::bail!("already dropped")
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    unsafe impl RepeatableTryDrop for FailsAfterSuccess {}
+
+    #[test]
+    fn test_verify_repeatable_adapter() {
+        use crate::verify::{RepeatableVerifier, ViolationKind};
+
+        for fail in [false, true] {
+            RepeatableVerifier::new(64)
+                .allow_panics(true)
+                .verify(|| RepeatableTryDropAdapter::new(CountsDrops::new(fail).0))
+                .unwrap_or_else(|violation| panic!("{violation}"));
+            RepeatableVerifier::new(64)
+                .verify(|| {
+                    RepeatableTryDropAdapter::with_panic_on_double_drop(
+                        RepeatableTryDropAdapter::new(CountsDrops::new(fail).0),
+                        false,
+                    )
+                })
+                .unwrap_or_else(|violation| panic!("{violation}"));
+        }
+
+        let violation = RepeatableVerifier::new(64)
+            .verify(|| RepeatableTryDropAdapter::new(CountsDrops::new(false).0))
+            .expect_err("a double drop panic wasn't reported");
+        assert_eq!(
+            violation.kind,
+            ViolationKind::Panicked(
+                "tried to drop object twice, this is an invalid operation".into()
+            )
+        );
+        assert_eq!(violation.call, 1);
+    }
+
+    #[test]
+    fn test_verify_repeatable_violation() {
+        use crate::verify::{verify_repeatable, RepeatableVerifier, ViolationKind};
+
+        let violation = RepeatableVerifier::new(64)
+            .seed(7)
+            .verify(|| FailsAfterSuccess(false))
+            .expect_err("the error after a success wasn't reported");
+        assert_eq!(violation.kind, ViolationKind::ErrorAfterSuccess);
+        assert_eq!(violation.call, 1);
+        assert_eq!(violation.seed, 7);
+        assert!(violation.to_string().starts_with(&format!(
+            "iteration {} (seed 0x7), call 1: ",
+            violation.iteration
+        )));
+
+        // the same seed finds the same violation again
+        let again = RepeatableVerifier::new(64)
+            .seed(7)
+            .verify(|| FailsAfterSuccess(false));
+        assert_eq!(again, Err(violation));
+
+        assert_eq!(
+            verify_repeatable::<FailsAfterSuccess>(64).map_err(|violation| violation.kind),
+            Err(ViolationKind::ErrorAfterSuccess)
+        );
+    }
+
+    #[test]
+    fn test_into_inner() {
+        let (value, times_dropped) = CountsDrops::new(false);
+        let inner = DropAdapter::into_inner(value.adapt());
+        assert_eq!(
+            times_dropped.get(),
+            0,
+            "try drop was called by `into_inner`"
+        );
+        drop(inner);
+        assert_eq!(
+            times_dropped.get(),
+            0,
+            "try drop was called after `into_inner`"
+        );
+    }
+
+    #[test]
+    fn test_try_into_inner() {
+        let (value, times_dropped) = CountsDrops::new(false);
+        let inner = DropAdapter::try_into_inner(value.adapt())
+            .unwrap_or_else(|_| panic!("try drop should not have failed"));
+        assert_eq!(times_dropped.get(), 1);
+        drop(inner);
+        assert_eq!(
+            times_dropped.get(),
+            1,
+            "try drop was called after `try_into_inner`"
+        );
+    }
+
+    #[test]
+    fn test_try_into_inner_error() {
+        let (value, times_dropped) = CountsDrops::new(true);
+        let (inner, error) = DropAdapter::try_into_inner(value.adapt())
+            .err()
+            .expect("try drop should have failed");
+        assert_eq!(error.to_string(), "this will always fail");
+        drop(inner);
+        assert_eq!(
+            times_dropped.get(),
+            1,
+            "try drop was called after `try_into_inner`"
+        );
+    }
+
+    #[test]
+    fn test_forget() {
+        let (value, times_dropped) = CountsDrops::new(true);
+        DropAdapter::forget(value.adapt());
+        assert_eq!(times_dropped.get(), 0, "try drop was called by `forget`");
+    }
+
+    #[test]
+    fn test_leak() {
+        let (value, times_dropped) = CountsDrops::new(true);
+        let leaked = DropAdapter::leak(RepeatableTryDropAdapter::new(value).adapt());
+        assert_eq!(times_dropped.get(), 0, "try drop was called by `leak`");
+
+        // the leaked value can still be finalized by hand later on
+        assert!(leaked.safe_try_drop().is_err());
+        assert_eq!(times_dropped.get(), 1);
+    }
+
+    #[test]
+    fn test_accessors() {
+        // these have to compile the same way regardless of whether `shrinkwraprs` is enabled.
+        let (value, times_dropped) = CountsDrops::new(false);
+        let adapter = RepeatableTryDropAdapter::new(value);
+        assert!(!RepeatableTryDropAdapter::inner(&adapter).fail);
+        let adapter = adapter.adapt();
+        assert!(!RepeatableTryDropAdapter::inner(DropAdapter::inner(&adapter)).fail);
+        let value = RepeatableTryDropAdapter::into_inner(DropAdapter::into_inner(adapter));
+        drop(value);
+        assert_eq!(times_dropped.get(), 0);
+
+        let adapter = AssertInfallibleTryDropStrategyAdapter::new(NoOpDropStrategy);
+        let NoOpDropStrategy = *AssertInfallibleTryDropStrategyAdapter::inner(&adapter);
+        let NoOpDropStrategy = AssertInfallibleTryDropStrategyAdapter::into_inner(adapter);
+
+        // SAFETY: `NoOpDropStrategy` never fails.
+        let adapter = unsafe { AssumeInfallibleAdapter::new(NoOpDropStrategy) };
+        let NoOpDropStrategy = *AssumeInfallibleAdapter::inner(&adapter);
+        let NoOpDropStrategy = AssumeInfallibleAdapter::into_inner(adapter);
+    }
+
+    #[test]
+    fn test_sizes() {
+        use core::mem::size_of;
+
+        assert_eq!(
+            size_of::<DropAdapter<CountsDrops>>(),
+            size_of::<CountsDrops>()
+        );
+        assert_eq!(
+            size_of::<WithStrategies<CountsDrops, NoOpDropStrategy, NoOpDropStrategy>>(),
+            size_of::<CountsDrops>()
+        );
+
+        // the flags of the repeatable adapter are packed into a single byte.
+        assert_eq!(size_of::<RepeatableFlags>(), 1);
+    }
+
+    #[test]
+    fn test_disarm() {
+        let (value, times_dropped) = CountsDrops::new(false);
+        let mut adapter = RepeatableTryDropAdapter::new(value);
+
+        #[cfg(feature = "shrinkwraprs")]
+        RepeatableTryDropAdapter::disarm(&mut adapter);
+
+        #[cfg(not(feature = "shrinkwraprs"))]
+        adapter.disarm();
+
+        drop(adapter.adapt());
+        assert_eq!(times_dropped.get(), 0, "try drop was called after `disarm`");
+    }
+
+    #[test]
+    fn test_reset() {
+        let (value, times_dropped) = CountsDrops::new(false);
+        let mut adapter = RepeatableTryDropAdapter::new(value);
+        adapter.safe_try_drop().unwrap();
+
+        // SAFETY: `CountsDrops` can be dropped any number of times.
+        #[cfg(feature = "shrinkwraprs")]
+        unsafe {
+            RepeatableTryDropAdapter::reset(&mut adapter)
+        };
+
+        // SAFETY: see above.
+        #[cfg(not(feature = "shrinkwraprs"))]
+        unsafe {
+            adapter.reset()
+        };
+
+        adapter.safe_try_drop().unwrap();
+        assert_eq!(
+            times_dropped.get(),
+            2,
+            "try drop didn't run again after `reset`"
+        );
+    }
+
+    #[derive(Debug)]
+    struct RecordsErrors(Rc<RefCell<Vec<String>>>);
+
+    impl TryDropStrategy for RecordsErrors {
+        fn handle_error(&self, error: anyhow::Error) {
+            self.0.borrow_mut().push(format!("{error:#}"))
+        }
+    }
+
+    #[derive(Debug)]
+    struct FailsWithRecords(RecordsErrors);
+
+    impl PureTryDrop for FailsWithRecords {
+        type Error = anyhow::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = RecordsErrors;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &self.0
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            anyhow::bail!("connection reset")
+        }
+    }
+
+    #[test]
+    fn test_map_err() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let value = FailsWithRecords(RecordsErrors(Rc::clone(&errors)));
+        drop(
+            value.adapt_map_err(|error| error.context("failed while closing database connection")),
+        );
+        assert_eq!(
+            *errors.borrow(),
+            ["failed while closing database connection: connection reset"]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "derives")]
+    fn test_map_err_debug() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let value = FailsWithRecords(RecordsErrors(Rc::clone(&errors)));
+        let adapter = value.adapt_map_err(|error| error.context("closing"));
+        assert_eq!(
+            format!("{adapter:?}"),
+            "MapErrDropAdapter { inner: FailsWithRecords(RecordsErrors(RefCell { value: [] })), .. }"
+        );
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        use crate::test_utils::FallibleDropStrategy;
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let handler = FallbackTryDropStrategyHandler::new(
+            RecordsErrors(Rc::clone(&errors)),
+            FallibleDropStrategy,
+        );
+        handler.handle_errors(["first", "second", "third"].map(|error| anyhow::anyhow!(error)));
+        assert_eq!(*errors.borrow(), ["first", "second", "third"]);
+
+        errors.borrow_mut().clear();
+        let handler = FallbackTryDropStrategyHandler::new(
+            RecordsErrors(Rc::clone(&errors)),
+            NoOpDropStrategy,
+        );
+        handler.handle_errors(["first", "second", "third"].map(|error| anyhow::anyhow!(error)));
+        assert!(errors.borrow().is_empty());
+    }
+
+    /// Records the errors it's handed under its name, then fails with its own error.
+    struct FailsAs(&'static str, Rc<RefCell<Vec<String>>>);
+
+    impl FallibleTryDropStrategy for FailsAs {
+        type Error = anyhow::Error;
+
+        fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+            self.1.borrow_mut().push(format!("{}: {error}", self.0));
+            Err(anyhow::anyhow!("{} failed", self.0))
+        }
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let chain = crate::fallback_chain![
+            FailsAs("a", Rc::clone(&errors)),
+            FailsAs("b", Rc::clone(&errors)),
+            RecordsErrors(Rc::clone(&errors)),
+        ];
+        chain.handle_error(anyhow::anyhow!("drop failed"));
+        assert_eq!(
+            *errors.borrow(),
+            ["a: drop failed", "b: a failed", "b failed"],
+            "the errors didn't cascade in order"
+        );
+
+        errors.borrow_mut().clear();
+        let chain = crate::fallback_chain![NoOpDropStrategy, RecordsErrors(Rc::clone(&errors))];
+        chain.handle_error(anyhow::anyhow!("drop failed"));
+        assert!(errors.borrow().is_empty());
+    }
+
+    struct Connection {
+        addr: &'static str,
+        state: &'static str,
+        records: RecordsErrors,
+    }
+
+    impl fmt::Debug for Connection {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.debug_struct("Connection")
+                .field("addr", &self.addr)
+                .field("state", &self.state)
+                .finish_non_exhaustive()
+        }
+    }
+
+    impl PureTryDrop for Connection {
+        type Error = anyhow::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = RecordsErrors;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &self.records
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            self.state = "HalfClosed";
+            anyhow::bail!("connection reset")
+        }
+    }
+
+    #[test]
+    fn test_debug_snapshot() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let connection = Connection {
+            addr: "127.0.0.1:8080",
+            state: "Open",
+            records: RecordsErrors(Rc::clone(&errors)),
+        };
+        drop(connection.adapt_with_debug_snapshot());
+        assert_eq!(
+            *errors.borrow(),
+            [
+                r#"failed to drop Connection { addr: "127.0.0.1:8080", state: "HalfClosed", .. }: connection reset"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_debug_snapshot_limit() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let connection = Connection {
+            addr: "127.0.0.1:8080",
+            state: "Open",
+            records: RecordsErrors(Rc::clone(&errors)),
+        };
+        drop(connection.adapt_with_debug_snapshot().limit(23));
+        assert_eq!(
+            *errors.borrow(),
+            [r#"failed to drop Connection { addr: "127...: connection reset"#]
+        );
+
+        assert_eq!(debug_snapshot(&"ééé", 4), "\"é...");
+        assert_eq!(debug_snapshot(&"ééé", 8), "\"ééé\"");
+        assert_eq!(debug_snapshot(&"ééé", 0), "...");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serializable_error() {
+        let error = anyhow::anyhow!("connection reset")
+            .context("failed to flush the buffer")
+            .context("failed to close the connection");
+        let expected = serde_json::json!({
+            "message": "failed to close the connection",
+            "chain": ["failed to flush the buffer", "connection reset"],
+        });
+        assert_eq!(
+            serde_json::to_value(SerializableError(&error)).unwrap(),
+            expected
+        );
+
+        let error = ArcError::new(error);
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap(),
+            expected
+        );
+        assert_eq!(
+            serde_json::to_value(ArcError::new(anyhow::anyhow!("no causes"))).unwrap(),
+            serde_json::json!({ "message": "no causes", "chain": [] })
+        );
+    }
+
+    #[cfg(feature = "ds-collect")]
+    struct PanicsOnDrop<'a> {
+        strategy: TryDropStrategyRef<'a, crate::drop_strategies::CollectDropStrategy>,
+        panic: bool,
+    }
+
+    #[cfg(feature = "ds-collect")]
+    impl<'a> PureTryDrop for PanicsOnDrop<'a> {
+        type Error = std::io::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = TryDropStrategyRef<'a, crate::drop_strategies::CollectDropStrategy>;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &self.strategy
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            if self.panic {
+                panic!("the socket was already closed")
+            } else {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+        }
+    }
+
+    #[cfg(feature = "ds-collect")]
+    #[test]
+    fn test_adapt_catching() {
+        use crate::drop_strategies::CollectDropStrategy;
+
+        let strategy = CollectDropStrategy::new();
+        drop(
+            PanicsOnDrop {
+                strategy: TryDropStrategyRef(&strategy),
+                panic: true,
+            }
+            .adapt_catching(),
+        );
+        let errors = strategy.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "destructor panicked: the socket was already closed"
+        );
+    }
+
+    #[cfg(feature = "ds-collect")]
+    #[test]
+    fn test_adapt_catching_without_panic() {
+        use crate::drop_strategies::CollectDropStrategy;
+
+        let strategy = CollectDropStrategy::new();
+        let mut adapter = PanicToErrorAdapter::new(PanicsOnDrop {
+            strategy: TryDropStrategyRef(&strategy),
+            panic: false,
+        });
+
+        // SAFETY: `PanicsOnDrop` doesn't free anything when dropped.
+        match unsafe { adapter.try_drop() } {
+            Err(PanicOrError::Error(error)) => {
+                assert_eq!(error.kind(), std::io::ErrorKind::BrokenPipe)
+            }
+            Err(PanicOrError::Panicked(error)) => panic!("the destructor didn't panic: {error}"),
+            Ok(()) => panic!("the destructor should have failed"),
+        }
+    }
+
+    #[test]
+    fn test_infallible_to_fallible_into_inner() {
+        let adapter =
+            InfallibleToFallibleTryDropStrategyAdapter::<_, anyhow::Error>::new(NoOpDropStrategy);
+        adapter
+            .try_handle_error(anyhow::anyhow!("this will always fail"))
+            .unwrap();
+        let NoOpDropStrategy = InfallibleToFallibleTryDropStrategyAdapter::into_inner(adapter);
+    }
+
+    #[cfg(feature = "thread-local")]
+    #[test]
+    fn test_with_strategies() {
+        use crate::drop_strategies::{AdHocDropStrategy, PanicDropStrategy};
+        use crate::test_utils::{ErrorsOnDrop, Fallible};
+
+        // the handlers panic, so the test fails if the error is routed to them.
+        crate::install_thread_local_handlers(
+            PanicDropStrategy::DEFAULT,
+            PanicDropStrategy::DEFAULT,
+        );
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let e = Rc::clone(&errors);
+        let value = ErrorsOnDrop::<Fallible, _>::not_given().with_strategies(
+            AdHocDropStrategy(move |error| e.borrow_mut().push(error.to_string())),
+            PanicDropStrategy::DEFAULT,
+        );
+        drop(value.adapt());
+        crate::uninstall_for_thread();
+
+        assert_eq!(*errors.borrow(), ["this will always fail"]);
+    }
+
+    #[test]
+    fn test_or_else() {
+        use crate::test_utils::FallibleDropStrategy;
+        use crate::FallibleTryDropStrategyExt;
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let fallback = Rc::new(RefCell::new(Vec::new()));
+
+        RecordsErrors(Rc::clone(&errors))
+            .or_else(RecordsErrors(Rc::clone(&fallback)))
+            .handle_error(anyhow::anyhow!("handled"));
+        assert_eq!(*errors.borrow(), ["handled"]);
+        assert!(fallback.borrow().is_empty());
+
+        FallibleDropStrategy
+            .or_else(RecordsErrors(Rc::clone(&fallback)))
+            .handle_error(anyhow::anyhow!("failed"));
+        assert_eq!(*fallback.borrow(), ["failed"]);
+    }
+
+    #[test]
+    fn test_and_then() {
+        use crate::test_utils::FallibleDropStrategy;
+        use crate::FallibleTryDropStrategyExt;
+
+        let first = Rc::new(RefCell::new(Vec::new()));
+        let second = Rc::new(RefCell::new(Vec::new()));
+
+        RecordsErrors(Rc::clone(&first))
+            .and_then(RecordsErrors(Rc::clone(&second)))
+            .try_handle_error(anyhow::anyhow!("inner").context("outer"))
+            .unwrap();
+        assert_eq!(*first.borrow(), ["outer: inner"]);
+        assert_eq!(*second.borrow(), ["outer: inner"]);
+
+        let error = FallibleDropStrategy
+            .and_then(RecordsErrors(Rc::clone(&second)))
+            .try_handle_error(anyhow::anyhow!("inner").context("failed"))
+            .unwrap_err();
+        assert_eq!(format!("{error:#}"), "failed: inner");
+        assert_eq!(
+            *second.borrow(),
+            ["outer: inner"],
+            "the second strategy shouldn't run if the first one failed"
+        );
+
+        let fallback = Rc::new(RefCell::new(Vec::new()));
+        NoOpDropStrategy
+            .and_then(FallibleDropStrategy)
+            .or_else(RecordsErrors(Rc::clone(&fallback)))
+            .handle_error(anyhow::anyhow!("chained"));
+        assert_eq!(*fallback.borrow(), ["chained"]);
+    }
 }
@@ -0,0 +1,100 @@
+//! Utilities for dropping many values which implement [`PureTryDrop`] at once.
+
+use crate::PureTryDrop;
+use std::vec::Vec;
+
+/// Execute the fallible destructor of every value, collecting the errors instead of handing them
+/// to the try drop strategies. This is useful when tearing down a pool of resources, where a
+/// failure to drop one of them shouldn't stop the rest from being dropped.
+///
+/// # Errors
+/// If any of the values fail to drop, every error is returned, in the same order as the values.
+pub fn try_drop_all<I, T>(iter: I) -> Result<(), Vec<T::Error>>
+where
+    I: IntoIterator<Item = T>,
+    T: PureTryDrop,
+{
+    let errors: Vec<_> = iter
+        .into_iter()
+        .filter_map(|mut value| {
+            // SAFETY: the value was moved into this scope without being adapted, so nothing else
+            // calls its `try_drop` function; it's executed once, then the value is dropped, just
+            // like `DropAdapter` does.
+            unsafe { value.try_drop() }.err()
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::NoOpDropStrategy;
+    use crate::test_utils::{ErrorsOnDrop, Fallible, Given, Infallible};
+    use std::string::ToString;
+    use std::vec;
+
+    type Strategies = Given<NoOpDropStrategy, NoOpDropStrategy>;
+
+    enum Mixed {
+        Fails(ErrorsOnDrop<Fallible, Strategies>),
+        Succeeds(ErrorsOnDrop<Infallible, Strategies>),
+    }
+
+    impl Mixed {
+        fn fails() -> Self {
+            Self::Fails(ErrorsOnDrop::given(NoOpDropStrategy, NoOpDropStrategy))
+        }
+
+        fn succeeds() -> Self {
+            Self::Succeeds(ErrorsOnDrop::given(NoOpDropStrategy, NoOpDropStrategy))
+        }
+    }
+
+    impl PureTryDrop for Mixed {
+        type Error = crate::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = NoOpDropStrategy;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            match self {
+                Self::Fails(value) => value.try_drop(),
+                Self::Succeeds(value) => value.try_drop().map_err(Into::into),
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_drop_all() {
+        let values = vec![
+            Mixed::succeeds(),
+            Mixed::fails(),
+            Mixed::succeeds(),
+            Mixed::fails(),
+        ];
+        let errors = try_drop_all(values).expect_err("no value failed to drop");
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|error| error.to_string() == "this will always fail"));
+    }
+
+    #[test]
+    fn test_try_drop_all_succeeds() {
+        assert!(try_drop_all(vec![Mixed::succeeds(), Mixed::succeeds()]).is_ok());
+        assert!(try_drop_all(Vec::<Mixed>::new()).is_ok());
+    }
+}
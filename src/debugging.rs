@@ -0,0 +1,318 @@
+//! Tools for debugging types which implement [`TryDrop`](crate::TryDrop).
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::boxed::Box;
+
+/// Counts how many times [`TryDrop::try_drop`](crate::TryDrop::try_drop) has been executed, even
+/// across implicit copies of the value it's stored in.
+///
+/// Store this in your type, then call [`ExecutionTracker::record`] inside of its `try_drop`
+/// function. Since this type implements [`Copy`] and all copies share the same counter, it can be
+/// embedded in types which implement [`Copy`] themselves, in order to detect the `try_drop`
+/// function being executed more than once on copies of the same value.
+#[cfg_attr(feature = "derives", derive(Debug))]
+#[derive(Copy, Clone)]
+pub struct ExecutionTracker {
+    executions: &'static AtomicUsize,
+}
+
+impl ExecutionTracker {
+    /// Create a new execution tracker. This leaks a small allocation, so it's intended to be used
+    /// in tests.
+    pub fn new() -> Self {
+        Self::from_static(Box::leak(Box::new(AtomicUsize::new(0))))
+    }
+
+    /// Create a new execution tracker which counts with the given counter.
+    pub const fn from_static(executions: &'static AtomicUsize) -> Self {
+        Self { executions }
+    }
+
+    /// Record an execution of the `try_drop` function.
+    pub fn record(&self) {
+        self.executions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get how many times the `try_drop` function was executed.
+    pub fn executions(&self) -> usize {
+        self.executions.load(Ordering::Relaxed)
+    }
+
+    /// Check whether or not the `try_drop` function was executed more than once.
+    pub fn repeated(&self) -> bool {
+        self.executions() > 1
+    }
+
+    /// Assert that the `try_drop` function was executed exactly once.
+    ///
+    /// # Panics
+    /// If the `try_drop` function was executed any other amount of times.
+    #[track_caller]
+    pub fn assert_executed_once(&self) {
+        let executions = self.executions();
+        assert_eq!(
+            executions, 1,
+            "expected try drop to be executed once, but it was executed {executions} times"
+        );
+    }
+}
+
+impl Default for ExecutionTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+mod drop_order {
+    use crate::ImpureTryDrop;
+    use core::cell::RefCell;
+    use std::thread_local;
+    use std::vec::Vec;
+
+    thread_local! {
+        static DROP_ORDER_LOG: RefCell<Vec<DropRecord>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// A drop of a [`FieldOrderProbe`], as recorded in the [`drop_order_log`].
+    #[cfg_attr(
+        feature = "derives",
+        derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash)
+    )]
+    #[derive(Copy, Clone)]
+    pub struct DropRecord {
+        /// The name of the probe which was dropped.
+        pub name: &'static str,
+
+        /// Whether or not the probe failed to drop.
+        pub failed: bool,
+
+        /// How many probes failed to drop on this thread before this one, since the log was last
+        /// taken.
+        pub failures_before: usize,
+    }
+
+    /// Records when it's try dropped, and whether it failed, into a thread local log which can be read
+    /// with [`drop_order_log`]. This is useful to find out in which order the fields of a struct are
+    /// dropped, and whether an earlier field failing to drop affects the later ones.
+    ///
+    /// Embed the probes as adapted fields of your struct, e.g. `DropAdapter<FieldOrderProbe>`, while
+    /// debugging, then check the order with [`assert_drop_order!`](crate::assert_drop_order).
+    #[cfg_attr(
+        feature = "derives",
+        derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+    )]
+    pub struct FieldOrderProbe {
+        name: &'static str,
+        fail: bool,
+    }
+
+    impl FieldOrderProbe {
+        /// Create a new probe with the given name which drops successfully.
+        pub const fn new(name: &'static str) -> Self {
+            Self { name, fail: false }
+        }
+
+        /// Create a new probe with the given name which always fails to drop.
+        pub const fn failing(name: &'static str) -> Self {
+            Self { name, fail: true }
+        }
+
+        /// Get the name of this probe.
+        pub const fn name(&self) -> &'static str {
+            self.name
+        }
+    }
+
+    impl ImpureTryDrop for FieldOrderProbe {
+        type Error = crate::Error;
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            DROP_ORDER_LOG.with(|log| {
+                let mut log = log.borrow_mut();
+                let failures_before = log.iter().filter(|record| record.failed).count();
+                log.push(DropRecord {
+                    name: self.name,
+                    failed: self.fail,
+                    failures_before,
+                })
+            });
+
+            if self.fail {
+                Err(anyhow::anyhow!("probe `{}` failed to drop", self.name))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Get the drops of the [`FieldOrderProbe`]s on this thread, in the order they happened, since the
+    /// log was last taken.
+    pub fn drop_order_log() -> Vec<DropRecord> {
+        DROP_ORDER_LOG.with(|log| log.borrow().clone())
+    }
+
+    /// Take the drops of the [`FieldOrderProbe`]s on this thread out of the log, in the order they
+    /// happened, leaving it empty.
+    pub fn take_drop_order_log() -> Vec<DropRecord> {
+        DROP_ORDER_LOG.with(|log| core::mem::take(&mut *log.borrow_mut()))
+    }
+
+    /// Assert that the [`FieldOrderProbe`]s with the given names were dropped on this thread in the
+    /// given order, taking them out of the log. Usually called through
+    /// [`assert_drop_order!`](crate::assert_drop_order).
+    ///
+    /// # Panics
+    /// If the probes were dropped in any other order.
+    #[track_caller]
+    pub fn assert_drop_order(expected: &[&str]) {
+        let log = take_drop_order_log();
+        let names: Vec<_> = log.iter().map(|record| record.name).collect();
+        assert_eq!(
+            names, expected,
+            "the probes were dropped in an unexpected order"
+        );
+    }
+
+    #[cfg(test)]
+    #[cfg(feature = "thread-local")]
+    mod tests {
+        use super::*;
+        use crate::adapters::DropAdapter;
+        use crate::drop_strategies::{NoOpDropStrategy, PanicDropStrategy};
+        use crate::PureTryDrop;
+
+        // the fields are only ever dropped
+        #[allow(dead_code)]
+        struct Probed {
+            a: DropAdapter<FieldOrderProbe>,
+            b: DropAdapter<FieldOrderProbe>,
+            c: DropAdapter<FieldOrderProbe>,
+        }
+
+        impl Probed {
+            fn new(b: FieldOrderProbe) -> Self {
+                Self {
+                    a: FieldOrderProbe::new("a").adapt(),
+                    b: b.adapt(),
+                    c: FieldOrderProbe::new("c").adapt(),
+                }
+            }
+        }
+
+        #[test]
+        fn test_declaration_order() {
+            crate::with_thread_local_handlers(
+                PanicDropStrategy::DEFAULT,
+                PanicDropStrategy::DEFAULT,
+                || drop(Probed::new(FieldOrderProbe::new("b"))),
+            );
+            crate::assert_drop_order!(["a", "b", "c"]);
+            assert!(drop_order_log().is_empty(), "the log wasn't taken");
+        }
+
+        #[test]
+        fn test_declaration_order_with_failure() {
+            let probed = Probed::new(FieldOrderProbe::failing("b"));
+            assert_eq!(probed.b.0.name(), "b");
+            crate::with_thread_local_handlers(NoOpDropStrategy, NoOpDropStrategy, || drop(probed));
+
+            let log = drop_order_log();
+            assert_eq!(
+                log.iter().map(|record| record.failed).collect::<Vec<_>>(),
+                [false, true, false]
+            );
+            assert_eq!(log[2].failures_before, 1);
+            crate::assert_drop_order!(["a", "b", "c"]);
+        }
+
+        #[test]
+        #[should_panic(expected = "the probes were dropped in an unexpected order")]
+        fn test_assert_drop_order_panics() {
+            crate::with_thread_local_handlers(
+                PanicDropStrategy::DEFAULT,
+                PanicDropStrategy::DEFAULT,
+                || drop(Probed::new(FieldOrderProbe::new("b"))),
+            );
+            crate::assert_drop_order!(["c", "b", "a"]);
+        }
+    }
+}
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+pub use drop_order::{
+    assert_drop_order, drop_order_log, take_drop_order_log, DropRecord, FieldOrderProbe,
+};
+
+/// Assert that the [`FieldOrderProbe`](crate::debugging::FieldOrderProbe)s with the given names
+/// were dropped on this thread in the given order. See
+/// [`debugging::assert_drop_order`](crate::debugging::assert_drop_order).
+#[cfg(any(feature = "global", feature = "thread-local"))]
+#[macro_export]
+macro_rules! assert_drop_order {
+    ([$($name:expr),* $(,)?]) => {
+        $crate::debugging::assert_drop_order(&[$($name),*])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::NoOpDropStrategy;
+    use crate::PureTryDrop;
+
+    #[derive(Copy, Clone)]
+    struct Tracked(ExecutionTracker);
+
+    impl PureTryDrop for Tracked {
+        type Error = crate::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = NoOpDropStrategy;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            self.0.record();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_detects_copy_out_of_drop_adapter() {
+        let tracker = ExecutionTracker::new();
+        let adapter = Tracked(tracker).adapt();
+        let copied = adapter.0;
+        drop(adapter);
+        drop(copied.adapt());
+        assert!(tracker.repeated(), "try drop was not executed twice");
+        assert_eq!(tracker.executions(), 2);
+    }
+
+    #[test]
+    fn test_sealed_drop_adapter_executes_once() {
+        let tracker = ExecutionTracker::new();
+        drop(Tracked(tracker).adapt_sealed());
+        tracker.assert_executed_once();
+    }
+
+    #[test]
+    fn test_sealed_drop_adapter_into_inner() {
+        let tracker = ExecutionTracker::new();
+        let inner = Tracked(tracker).adapt_sealed().into_inner();
+        assert_eq!(tracker.executions(), 0);
+        drop(inner.adapt_sealed());
+        tracker.assert_executed_once();
+    }
+
+    #[test]
+    #[should_panic(expected = "expected try drop to be executed once, but it was executed 0 times")]
+    fn test_assert_executed_once_panics() {
+        ExecutionTracker::new().assert_executed_once()
+    }
+}
@@ -0,0 +1,139 @@
+//! Find out more about the drop error which is currently being handled, such as the type of the
+//! value which failed to drop, for strategies which build rich reports out of drop errors.
+
+use crate::tier::{self, HandlingTier};
+use core::cell::RefCell;
+use std::error::Error as StdError;
+use std::io;
+use std::thread_local;
+use std::vec::Vec;
+
+/// What kind of error a drop error is, found by looking through its chain of sources.
+#[cfg_attr(feature = "derives", derive(Hash))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// An I/O error of the given kind, e.g. failing to flush or close a file.
+    Io(io::ErrorKind),
+
+    /// A handler wasn't initialized, so the error couldn't be handled by it.
+    #[cfg(any(feature = "global", feature = "thread-local"))]
+    Uninitialized,
+
+    /// Any other error.
+    Other,
+}
+
+impl ErrorCategory {
+    /// Find the category of the given error. The first error in the chain which has a category
+    /// other than [`ErrorCategory::Other`] wins.
+    pub fn of(error: &(dyn StdError + 'static)) -> Self {
+        let mut source = Some(error);
+
+        while let Some(error) = source {
+            if let Some(error) = error.downcast_ref::<io::Error>() {
+                return Self::Io(error.kind());
+            }
+
+            #[cfg(any(feature = "global", feature = "thread-local"))]
+            if error.is::<crate::handlers::UninitializedError>() {
+                return Self::Uninitialized;
+            }
+
+            source = error.source();
+        }
+
+        Self::Other
+    }
+}
+
+/// Everything known about the drop error which is currently being handled.
+#[cfg_attr(feature = "derives", derive(Hash))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct DropContext {
+    /// The type name of the value which failed to drop. This is `None` if the error isn't being
+    /// handled through one of the adapters of this crate, e.g. if a try drop strategy was called
+    /// directly.
+    pub type_name: Option<&'static str>,
+
+    /// The tier of error handling the error is going through. See [`tier::current_tier`].
+    pub tier: HandlingTier,
+
+    /// What kind of error it is.
+    pub category: ErrorCategory,
+}
+
+impl DropContext {
+    /// Get the context of the given drop error, which should be the one being handled right now.
+    pub fn of(error: &anyhow::Error) -> Self {
+        Self {
+            type_name: current_type_name(),
+            tier: tier::current_tier(error),
+            category: ErrorCategory::of(error.as_ref()),
+        }
+    }
+}
+
+thread_local! {
+    static TYPE_NAMES: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+}
+
+fn current_type_name() -> Option<&'static str> {
+    TYPE_NAMES
+        .try_with(|type_names| type_names.borrow().last().copied())
+        .ok()
+        .flatten()
+}
+
+/// Marks the drop error of a value as being handled until this is dropped.
+pub(crate) struct DroppingGuard(());
+
+impl Drop for DroppingGuard {
+    fn drop(&mut self) {
+        let _ = TYPE_NAMES.try_with(|type_names| type_names.borrow_mut().pop());
+    }
+}
+
+/// Mark the drop error of a value of type `T` as being handled, for as long as the returned guard
+/// lives. Drop errors which occur while it's being handled are marked on top of it.
+pub(crate) fn enter<T: ?Sized>() -> DroppingGuard {
+    let _ =
+        TYPE_NAMES.try_with(|type_names| type_names.borrow_mut().push(core::any::type_name::<T>()));
+    DroppingGuard(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_error_category() {
+        let error = anyhow::Error::new(io::Error::from(io::ErrorKind::BrokenPipe))
+            .context("failed to flush");
+        assert_eq!(
+            ErrorCategory::of(error.as_ref()),
+            ErrorCategory::Io(io::ErrorKind::BrokenPipe)
+        );
+
+        let error = Err::<(), _>(io::Error::from(io::ErrorKind::NotFound))
+            .context("failed to delete")
+            .unwrap_err();
+        assert_eq!(
+            ErrorCategory::of(error.as_ref()),
+            ErrorCategory::Io(io::ErrorKind::NotFound)
+        );
+
+        let error = anyhow::anyhow!("this will always fail");
+        assert_eq!(ErrorCategory::of(error.as_ref()), ErrorCategory::Other);
+    }
+
+    #[test]
+    fn test_drop_context_outside_of_adapter() {
+        let error = anyhow::anyhow!("this will always fail");
+        let context = DropContext::of(&error);
+        assert_eq!(context.type_name, None);
+        assert_eq!(context.tier, HandlingTier::Primary);
+    }
+}
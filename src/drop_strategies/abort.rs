@@ -1,4 +1,6 @@
-use crate::TryDropStrategy;
+//! Types and traits for the abort drop strategy.
+
+use crate::{InfallibleStrategy, TryDropStrategy};
 use std::process;
 
 /// A drop strategy that aborts the program if the drop fails.
@@ -14,5 +16,8 @@ impl TryDropStrategy for AbortDropStrategy {
     }
 }
 
+// SAFETY: aborting the program never fails.
+unsafe impl InfallibleStrategy for AbortDropStrategy {}
+
 // it is not possible to create tests for this strategy because this aborts the program, which
 // can't be caught.
\ No newline at end of file
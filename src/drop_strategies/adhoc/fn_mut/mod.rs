@@ -119,15 +119,12 @@ mod tests {
     use std::sync::atomic::AtomicBool;
     use crate::drop_strategies::PanicDropStrategy;
     use crate::{LOAD_ORDERING, STORE_ORDERING};
-    use crate::test_utils::fallible;
+    use crate::test_utils::{fallible, GLOBAL_LOCK};
     use super::*;
 
-    // we need this lock otherwise the test results will be inconsistent
-    static LOCK: Mutex<()> = parking_lot::const_mutex(());
-
     #[test]
     fn test_adhoc_mut_drop_strategy() {
-        let _lock = LOCK.lock();
+        let _lock = GLOBAL_LOCK.lock();
         let works = Arc::new(AtomicBool::new(false));
         let w = Arc::clone(&works);
         let strategy = AdHocMutDropStrategy::new(move |_| w.store(true, STORE_ORDERING));
@@ -138,7 +135,7 @@ mod tests {
 
     #[test]
     fn test_into_adhoc_mut_drop_strategy() {
-        let _lock = LOCK.lock();
+        let _lock = GLOBAL_LOCK.lock();
         let works = Arc::new(AtomicBool::new(false));
         let w = Arc::clone(&works);
         let strategy = move |_| w.store(true, STORE_ORDERING);
@@ -150,7 +147,7 @@ mod tests {
 
     #[test]
     fn test_adhoc_mut_fallible_drop_strategy() {
-        let _lock = LOCK.lock();
+        let _lock = GLOBAL_LOCK.lock();
         let works = Arc::new(AtomicBool::new(false));
         let w = Arc::clone(&works);
         let strategy = AdHocMutFallibleDropStrategy::<_, crate::Error>::new(move |_| {
@@ -164,7 +161,7 @@ mod tests {
 
     #[test]
     fn test_into_adhoc_mut_fallible_drop_strategy() {
-        let _lock = LOCK.lock();
+        let _lock = GLOBAL_LOCK.lock();
         let works = Arc::new(AtomicBool::new(false));
         let w = Arc::clone(&works);
         let strategy = move |_| {
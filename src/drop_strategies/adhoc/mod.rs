@@ -1,3 +1,5 @@
+//! Types and traits for the ad-hoc drop strategies, which use functions to handle errors.
+
 #[cfg(feature = "ds-adhoc-mut")]
 mod fn_mut;
 
@@ -21,6 +23,34 @@ impl<F: Fn(crate::Error)> AdHocDropStrategy<F> {
     pub fn take(this: Self) -> F {
         this.0
     }
+
+    /// Get a reference to the inner function. Unlike dereferencing, this is available regardless
+    /// of whether the `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn inner(&self) -> &F {
+        &self.0
+    }
+
+    /// Get a reference to the inner function. Unlike dereferencing, this is available regardless
+    /// of whether the `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn inner(this: &Self) -> &F {
+        &this.0
+    }
+
+    /// Take the inner function. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+
+    /// Take the inner function. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn into_inner(this: Self) -> F {
+        this.0
+    }
 }
 
 impl<F: Fn(crate::Error)> TryDropStrategy for AdHocDropStrategy<F> {
@@ -67,6 +97,55 @@ where
     pub fn take(this: Self) -> F {
         this.0
     }
+
+    /// Get a reference to the inner function. Unlike dereferencing, this is available regardless
+    /// of whether the `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn inner(&self) -> &F {
+        &self.0
+    }
+
+    /// Get a reference to the inner function. Unlike dereferencing, this is available regardless
+    /// of whether the `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn inner(this: &Self) -> &F {
+        &this.0
+    }
+
+    /// Take the inner function. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(not(feature = "shrinkwraprs"))]
+    pub fn into_inner(self) -> F {
+        self.0
+    }
+
+    /// Take the inner function. Unlike `take`, this is available regardless of whether the
+    /// `shrinkwraprs` feature is enabled.
+    #[cfg(feature = "shrinkwraprs")]
+    pub fn into_inner(this: Self) -> F {
+        this.0
+    }
+}
+
+impl<F> AdHocFallibleDropStrategy<F, anyhow::Error>
+where
+    F: Fn(crate::Error) -> anyhow::Result<()>,
+{
+    /// Create a new fallible drop strategy from a function which returns an [`anyhow::Result`].
+    /// Unlike the constructor, the error type doesn't have to be annotated.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use try_drop::drop_strategies::AdHocFallibleDropStrategy;
+    ///
+    /// let strategy = AdHocFallibleDropStrategy::from_result_fn(|error| {
+    ///     anyhow::ensure!(!error.to_string().is_empty(), "got an empty drop error");
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn from_result_fn(f: F) -> Self {
+        Self(f)
+    }
 }
 
 impl<F, E> FallibleTryDropStrategy for AdHocFallibleDropStrategy<F, E>
@@ -107,6 +186,18 @@ where
     E: Into<anyhow::Error>,
 {}
 
+/// Signifies that this type can be converted into an [`AdHocFallibleDropStrategy`] whose error
+/// type is [`anyhow::Error`]. Unlike [`IntoAdHocFallibleDropStrategy`], the error type doesn't have
+/// to be annotated.
+pub trait IntoAdHocResultDropStrategy: Fn(crate::Error) -> anyhow::Result<()> + Sized {
+    /// Convert this type into an [`AdHocFallibleDropStrategy`].
+    fn into_result_drop_strategy(self) -> AdHocFallibleDropStrategy<Self, anyhow::Error> {
+        AdHocFallibleDropStrategy(self)
+    }
+}
+
+impl<T: Fn(crate::Error) -> anyhow::Result<()>> IntoAdHocResultDropStrategy for T {}
+
 #[cfg(test)]
 mod tests {
     use std::cell::Cell;
@@ -162,4 +253,31 @@ mod tests {
         drop(fallible());
         assert!(works.get(), "the strategy should have worked");
     }
+
+    #[test]
+    fn test_adhoc_fallible_drop_strategy_from_result_fn() {
+        let works = Rc::new(Cell::new(false));
+        let w = Rc::clone(&works);
+        let strategy = AdHocFallibleDropStrategy::from_result_fn(move |_| {
+            w.set(true);
+            Ok(())
+        });
+        crate::install_thread_local_handlers(strategy, PanicDropStrategy::DEFAULT);
+        drop(fallible());
+        assert!(works.get(), "the strategy should have worked");
+    }
+
+    #[test]
+    fn test_into_adhoc_result_drop_strategy() {
+        let works = Rc::new(Cell::new(false));
+        let w = Rc::clone(&works);
+        let strategy = move |_| {
+            w.set(true);
+            Ok(())
+        };
+        let strategy = strategy.into_result_drop_strategy();
+        crate::install_thread_local_handlers(strategy, PanicDropStrategy::DEFAULT);
+        drop(fallible());
+        assert!(works.get(), "the strategy should have worked");
+    }
 }
\ No newline at end of file
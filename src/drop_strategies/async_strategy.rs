@@ -0,0 +1,170 @@
+//! Bridges which adapt an [`AsyncTryDropStrategy`] to a [`TryDropStrategy`]. This is built on top
+//! of the tokio runtime.
+
+use crate::{AsyncTryDropStrategy, TryDropStrategy};
+use std::sync::Arc;
+pub use tokio::runtime::Handle;
+
+/// A try drop strategy which handles errors with an [`AsyncTryDropStrategy`] by blocking on a
+/// handle to the tokio runtime until it is done.
+///
+/// # Panics
+/// Like [`Handle::block_on`], handling an error will panic if called from within an asynchronous
+/// execution context.
+#[cfg_attr(feature = "derives", derive(Debug, Clone))]
+pub struct BlockOnStrategy<S: AsyncTryDropStrategy> {
+    /// The asynchronous try drop strategy.
+    pub strategy: S,
+    handle: Handle,
+}
+
+impl<S: AsyncTryDropStrategy> BlockOnStrategy<S> {
+    /// Create a new block on strategy from a handle to the current tokio runtime.
+    pub fn new(strategy: S) -> Self {
+        Self::new_with(strategy, Handle::current())
+    }
+
+    /// Create a new block on strategy, with a handle to a tokio runtime.
+    pub fn new_with(strategy: S, handle: Handle) -> Self {
+        Self { strategy, handle }
+    }
+}
+
+impl<S: AsyncTryDropStrategy> TryDropStrategy for BlockOnStrategy<S> {
+    fn handle_error(&self, error: crate::Error) {
+        self.handle
+            .block_on(AsyncTryDropStrategy::handle_error(&self.strategy, error))
+    }
+}
+
+/// A try drop strategy which handles errors with an [`AsyncTryDropStrategy`] by spawning it onto
+/// the tokio runtime, without waiting for it to finish.
+///
+/// If the spawned task fails, such as if it panics or if it is cancelled, the error is redirected
+/// to the secondary try drop strategy.
+///
+/// # Notes
+/// If the runtime is shut down, neither the asynchronous strategy nor the secondary strategy will
+/// be run.
+#[cfg_attr(feature = "derives", derive(Debug, Clone))]
+pub struct SpawnStrategy<S, F>
+where
+    S: AsyncTryDropStrategy + Send + Sync + 'static,
+    F: TryDropStrategy + Send + Sync + 'static,
+{
+    strategy: Arc<S>,
+    secondary: Arc<F>,
+    handle: Handle,
+}
+
+impl<S, F> SpawnStrategy<S, F>
+where
+    S: AsyncTryDropStrategy + Send + Sync + 'static,
+    F: TryDropStrategy + Send + Sync + 'static,
+{
+    /// Create a new spawn strategy from a handle to the current tokio runtime.
+    pub fn new(strategy: S, secondary: F) -> Self {
+        Self::new_with(strategy, secondary, Handle::current())
+    }
+
+    /// Create a new spawn strategy, with a handle to a tokio runtime.
+    pub fn new_with(strategy: S, secondary: F, handle: Handle) -> Self {
+        Self {
+            strategy: Arc::new(strategy),
+            secondary: Arc::new(secondary),
+            handle,
+        }
+    }
+}
+
+impl<S, F> TryDropStrategy for SpawnStrategy<S, F>
+where
+    S: AsyncTryDropStrategy + Send + Sync + 'static,
+    F: TryDropStrategy + Send + Sync + 'static,
+{
+    fn handle_error(&self, error: crate::Error) {
+        let strategy = Arc::clone(&self.strategy);
+        let task = self
+            .handle
+            .spawn(async move { AsyncTryDropStrategy::handle_error(&*strategy, error).await });
+        let secondary = Arc::clone(&self.secondary);
+
+        self.handle.spawn(async move {
+            if let Err(error) = task.await {
+                secondary.handle_error(error.into())
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::AdHocDropStrategy;
+    use std::string::{String, ToString};
+    use std::sync::mpsc::{self, Sender};
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::runtime::Runtime;
+
+    struct ChannelStrategy(Sender<String>);
+
+    impl AsyncTryDropStrategy for ChannelStrategy {
+        async fn handle_error(&self, error: crate::Error) {
+            tokio::task::yield_now().await;
+            let _ = self.0.send(error.to_string());
+        }
+    }
+
+    struct PanickingStrategy;
+
+    impl AsyncTryDropStrategy for PanickingStrategy {
+        async fn handle_error(&self, _error: crate::Error) {
+            panic!("this strategy always panics")
+        }
+    }
+
+    #[test]
+    fn test_block_on_strategy() {
+        let runtime = Runtime::new().expect("failed to create runtime");
+        let (sender, receiver) = mpsc::channel();
+        let strategy = BlockOnStrategy::new_with(ChannelStrategy(sender), runtime.handle().clone());
+        TryDropStrategy::handle_error(&strategy, anyhow::anyhow!("test"));
+        assert_eq!(receiver.try_recv().as_deref(), Ok("test"));
+    }
+
+    #[test]
+    fn test_spawn_strategy() {
+        let runtime = Runtime::new().expect("failed to create runtime");
+        let (sender, receiver) = mpsc::channel();
+        let strategy = SpawnStrategy::new_with(
+            ChannelStrategy(sender),
+            AdHocDropStrategy(|_| panic!("the spawned task should not fail")),
+            runtime.handle().clone(),
+        );
+        TryDropStrategy::handle_error(&strategy, anyhow::anyhow!("test"));
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)).as_deref(),
+            Ok("test")
+        );
+    }
+
+    #[test]
+    fn test_spawn_strategy_secondary() {
+        let runtime = Runtime::new().expect("failed to create runtime");
+        let (sender, receiver) = mpsc::channel();
+        let sender = Mutex::new(sender);
+        let strategy = SpawnStrategy::new_with(
+            PanickingStrategy,
+            AdHocDropStrategy(move |error| {
+                let _ = sender.lock().unwrap().send(error.to_string());
+            }),
+            runtime.handle().clone(),
+        );
+        TryDropStrategy::handle_error(&strategy, anyhow::anyhow!("test"));
+        let error = receiver
+            .recv_timeout(Duration::from_secs(5))
+            .expect("the secondary strategy was not called");
+        assert!(error.contains("panicked"), "unexpected error: {error}");
+    }
+}
@@ -0,0 +1,329 @@
+//! Find out which drop strategies were compiled in, e.g. to let the user choose one at runtime.
+
+use crate::DynFallibleTryDropStrategy;
+use std::boxed::Box;
+
+/// A boxed drop strategy, as created by [`StrategyDescriptor::factory`].
+pub type BoxedDropStrategy = Box<dyn DynFallibleTryDropStrategy + Send + Sync>;
+
+/// Describes a drop strategy which was compiled in.
+#[cfg_attr(feature = "derives", derive(Debug, Copy, Clone))]
+pub struct StrategyDescriptor {
+    /// The name of the drop strategy, which is the name of its feature without the `ds-` prefix.
+    /// This is stable, so it's suitable for configuration files and command line flags.
+    pub name: &'static str,
+
+    /// A short, human readable description of the drop strategy.
+    pub description: &'static str,
+
+    /// Whether or not the drop strategy needs more than its name to be created, such as a path,
+    /// a capacity, or another drop strategy to wrap.
+    pub needs_configuration: bool,
+
+    /// Creates the drop strategy with its default configuration, if it has one.
+    pub factory: Option<fn() -> BoxedDropStrategy>,
+}
+
+impl StrategyDescriptor {
+    /// Create the drop strategy with its default configuration, if it has one.
+    pub fn create(&self) -> Option<BoxedDropStrategy> {
+        self.factory.map(|factory| factory())
+    }
+}
+
+static AVAILABLE: &[StrategyDescriptor] = &[
+    #[cfg(feature = "ds-abort")]
+    StrategyDescriptor {
+        name: "abort",
+        description: "aborts the program",
+        needs_configuration: false,
+        factory: Some(|| -> BoxedDropStrategy { Box::new(super::AbortDropStrategy) }),
+    },
+    #[cfg(feature = "ds-adhoc")]
+    StrategyDescriptor {
+        name: "adhoc",
+        description: "calls a function",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-adhoc-mut")]
+    StrategyDescriptor {
+        name: "adhoc-mut",
+        description: "calls a mutable function",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "async-strategy")]
+    StrategyDescriptor {
+        name: "async-strategy",
+        description: "handles errors on an async runtime",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-broadcast")]
+    StrategyDescriptor {
+        name: "broadcast",
+        description: "sends errors to many receivers",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-channel")]
+    StrategyDescriptor {
+        name: "channel",
+        description: "sends errors to a single receiver",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-collect")]
+    StrategyDescriptor {
+        name: "collect",
+        description: "collects errors in memory",
+        needs_configuration: false,
+        factory: Some(|| -> BoxedDropStrategy { Box::new(super::CollectDropStrategy::new()) }),
+    },
+    #[cfg(feature = "ds-counting")]
+    StrategyDescriptor {
+        name: "counting",
+        description: "counts errors, then delegates them to another drop strategy",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-dedup")]
+    StrategyDescriptor {
+        name: "dedup",
+        description: "delegates errors to another drop strategy, skipping repeated ones",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-exit")]
+    StrategyDescriptor {
+        name: "exit",
+        description: "exits the program",
+        needs_configuration: false,
+        factory: Some(|| -> BoxedDropStrategy { Box::new(super::ExitDropStrategy::DEFAULT) }),
+    },
+    #[cfg(feature = "ds-fmt-write")]
+    StrategyDescriptor {
+        name: "fmt-write",
+        description: "writes errors to a formatter",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-hook")]
+    StrategyDescriptor {
+        name: "hook",
+        description: "calls a hook with errors and their drop context",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-log")]
+    StrategyDescriptor {
+        name: "log",
+        description: "logs errors with the `log` crate",
+        needs_configuration: false,
+        factory: Some(|| -> BoxedDropStrategy { Box::new(super::LogDropStrategy::DEFAULT) }),
+    },
+    #[cfg(feature = "ds-metrics")]
+    StrategyDescriptor {
+        name: "metrics",
+        description:
+            "counts errors with the `metrics` crate, then delegates them to another drop strategy",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-noop")]
+    StrategyDescriptor {
+        name: "noop",
+        description: "ignores errors",
+        needs_configuration: false,
+        factory: Some(|| -> BoxedDropStrategy { Box::new(super::NoOpDropStrategy) }),
+    },
+    #[cfg(feature = "ds-once-cell")]
+    StrategyDescriptor {
+        name: "once-cell",
+        description: "stores errors in a cell",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-panic")]
+    StrategyDescriptor {
+        name: "panic",
+        description: "panics",
+        needs_configuration: false,
+        factory: Some(|| -> BoxedDropStrategy { Box::new(super::PanicDropStrategy::DEFAULT) }),
+    },
+    #[cfg(feature = "ds-predicate")]
+    StrategyDescriptor {
+        name: "predicate",
+        description: "routes errors to one of two drop strategies",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-rate-limit")]
+    StrategyDescriptor {
+        name: "rate-limit",
+        description: "delegates errors to another drop strategy at a limited rate",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-salvage")]
+    StrategyDescriptor {
+        name: "salvage",
+        description: "keeps the values which failed to drop",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-sampling")]
+    StrategyDescriptor {
+        name: "sampling",
+        description: "delegates a sample of errors to another drop strategy",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-sequence")]
+    StrategyDescriptor {
+        name: "sequence",
+        description: "hands errors to many drop strategies, in order",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-syslog")]
+    StrategyDescriptor {
+        name: "syslog",
+        description: "writes errors to syslog",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-timeout")]
+    StrategyDescriptor {
+        name: "timeout",
+        description: "delegates errors to another drop strategy, bounding how long it may take",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-tracing")]
+    StrategyDescriptor {
+        name: "tracing",
+        description: "emits errors as `tracing` events",
+        needs_configuration: false,
+        factory: Some(|| -> BoxedDropStrategy { Box::new(super::TracingDropStrategy::DEFAULT) }),
+    },
+    #[cfg(feature = "ds-truncate")]
+    StrategyDescriptor {
+        name: "truncate",
+        description: "truncates long errors, then delegates them to another drop strategy",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-unreachable")]
+    StrategyDescriptor {
+        name: "unreachable",
+        description: "asserts that errors never happen",
+        needs_configuration: true,
+        factory: None,
+    },
+    #[cfg(feature = "ds-write")]
+    StrategyDescriptor {
+        name: "write",
+        description: "writes errors to standard error",
+        needs_configuration: false,
+        factory: Some(|| -> BoxedDropStrategy { Box::new(super::WriteDropStrategy::stderr()) }),
+    },
+];
+
+/// Get the drop strategies which were compiled in, sorted by name.
+pub fn available() -> &'static [StrategyDescriptor] {
+    AVAILABLE
+}
+
+/// Find a drop strategy which was compiled in by its name, e.g. to parse a command line flag.
+pub fn find(name: &str) -> Option<&'static StrategyDescriptor> {
+    AVAILABLE.iter().find(|descriptor| descriptor.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_available() {
+        let features = [
+            ("abort", cfg!(feature = "ds-abort")),
+            ("adhoc", cfg!(feature = "ds-adhoc")),
+            ("adhoc-mut", cfg!(feature = "ds-adhoc-mut")),
+            ("async-strategy", cfg!(feature = "async-strategy")),
+            ("broadcast", cfg!(feature = "ds-broadcast")),
+            ("channel", cfg!(feature = "ds-channel")),
+            ("collect", cfg!(feature = "ds-collect")),
+            ("counting", cfg!(feature = "ds-counting")),
+            ("dedup", cfg!(feature = "ds-dedup")),
+            ("exit", cfg!(feature = "ds-exit")),
+            ("fmt-write", cfg!(feature = "ds-fmt-write")),
+            ("hook", cfg!(feature = "ds-hook")),
+            ("log", cfg!(feature = "ds-log")),
+            ("metrics", cfg!(feature = "ds-metrics")),
+            ("noop", cfg!(feature = "ds-noop")),
+            ("once-cell", cfg!(feature = "ds-once-cell")),
+            ("panic", cfg!(feature = "ds-panic")),
+            ("predicate", cfg!(feature = "ds-predicate")),
+            ("rate-limit", cfg!(feature = "ds-rate-limit")),
+            ("salvage", cfg!(feature = "ds-salvage")),
+            ("sampling", cfg!(feature = "ds-sampling")),
+            ("sequence", cfg!(feature = "ds-sequence")),
+            ("syslog", cfg!(feature = "ds-syslog")),
+            ("timeout", cfg!(feature = "ds-timeout")),
+            ("tracing", cfg!(feature = "ds-tracing")),
+            ("truncate", cfg!(feature = "ds-truncate")),
+            ("unreachable", cfg!(feature = "ds-unreachable")),
+            ("write", cfg!(feature = "ds-write")),
+        ];
+        let expected: Vec<_> = features
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect();
+        let names: Vec<_> = available()
+            .iter()
+            .map(|descriptor| descriptor.name)
+            .collect();
+        assert_eq!(names, expected);
+
+        for descriptor in available() {
+            assert!(core::ptr::eq(find(descriptor.name).unwrap(), descriptor));
+            assert_eq!(
+                descriptor.factory.is_none(),
+                descriptor.needs_configuration,
+                "{} can't be created by its name alone",
+                descriptor.name,
+            );
+        }
+
+        assert!(find("does-not-exist").is_none());
+    }
+
+    #[test]
+    fn test_factories() {
+        for descriptor in available() {
+            let strategy = match descriptor.create() {
+                Some(strategy) => strategy,
+                None => continue,
+            };
+
+            match descriptor.name {
+                // these end the program.
+                "abort" | "exit" => {}
+                "panic" => {
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                        strategy.dyn_try_handle_error(anyhow::anyhow!("panic"))
+                    }));
+                    assert!(result.is_err(), "the panic drop strategy didn't panic");
+                }
+                name => strategy
+                    .dyn_try_handle_error(anyhow::anyhow!("this will always fail"))
+                    .unwrap_or_else(|error| panic!("{name} failed to handle an error: {error}")),
+            }
+        }
+    }
+}
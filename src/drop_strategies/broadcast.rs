@@ -7,36 +7,184 @@ mod private {
 
 use crate::{FallibleTryDropStrategy, TryDropStrategy};
 
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use std::error::Error as StdError;
+use std::fmt;
 use std::marker::PhantomData;
+use std::sync::Arc;
+use std::task::Wake;
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+use std::vec::Vec;
 
-use crate::adapters::ArcError;
+pub use crate::adapters::ArcError;
 pub use tokio::runtime::Handle;
+use tokio::runtime::Runtime;
 use tokio::sync::broadcast;
-use tokio::sync::broadcast::error::SendError;
-use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+pub use tokio::sync::broadcast::error::{RecvError, SendError, TryRecvError};
 pub use tokio::sync::broadcast::Receiver as AsyncReceiver;
 use tokio::sync::broadcast::{Receiver, Sender};
 
+/// The error returned by [`BlockingReceiver::recv_timeout`].
+#[cfg_attr(
+    feature = "derives",
+    derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Debug)]
+pub enum RecvTimeoutError {
+    /// No message was received before the timeout elapsed.
+    Timeout,
+
+    /// The drop strategy was dropped, and there are no messages left.
+    Closed,
+
+    /// The receiver lagged behind, and this many messages were skipped. See [`BlockingReceiver`]
+    /// for more information.
+    Lagged(u64),
+}
+
+impl From<RecvError> for RecvTimeoutError {
+    fn from(error: RecvError) -> Self {
+        match error {
+            RecvError::Closed => Self::Closed,
+            RecvError::Lagged(skipped) => Self::Lagged(skipped),
+        }
+    }
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Timeout => f.write_str("timed out waiting on channel"),
+            Self::Closed => f.write_str("channel closed"),
+            Self::Lagged(skipped) => write!(f, "channel lagged by {skipped}"),
+        }
+    }
+}
+
+impl StdError for RecvTimeoutError {}
+
+/// Wakes a thread parked by [`BlockingReceiver::recv_timeout`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
 /// An async receiver, which is made sync via blocking on a handle to the tokio runtime.
+///
+/// # Lagging
+/// The channel only buffers up to its capacity. If more messages than that are sent before they
+/// are received, the oldest ones are overwritten and the receiver *lags* behind. The next receive
+/// then returns a `Lagged` error with the amount of skipped messages, after which receiving
+/// continues from the oldest message still buffered.
 #[cfg_attr(feature = "derives", derive(Debug))]
 pub struct BlockingReceiver<T> {
     receiver: Receiver<T>,
     handle: Handle,
+    lagged: Option<u64>,
+
+    // keeps a shared runtime alive for as long as `handle` is used.
+    runtime: Option<Arc<Runtime>>,
 }
 
 impl<T: Clone> BlockingReceiver<T> {
     pub(crate) fn new(receiver: Receiver<T>, handle: Handle) -> Self {
-        Self { receiver, handle }
+        Self {
+            receiver,
+            handle,
+            lagged: None,
+            runtime: None,
+        }
+    }
+
+    pub(crate) fn with_runtime(mut self, runtime: Option<Arc<Runtime>>) -> Self {
+        self.runtime = runtime;
+        self
     }
 
     /// Receive a message from the channel, blocking until one is available.
     pub fn recv(&mut self) -> Result<T, RecvError> {
-        self.handle.block_on(self.receiver.recv())
+        match self.lagged.take() {
+            Some(skipped) => Err(RecvError::Lagged(skipped)),
+            None => self.handle.block_on(self.receiver.recv()),
+        }
+    }
+
+    /// Receive a message from the channel, blocking until one is available or the `timeout`
+    /// elapses.
+    ///
+    /// Unlike [`BlockingReceiver::recv`], this doesn't use the tokio runtime: the current thread is
+    /// parked until a message is sent or the deadline passes. It works with any kind of runtime,
+    /// even one without its time driver enabled.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        if let Some(skipped) = self.lagged.take() {
+            return Err(RecvTimeoutError::Lagged(skipped));
+        }
+
+        let deadline = Instant::now() + timeout;
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut recv = pin!(self.receiver.recv());
+
+        loop {
+            if let Poll::Ready(result) = recv.as_mut().poll(&mut context) {
+                return result.map_err(Into::into);
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(RecvTimeoutError::Timeout);
+            }
+
+            thread::park_timeout(deadline - now);
+        }
     }
 
     /// Try to receive a message from the channel, without blocking.
     pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
-        self.receiver.try_recv()
+        match self.lagged.take() {
+            Some(skipped) => Err(TryRecvError::Lagged(skipped)),
+            None => self.receiver.try_recv(),
+        }
+    }
+
+    /// Receive up to `max` messages which are already buffered in the channel, without blocking.
+    ///
+    /// # Errors
+    /// If the receiver lagged behind before any message was received, a `Lagged` error is
+    /// returned. If it lagged behind after some messages were received, those are returned
+    /// instead, and the `Lagged` error is returned by the next receive.
+    pub fn recv_many(&mut self, max: usize) -> Result<Vec<T>, RecvError> {
+        let mut messages = Vec::new();
+
+        while messages.len() < max {
+            match self.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Lagged(skipped)) if messages.is_empty() => {
+                    return Err(RecvError::Lagged(skipped))
+                }
+                Err(TryRecvError::Lagged(skipped)) => {
+                    self.lagged = Some(skipped);
+                    break;
+                }
+                Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+            }
+        }
+
+        Ok(messages)
+    }
+
+    /// Receive all messages which are already buffered in the channel, without blocking.
+    ///
+    /// # Errors
+    /// See [`BlockingReceiver::recv_many`].
+    pub fn drain(&mut self) -> Result<Vec<T>, RecvError> {
+        self.recv_many(usize::MAX)
     }
 }
 
@@ -70,6 +218,7 @@ impl private::Sealed for NeedsReceivers {}
 pub struct BroadcastDropStrategy<M: Mode> {
     sender: Sender<ArcError>,
     handle: Handle,
+    runtime: Option<Arc<Runtime>>,
     _mode: PhantomData<M>,
 }
 
@@ -88,15 +237,31 @@ impl<M: Mode> BroadcastDropStrategy<M> {
             Self {
                 sender,
                 handle,
+                runtime: None,
                 _mode: PhantomData,
             },
             receiver,
         )
     }
 
+    /// Create a new broadcast drop strategy with a tokio runtime which is shared with the rest of
+    /// the application, instead of creating one just for broadcasting drop errors.
+    ///
+    /// The runtime is kept alive for as long as the drop strategy or any of its receivers are.
+    pub fn new_with_arc(
+        capacity: usize,
+        runtime: Arc<Runtime>,
+    ) -> (Self, BlockingReceiver<ArcError>) {
+        let (mut this, receiver) = Self::new_with(capacity, runtime.handle().clone());
+        let receiver = receiver.with_runtime(Some(Arc::clone(&runtime)));
+        this.runtime = Some(runtime);
+        (this, receiver)
+    }
+
     /// Subscribe to this drop strategy, receiving new errors.
     pub fn subscribe(&self) -> BlockingReceiver<ArcError> {
         BlockingReceiver::new(self.sender.subscribe(), self.handle.clone())
+            .with_runtime(self.runtime.clone())
     }
 }
 
@@ -113,3 +278,118 @@ impl FallibleTryDropStrategy for BroadcastDropStrategy<NeedsReceivers> {
         self.sender.send(ArcError::new(error)).map(|_| ())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+    use tokio::runtime::Builder;
+
+    fn send(strategy: &BroadcastDropStrategy<OkIfAlone>, count: usize) {
+        for index in 0..count {
+            strategy.handle_error(anyhow::anyhow!("error {}", index))
+        }
+    }
+
+    #[test]
+    fn test_drain() {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let (strategy, mut receiver) =
+            BroadcastDropStrategy::<OkIfAlone>::new_with(8, runtime.handle().clone());
+        send(&strategy, 5);
+
+        let errors = receiver.drain().unwrap();
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            ["error 0", "error 1", "error 2", "error 3", "error 4"],
+        );
+        assert!(receiver.drain().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_recv_many() {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let (strategy, mut receiver) =
+            BroadcastDropStrategy::<OkIfAlone>::new_with(8, runtime.handle().clone());
+        send(&strategy, 5);
+
+        assert_eq!(receiver.recv_many(3).unwrap().len(), 3);
+        assert_eq!(receiver.recv_many(3).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_try_recv_empty() {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let (_strategy, mut receiver) =
+            BroadcastDropStrategy::<OkIfAlone>::new_with(8, runtime.handle().clone());
+
+        assert!(matches!(receiver.try_recv(), Err(TryRecvError::Empty)));
+    }
+
+    #[test]
+    fn test_recv_timeout() {
+        // neither the time driver nor a worker thread is needed
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let (strategy, mut receiver) =
+            BroadcastDropStrategy::<OkIfAlone>::new_with(8, runtime.handle().clone());
+
+        assert!(matches!(
+            receiver.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Timeout)
+        ));
+
+        let sender = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            strategy.handle_error(anyhow::anyhow!("error 0"));
+            strategy
+        });
+        let error = receiver
+            .recv_timeout(Duration::from_secs(10))
+            .expect("the error should have been received before the timeout");
+        assert_eq!(error.to_string(), "error 0");
+
+        drop(sender.join().unwrap());
+        assert!(matches!(
+            receiver.recv_timeout(Duration::from_secs(10)),
+            Err(RecvTimeoutError::Closed)
+        ));
+    }
+
+    #[test]
+    fn test_new_with_arc() {
+        let runtime = Arc::new(Builder::new_current_thread().build().unwrap());
+        let (first, mut first_receiver) =
+            BroadcastDropStrategy::<OkIfAlone>::new_with_arc(8, Arc::clone(&runtime));
+        let (second, mut second_receiver) =
+            BroadcastDropStrategy::<OkIfAlone>::new_with_arc(8, Arc::clone(&runtime));
+
+        // the strategies and their receivers keep the runtime alive on their own
+        drop(runtime);
+        first.handle_error(anyhow::anyhow!("first"));
+        second.handle_error(anyhow::anyhow!("second"));
+
+        assert_eq!(first_receiver.recv().unwrap().to_string(), "first");
+        assert_eq!(second_receiver.recv().unwrap().to_string(), "second");
+        assert!(first_receiver.drain().unwrap().is_empty());
+        assert!(second_receiver.drain().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_recv_many_surfaces_lag() {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let (strategy, mut receiver) =
+            BroadcastDropStrategy::<OkIfAlone>::new_with(2, runtime.handle().clone());
+        send(&strategy, 5);
+
+        assert!(matches!(receiver.drain(), Err(RecvError::Lagged(3))));
+        assert_eq!(
+            receiver
+                .drain()
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["error 3", "error 4"],
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Types and traits for the channel drop strategy. This is built on top of the standard library's
+//! mpsc channel.
+//!
+//! As there's only a single receiver, drop errors are moved into the channel as they are, without
+//! being wrapped in an [`ArcError`](crate::adapters::ArcError). This means that the receiver can
+//! downcast them, like any other [`anyhow::Error`].
+
+mod private {
+    pub trait Sealed {}
+}
+
+use crate::{FallibleTryDropStrategy, TryDropStrategy};
+use std::marker::PhantomData;
+use std::sync::mpsc;
+
+pub use std::sync::mpsc::{Receiver, RecvError, SendError, Sender, TryRecvError};
+
+/// How to handle errors when sending a message to the receiver.
+pub trait Mode: private::Sealed {}
+
+/// Continue on sending errors to nobody if the receiver is gone.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub enum OkIfAlone {}
+
+impl Mode for OkIfAlone {}
+
+impl private::Sealed for OkIfAlone {}
+
+/// Return an error if the receiver is gone.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub enum NeedsReceiver {}
+
+impl Mode for NeedsReceiver {}
+
+impl private::Sealed for NeedsReceiver {}
+
+/// A drop strategy which sends a drop error to a receiver, usually on another thread.
+#[cfg_attr(feature = "derives", derive(Debug, Clone))]
+pub struct ChannelDropStrategy<M: Mode> {
+    sender: Sender<crate::Error>,
+    _mode: PhantomData<M>,
+}
+
+impl<M: Mode> ChannelDropStrategy<M> {
+    /// Create a new channel drop strategy, along with the receiver of the errors.
+    pub fn new() -> (Self, Receiver<crate::Error>) {
+        let (sender, receiver) = mpsc::channel();
+        (Self::from_sender(sender), receiver)
+    }
+
+    /// Create a new channel drop strategy which sends errors to the given sender.
+    pub fn from_sender(sender: Sender<crate::Error>) -> Self {
+        Self {
+            sender,
+            _mode: PhantomData,
+        }
+    }
+}
+
+impl TryDropStrategy for ChannelDropStrategy<OkIfAlone> {
+    fn handle_error(&self, error: crate::Error) {
+        let _ = self.sender.send(error);
+    }
+}
+
+impl FallibleTryDropStrategy for ChannelDropStrategy<NeedsReceiver> {
+    type Error = SendError<crate::Error>;
+
+    fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
+        self.sender.send(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::PanicDropStrategy;
+    use crate::test_utils::{ErrorsOnDrop, Fallible};
+    use crate::PureTryDrop;
+    use std::string::ToString;
+    use std::thread;
+
+    #[test]
+    fn test_channel_drop_strategy() {
+        let (strategy, receiver) = ChannelDropStrategy::<OkIfAlone>::new();
+        let handle = thread::spawn(move || receiver.recv().unwrap().to_string());
+        let errors =
+            ErrorsOnDrop::<Fallible, _>::given(strategy, PanicDropStrategy::DEFAULT).adapt();
+        drop(errors);
+        assert_eq!(handle.join().unwrap(), "this will always fail");
+    }
+
+    #[test]
+    fn test_downcast() {
+        use crate::adapters::WithStrategies;
+        use crate::ImpureTryDrop;
+        use std::io;
+
+        struct FailsToFlush;
+
+        impl ImpureTryDrop for FailsToFlush {
+            type Error = io::Error;
+
+            unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+                Err(io::Error::new(io::ErrorKind::WriteZero, "failed to flush"))
+            }
+        }
+
+        let (strategy, receiver) = ChannelDropStrategy::<NeedsReceiver>::new();
+        drop(WithStrategies::new(FailsToFlush, strategy, PanicDropStrategy::DEFAULT).adapt());
+        let error = receiver
+            .recv()
+            .unwrap()
+            .downcast::<io::Error>()
+            .expect("the error was wrapped on its way through the channel");
+        assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_ok_if_alone() {
+        let (strategy, receiver) = ChannelDropStrategy::<OkIfAlone>::new();
+        drop(receiver);
+        strategy.handle_error(anyhow::anyhow!("this will always fail"));
+    }
+
+    #[test]
+    fn test_needs_receiver() {
+        let (strategy, receiver) = ChannelDropStrategy::<NeedsReceiver>::new();
+        drop(receiver);
+        strategy
+            .try_handle_error(anyhow::anyhow!("this will always fail"))
+            .expect_err("sending with no receiver should fail");
+    }
+}
@@ -0,0 +1,508 @@
+//! Types and traits for the collect drop strategy.
+
+use crate::TryDropStrategy;
+use core::ptr;
+use core::sync::atomic::{AtomicU64, Ordering};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use std::vec::Vec;
+
+pub use crate::adapters::ArcError;
+
+/// The sequence number of the next error to be collected, shared by every collector, so that
+/// errors of different collectors can be merged in order.
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// An error collected by the [`CollectDropStrategy`].
+#[cfg_attr(feature = "derives", derive(Debug))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Clone)]
+pub struct CollectedError {
+    /// The order in which the error was reported. This is unique across all collectors, and is
+    /// taken before the collector is locked, so unlike the position of the error in the
+    /// collector, it isn't affected by lock contention.
+    pub sequence: u64,
+
+    /// When the error was reported.
+    pub timestamp: SystemTime,
+
+    /// The error itself.
+    pub error: ArcError,
+}
+
+/// What a [`CollectDropStrategy`] does with an error once it already keeps as many errors in
+/// memory as it may.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Clone)]
+pub enum Overflow {
+    /// Forget the oldest error to make room for the new one.
+    DropOldest,
+
+    /// Forget the new error. Errors are collected again once there's room for them, e.g. after
+    /// [`CollectDropStrategy::take_all`].
+    DropNewest,
+
+    /// Append the new error to the file at the given path, one line per error formatted with
+    /// `{:#}`, instead of keeping it in memory. The file is created if it doesn't exist. If
+    /// writing to it fails, the error is forgotten instead.
+    SpillToFile(PathBuf),
+
+    /// Stop collecting errors for good, forgetting the new error and every one after it, even if
+    /// there's room for them again.
+    StopCollecting,
+}
+
+/// The errors taken out of a [`CollectDropStrategy`] by [`CollectDropStrategy::take_all`], along
+/// with what happened to the ones which didn't fit in memory.
+#[cfg_attr(feature = "derives", derive(Debug))]
+#[derive(Clone)]
+pub struct TakenErrors {
+    /// The errors which were kept in memory, in the order they were handled.
+    pub errors: Vec<CollectedError>,
+
+    /// How many errors were forgotten because they didn't fit in memory.
+    pub overflowed: usize,
+
+    /// How many errors were appended to the spill file because they didn't fit in memory.
+    pub spilled: usize,
+
+    /// The file the spilled errors were appended to, if any were.
+    pub spill_path: Option<PathBuf>,
+}
+
+#[cfg_attr(feature = "derives", derive(Debug))]
+struct Collector {
+    errors: VecDeque<CollectedError>,
+    max_in_memory: usize,
+    overflow: Overflow,
+    overflowed: usize,
+    spilled: usize,
+    stopped: bool,
+    spill_file: Option<File>,
+}
+
+impl Collector {
+    fn push(&mut self, collected: CollectedError) {
+        if self.stopped {
+            self.overflowed += 1;
+            return;
+        }
+
+        if self.errors.len() < self.max_in_memory {
+            self.errors.push_back(collected);
+            return;
+        }
+
+        match &self.overflow {
+            Overflow::DropOldest => {
+                if self.errors.pop_front().is_some() {
+                    self.errors.push_back(collected);
+                }
+
+                self.overflowed += 1;
+            }
+            Overflow::DropNewest => self.overflowed += 1,
+            Overflow::SpillToFile(path) => match spill(&mut self.spill_file, path, &collected) {
+                Ok(()) => self.spilled += 1,
+                Err(_) => self.overflowed += 1,
+            },
+            Overflow::StopCollecting => {
+                self.stopped = true;
+                self.overflowed += 1;
+            }
+        }
+    }
+}
+
+/// Append `collected` to the spill file, opening it first if it isn't yet.
+fn spill(file: &mut Option<File>, path: &Path, collected: &CollectedError) -> io::Result<()> {
+    let file = match file {
+        Some(file) => file,
+        None => file.insert(OpenOptions::new().create(true).append(true).open(path)?),
+    };
+
+    writeln!(file, "{:#}", collected.error)
+}
+
+/// A drop strategy which collects every drop error it handles, in the order they were handled.
+///
+/// Unlike the [`BroadcastDropStrategy`], this doesn't need a runtime, which makes it useful for
+/// inspecting the errors of a batch of dropped values, for example in tests.
+///
+/// By default, there's no limit to how many errors are kept in memory, which can exhaust it during
+/// a pathological teardown. Use [`CollectDropStrategy::bounded`] to limit it.
+///
+/// [`BroadcastDropStrategy`]: crate::drop_strategies::BroadcastDropStrategy
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct CollectDropStrategy {
+    errors: Mutex<Collector>,
+}
+
+impl CollectDropStrategy {
+    /// Create a new collect drop strategy which hasn't collected any errors yet, and keeps every
+    /// error in memory.
+    pub const fn new() -> Self {
+        Self::bounded(usize::MAX, Overflow::DropNewest)
+    }
+
+    /// Create a new collect drop strategy which hasn't collected any errors yet, and keeps at most
+    /// `max_in_memory` errors in memory, handling the rest as `overflow` says.
+    pub const fn bounded(max_in_memory: usize, overflow: Overflow) -> Self {
+        Self {
+            errors: parking_lot::const_mutex(Collector {
+                errors: VecDeque::new(),
+                max_in_memory,
+                overflow,
+                overflowed: 0,
+                spilled: 0,
+                stopped: false,
+                spill_file: None,
+            }),
+        }
+    }
+
+    /// Get the errors which have been collected so far, in the order they were handled.
+    pub fn errors(&self) -> Vec<ArcError> {
+        self.errors
+            .lock()
+            .errors
+            .iter()
+            .map(|collected| collected.error.clone())
+            .collect()
+    }
+
+    /// Get the errors which have been collected so far, ordered by when they were reported rather
+    /// than by when they were handled.
+    pub fn snapshot_sorted(&self) -> Vec<CollectedError> {
+        let mut errors: Vec<_> = self.errors.lock().errors.iter().cloned().collect();
+        errors.sort_unstable_by_key(|collected| collected.sequence);
+        errors
+    }
+
+    /// Copy the errors collected by `other` into this collector, keeping all of the errors
+    /// ordered by when they were reported. This is useful to combine the collectors of several
+    /// scopes into one report.
+    ///
+    /// The errors of `other` are collected as if they were handled right now, so if they don't
+    /// all fit in memory, the overflow of this collector is applied to them. The errors this
+    /// collector already kept are only forgotten if the overflow makes room for new errors, as
+    /// with [`Overflow::DropOldest`].
+    pub fn merge(&self, other: &Self) {
+        if ptr::eq(self, other) {
+            return;
+        }
+
+        let mut other: Vec<_> = other.errors.lock().errors.iter().cloned().collect();
+        other.sort_by_key(|collected| collected.sequence);
+
+        let mut collector = self.errors.lock();
+        other
+            .into_iter()
+            .for_each(|collected| collector.push(collected));
+        collector
+            .errors
+            .make_contiguous()
+            .sort_by_key(|collected| collected.sequence);
+    }
+
+    /// Forget all of the errors which have been collected so far, along with how many of them
+    /// overflowed or were spilled.
+    pub fn clear(&self) {
+        self.take_all();
+    }
+
+    /// Take the errors which have been collected so far out of the collector, along with how many
+    /// of them overflowed or were spilled since the last time. Spilled errors stay in the spill
+    /// file.
+    ///
+    /// This makes room for new errors, unless the collector stopped collecting because of
+    /// [`Overflow::StopCollecting`].
+    pub fn take_all(&self) -> TakenErrors {
+        let mut collector = self.errors.lock();
+        let spilled = core::mem::take(&mut collector.spilled);
+        let spill_path = match &collector.overflow {
+            Overflow::SpillToFile(path) if spilled > 0 => Some(path.clone()),
+            _ => None,
+        };
+
+        TakenErrors {
+            errors: collector.errors.drain(..).collect(),
+            overflowed: core::mem::take(&mut collector.overflowed),
+            spilled,
+            spill_path,
+        }
+    }
+}
+
+impl Default for CollectDropStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryDropStrategy for CollectDropStrategy {
+    fn handle_error(&self, error: crate::Error) {
+        let sequence = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        let timestamp = SystemTime::now();
+        self.errors.lock().push(CollectedError {
+            sequence,
+            timestamp,
+            error: ArcError::new(error),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::NoOpDropStrategy;
+    use crate::PureTryDrop;
+    use std::format;
+    use std::string::{String, ToString};
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    struct Fails(usize, Arc<CollectDropStrategy>);
+
+    impl PureTryDrop for Fails {
+        type Error = crate::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = CollectDropStrategy;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &self.1
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            anyhow::bail!("drop {} failed", self.0)
+        }
+    }
+
+    #[test]
+    fn test_collect_drop_strategy() {
+        let strategy = Arc::new(CollectDropStrategy::new());
+
+        for index in 0..3 {
+            drop(Fails(index, Arc::clone(&strategy)).adapt());
+        }
+
+        let messages: Vec<String> = strategy.errors().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            messages,
+            ["drop 0 failed", "drop 1 failed", "drop 2 failed"]
+        );
+
+        strategy.clear();
+        assert!(strategy.errors().is_empty());
+    }
+
+    fn messages(errors: &[CollectedError]) -> Vec<String> {
+        errors
+            .iter()
+            .map(|collected| collected.error.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshot_sorted() {
+        const THREADS: usize = 4;
+        const ERRORS: usize = 32;
+
+        let strategy = CollectDropStrategy::new();
+        let barrier = Barrier::new(THREADS);
+        thread::scope(|scope| {
+            for thread in 0..THREADS {
+                let strategy = &strategy;
+                let barrier = &barrier;
+                scope.spawn(move || {
+                    barrier.wait();
+
+                    for index in 0..ERRORS {
+                        strategy.handle_error(anyhow::anyhow!("{thread}:{index}"));
+                    }
+                });
+            }
+        });
+
+        let snapshot = strategy.snapshot_sorted();
+        assert_eq!(snapshot.len(), THREADS * ERRORS);
+        assert!(
+            snapshot
+                .windows(2)
+                .all(|pair| pair[0].sequence < pair[1].sequence),
+            "snapshot is not ordered by sequence"
+        );
+
+        // every thread reported its errors in order, so they must stay in order in the snapshot
+        let messages = messages(&snapshot);
+        for thread in 0..THREADS {
+            let reported: Vec<_> = messages
+                .iter()
+                .filter(|message| message.starts_with(&format!("{thread}:")))
+                .cloned()
+                .collect();
+            let expected: Vec<_> = (0..ERRORS)
+                .map(|index| format!("{thread}:{index}"))
+                .collect();
+            assert_eq!(reported, expected);
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let first = CollectDropStrategy::new();
+        let second = CollectDropStrategy::new();
+        first.handle_error(anyhow::anyhow!("a"));
+        second.handle_error(anyhow::anyhow!("b"));
+        first.handle_error(anyhow::anyhow!("c"));
+        second.handle_error(anyhow::anyhow!("d"));
+
+        first.merge(&second);
+        first.merge(&first);
+        assert_eq!(messages(&first.snapshot_sorted()), ["a", "b", "c", "d"]);
+        assert_eq!(
+            messages(&second.snapshot_sorted()),
+            ["b", "d"],
+            "merge should leave the other collector untouched"
+        );
+    }
+
+    #[test]
+    fn test_merge_overflow() {
+        let first = CollectDropStrategy::bounded(2, Overflow::StopCollecting);
+        let second = CollectDropStrategy::new();
+        for error in ["a", "b", "c"] {
+            first.handle_error(anyhow::anyhow!(error));
+        }
+        second.handle_error(anyhow::anyhow!("d"));
+
+        first.merge(&second);
+        let (messages, taken) = take_messages(&first);
+        assert_eq!(
+            messages,
+            ["a", "b"],
+            "merging forgot the errors which were already collected"
+        );
+        assert_eq!(taken.overflowed, 2);
+    }
+
+    #[test]
+    fn test_merge_drop_newest() {
+        let first = CollectDropStrategy::bounded(3, Overflow::DropNewest);
+        let second = CollectDropStrategy::new();
+        second.handle_error(anyhow::anyhow!("a"));
+        first.handle_error(anyhow::anyhow!("b"));
+        second.handle_error(anyhow::anyhow!("c"));
+        first.handle_error(anyhow::anyhow!("d"));
+        second.handle_error(anyhow::anyhow!("e"));
+
+        first.merge(&second);
+        let (messages, taken) = take_messages(&first);
+        assert_eq!(messages, ["a", "b", "d"]);
+        assert_eq!(taken.overflowed, 2);
+    }
+
+    fn take_messages(strategy: &CollectDropStrategy) -> (Vec<String>, TakenErrors) {
+        let taken = strategy.take_all();
+        (messages(&taken.errors), taken)
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let strategy = CollectDropStrategy::bounded(2, Overflow::DropOldest);
+        for error in ["a", "b", "c", "d"] {
+            strategy.handle_error(anyhow::anyhow!(error));
+        }
+
+        let (messages, taken) = take_messages(&strategy);
+        assert_eq!(messages, ["c", "d"]);
+        assert_eq!(taken.overflowed, 2);
+        assert_eq!(taken.spilled, 0);
+        assert_eq!(taken.spill_path, None);
+    }
+
+    #[test]
+    fn test_drop_newest() {
+        let strategy = CollectDropStrategy::bounded(2, Overflow::DropNewest);
+        for error in ["a", "b", "c", "d"] {
+            strategy.handle_error(anyhow::anyhow!(error));
+        }
+
+        let (messages, taken) = take_messages(&strategy);
+        assert_eq!(messages, ["a", "b"]);
+        assert_eq!(taken.overflowed, 2);
+
+        strategy.handle_error(anyhow::anyhow!("e"));
+        let (messages, taken) = take_messages(&strategy);
+        assert_eq!(
+            messages,
+            ["e"],
+            "taking the errors didn't make room for new ones"
+        );
+        assert_eq!(taken.overflowed, 0);
+    }
+
+    #[test]
+    fn test_stop_collecting() {
+        let strategy = CollectDropStrategy::bounded(2, Overflow::StopCollecting);
+        for error in ["a", "b", "c"] {
+            strategy.handle_error(anyhow::anyhow!(error));
+        }
+
+        let (messages, taken) = take_messages(&strategy);
+        assert_eq!(messages, ["a", "b"]);
+        assert_eq!(taken.overflowed, 1);
+
+        strategy.handle_error(anyhow::anyhow!("d"));
+        let (messages, taken) = take_messages(&strategy);
+        assert!(messages.is_empty(), "collected an error after stopping");
+        assert_eq!(taken.overflowed, 1);
+    }
+
+    #[test]
+    fn test_spill_to_file() {
+        let path =
+            std::env::temp_dir().join(format!("try-drop-collect-spill-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let strategy = CollectDropStrategy::bounded(1, Overflow::SpillToFile(path.clone()));
+        strategy.handle_error(anyhow::anyhow!("a"));
+        strategy.handle_error(anyhow::anyhow!("inner").context("b"));
+        strategy.handle_error(anyhow::anyhow!("c"));
+
+        let (messages, taken) = take_messages(&strategy);
+        assert_eq!(messages, ["a"]);
+        assert_eq!(taken.overflowed, 0);
+        assert_eq!(taken.spilled, 2);
+        assert_eq!(taken.spill_path.as_ref(), Some(&path));
+
+        let spilled = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(spilled, "b: inner\nc\n");
+    }
+
+    #[test]
+    fn test_spill_to_file_fails() {
+        let path = std::env::temp_dir()
+            .join("try-drop-collect-spill-missing")
+            .join("spill.log");
+        let strategy = CollectDropStrategy::bounded(0, Overflow::SpillToFile(path));
+        strategy.handle_error(anyhow::anyhow!("a"));
+
+        let taken = strategy.take_all();
+        assert_eq!(taken.overflowed, 1);
+        assert_eq!(taken.spilled, 0);
+        assert_eq!(taken.spill_path, None);
+    }
+}
@@ -0,0 +1,126 @@
+//! Types and traits for the counting drop strategy.
+
+use crate::TryDropStrategy;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "std")]
+use crate::tier::{self, HandlingTier};
+
+/// A drop strategy which counts how many drop errors it has handled, then delegates them to an
+/// inner drop strategy.
+#[cfg_attr(feature = "derives", derive(Debug, Default))]
+pub struct CountingDropStrategy<S: TryDropStrategy> {
+    /// The drop strategy to delegate the errors to.
+    pub inner: S,
+
+    count: AtomicUsize,
+
+    #[cfg(feature = "std")]
+    tier_counts: [AtomicUsize; 3],
+}
+
+impl<S: TryDropStrategy> CountingDropStrategy<S> {
+    /// Create a new counting drop strategy which delegates to the given drop strategy.
+    pub const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            count: AtomicUsize::new(0),
+
+            #[cfg(feature = "std")]
+            tier_counts: [
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+                AtomicUsize::new(0),
+            ],
+        }
+    }
+
+    /// Get how many drop errors this drop strategy has handled.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Get how many drop errors this drop strategy has handled in the given tier of error
+    /// handling.
+    #[cfg(feature = "std")]
+    pub fn count_in(&self, tier: HandlingTier) -> usize {
+        self.tier_counts[tier_index(tier)].load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(feature = "std")]
+fn tier_index(tier: HandlingTier) -> usize {
+    match tier {
+        HandlingTier::Primary => 0,
+        HandlingTier::Fallback => 1,
+        HandlingTier::LastResort => 2,
+    }
+}
+
+impl<S: TryDropStrategy> TryDropStrategy for CountingDropStrategy<S> {
+    fn handle_error(&self, error: crate::Error) {
+        self.count.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(feature = "std")]
+        self.tier_counts[tier_index(tier::current_tier(&error))].fetch_add(1, Ordering::Relaxed);
+
+        self.inner.handle_error(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{FallbackTryDropStrategyHandler, TryDropStrategyRef};
+    use crate::drop_strategies::NoOpDropStrategy;
+    use crate::test_utils::FallibleDropStrategy;
+    use crate::PureTryDrop;
+
+    struct Fails<'a>(&'a CountingDropStrategy<NoOpDropStrategy>);
+
+    impl PureTryDrop for Fails<'_> {
+        type Error = crate::Error;
+        type FallbackTryDropStrategy = NoOpDropStrategy;
+        type TryDropStrategy = CountingDropStrategy<NoOpDropStrategy>;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &NoOpDropStrategy
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            self.0
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            anyhow::bail!("this will always fail")
+        }
+    }
+
+    #[test]
+    fn test_counting_drop_strategy() {
+        let strategy = CountingDropStrategy::new(NoOpDropStrategy);
+
+        for _ in 0..3 {
+            drop(Fails(&strategy).adapt());
+        }
+
+        assert_eq!(strategy.count(), 3);
+    }
+
+    #[test]
+    fn test_count_in() {
+        let strategy = CountingDropStrategy::new(NoOpDropStrategy);
+        drop(Fails(&strategy).adapt());
+
+        let handler = FallbackTryDropStrategyHandler::new(
+            TryDropStrategyRef(&strategy),
+            FallibleDropStrategy,
+        );
+        handler.handle_error(anyhow::anyhow!("this will always fail"));
+
+        assert_eq!(strategy.count(), 2);
+        assert_eq!(strategy.count_in(HandlingTier::Primary), 1);
+        assert_eq!(strategy.count_in(HandlingTier::Fallback), 1);
+        assert_eq!(strategy.count_in(HandlingTier::LastResort), 0);
+    }
+}
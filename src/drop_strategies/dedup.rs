@@ -0,0 +1,163 @@
+//! Types and traits for the dedup drop strategy.
+
+use crate::FallibleTryDropStrategy;
+use std::string::{String, ToString};
+use std::sync::{Mutex, PoisonError};
+
+#[cfg_attr(feature = "derives", derive(Debug))]
+#[derive(Default)]
+struct Last {
+    message: Option<String>,
+    suppressed: usize,
+}
+
+/// A drop strategy which only hands an error to an inner drop strategy if its message differs from
+/// the message of the previous error, so that a drop error in a tight loop doesn't flood the logs.
+///
+/// Optionally, the number of suppressed duplicates can be reported once a different error comes
+/// in, as an error of its own.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct DedupDropStrategy<S: FallibleTryDropStrategy> {
+    /// The drop strategy to hand the errors to.
+    pub inner: S,
+
+    /// Whether or not to report how many duplicates were suppressed when a different error comes
+    /// in.
+    pub report_suppressed: bool,
+
+    last: Mutex<Last>,
+}
+
+impl<S: FallibleTryDropStrategy> DedupDropStrategy<S> {
+    /// Create a new dedup drop strategy which doesn't report suppressed duplicates.
+    pub const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            report_suppressed: false,
+            last: Mutex::new(Last {
+                message: None,
+                suppressed: 0,
+            }),
+        }
+    }
+
+    /// Report how many duplicates were suppressed when a different error comes in.
+    pub const fn with_suppressed_count(mut self) -> Self {
+        self.report_suppressed = true;
+        self
+    }
+
+    /// Forget the previous error, so that the next error is handed to the inner drop strategy even
+    /// if it's the same. Any suppressed duplicates which weren't reported are forgotten too.
+    pub fn reset(&self) {
+        *self.last.lock().unwrap_or_else(PoisonError::into_inner) = Last::default();
+    }
+}
+
+impl<S: FallibleTryDropStrategy + Default> Default for DedupDropStrategy<S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<S: FallibleTryDropStrategy> FallibleTryDropStrategy for DedupDropStrategy<S> {
+    type Error = S::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        let message = error.to_string();
+        let mut last = self.last.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if last.message.as_deref() == Some(message.as_str()) {
+            last.suppressed += 1;
+            return Ok(());
+        }
+
+        let suppressed = core::mem::take(&mut last.suppressed);
+        last.message = Some(message);
+        drop(last);
+
+        if self.report_suppressed && suppressed != 0 {
+            self.inner.try_handle_error(anyhow::anyhow!(
+                "the previous drop error was repeated {} more times",
+                suppressed
+            ))?;
+        }
+
+        self.inner.try_handle_error(error)
+    }
+
+    fn on_uninstall(&self) {
+        self.inner.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.inner.on_fork_child()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::AdHocDropStrategy;
+    use std::sync::Arc;
+    use std::vec::Vec;
+
+    type Seen = Arc<Mutex<Vec<String>>>;
+
+    fn recorder() -> (
+        DedupDropStrategy<AdHocDropStrategy<impl Fn(anyhow::Error)>>,
+        Seen,
+    ) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let s = Arc::clone(&seen);
+        let strategy = DedupDropStrategy::new(AdHocDropStrategy(move |error: anyhow::Error| {
+            s.lock().unwrap().push(error.to_string())
+        }));
+        (strategy, seen)
+    }
+
+    #[test]
+    fn test_dedup_drop_strategy() {
+        let (strategy, seen) = recorder();
+
+        for _ in 0..3 {
+            strategy
+                .try_handle_error(anyhow::anyhow!("this will always fail"))
+                .unwrap();
+        }
+
+        assert_eq!(*seen.lock().unwrap(), ["this will always fail"]);
+    }
+
+    #[test]
+    fn test_suppressed_count() {
+        let (strategy, seen) = recorder();
+        let strategy = strategy.with_suppressed_count();
+
+        for message in ["a", "a", "a", "b", "b", "a"] {
+            strategy
+                .try_handle_error(anyhow::Error::msg(message))
+                .unwrap();
+        }
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "a",
+                "the previous drop error was repeated 2 more times",
+                "b",
+                "the previous drop error was repeated 1 more times",
+                "a",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reset() {
+        let (strategy, seen) = recorder();
+        strategy.try_handle_error(anyhow::Error::msg("a")).unwrap();
+        strategy.reset();
+        strategy.try_handle_error(anyhow::Error::msg("a")).unwrap();
+        assert_eq!(*seen.lock().unwrap(), ["a", "a"]);
+    }
+}
@@ -0,0 +1,140 @@
+//! Zero sized drop strategies which are always available, intended to be used as defaults for
+//! type parameters.
+//!
+//! Unlike the other drop strategies, these aren't behind any feature and can't be configured, so
+//! that generic code can use them as defaults for its drop strategy type parameters without
+//! having to worry about which features are enabled:
+//!
+//! ```rust
+//! use try_drop::drop_strategies::{DefaultNoOp, DefaultPanic};
+//! use try_drop::{FallibleTryDropStrategy, TryDropStrategy};
+//!
+//! pub struct Connection<D: FallibleTryDropStrategy = DefaultNoOp, F: TryDropStrategy = DefaultPanic> {
+//!     strategy: D,
+//!     fallback: F,
+//! }
+//! ```
+//!
+//! If you need to configure them, use the [`NoOpDropStrategy`](super::NoOpDropStrategy) or the
+//! [`PanicDropStrategy`](super::PanicDropStrategy) instead.
+
+use crate::{InfallibleStrategy, TryDropStrategy};
+
+/// A drop strategy which does nothing if a drop error occurs. This is always available, unlike
+/// the `NoOpDropStrategy`.
+///
+/// This implements [`FallibleTryDropStrategy`](crate::FallibleTryDropStrategy) as well, through
+/// [`TryDropStrategy`].
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Default)]
+pub struct DefaultNoOp;
+
+impl TryDropStrategy for DefaultNoOp {
+    fn handle_error(&self, _error: crate::Error) {}
+}
+
+// SAFETY: ignoring the error never fails.
+unsafe impl InfallibleStrategy for DefaultNoOp {}
+
+/// A drop strategy which panics with a fixed message if a drop error occurs. This is always
+/// available, unlike the `PanicDropStrategy`, and doesn't need to allocate.
+///
+/// This implements [`FallibleTryDropStrategy`](crate::FallibleTryDropStrategy) as well, through
+/// [`TryDropStrategy`].
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Default)]
+pub struct DefaultPanic;
+
+impl DefaultPanic {
+    /// The message to panic with.
+    pub const MESSAGE: &'static str = "error occurred when dropping an object";
+}
+
+impl TryDropStrategy for DefaultPanic {
+    fn handle_error(&self, error: crate::Error) {
+        panic!("{}: {:?}", Self::MESSAGE, error)
+    }
+}
+
+// SAFETY: this only ever panics.
+unsafe impl InfallibleStrategy for DefaultPanic {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FallibleTryDropStrategy, PureTryDrop};
+
+    /// A downstream style generic type, using the defaults for its drop strategies.
+    struct Resource<D: FallibleTryDropStrategy = DefaultNoOp, F: TryDropStrategy = DefaultPanic> {
+        fail: bool,
+        strategy: D,
+        fallback: F,
+    }
+
+    impl Resource {
+        fn new(fail: bool) -> Self {
+            Self {
+                fail,
+                strategy: DefaultNoOp,
+                fallback: DefaultPanic,
+            }
+        }
+    }
+
+    impl<D: FallibleTryDropStrategy, F: TryDropStrategy> PureTryDrop for Resource<D, F> {
+        type Error = crate::Error;
+        type FallbackTryDropStrategy = F;
+        type TryDropStrategy = D;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &self.fallback
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &self.strategy
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            if self.fail {
+                anyhow::bail!("this will always fail")
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_no_op() {
+        drop(Resource::new(true).adapt());
+    }
+
+    #[test]
+    fn test_default_no_op_is_infallible() {
+        DefaultNoOp
+            .try_handle_error(anyhow::anyhow!("this will always fail"))
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "error occurred when dropping an object: this will always fail")]
+    fn test_default_panic() {
+        DefaultPanic.handle_error(anyhow::anyhow!("this will always fail"))
+    }
+
+    #[test]
+    #[should_panic(expected = "error occurred when dropping an object: this will always fail")]
+    fn test_default_panic_as_primary() {
+        let resource: Resource<DefaultPanic, DefaultNoOp> = Resource {
+            fail: true,
+            strategy: DefaultPanic,
+            fallback: DefaultNoOp,
+        };
+        drop(resource.adapt());
+    }
+}
@@ -1,4 +1,11 @@
-use crate::TryDropStrategy;
+//! Types and traits for the exit drop strategy.
+
+use super::terminal;
+use crate::{InfallibleStrategy, TryDropStrategy};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use std::io::{self, Write};
 use std::process;
 
 /// A drop strategy which exits the program with a specific exit code if the drop fails.
@@ -9,6 +16,13 @@ use std::process;
 pub struct ExitDropStrategy {
     /// The exit code to use if the drop fails.
     pub exit_code: i32,
+
+    /// Whether or not to flush standard output and standard error before exiting, so that
+    /// buffered output isn't lost.
+    pub flush_before_exit: bool,
+
+    // a function pointer rather than a `TerminalHook`, so that this stays `Copy`
+    before_terminal: Option<BeforeTerminal>,
 }
 
 impl ExitDropStrategy {
@@ -17,7 +31,73 @@ impl ExitDropStrategy {
 
     /// Create a new exit drop strategy.
     pub const fn new(exit_code: i32) -> Self {
-        Self { exit_code }
+        Self {
+            exit_code,
+            flush_before_exit: true,
+            before_terminal: None,
+        }
+    }
+
+    /// Set whether or not to flush standard output and standard error before exiting. This also
+    /// decides whether or not the drop strategies registered with
+    /// `try_drop::flush::register` are flushed, if the `async-flush` feature is enabled.
+    pub const fn with_flush(mut self, flush_before_exit: bool) -> Self {
+        self.flush_before_exit = flush_before_exit;
+        self
+    }
+
+    /// Run `f` with the error right before exiting, e.g. to flush metrics. It's run before
+    /// standard output and standard error are flushed. If it panics, the panic is swallowed.
+    ///
+    /// Unlike [`PanicDropStrategy::with_before_terminal`](super::PanicDropStrategy::with_before_terminal),
+    /// this only takes a function pointer, so that this drop strategy stays [`Copy`]. A closure
+    /// which doesn't capture anything can still be used.
+    pub const fn with_before_terminal(mut self, f: fn(&crate::Error)) -> Self {
+        self.before_terminal = Some(BeforeTerminal(f));
+        self
+    }
+}
+
+/// A hook run right before exiting, which compares, orders and hashes by its address like
+/// [`TerminalHook`](terminal::TerminalHook)s do.
+#[derive(Copy, Clone)]
+struct BeforeTerminal(fn(&crate::Error));
+
+impl BeforeTerminal {
+    fn addr(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl fmt::Debug for BeforeTerminal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&(self.addr() as *const ()), f)
+    }
+}
+
+impl PartialEq for BeforeTerminal {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl Eq for BeforeTerminal {}
+
+impl PartialOrd for BeforeTerminal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BeforeTerminal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+impl Hash for BeforeTerminal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr().hash(state)
     }
 }
 
@@ -28,10 +108,52 @@ impl Default for ExitDropStrategy {
 }
 
 impl TryDropStrategy for ExitDropStrategy {
-    fn handle_error(&self, _error: crate::Error) {
+    fn handle_error(&self, error: crate::Error) {
+        if let Some(hook) = self.before_terminal {
+            terminal::call_hook(&hook.0, &error)
+        }
+
+        if self.flush_before_exit {
+            terminal::flush_registered();
+
+            // we're about to exit anyways, there's nothing useful to do if flushing fails
+            let _ = io::stdout().flush();
+            let _ = io::stderr().flush();
+        }
+
         process::exit(self.exit_code)
     }
 }
 
-// it is not possible to create tests for this strategy because this exits the program, which can't
-// be caught.
\ No newline at end of file
+// SAFETY: the program exits regardless of whether flushing fails.
+unsafe impl InfallibleStrategy for ExitDropStrategy {}
+
+// it is not possible to test the handling of errors in this strategy because this exits the
+// program, which can't be caught.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flush_by_default() {
+        assert!(ExitDropStrategy::default().flush_before_exit);
+        assert!(ExitDropStrategy::new(2).flush_before_exit);
+    }
+
+    #[test]
+    fn test_with_flush() {
+        let strategy = ExitDropStrategy::new(2).with_flush(false);
+        assert_eq!(strategy.exit_code, 2);
+        assert!(!strategy.flush_before_exit);
+    }
+
+    #[cfg(feature = "derives")]
+    #[test]
+    fn test_before_terminal_eq() {
+        fn hook(_: &crate::Error) {}
+
+        let strategy = ExitDropStrategy::new(2).with_before_terminal(hook);
+        assert_eq!(strategy, strategy);
+        assert_ne!(strategy, ExitDropStrategy::new(2));
+    }
+}
@@ -0,0 +1,76 @@
+//! Types and traits for the [`fmt::Write`](core::fmt::Write) drop strategy.
+
+use crate::FallibleTryDropStrategy;
+use core::fmt;
+use core::fmt::Write;
+
+#[cfg(feature = "parking_lot")]
+use parking_lot::Mutex;
+
+#[cfg(not(feature = "parking_lot"))]
+use spin::Mutex;
+
+/// A drop strategy which writes the message of an error to a [`fmt::Write`]r. Unlike the
+/// [`WriteDropStrategy`], this can be used in `no_std` environments.
+///
+/// [`WriteDropStrategy`]: crate::drop_strategies::WriteDropStrategy
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct FmtWriteDropStrategy<W: Write> {
+    /// The writer to write to.
+    pub writer: Mutex<W>,
+
+    /// Whether or not to append a newline to the end of the message.
+    pub new_line: bool,
+}
+
+impl<W: Write> FmtWriteDropStrategy<W> {
+    /// Creates a new [`FmtWriteDropStrategy`] with the given writer.
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+            new_line: true,
+        }
+    }
+
+    /// Sets whether or not to append a newline to the end of the message.
+    pub fn new_line(&mut self, new_line: bool) -> &mut Self {
+        self.new_line = new_line;
+        self
+    }
+}
+
+impl<W: Write> FallibleTryDropStrategy for FmtWriteDropStrategy<W> {
+    type Error = fmt::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        #[cfg(feature = "std")]
+        let error = crate::fmt::render(&error);
+
+        let mut writer = self.writer.lock();
+        write!(writer, "{error}")?;
+
+        if self.new_line {
+            writer.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::PanicDropStrategy;
+    use crate::test_utils::fallible_given;
+    use std::string::String;
+
+    #[test]
+    fn test_fmt_write_drop_strategy() {
+        let mut string = String::new();
+        drop(fallible_given(
+            FmtWriteDropStrategy::new(&mut string),
+            PanicDropStrategy::DEFAULT,
+        ));
+        assert_eq!(string, "this will always fail\n");
+    }
+}
@@ -0,0 +1,185 @@
+//! Types and traits for the hook drop strategy, which hands drop errors to the hook of another
+//! error reporting framework, such as a crash reporter, along with their [`DropContext`].
+
+use crate::drop_context::DropContext;
+use crate::TryDropStrategy;
+use std::error::Error as StdError;
+use std::string::{String, ToString};
+
+/// A minimal error reporting hook, which is given the structured context of a drop error
+/// alongside it, so that it can build rich reports.
+///
+/// This is implemented for functions which take both.
+pub trait ErrorHook {
+    /// Report the given drop error.
+    fn report(&self, error: &(dyn StdError + 'static), context: &DropContext);
+
+    /// Report the given drop error, as the `anyhow` error it's handled as. By default, this
+    /// forwards to [`ErrorHook::report`].
+    fn report_anyhow(&self, error: &anyhow::Error, context: &DropContext) {
+        self.report(error.as_ref(), context)
+    }
+}
+
+impl<F: Fn(&(dyn StdError + 'static), &DropContext)> ErrorHook for F {
+    fn report(&self, error: &(dyn StdError + 'static), context: &DropContext) {
+        self(error, context)
+    }
+}
+
+/// A drop strategy which reports every drop error, along with its [`DropContext`], to a hook.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+)]
+pub struct HookDropStrategy<H: ErrorHook>(pub H);
+
+impl<H: ErrorHook> TryDropStrategy for HookDropStrategy<H> {
+    fn handle_error(&self, error: crate::Error) {
+        let context = DropContext::of(&error);
+        self.0.report_anyhow(&error, &context)
+    }
+}
+
+/// Adapts a function which takes an `anyhow` error into an [`ErrorHook`], ignoring the context.
+///
+/// If it's reported to through [`ErrorHook::report`] instead of by the [`HookDropStrategy`], the
+/// function is given a new `anyhow` error made out of the message of the error.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+)]
+pub struct AnyhowFnHook<F: Fn(&anyhow::Error)>(pub F);
+
+impl<F: Fn(&anyhow::Error)> ErrorHook for AnyhowFnHook<F> {
+    fn report(&self, error: &(dyn StdError + 'static), _context: &DropContext) {
+        self.0(&anyhow::Error::msg(error.to_string()))
+    }
+
+    fn report_anyhow(&self, error: &anyhow::Error, _context: &DropContext) {
+        self.0(error)
+    }
+}
+
+/// Adapts a function which takes the message of an error into an [`ErrorHook`], ignoring the
+/// context.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+)]
+pub struct MessageFnHook<F: Fn(String)>(pub F);
+
+impl<F: Fn(String)> ErrorHook for MessageFnHook<F> {
+    fn report(&self, error: &(dyn StdError + 'static), _context: &DropContext) {
+        self.0(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_context::ErrorCategory;
+    use crate::test_utils::FallibleDropStrategy;
+    use crate::tier::HandlingTier;
+    use crate::PureTryDrop;
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    type Reports = Rc<RefCell<Vec<(String, DropContext)>>>;
+
+    fn recorder() -> (impl Fn(&(dyn StdError + 'static), &DropContext), Reports) {
+        let reports = Rc::new(RefCell::new(Vec::new()));
+        let r = Rc::clone(&reports);
+        let hook = move |error: &(dyn StdError + 'static), context: &DropContext| {
+            r.borrow_mut().push((error.to_string(), *context))
+        };
+        (hook, reports)
+    }
+
+    struct BrokenPipe<D, H: ErrorHook> {
+        primary: D,
+        fallback: HookDropStrategy<H>,
+    }
+
+    impl<D: crate::FallibleTryDropStrategy, H: ErrorHook> PureTryDrop for BrokenPipe<D, H> {
+        type Error = io::Error;
+        type FallbackTryDropStrategy = HookDropStrategy<H>;
+        type TryDropStrategy = D;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &self.fallback
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &self.primary
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "failed to flush"))
+        }
+    }
+
+    #[test]
+    fn test_hook_drop_strategy() {
+        let (hook, reports) = recorder();
+        drop(
+            BrokenPipe {
+                primary: FallibleDropStrategy,
+                fallback: HookDropStrategy(hook),
+            }
+            .adapt(),
+        );
+
+        let reports = reports.borrow();
+        let (message, context) = &reports[0];
+        assert_eq!(reports.len(), 1);
+        assert_eq!(message, "failed to flush");
+        // the hook is a closure, so only the start of the type name can be written down
+        let type_name = context.type_name.expect("the type name wasn't passed");
+        assert!(
+            type_name.starts_with(&std::format!("{}::BrokenPipe<", module_path!())),
+            "{type_name}"
+        );
+        assert_eq!(context.tier, HandlingTier::Fallback);
+        assert_eq!(
+            context.category,
+            ErrorCategory::Io(io::ErrorKind::BrokenPipe)
+        );
+    }
+
+    #[test]
+    fn test_hook_as_primary() {
+        let (hook, reports) = recorder();
+        HookDropStrategy(hook).handle_error(anyhow::anyhow!("this will always fail"));
+
+        let reports = reports.borrow();
+        let (message, context) = &reports[0];
+        assert_eq!(message, "this will always fail");
+        assert_eq!(context.type_name, None);
+        assert_eq!(context.tier, HandlingTier::Primary);
+        assert_eq!(context.category, ErrorCategory::Other);
+    }
+
+    #[test]
+    fn test_fn_hooks() {
+        let messages = RefCell::new(Vec::new());
+        let error = anyhow::anyhow!("this will always fail").context("failed to drop");
+
+        HookDropStrategy(MessageFnHook(|message| messages.borrow_mut().push(message)))
+            .handle_error(anyhow::anyhow!("this will always fail"));
+        HookDropStrategy(AnyhowFnHook(|error: &anyhow::Error| {
+            messages.borrow_mut().push(std::format!("{error:#}"))
+        }))
+        .handle_error(error);
+
+        assert_eq!(
+            *messages.borrow(),
+            [
+                "this will always fail",
+                "failed to drop: this will always fail"
+            ]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Types and traits for the log drop strategy. This is built on top of the `log` crate.
+
+use crate::TryDropStrategy;
+
+pub use log::Level;
+
+/// A drop strategy which logs the message of an error through the `log` facade.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub struct LogDropStrategy {
+    /// The level to log the error at.
+    pub level: Level,
+
+    /// The target of the log record. If this is `None`, the default target is used.
+    pub target: Option<&'static str>,
+}
+
+impl LogDropStrategy {
+    /// The default log drop strategy, which logs at the error level with the default target.
+    pub const DEFAULT: Self = Self::new();
+
+    /// Create a new log drop strategy.
+    pub const fn new() -> Self {
+        Self {
+            level: Level::Error,
+            target: None,
+        }
+    }
+
+    /// Set the level to log the error at.
+    pub const fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set the target of the log record.
+    pub const fn with_target(mut self, target: &'static str) -> Self {
+        self.target = Some(target);
+        self
+    }
+}
+
+impl Default for LogDropStrategy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TryDropStrategy for LogDropStrategy {
+    fn handle_error(&self, error: crate::Error) {
+        #[cfg(feature = "std")]
+        let error = crate::fmt::render(&error);
+
+        match self.target {
+            Some(target) => log::log!(target: target, self.level, "{}", error),
+            None => log::log!(self.level, "{}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::PanicDropStrategy;
+    use crate::test_utils::{ErrorsOnDrop, Fallible};
+    use crate::PureTryDrop;
+    use log::{LevelFilter, Log, Metadata, Record};
+    use std::string::{String, ToString};
+    use std::sync::Mutex;
+    use std::vec::Vec;
+
+    struct CapturingLogger(Mutex<Vec<(Level, String, String)>>);
+
+    impl Log for CapturingLogger {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push((
+                record.level(),
+                record.target().to_string(),
+                record.args().to_string(),
+            ))
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+
+    #[test]
+    fn test_log_drop_strategy() {
+        log::set_logger(&LOGGER).expect("logger was already set");
+        log::set_max_level(LevelFilter::Trace);
+
+        drop(
+            ErrorsOnDrop::<Fallible, _>::given(
+                LogDropStrategy::DEFAULT,
+                PanicDropStrategy::DEFAULT,
+            )
+            .adapt(),
+        );
+        drop(
+            ErrorsOnDrop::<Fallible, _>::given(
+                LogDropStrategy::new()
+                    .with_level(Level::Warn)
+                    .with_target("drops"),
+                PanicDropStrategy::DEFAULT,
+            )
+            .adapt(),
+        );
+
+        let records = LOGGER.0.lock().unwrap();
+        assert_eq!(
+            *records,
+            [
+                (
+                    Level::Error,
+                    module_path!().trim_end_matches("::tests").to_string(),
+                    "this will always fail".to_string()
+                ),
+                (
+                    Level::Warn,
+                    "drops".to_string(),
+                    "this will always fail".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Types and traits for the metrics drop strategy. This is built on top of the `metrics` crate.
+
+use crate::TryDropStrategy;
+use metrics::{IntoLabels, Label, SharedString};
+use std::vec::Vec;
+
+/// The name of the counter which is incremented by default.
+pub const DEFAULT_COUNTER_NAME: &str = "try_drop_errors";
+
+/// A drop strategy which increments a counter through the `metrics` crate for every drop error,
+/// then delegates it to an inner drop strategy. This is useful to graph how often values fail to
+/// drop.
+#[cfg_attr(feature = "derives", derive(Debug, Clone, Eq, PartialEq))]
+pub struct MetricsDropStrategy<S: TryDropStrategy> {
+    /// The drop strategy to delegate the errors to.
+    pub inner: S,
+
+    /// The name of the counter to increment.
+    pub name: SharedString,
+
+    /// The labels of the counter to increment.
+    pub labels: Vec<Label>,
+}
+
+impl<S: TryDropStrategy> MetricsDropStrategy<S> {
+    /// Create a new metrics drop strategy which increments the [`DEFAULT_COUNTER_NAME`] counter
+    /// without any labels, then delegates to the given drop strategy.
+    pub const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            name: SharedString::const_str(DEFAULT_COUNTER_NAME),
+            labels: Vec::new(),
+        }
+    }
+
+    /// Set the name of the counter to increment.
+    pub fn with_name(mut self, name: impl Into<SharedString>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Add a label to the counter to increment.
+    pub fn with_label(
+        mut self,
+        key: impl Into<SharedString>,
+        value: impl Into<SharedString>,
+    ) -> Self {
+        self.labels.push(Label::new(key, value));
+        self
+    }
+
+    /// Add the given labels to the counter to increment.
+    pub fn with_labels(mut self, labels: impl IntoLabels) -> Self {
+        self.labels.extend(labels.into_labels());
+        self
+    }
+}
+
+impl<S: TryDropStrategy> TryDropStrategy for MetricsDropStrategy<S> {
+    fn handle_error(&self, error: crate::Error) {
+        metrics::counter!(self.name.clone(), self.labels.iter()).increment(1);
+        self.inner.handle_error(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::NoOpDropStrategy;
+    use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, Unit};
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Records the counters which are registered through it, ignoring everything else.
+    #[derive(Default)]
+    struct TestRecorder {
+        counters: Mutex<HashMap<Key, Arc<AtomicU64>>>,
+    }
+
+    impl TestRecorder {
+        fn count(&self, key: &Key) -> u64 {
+            self.counters
+                .lock()
+                .unwrap()
+                .get(key)
+                .map_or(0, |count| count.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let mut counters = self.counters.lock().unwrap();
+            Counter::from_arc(Arc::clone(counters.entry(key.clone()).or_default()))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_metrics_drop_strategy() {
+        let recorder = TestRecorder::default();
+        let strategy = MetricsDropStrategy::new(NoOpDropStrategy);
+        let labeled = MetricsDropStrategy::new(NoOpDropStrategy)
+            .with_name("connection_drop_errors")
+            .with_label("service", "database");
+
+        metrics::with_local_recorder(&recorder, || {
+            for _ in 0..3 {
+                strategy.handle_error(anyhow::anyhow!("this will always fail"));
+            }
+
+            labeled.handle_error(anyhow::anyhow!("this will always fail"));
+        });
+
+        assert_eq!(
+            recorder.count(&Key::from_static_name(DEFAULT_COUNTER_NAME)),
+            3
+        );
+        assert_eq!(
+            recorder.count(&Key::from_parts(
+                "connection_drop_errors",
+                [Label::new("service", "database")].iter()
+            )),
+            1
+        );
+        assert_eq!(
+            recorder.count(&Key::from_static_name("connection_drop_errors")),
+            0
+        );
+    }
+}
@@ -1,25 +1,87 @@
 //! Numerous strategies for handling drop errors.
 
+pub mod defaults;
+
+#[cfg(feature = "std")]
+pub mod available;
+
 #[cfg(feature = "ds-abort")]
-mod abort;
+pub mod abort;
 
 #[cfg(feature = "ds-broadcast")]
 pub mod broadcast;
 
+#[cfg(feature = "async-strategy")]
+pub mod async_strategy;
+
+#[cfg(feature = "ds-channel")]
+pub mod channel;
+
+#[cfg(feature = "ds-collect")]
+pub mod collect;
+
+#[cfg(feature = "ds-counting")]
+pub mod counting;
+
+#[cfg(feature = "ds-dedup")]
+pub mod dedup;
+
 #[cfg(feature = "ds-exit")]
-mod exit;
+pub mod exit;
+
+#[cfg(feature = "ds-hook")]
+pub mod hook;
+
+#[cfg(feature = "ds-log")]
+pub mod log;
+
+#[cfg(feature = "ds-metrics")]
+pub mod metrics;
 
 #[cfg(feature = "ds-noop")]
-mod noop;
+pub mod noop;
 
 #[cfg(feature = "ds-panic")]
-mod panic;
+pub mod panic;
+
+#[cfg(feature = "ds-predicate")]
+pub mod predicate;
+
+#[cfg(feature = "ds-rate-limit")]
+pub mod rate_limit;
+
+#[cfg(feature = "ds-salvage")]
+pub mod salvage;
+
+#[cfg(feature = "ds-sampling")]
+pub mod sampling;
+
+#[cfg(feature = "ds-sequence")]
+pub mod sequence;
+
+#[cfg(feature = "ds-syslog")]
+pub mod syslog;
+
+#[cfg(any(feature = "ds-exit", feature = "ds-panic"))]
+pub mod terminal;
+
+#[cfg(feature = "ds-timeout")]
+pub mod timeout;
+
+#[cfg(feature = "ds-tracing")]
+pub mod tracing;
+
+#[cfg(feature = "ds-truncate")]
+pub mod truncate;
 
 #[cfg(feature = "ds-write")]
-mod write;
+pub mod write;
+
+#[cfg(feature = "ds-fmt-write")]
+pub mod fmt_write;
 
 #[cfg(feature = "ds-adhoc")]
-mod adhoc;
+pub mod adhoc;
 
 #[cfg(feature = "ds-once-cell")]
 pub mod once_cell;
@@ -27,28 +89,89 @@ pub mod once_cell;
 #[cfg(feature = "ds-unreachable")]
 pub mod unreachable;
 
+pub use defaults::{DefaultNoOp, DefaultPanic};
+
+#[cfg(feature = "std")]
+pub use available::{available, find, StrategyDescriptor};
+
 #[cfg(feature = "ds-abort")]
 pub use abort::AbortDropStrategy;
 
 #[cfg(feature = "ds-broadcast")]
 pub use broadcast::BroadcastDropStrategy;
 
+#[cfg(feature = "async-strategy")]
+pub use async_strategy::{BlockOnStrategy, SpawnStrategy};
+
+#[cfg(feature = "ds-channel")]
+pub use channel::ChannelDropStrategy;
+
+#[cfg(feature = "ds-collect")]
+pub use collect::CollectDropStrategy;
+
+#[cfg(feature = "ds-counting")]
+pub use counting::CountingDropStrategy;
+
+#[cfg(feature = "ds-dedup")]
+pub use dedup::DedupDropStrategy;
+
 #[cfg(feature = "ds-exit")]
 pub use exit::ExitDropStrategy;
 
+#[cfg(feature = "ds-hook")]
+pub use hook::HookDropStrategy;
+
+#[cfg(feature = "ds-log")]
+pub use self::log::LogDropStrategy;
+
+#[cfg(feature = "ds-metrics")]
+pub use self::metrics::MetricsDropStrategy;
+
 #[cfg(feature = "ds-noop")]
 pub use noop::NoOpDropStrategy;
 
 #[cfg(feature = "ds-panic")]
 pub use panic::PanicDropStrategy;
 
+#[cfg(feature = "ds-predicate")]
+pub use predicate::{FalliblePredicateDropStrategy, PredicateDropStrategy};
+
+#[cfg(feature = "ds-rate-limit")]
+pub use rate_limit::RateLimitDropStrategy;
+
+#[cfg(feature = "ds-salvage")]
+pub use salvage::SalvageDropStrategy;
+
+#[cfg(feature = "ds-sampling")]
+pub use sampling::SamplingDropStrategy;
+
+#[cfg(feature = "ds-sequence")]
+pub use sequence::{FirstSuccessDropStrategy, SequenceDropStrategy, VecDropStrategy};
+
+#[cfg(feature = "ds-syslog")]
+pub use self::syslog::SyslogDropStrategy;
+
+#[cfg(feature = "ds-timeout")]
+pub use timeout::TimeoutDropStrategy;
+
+#[cfg(feature = "ds-tracing")]
+pub use self::tracing::{SpanExtensionDropStrategy, TracingDropStrategy, TryDropLayer};
+
+#[cfg(feature = "ds-truncate")]
+pub use truncate::TruncateDropStrategy;
+
 #[cfg(feature = "ds-write")]
-pub use write::{ThreadUnsafeWriteDropStrategy, WriteDropStrategy};
+pub use write::{
+    ColorChoice, DualFormatWriteStrategy, ThreadUnsafeWriteDropStrategy, WriteDropStrategy,
+};
+
+#[cfg(feature = "ds-fmt-write")]
+pub use fmt_write::FmtWriteDropStrategy;
 
 #[cfg(feature = "ds-adhoc")]
 pub use adhoc::{
     AdHocDropStrategy, AdHocFallibleDropStrategy, IntoAdHocDropStrategy,
-    IntoAdHocFallibleDropStrategy,
+    IntoAdHocFallibleDropStrategy, IntoAdHocResultDropStrategy,
 };
 
 #[cfg(feature = "ds-adhoc-mut")]
@@ -60,4 +183,162 @@ pub use adhoc::{
 };
 
 #[cfg(feature = "ds-once-cell")]
-pub use self::once_cell::{OnceCell, OnceCellDropStrategy, ThreadUnsafeOnceCellDropStrategy};
+pub use self::once_cell::{
+    ErrorCell, OnceCell, OnceCellDropStrategy, ThreadUnsafeErrorCell,
+    ThreadUnsafeOnceCellDropStrategy,
+};
+
+#[cfg(test)]
+#[cfg(feature = "std")]
+mod tests {
+    use std::borrow::ToOwned;
+    use std::path::{Path, PathBuf};
+    use std::string::String;
+    use std::vec::Vec;
+    use std::{env, format, fs};
+
+    /// The file of the module `name` declared in a module whose submodules are in `dir`.
+    fn module_file(dir: &Path, name: &str) -> PathBuf {
+        let file = dir.join(format!("{name}.rs"));
+
+        if file.exists() {
+            file
+        } else {
+            dir.join(name).join("mod.rs")
+        }
+    }
+
+    /// Split a `use` tree into the paths it imports and the names it imports them as.
+    fn expand_use(prefix: &str, tree: &str, imports: &mut Vec<(String, String)>) {
+        let Some(open) = tree.find('{') else {
+            let (path, name) = tree
+                .split_once(" as ")
+                .unwrap_or((tree, tree.rsplit("::").next().unwrap()));
+            imports.push((format!("{prefix}{path}"), name.to_owned()));
+            return;
+        };
+
+        let prefix = format!("{prefix}{}", &tree[..open]);
+        let inner = &tree[open + 1..tree.len() - 1];
+        let (mut depth, mut start) = (0, 0);
+
+        for (index, c) in inner.char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                ',' if depth == 0 => {
+                    expand_use(&prefix, &inner[start..index], imports);
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+
+        if start < inner.len() {
+            expand_use(&prefix, &inner[start..], imports)
+        }
+    }
+
+    /// Collect the public items of the module in `file`, whose submodules are in `dir`, as if they
+    /// were declared at `path`. Private submodules are only followed through glob re-exports.
+    fn collect(file: &Path, dir: &Path, path: &str, api: &mut Vec<String>) {
+        let source = fs::read_to_string(file).unwrap();
+        let private_modules: Vec<_> = source
+            .lines()
+            .filter_map(|line| line.strip_prefix("mod ")?.strip_suffix(';'))
+            .collect();
+        let mut lines = source.lines();
+        let mut deprecated = false;
+
+        while let Some(line) = lines.next() {
+            if line.starts_with("#[deprecated") {
+                deprecated = true;
+                continue;
+            }
+
+            let Some(item) = line.strip_prefix("pub ") else {
+                continue;
+            };
+            let attributes = if core::mem::take(&mut deprecated) {
+                "#[deprecated] "
+            } else {
+                ""
+            };
+
+            if let Some(tree) = item.strip_prefix("use ") {
+                let mut tree = tree.to_owned();
+
+                while !tree.ends_with(';') {
+                    tree.push(' ');
+                    tree.push_str(lines.next().unwrap().trim());
+                }
+
+                let tree = tree.trim_end_matches(';').replace("{ ", "{").replace(", ", ",");
+                let mut imports = Vec::new();
+                expand_use("", tree.replace(",}", "}").trim(), &mut imports);
+
+                for (source, name) in imports {
+                    let module = source.trim_start_matches("self::").strip_suffix("::*");
+
+                    match module.filter(|module| private_modules.contains(module)) {
+                        Some(module) => {
+                            collect(&module_file(dir, module), &dir.join(module), path, api)
+                        }
+                        None => api.push(format!("{attributes}pub use {path}::{name} = {source}")),
+                    }
+                }
+
+                continue;
+            }
+
+            let mut words = item
+                .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .filter(|word| !word.is_empty() && !matches!(*word, "unsafe" | "async"));
+            let (mut kind, mut name) = (words.next().unwrap(), words.next().unwrap());
+
+            if (kind, name) == ("const", "fn") {
+                kind = "fn";
+                name = words.next().unwrap();
+            }
+
+            let mut entry = format!("{attributes}pub {kind} {path}::{name}");
+
+            match kind {
+                "type" => {
+                    let (_, target) = item.split_once('=').unwrap();
+                    entry.push_str(&format!(" = {}", target.trim().trim_end_matches(';')));
+                }
+                "mod" => collect(
+                    &module_file(dir, name),
+                    &dir.join(name),
+                    &format!("{path}::{name}"),
+                    api,
+                ),
+                _ => {}
+            }
+
+            api.push(entry);
+        }
+    }
+
+    // If this fails, a public item has been added, removed or moved. Make sure that it was
+    // deliberate, then run this test again with `UPDATE_PUBLIC_API=1` to update the snapshot.
+    #[test]
+    fn test_public_api() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/drop_strategies");
+        let path = "try_drop::drop_strategies";
+        let mut api = Vec::new();
+        collect(&dir.join("mod.rs"), &dir, path, &mut api);
+        api.sort();
+
+        let mut actual = api.join("\n");
+        actual.push('\n');
+        let snapshot = dir.join("public_api.txt");
+
+        if env::var_os("UPDATE_PUBLIC_API").is_some() {
+            fs::write(&snapshot, &actual).unwrap();
+        }
+
+        assert_eq!(actual, fs::read_to_string(snapshot).unwrap_or_default());
+    }
+}
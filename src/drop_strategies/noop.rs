@@ -1,4 +1,6 @@
-use crate::TryDropStrategy;
+//! Types and traits for the no-op drop strategy.
+
+use crate::{InfallibleStrategy, TryDropStrategy};
 
 /// A drop strategy which does nothing if a drop error occurs.
 #[cfg_attr(
@@ -11,6 +13,9 @@ impl TryDropStrategy for NoOpDropStrategy {
     fn handle_error(&self, _error: crate::Error) {}
 }
 
+// SAFETY: ignoring the error never fails.
+unsafe impl InfallibleStrategy for NoOpDropStrategy {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
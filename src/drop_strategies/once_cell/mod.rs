@@ -1,17 +1,17 @@
 //! Types and utilities for the once cell try drop strategy.
 mod thread_unsafe;
-pub use thread_unsafe::ThreadUnsafeOnceCellDropStrategy;
+pub use thread_unsafe::{ThreadUnsafeErrorCell, ThreadUnsafeOnceCellDropStrategy};
 mod private {
     pub trait Sealed {}
 }
 
 use crate::{FallibleTryDropStrategy, TryDropStrategy};
 pub use once_cell::sync::OnceCell;
+pub use once_cell::unsync::OnceCell as ThreadUnsafeOnceCell;
 use std::error::Error as StdError;
 use std::fmt;
 use std::marker::PhantomData;
-use std::sync::Arc;
-pub use thread_unsafe::*;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 /// Ignore the occupied error value and continue.
 #[cfg_attr(
@@ -28,10 +28,24 @@ impl private::Sealed for Ignore {}
     feature = "derives",
     derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
 )]
-pub enum Error {}
+pub enum ErrorIfOccupied {}
 
-impl Mode for Error {}
-impl private::Sealed for Error {}
+impl Mode for ErrorIfOccupied {}
+impl private::Sealed for ErrorIfOccupied {}
+
+/// Replace the occupied error value with the newest one.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub enum Replace {}
+
+impl Mode for Replace {}
+impl private::Sealed for Replace {}
+
+/// Return an error with the underlying error value if the cell is occupied.
+#[deprecated(note = "renamed to `ErrorIfOccupied`")]
+pub type Error = ErrorIfOccupied;
 
 /// How to handle cases where the error value is already occupied.
 pub trait Mode: private::Sealed {}
@@ -52,6 +66,104 @@ impl fmt::Display for AlreadyOccupiedError {
     }
 }
 
+/// A cell which a once cell drop strategy can store its error value in.
+pub trait ErrorStorage: private::Sealed {
+    /// Set the error value of this cell if it's empty, otherwise give the error value back.
+    fn set(&self, error: anyhow::Error) -> Result<(), anyhow::Error>;
+}
+
+/// A cell whose error value can be taken out or replaced through a shared reference, which the
+/// [`Replace`] mode and `take` need.
+pub trait ReplaceableErrorStorage: ErrorStorage {
+    /// Set the error value of this cell, returning the previous error value if there was one.
+    fn replace(&self, error: anyhow::Error) -> Option<anyhow::Error>;
+
+    /// Take the error value out of this cell, leaving it empty.
+    fn take(&self) -> Option<anyhow::Error>;
+}
+
+impl ErrorStorage for OnceCell<anyhow::Error> {
+    fn set(&self, error: anyhow::Error) -> Result<(), anyhow::Error> {
+        OnceCell::set(self, error)
+    }
+}
+
+impl private::Sealed for OnceCell<anyhow::Error> {}
+
+/// A thread safe cell which stores the error value of an [`OnceCellDropStrategy`].
+///
+/// Unlike [`OnceCell`], the error value can be taken out or replaced through a shared reference.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct ErrorCell(Mutex<Option<anyhow::Error>>);
+
+impl ErrorCell {
+    /// Create a new, empty cell.
+    pub const fn new() -> Self {
+        Self(Mutex::new(None))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<anyhow::Error>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Check whether or not there is an error value in this cell.
+    pub fn is_occupied(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    /// Set the error value of this cell if it's empty, otherwise give the error value back.
+    pub fn set(&self, error: anyhow::Error) -> Result<(), anyhow::Error> {
+        let mut slot = self.lock();
+
+        match *slot {
+            Some(_) => Err(error),
+            None => {
+                *slot = Some(error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Set the error value of this cell, returning the previous error value if there was one.
+    pub fn replace(&self, error: anyhow::Error) -> Option<anyhow::Error> {
+        self.lock().replace(error)
+    }
+
+    /// Take the error value out of this cell, leaving it empty.
+    pub fn take(&self) -> Option<anyhow::Error> {
+        self.lock().take()
+    }
+
+    /// Consume this cell, returning the error value if there was one.
+    pub fn into_inner(self) -> Option<anyhow::Error> {
+        self.0.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for ErrorCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorStorage for ErrorCell {
+    fn set(&self, error: anyhow::Error) -> Result<(), anyhow::Error> {
+        ErrorCell::set(self, error)
+    }
+}
+
+impl ReplaceableErrorStorage for ErrorCell {
+    fn replace(&self, error: anyhow::Error) -> Option<anyhow::Error> {
+        ErrorCell::replace(self, error)
+    }
+
+    fn take(&self) -> Option<anyhow::Error> {
+        ErrorCell::take(self)
+    }
+}
+
+impl private::Sealed for ErrorCell {}
+
 /// A try drop strategy which sets an error value once.
 //
 /// This try drop strategy can only handle single errors. If you want to handle multiple errors,
@@ -60,6 +172,10 @@ impl fmt::Display for AlreadyOccupiedError {
 /// The most common use case of this is when you want to get an error from inside a function which
 /// calls [`TryDrop`](crate::TryDrop).
 ///
+/// By default, the error value is stored in an [`OnceCell`]. To replace it with the newest one
+/// using the [`Replace`] mode, or to take it out with [`OnceCellDropStrategy::take`], store it in
+/// an [`ErrorCell`] instead.
+///
 /// # Examples
 /// ```ignore
 /// use once_cell::sync::OnceCell;
@@ -86,13 +202,21 @@ impl fmt::Display for AlreadyOccupiedError {
 /// ```
 ///
 /// [`BroadcastDropStrategy`]: crate::drop_strategies::BroadcastDropStrategy
-#[cfg_attr(feature = "derives", derive(Debug, Clone, Default))]
-pub struct OnceCellDropStrategy<M: Mode> {
+#[cfg_attr(feature = "derives", derive(Debug, Default))]
+pub struct OnceCellDropStrategy<M: Mode, C: ErrorStorage = OnceCell<anyhow::Error>> {
     /// The inner error value.
-    pub inner: Arc<OnceCell<anyhow::Error>>,
+    pub inner: Arc<C>,
     _mode: PhantomData<M>,
 }
 
+// a derived implementation would needlessly require the cell to be `Clone`.
+#[cfg(feature = "derives")]
+impl<M: Mode, C: ErrorStorage> Clone for OnceCellDropStrategy<M, C> {
+    fn clone(&self) -> Self {
+        Self::new(Arc::clone(&self.inner))
+    }
+}
+
 impl OnceCellDropStrategy<Ignore> {
     /// Create a new once cell drop strategy which will ignore if there is already an error value in
     /// its cell.
@@ -101,7 +225,7 @@ impl OnceCellDropStrategy<Ignore> {
     }
 }
 
-impl OnceCellDropStrategy<Error> {
+impl OnceCellDropStrategy<ErrorIfOccupied> {
     /// Create a new once cell drop strategy which will error if there is already an error value in
     /// its cell.
     pub fn error(item: Arc<OnceCell<anyhow::Error>>) -> Self {
@@ -109,9 +233,17 @@ impl OnceCellDropStrategy<Error> {
     }
 }
 
-impl<M: Mode> OnceCellDropStrategy<M> {
+impl OnceCellDropStrategy<Replace, ErrorCell> {
+    /// Create a new once cell drop strategy which will replace the error value in its cell with the
+    /// newest one.
+    pub fn replace(item: Arc<ErrorCell>) -> Self {
+        Self::new(item)
+    }
+}
+
+impl<M: Mode, C: ErrorStorage> OnceCellDropStrategy<M, C> {
     /// Creates a new drop strategy which sets an error value once.
-    pub fn new(item: Arc<OnceCell<anyhow::Error>>) -> Self {
+    pub fn new(item: Arc<C>) -> Self {
         Self {
             inner: item,
             _mode: PhantomData,
@@ -119,13 +251,26 @@ impl<M: Mode> OnceCellDropStrategy<M> {
     }
 }
 
-impl TryDropStrategy for OnceCellDropStrategy<Ignore> {
+impl<M: Mode, C: ReplaceableErrorStorage> OnceCellDropStrategy<M, C> {
+    /// Take the stored error value out of the cell, leaving it empty.
+    pub fn take(&self) -> Option<anyhow::Error> {
+        self.inner.take()
+    }
+}
+
+impl<C: ErrorStorage> TryDropStrategy for OnceCellDropStrategy<Ignore, C> {
     fn handle_error(&self, error: anyhow::Error) {
         let _ = self.inner.set(error);
     }
 }
 
-impl FallibleTryDropStrategy for OnceCellDropStrategy<Error> {
+impl<C: ReplaceableErrorStorage> TryDropStrategy for OnceCellDropStrategy<Replace, C> {
+    fn handle_error(&self, error: anyhow::Error) {
+        self.inner.replace(error);
+    }
+}
+
+impl<C: ErrorStorage> FallibleTryDropStrategy for OnceCellDropStrategy<ErrorIfOccupied, C> {
     type Error = AlreadyOccupiedError;
 
     fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
@@ -138,9 +283,25 @@ mod tests {
     use super::*;
     use crate::drop_strategies::PanicDropStrategy;
     use crate::test_utils::{ErrorsOnDrop, Fallible};
-    use crate::PureTryDrop;
+    use crate::{DynFallibleTryDropStrategy, PureTryDrop};
+    use std::string::{String, ToString};
 
     fn test<M: Mode>()
+    where
+        OnceCellDropStrategy<M, ErrorCell>: FallibleTryDropStrategy,
+    {
+        let item = Arc::new(ErrorCell::new());
+        let strategy = OnceCellDropStrategy::<M, _>::new(Arc::clone(&item));
+        let errors =
+            ErrorsOnDrop::<Fallible, _>::given(strategy, PanicDropStrategy::DEFAULT).adapt();
+        drop(errors);
+        Arc::try_unwrap(item)
+            .unwrap_or_else(|_| panic!("item still referenced by `errors`"))
+            .into_inner()
+            .expect("no error occupied in `OnceCellDropStrategy`");
+    }
+
+    fn test_once_cell<M: Mode>()
     where
         OnceCellDropStrategy<M>: FallibleTryDropStrategy,
     {
@@ -155,13 +316,52 @@ mod tests {
             .expect("no error occupied in `OnceCellDropStrategy`");
     }
 
+    fn handle_twice<M: Mode>() -> (OnceCellDropStrategy<M, ErrorCell>, Result<(), String>)
+    where
+        OnceCellDropStrategy<M, ErrorCell>: FallibleTryDropStrategy,
+    {
+        let strategy = OnceCellDropStrategy::<M, _>::new(Arc::new(ErrorCell::new()));
+        strategy
+            .dyn_try_handle_error(anyhow::anyhow!("first"))
+            .expect("the cell was empty");
+        let second = strategy
+            .dyn_try_handle_error(anyhow::anyhow!("second"))
+            .map_err(|error| error.to_string());
+        (strategy, second)
+    }
+
     #[test]
     fn test_ignore() {
         test::<Ignore>();
+        test_once_cell::<Ignore>();
+
+        let (strategy, second) = handle_twice::<Ignore>();
+        assert_eq!(second, Ok(()));
+        assert_eq!(strategy.take().unwrap().to_string(), "first");
+        assert!(strategy.take().is_none());
     }
 
     #[test]
     fn test_error() {
-        test::<Error>();
+        test::<ErrorIfOccupied>();
+        test_once_cell::<ErrorIfOccupied>();
+
+        let (strategy, second) = handle_twice::<ErrorIfOccupied>();
+        assert_eq!(
+            second,
+            Err("an already existing error was occupied in this cell".to_string())
+        );
+        assert_eq!(strategy.take().unwrap().to_string(), "first");
+        assert!(strategy.take().is_none());
+    }
+
+    #[test]
+    fn test_replace() {
+        test::<Replace>();
+
+        let (strategy, second) = handle_twice::<Replace>();
+        assert_eq!(second, Ok(()));
+        assert_eq!(strategy.take().unwrap().to_string(), "second");
+        assert!(strategy.take().is_none());
     }
 }
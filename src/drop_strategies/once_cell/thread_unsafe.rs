@@ -1,19 +1,109 @@
-use super::{AlreadyOccupiedError, Error, Ignore, Mode};
+use super::{
+    private, AlreadyOccupiedError, ErrorIfOccupied, ErrorStorage, Ignore, Mode, Replace,
+    ReplaceableErrorStorage,
+};
 use crate::{FallibleTryDropStrategy, TryDropStrategy};
+use core::cell::RefCell;
 use once_cell::unsync::OnceCell;
 use std::marker::PhantomData;
 use std::rc::Rc;
 
+impl ErrorStorage for OnceCell<anyhow::Error> {
+    fn set(&self, error: anyhow::Error) -> Result<(), anyhow::Error> {
+        OnceCell::set(self, error)
+    }
+}
+
+impl private::Sealed for OnceCell<anyhow::Error> {}
+
+/// A cell which stores the error value of a [`ThreadUnsafeOnceCellDropStrategy`].
+///
+/// For more information see it's thread safe counterpart.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct ThreadUnsafeErrorCell(RefCell<Option<anyhow::Error>>);
+
+impl ThreadUnsafeErrorCell {
+    /// Create a new, empty cell.
+    pub const fn new() -> Self {
+        Self(RefCell::new(None))
+    }
+
+    /// Check whether or not there is an error value in this cell.
+    pub fn is_occupied(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+
+    /// Set the error value of this cell if it's empty, otherwise give the error value back.
+    pub fn set(&self, error: anyhow::Error) -> Result<(), anyhow::Error> {
+        let mut slot = self.0.borrow_mut();
+
+        match *slot {
+            Some(_) => Err(error),
+            None => {
+                *slot = Some(error);
+                Ok(())
+            }
+        }
+    }
+
+    /// Set the error value of this cell, returning the previous error value if there was one.
+    pub fn replace(&self, error: anyhow::Error) -> Option<anyhow::Error> {
+        self.0.borrow_mut().replace(error)
+    }
+
+    /// Take the error value out of this cell, leaving it empty.
+    pub fn take(&self) -> Option<anyhow::Error> {
+        self.0.borrow_mut().take()
+    }
+
+    /// Consume this cell, returning the error value if there was one.
+    pub fn into_inner(self) -> Option<anyhow::Error> {
+        self.0.into_inner()
+    }
+}
+
+impl Default for ThreadUnsafeErrorCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ErrorStorage for ThreadUnsafeErrorCell {
+    fn set(&self, error: anyhow::Error) -> Result<(), anyhow::Error> {
+        ThreadUnsafeErrorCell::set(self, error)
+    }
+}
+
+impl ReplaceableErrorStorage for ThreadUnsafeErrorCell {
+    fn replace(&self, error: anyhow::Error) -> Option<anyhow::Error> {
+        ThreadUnsafeErrorCell::replace(self, error)
+    }
+
+    fn take(&self) -> Option<anyhow::Error> {
+        ThreadUnsafeErrorCell::take(self)
+    }
+}
+
+impl private::Sealed for ThreadUnsafeErrorCell {}
+
 /// A drop strategy which sets an error value once.
 ///
 /// For more information see it's thread safe counterpart.
-#[cfg_attr(feature = "derives", derive(Debug, Clone, Default))]
-pub struct ThreadUnsafeOnceCellDropStrategy<M: Mode> {
+#[cfg_attr(feature = "derives", derive(Debug, Default))]
+pub struct ThreadUnsafeOnceCellDropStrategy<M: Mode, C: ErrorStorage = OnceCell<anyhow::Error>> {
     /// The inner error value.
-    pub inner: Rc<OnceCell<anyhow::Error>>,
+    pub inner: Rc<C>,
     _marker: PhantomData<M>,
 }
 
+// a derived implementation would needlessly require the cell to be `Clone`.
+#[cfg(feature = "derives")]
+impl<M: Mode, C: ErrorStorage> Clone for ThreadUnsafeOnceCellDropStrategy<M, C> {
+    fn clone(&self) -> Self {
+        Self::new(Rc::clone(&self.inner))
+    }
+}
+
 impl ThreadUnsafeOnceCellDropStrategy<Ignore> {
     /// Create a new once cell drop strategy which will ignore if there is already an error value in
     /// its cell.
@@ -22,7 +112,7 @@ impl ThreadUnsafeOnceCellDropStrategy<Ignore> {
     }
 }
 
-impl ThreadUnsafeOnceCellDropStrategy<Error> {
+impl ThreadUnsafeOnceCellDropStrategy<ErrorIfOccupied> {
     /// Create a new once cell drop strategy which will error if there is already an error value in
     /// its cell.
     pub fn error(value: Rc<OnceCell<anyhow::Error>>) -> Self {
@@ -30,9 +120,17 @@ impl ThreadUnsafeOnceCellDropStrategy<Error> {
     }
 }
 
-impl<M: Mode> ThreadUnsafeOnceCellDropStrategy<M> {
+impl ThreadUnsafeOnceCellDropStrategy<Replace, ThreadUnsafeErrorCell> {
+    /// Create a new once cell drop strategy which will replace the error value in its cell with the
+    /// newest one.
+    pub fn replace(value: Rc<ThreadUnsafeErrorCell>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<M: Mode, C: ErrorStorage> ThreadUnsafeOnceCellDropStrategy<M, C> {
     /// Create a new once cell drop strategy which sets an error value once.
-    pub fn new(value: Rc<OnceCell<anyhow::Error>>) -> Self {
+    pub fn new(value: Rc<C>) -> Self {
         Self {
             inner: value,
             _marker: PhantomData,
@@ -40,13 +138,28 @@ impl<M: Mode> ThreadUnsafeOnceCellDropStrategy<M> {
     }
 }
 
-impl TryDropStrategy for ThreadUnsafeOnceCellDropStrategy<Ignore> {
+impl<M: Mode, C: ReplaceableErrorStorage> ThreadUnsafeOnceCellDropStrategy<M, C> {
+    /// Take the stored error value out of the cell, leaving it empty.
+    pub fn take(&self) -> Option<anyhow::Error> {
+        self.inner.take()
+    }
+}
+
+impl<C: ErrorStorage> TryDropStrategy for ThreadUnsafeOnceCellDropStrategy<Ignore, C> {
     fn handle_error(&self, error: anyhow::Error) {
         let _ = self.inner.set(error);
     }
 }
 
-impl FallibleTryDropStrategy for ThreadUnsafeOnceCellDropStrategy<Error> {
+impl<C: ReplaceableErrorStorage> TryDropStrategy for ThreadUnsafeOnceCellDropStrategy<Replace, C> {
+    fn handle_error(&self, error: anyhow::Error) {
+        self.inner.replace(error);
+    }
+}
+
+impl<C: ErrorStorage> FallibleTryDropStrategy
+    for ThreadUnsafeOnceCellDropStrategy<ErrorIfOccupied, C>
+{
     type Error = AlreadyOccupiedError;
 
     fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
@@ -58,6 +171,8 @@ impl FallibleTryDropStrategy for ThreadUnsafeOnceCellDropStrategy<Error> {
 mod tests {
     use crate::drop_strategies::PanicDropStrategy;
     use crate::test_utils::fallible_given;
+    use crate::DynFallibleTryDropStrategy;
+    use std::string::ToString;
     use super::*;
 
     fn test<M: Mode>()
@@ -73,13 +188,43 @@ mod tests {
             .expect("no error occupied in `OnceCellDropStrategy`");
     }
 
+    fn handle_twice<M: Mode>() -> ThreadUnsafeOnceCellDropStrategy<M, ThreadUnsafeErrorCell>
+    where
+        ThreadUnsafeOnceCellDropStrategy<M, ThreadUnsafeErrorCell>: FallibleTryDropStrategy,
+    {
+        let strategy =
+            ThreadUnsafeOnceCellDropStrategy::<M, _>::new(Rc::new(ThreadUnsafeErrorCell::new()));
+        for error in ["first", "second"] {
+            let _ = strategy.dyn_try_handle_error(anyhow::anyhow!(error));
+        }
+        strategy
+    }
+
     #[test]
     fn test_error() {
-        test::<Error>();
+        test::<ErrorIfOccupied>();
+        assert_eq!(
+            handle_twice::<ErrorIfOccupied>()
+                .take()
+                .unwrap()
+                .to_string(),
+            "first"
+        );
     }
 
     #[test]
     fn test_ignore() {
         test::<Ignore>();
+        assert_eq!(
+            handle_twice::<Ignore>().take().unwrap().to_string(),
+            "first"
+        );
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_replace() {
+        let strategy = handle_twice::<Replace>();
+        assert_eq!(strategy.take().unwrap().to_string(), "second");
+        assert!(strategy.take().is_none());
+    }
+}
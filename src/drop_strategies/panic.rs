@@ -1,6 +1,14 @@
-use crate::{Error, TryDropStrategy};
+//! Types and traits for the panic drop strategy.
+
+use super::terminal::{self, SharedFn, TerminalHook};
+use crate::{Error, InfallibleStrategy, TryDropStrategy};
 use std::borrow::Cow;
+use std::format;
 use std::string::String;
+use std::sync::Arc;
+
+#[cfg(feature = "std")]
+use std::backtrace::{Backtrace, BacktraceStatus};
 
 /// A drop strategy that panics with a message if a drop error occurs.
 #[cfg_attr(
@@ -10,6 +18,32 @@ use std::string::String;
 pub struct PanicDropStrategy {
     /// The message to panic with.
     pub message: Cow<'static, str>,
+
+    /// Whether or not to capture a backtrace and print it in the panic message. Only has an
+    /// effect if the `std` feature is enabled.
+    pub capture_backtrace: bool,
+
+    before_terminal: Option<TerminalHook>,
+    formatter: Option<PanicFormatter>,
+}
+
+/// Formats the message a [`PanicDropStrategy`] panics with from the error, e.g. to redact parts of
+/// it or to add structured data.
+///
+/// Like [`TerminalHook`], it's cheap to clone, and clones compare equal to each other.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PanicFormatter(SharedFn<dyn Fn(&Error) -> String + Send + Sync>);
+
+impl PanicFormatter {
+    /// Create a new panic formatter from a function.
+    pub fn new(f: impl Fn(&Error) -> String + Send + Sync + 'static) -> Self {
+        Self(SharedFn::new(Arc::new(f)))
+    }
+
+    /// Format the panic message from the error.
+    pub fn format(&self, error: &Error) -> String {
+        (self.0)(error)
+    }
 }
 
 impl PanicDropStrategy {
@@ -20,6 +54,9 @@ impl PanicDropStrategy {
     pub fn with_message(message: impl Into<Cow<'static, str>>) -> Self {
         Self {
             message: message.into(),
+            capture_backtrace: false,
+            before_terminal: None,
+            formatter: None,
         }
     }
 
@@ -27,6 +64,9 @@ impl PanicDropStrategy {
     pub const fn with_static_message(message: &'static str) -> Self {
         Self {
             message: Cow::Borrowed(message),
+            capture_backtrace: false,
+            before_terminal: None,
+            formatter: None,
         }
     }
 
@@ -34,16 +74,67 @@ impl PanicDropStrategy {
     pub const fn with_dynamic_message(message: String) -> Self {
         Self {
             message: Cow::Owned(message),
+            capture_backtrace: false,
+            before_terminal: None,
+            formatter: None,
         }
     }
+
+    /// Capture a backtrace and print it in the panic message if a drop error occurs.
+    ///
+    /// This respects the `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` environment variables, only
+    /// capturing a backtrace if they enable it.
+    #[cfg(feature = "std")]
+    pub const fn with_backtrace(mut self) -> Self {
+        self.capture_backtrace = true;
+        self
+    }
+
+    /// Run `f` with the error right before panicking, e.g. to flush metrics. It's run before the
+    /// drop strategies registered with `try_drop::flush::register` are flushed, if the
+    /// `async-flush` feature is enabled. See [`TerminalHook`] for more information.
+    pub fn with_before_terminal(mut self, f: impl Fn(&Error) + Send + Sync + 'static) -> Self {
+        self.before_terminal = Some(TerminalHook::new(f));
+        self
+    }
+
+    /// Panic with the message `f` formats from the error, instead of the message of this drop
+    /// strategy followed by the error. If this is set, [`Self::message`] is ignored.
+    pub fn with_formatter(mut self, f: impl Fn(&Error) -> String + Send + Sync + 'static) -> Self {
+        self.formatter = Some(PanicFormatter::new(f));
+        self
+    }
 }
 
 impl TryDropStrategy for PanicDropStrategy {
     fn handle_error(&self, error: Error) {
-        Err(error).expect(&self.message)
+        if let Some(hook) = &self.before_terminal {
+            hook.call(&error)
+        }
+
+        terminal::flush_registered();
+
+        let message = match &self.formatter {
+            Some(formatter) => formatter.format(&error),
+            None => format!("{}: {:?}", self.message, error),
+        };
+
+        #[cfg(feature = "std")]
+        if self.capture_backtrace {
+            let backtrace = Backtrace::capture();
+
+            if let BacktraceStatus::Captured = backtrace.status() {
+                panic!("{message}\n\ndrop strategy backtrace:\n{backtrace}")
+            }
+        }
+
+        panic!("{message}")
     }
 }
 
+// SAFETY: this only ever panics.
+unsafe impl InfallibleStrategy for PanicDropStrategy {}
+
 impl Default for PanicDropStrategy {
     fn default() -> Self {
         Self::DEFAULT
@@ -56,7 +147,10 @@ mod tests {
     use crate::drop_strategies::AbortDropStrategy;
     use crate::test_utils::{ErrorsOnDrop, Fallible};
     use crate::PureTryDrop;
+    use std::panic::{self, AssertUnwindSafe};
     use std::string::ToString;
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
 
     #[test]
     fn test_with_message() {
@@ -76,6 +170,54 @@ mod tests {
         assert_eq!(strategy.message, "test message");
     }
 
+    #[test]
+    fn test_with_backtrace() {
+        let strategy = PanicDropStrategy::with_message("test message");
+        assert!(!strategy.capture_backtrace);
+        assert!(strategy.with_backtrace().capture_backtrace);
+    }
+
+    #[test]
+    fn test_before_terminal() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let s = Arc::clone(&seen);
+        let strategy = PanicDropStrategy::DEFAULT
+            .with_before_terminal(move |error| s.lock().unwrap().push(error.to_string()));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            strategy.handle_error(anyhow::anyhow!("metrics weren't flushed"))
+        }));
+
+        assert!(result.is_err(), "the panic drop strategy didn't panic");
+        assert_eq!(*seen.lock().unwrap(), ["metrics weren't flushed"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "error occurred when dropping an object: this will always fail")]
+    fn test_before_terminal_panics() {
+        let strategy = PanicDropStrategy::DEFAULT
+            .with_before_terminal(|_| panic!("the audit log is unavailable"));
+        strategy.handle_error(anyhow::anyhow!("this will always fail"))
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to close the session of [redacted]: connection reset")]
+    fn test_with_formatter() {
+        let strategy = PanicDropStrategy::DEFAULT.with_formatter(|error| {
+            std::format!("failed to close the session of [redacted]: {error}")
+        });
+        strategy.handle_error(anyhow::anyhow!("connection reset"))
+    }
+
+    #[test]
+    #[should_panic(expected = "error occurred when dropping an object: this will always fail")]
+    fn test_strategy_with_backtrace() {
+        let _errors = ErrorsOnDrop::<Fallible, _>::given(
+            PanicDropStrategy::DEFAULT.with_backtrace(),
+            AbortDropStrategy,
+        )
+        .adapt();
+    }
+
     #[test]
     #[should_panic]
     fn test_strategy() {
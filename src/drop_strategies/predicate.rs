@@ -0,0 +1,202 @@
+//! Types and traits for the predicate drop strategy.
+
+use crate::{FallibleTryDropStrategy, TryDropStrategy};
+
+/// A drop strategy which routes an error to one of two drop strategies, depending on a predicate.
+/// Errors for which the predicate returns `true` go to `if_true`, the rest go to `if_false`.
+///
+/// The predicate receives a reference to the error, so it can look into it with
+/// [`anyhow::Error::downcast_ref`]:
+///
+/// ```rust
+/// use std::io;
+/// use try_drop::drop_strategies::{NoOpDropStrategy, PredicateDropStrategy, WriteDropStrategy};
+///
+/// // a closed pipe isn't worth reporting
+/// let strategy = PredicateDropStrategy::new(
+///     |error: &anyhow::Error| {
+///         error
+///             .downcast_ref::<io::Error>()
+///             .map_or(false, |error| error.kind() == io::ErrorKind::BrokenPipe)
+///     },
+///     NoOpDropStrategy,
+///     WriteDropStrategy::stderr(),
+/// );
+/// ```
+///
+/// See [`FalliblePredicateDropStrategy`] for the fallible version.
+#[cfg_attr(feature = "derives", derive(Debug, Copy, Clone, Default))]
+pub struct PredicateDropStrategy<P, A, B>
+where
+    P: Fn(&anyhow::Error) -> bool,
+    A: TryDropStrategy,
+    B: TryDropStrategy,
+{
+    /// Decides which drop strategy an error goes to.
+    pub predicate: P,
+
+    /// The drop strategy to use if the predicate returns `true`.
+    pub if_true: A,
+
+    /// The drop strategy to use if the predicate returns `false`.
+    pub if_false: B,
+}
+
+impl<P, A, B> PredicateDropStrategy<P, A, B>
+where
+    P: Fn(&anyhow::Error) -> bool,
+    A: TryDropStrategy,
+    B: TryDropStrategy,
+{
+    /// Create a new predicate drop strategy.
+    pub const fn new(predicate: P, if_true: A, if_false: B) -> Self {
+        Self {
+            predicate,
+            if_true,
+            if_false,
+        }
+    }
+}
+
+impl<P, A, B> TryDropStrategy for PredicateDropStrategy<P, A, B>
+where
+    P: Fn(&anyhow::Error) -> bool,
+    A: TryDropStrategy,
+    B: TryDropStrategy,
+{
+    fn handle_error(&self, error: anyhow::Error) {
+        if (self.predicate)(&error) {
+            self.if_true.handle_error(error)
+        } else {
+            self.if_false.handle_error(error)
+        }
+    }
+
+    fn on_uninstall(&self) {
+        self.if_true.on_uninstall();
+        self.if_false.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.if_true.on_fork_child();
+        self.if_false.on_fork_child()
+    }
+}
+
+/// A drop strategy which routes an error to one of two fallible drop strategies, depending on a
+/// predicate. See [`PredicateDropStrategy`] for more information.
+///
+/// The errors of both drop strategies are turned into [`anyhow::Error`]s.
+#[cfg_attr(feature = "derives", derive(Debug, Copy, Clone, Default))]
+pub struct FalliblePredicateDropStrategy<P, A, B>
+where
+    P: Fn(&anyhow::Error) -> bool,
+    A: FallibleTryDropStrategy,
+    B: FallibleTryDropStrategy,
+{
+    /// Decides which drop strategy an error goes to.
+    pub predicate: P,
+
+    /// The drop strategy to use if the predicate returns `true`.
+    pub if_true: A,
+
+    /// The drop strategy to use if the predicate returns `false`.
+    pub if_false: B,
+}
+
+impl<P, A, B> FalliblePredicateDropStrategy<P, A, B>
+where
+    P: Fn(&anyhow::Error) -> bool,
+    A: FallibleTryDropStrategy,
+    B: FallibleTryDropStrategy,
+{
+    /// Create a new fallible predicate drop strategy.
+    pub const fn new(predicate: P, if_true: A, if_false: B) -> Self {
+        Self {
+            predicate,
+            if_true,
+            if_false,
+        }
+    }
+}
+
+impl<P, A, B> FallibleTryDropStrategy for FalliblePredicateDropStrategy<P, A, B>
+where
+    P: Fn(&anyhow::Error) -> bool,
+    A: FallibleTryDropStrategy,
+    B: FallibleTryDropStrategy,
+{
+    type Error = anyhow::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        if (self.predicate)(&error) {
+            self.if_true.try_handle_error(error).map_err(Into::into)
+        } else {
+            self.if_false.try_handle_error(error).map_err(Into::into)
+        }
+    }
+
+    fn on_uninstall(&self) {
+        self.if_true.on_uninstall();
+        self.if_false.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.if_true.on_fork_child();
+        self.if_false.on_fork_child()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "ds-collect")]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::CollectDropStrategy;
+    use crate::test_utils::FallibleDropStrategy;
+    use std::io;
+    use std::string::ToString;
+
+    fn is_io_error(error: &anyhow::Error) -> bool {
+        error.downcast_ref::<io::Error>().is_some()
+    }
+
+    #[test]
+    fn test_predicate_drop_strategy() {
+        let strategy = PredicateDropStrategy::new(
+            is_io_error,
+            CollectDropStrategy::new(),
+            CollectDropStrategy::new(),
+        );
+        strategy.handle_error(io::Error::from(io::ErrorKind::BrokenPipe).into());
+        strategy.handle_error(core::fmt::Error.into());
+        strategy.handle_error(io::Error::from(io::ErrorKind::NotFound).into());
+
+        let io_errors = strategy.if_true.errors();
+        assert_eq!(io_errors.len(), 2);
+        assert!(io_errors
+            .iter()
+            .all(|error| error.0.downcast_ref::<io::Error>().is_some()));
+
+        let other_errors = strategy.if_false.errors();
+        assert_eq!(other_errors.len(), 1);
+        assert_eq!(
+            other_errors[0].to_string(),
+            core::fmt::Error.to_string(),
+            "the wrong error was routed to the other drop strategy"
+        );
+    }
+
+    #[test]
+    fn test_fallible_predicate_drop_strategy() {
+        let strategy = FalliblePredicateDropStrategy::new(
+            is_io_error,
+            CollectDropStrategy::new(),
+            FallibleDropStrategy,
+        );
+        strategy
+            .try_handle_error(io::Error::from(io::ErrorKind::BrokenPipe).into())
+            .expect("the io error should have been collected");
+        assert!(strategy.try_handle_error(core::fmt::Error.into()).is_err());
+        assert_eq!(strategy.if_true.errors().len(), 1);
+    }
+}
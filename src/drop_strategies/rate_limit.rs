@@ -0,0 +1,202 @@
+//! Types and traits for the rate limit drop strategy.
+
+use crate::sampling::{self, SamplingDecision};
+use crate::FallibleTryDropStrategy;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+#[cfg_attr(feature = "derives", derive(Debug))]
+#[derive(Default)]
+struct State {
+    last_forwarded: Option<Instant>,
+    dropped: usize,
+}
+
+/// A drop strategy which hands errors to an inner drop strategy at most once every
+/// `min_interval`, so that tearing down lots of resources at once doesn't overwhelm the inner drop
+/// strategy. Errors which arrive too soon are dropped.
+///
+/// The decision is shared with the other sampling layers through [`sampling`]: errors which a
+/// sampling layer before this one has already made a decision for are forwarded or dropped
+/// accordingly, and forwarded errors are marked as selected. The handlers carry the decision over
+/// to the errors the inner drop strategy fails to handle, so that a sampling fallback handler
+/// doesn't drop them.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct RateLimitDropStrategy<S: FallibleTryDropStrategy> {
+    /// The drop strategy to hand the errors to.
+    pub inner: S,
+
+    /// The minimum amount of time between two errors handed to the inner drop strategy.
+    pub min_interval: Duration,
+
+    /// Whether or not to count the errors which were dropped.
+    pub count_dropped: bool,
+
+    /// Whether or not to honor the decision a sampling layer before this one has made for an
+    /// error, instead of making an independent one.
+    pub honor_sampling_decision: bool,
+
+    state: Mutex<State>,
+}
+
+impl<S: FallibleTryDropStrategy> RateLimitDropStrategy<S> {
+    /// Create a new rate limit drop strategy which doesn't count the errors it drops.
+    pub const fn new(inner: S, min_interval: Duration) -> Self {
+        Self {
+            inner,
+            min_interval,
+            count_dropped: false,
+            honor_sampling_decision: true,
+            state: Mutex::new(State {
+                last_forwarded: None,
+                dropped: 0,
+            }),
+        }
+    }
+
+    /// Count the errors which were dropped, which can be retrieved by [`Self::dropped`].
+    pub const fn with_drop_counter(mut self) -> Self {
+        self.count_dropped = true;
+        self
+    }
+
+    /// Make an independent decision for every error, ignoring the decision a sampling layer before
+    /// this one has made.
+    pub const fn with_independent_sampling(mut self) -> Self {
+        self.honor_sampling_decision = false;
+        self
+    }
+
+    /// Get how many errors were dropped so far. This is always zero if the errors aren't counted.
+    pub fn dropped(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .dropped
+    }
+
+    fn try_handle_error_at(&self, error: anyhow::Error, now: Instant) -> Result<(), S::Error> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let decision = match sampling::sampling_decision(&error) {
+            Some(decision) if self.honor_sampling_decision => decision,
+            _ => match state.last_forwarded {
+                Some(last) if now.saturating_duration_since(last) < self.min_interval => {
+                    SamplingDecision::Skipped
+                }
+                _ => {
+                    state.last_forwarded = Some(now);
+                    SamplingDecision::Selected
+                }
+            },
+        };
+
+        match decision {
+            SamplingDecision::Skipped => {
+                if self.count_dropped {
+                    state.dropped += 1;
+                }
+
+                Ok(())
+            }
+            SamplingDecision::Selected => {
+                drop(state);
+                sampling::mark(&error, SamplingDecision::Selected);
+                self.inner.try_handle_error(error)
+            }
+        }
+    }
+}
+
+impl<S: FallibleTryDropStrategy> FallibleTryDropStrategy for RateLimitDropStrategy<S> {
+    type Error = S::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        self.try_handle_error_at(error, Instant::now())
+    }
+
+    fn on_uninstall(&self) {
+        self.inner.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.inner.on_fork_child()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::AdHocDropStrategy;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn counter() -> (AdHocDropStrategy<impl Fn(anyhow::Error)>, Arc<AtomicUsize>) {
+        let forwarded = Arc::new(AtomicUsize::new(0));
+        let f = Arc::clone(&forwarded);
+        let strategy = AdHocDropStrategy(move |_| {
+            f.fetch_add(1, Ordering::Relaxed);
+        });
+        (strategy, forwarded)
+    }
+
+    #[test]
+    fn test_rate_limit_drop_strategy() {
+        let (inner, forwarded) = counter();
+        let strategy =
+            RateLimitDropStrategy::new(inner, Duration::from_secs(3600)).with_drop_counter();
+
+        for _ in 0..2 {
+            strategy
+                .try_handle_error(anyhow::anyhow!("this will always fail"))
+                .unwrap();
+        }
+
+        assert_eq!(forwarded.load(Ordering::Relaxed), 1);
+        assert_eq!(strategy.dropped(), 1);
+    }
+
+    #[test]
+    fn test_forwards_after_interval() {
+        let (inner, forwarded) = counter();
+        let strategy = RateLimitDropStrategy::new(inner, Duration::from_secs(1));
+        let start = Instant::now();
+
+        for offset in [0, 500, 1000, 1500, 2500] {
+            strategy
+                .try_handle_error_at(
+                    anyhow::anyhow!("this will always fail"),
+                    start + Duration::from_millis(offset),
+                )
+                .unwrap();
+        }
+
+        assert_eq!(forwarded.load(Ordering::Relaxed), 3);
+        assert_eq!(
+            strategy.dropped(),
+            0,
+            "errors were counted without a counter"
+        );
+    }
+
+    #[test]
+    fn test_honors_sampling_decision() {
+        let (inner, forwarded) = counter();
+        let strategy =
+            RateLimitDropStrategy::new(inner, Duration::from_secs(3600)).with_drop_counter();
+        let marked = |decision| {
+            let error = anyhow::anyhow!("this will always fail");
+            let _scope = sampling::enter(&error);
+            sampling::mark(&error, decision);
+            strategy.try_handle_error(error).unwrap();
+        };
+
+        marked(SamplingDecision::Skipped);
+        strategy
+            .try_handle_error(anyhow::anyhow!("this will always fail"))
+            .unwrap();
+        marked(SamplingDecision::Selected);
+
+        assert_eq!(forwarded.load(Ordering::Relaxed), 2);
+        assert_eq!(strategy.dropped(), 1);
+    }
+}
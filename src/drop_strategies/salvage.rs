@@ -0,0 +1,258 @@
+//! Types and traits for the salvage drop strategy, which stores the leftover work of values which
+//! failed to drop, so that the owner can retry it later.
+
+use crate::adapters::{
+    FallbackTryDropStrategyHandler, FallibleTryDropStrategyRef, TryDropStrategyRef,
+};
+use crate::{FallibleTryDropStrategy, PureTryDrop, TryDropStrategy};
+use std::error::Error as StdError;
+use std::fmt;
+use std::mem;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::vec::Vec;
+
+/// A type whose drop can be retried later, by handing out an owned token which describes the
+/// leftover work, such as the path of a file which failed to be deleted.
+pub trait Salvage {
+    /// The token which describes the leftover work.
+    type Item: Send + Sync + 'static;
+
+    /// Salvage the leftover work after a failed drop, if there is any.
+    fn salvage(&mut self) -> Option<Self::Item>;
+
+    /// Adapts this type like [`PureTryDrop::adapt`], but salvages the leftover work if dropping
+    /// fails. See [`SalvageDropAdapter`].
+    fn adapt_salvage(self) -> SalvageDropAdapter<Self>
+    where
+        Self: PureTryDrop + Sized,
+    {
+        SalvageDropAdapter(self)
+    }
+}
+
+/// A drop error which carries the salvaged leftover work of the value which failed to drop.
+pub struct Salvaged<T> {
+    /// The leftover work.
+    pub item: T,
+
+    /// The original drop error.
+    pub error: anyhow::Error,
+}
+
+impl<T> fmt::Debug for Salvaged<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Salvaged")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for Salvaged<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl<T> StdError for Salvaged<T> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+/// An adapter like [`DropAdapter`], which salvages the leftover work of the value if dropping it
+/// fails, and attaches it to the drop error as [`Salvaged`].
+///
+/// [`DropAdapter`]: crate::adapters::DropAdapter
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct SalvageDropAdapter<TD: PureTryDrop + Salvage>(pub TD);
+
+impl<TD: PureTryDrop + Salvage> Drop for SalvageDropAdapter<TD> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: we called this function inside a `Drop::drop` context.
+        let result = unsafe { self.0.try_drop() };
+        if let Err(error) = result {
+            self.handle_drop_error(error)
+        }
+    }
+}
+
+impl<TD: PureTryDrop + Salvage> SalvageDropAdapter<TD> {
+    #[cold]
+    #[inline(never)]
+    fn handle_drop_error(&mut self, error: TD::Error) {
+        let error = error.into();
+
+        #[cfg(any(feature = "error-telemetry", test))]
+        crate::error_telemetry::record::<TD>(&error);
+
+        let error = match self.0.salvage() {
+            Some(item) => anyhow::Error::new(Salvaged { item, error }),
+            None => error,
+        };
+
+        let handler = FallbackTryDropStrategyHandler::new(
+            TryDropStrategyRef(self.0.fallback_try_drop_strategy()),
+            FallibleTryDropStrategyRef(self.0.try_drop_strategy()),
+        );
+
+        let _guard = crate::drop_context::enter::<TD>();
+        handler.handle_error(error)
+    }
+}
+
+/// A drop strategy which stores the leftover work of values which failed to drop, for the owner to
+/// retry later with [`SalvageDropStrategy::drain`].
+///
+/// Only drop errors which are [`Salvaged`], e.g. through the [`SalvageDropAdapter`], can be
+/// handled by this. Every other drop error is handed back, for the fallback try drop strategy to
+/// handle.
+///
+/// Cloning this drop strategy shares the queue.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct SalvageDropStrategy<T> {
+    queue: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T> SalvageDropStrategy<T> {
+    /// Create a new salvage drop strategy with an empty queue.
+    pub fn new() -> Self {
+        Self::with_queue(Arc::default())
+    }
+
+    /// Create a new salvage drop strategy which stores the leftover work in the given queue.
+    pub fn with_queue(queue: Arc<Mutex<Vec<T>>>) -> Self {
+        Self { queue }
+    }
+
+    /// Get the queue which the leftover work is stored in.
+    pub fn queue(&self) -> &Arc<Mutex<Vec<T>>> {
+        &self.queue
+    }
+
+    /// Take all the leftover work out of the queue, in the order it was stored.
+    pub fn drain(&self) -> Vec<T> {
+        mem::take(&mut *self.queue.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+}
+
+impl<T> Clone for SalvageDropStrategy<T> {
+    fn clone(&self) -> Self {
+        Self::with_queue(Arc::clone(&self.queue))
+    }
+}
+
+impl<T> Default for SalvageDropStrategy<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send + Sync + 'static> FallibleTryDropStrategy for SalvageDropStrategy<T> {
+    type Error = anyhow::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        let salvaged = error.downcast::<Salvaged<T>>()?;
+        self.queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(salvaged.item);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::DefaultPanic;
+    use std::cell::RefCell;
+    use std::format;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+    use std::string::ToString;
+
+    /// Pretends to delete a file when dropped, failing as long as the file is locked.
+    struct Deletion {
+        path: PathBuf,
+        locked: bool,
+        deleted: Rc<RefCell<Vec<PathBuf>>>,
+        strategy: SalvageDropStrategy<PathBuf>,
+    }
+
+    impl PureTryDrop for Deletion {
+        type Error = anyhow::Error;
+        type FallbackTryDropStrategy = DefaultPanic;
+        type TryDropStrategy = SalvageDropStrategy<PathBuf>;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &DefaultPanic
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &self.strategy
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            if self.locked {
+                anyhow::bail!("failed to delete {}", self.path.display())
+            }
+
+            self.deleted.borrow_mut().push(mem::take(&mut self.path));
+            Ok(())
+        }
+    }
+
+    impl Salvage for Deletion {
+        type Item = PathBuf;
+
+        fn salvage(&mut self) -> Option<Self::Item> {
+            Some(mem::take(&mut self.path))
+        }
+    }
+
+    #[test]
+    fn test_salvage_drop_strategy() {
+        let strategy = SalvageDropStrategy::new();
+        let deleted = Rc::new(RefCell::new(Vec::new()));
+        let deletion = |path: PathBuf, locked| Deletion {
+            path,
+            locked,
+            deleted: Rc::clone(&deleted),
+            strategy: strategy.clone(),
+        };
+
+        for index in 0..4 {
+            drop(deletion(PathBuf::from(format!("{index}.tmp")), index % 2 == 0).adapt_salvage());
+        }
+
+        assert_eq!(*deleted.borrow(), ["1.tmp", "3.tmp"].map(PathBuf::from));
+
+        // retry until the queue is empty, the files are unlocked by now.
+        loop {
+            let queue = strategy.drain();
+
+            if queue.is_empty() {
+                break;
+            }
+
+            for path in queue {
+                drop(deletion(path, false).adapt_salvage());
+            }
+        }
+
+        assert_eq!(
+            *deleted.borrow(),
+            ["1.tmp", "3.tmp", "0.tmp", "2.tmp"].map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn test_unsalvageable_error_is_handed_back() {
+        let strategy = SalvageDropStrategy::<PathBuf>::new();
+        let error = strategy
+            .try_handle_error(anyhow::anyhow!("this will always fail"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "this will always fail");
+        assert!(strategy.drain().is_empty());
+    }
+}
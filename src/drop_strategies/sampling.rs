@@ -0,0 +1,202 @@
+//! Types and traits for the sampling drop strategy.
+
+use crate::sampling::{self, SamplingDecision};
+use crate::TryDropStrategy;
+use core::num::NonZeroUsize;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A drop strategy which only hands one in every `every` errors to an inner drop strategy,
+/// starting with the first one. The other errors are dropped. This is useful to keep an eye on
+/// drop errors which are too frequent to handle every one of them.
+///
+/// Like the [`RateLimitDropStrategy`], the decision is shared with the other sampling layers
+/// through [`sampling`]: errors which a sampling layer before this one has already made a decision
+/// for are forwarded or dropped accordingly. As this can't fail, it can be used as a fallback
+/// handler too.
+///
+/// [`RateLimitDropStrategy`]: crate::drop_strategies::RateLimitDropStrategy
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct SamplingDropStrategy<S: TryDropStrategy> {
+    /// The drop strategy to hand the errors to.
+    pub inner: S,
+
+    /// How many errors there are for every error handed to the inner drop strategy.
+    pub every: NonZeroUsize,
+
+    /// Whether or not to honor the decision a sampling layer before this one has made for an
+    /// error, instead of making an independent one.
+    pub honor_sampling_decision: bool,
+
+    seen: AtomicUsize,
+}
+
+impl<S: TryDropStrategy> SamplingDropStrategy<S> {
+    /// Create a new sampling drop strategy which hands one in every `every` errors to the inner
+    /// drop strategy.
+    pub const fn new(inner: S, every: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            every,
+            honor_sampling_decision: true,
+            seen: AtomicUsize::new(0),
+        }
+    }
+
+    /// Make an independent decision for every error, ignoring the decision a sampling layer before
+    /// this one has made.
+    pub const fn with_independent_sampling(mut self) -> Self {
+        self.honor_sampling_decision = false;
+        self
+    }
+}
+
+impl<S: TryDropStrategy> TryDropStrategy for SamplingDropStrategy<S> {
+    fn handle_error(&self, error: anyhow::Error) {
+        let decision = match sampling::sampling_decision(&error) {
+            Some(decision) if self.honor_sampling_decision => decision,
+            _ if self
+                .seen
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(self.every.get()) =>
+            {
+                SamplingDecision::Selected
+            }
+            _ => SamplingDecision::Skipped,
+        };
+
+        if decision == SamplingDecision::Selected {
+            sampling::mark(&error, SamplingDecision::Selected);
+            self.inner.handle_error(error)
+        }
+    }
+
+    fn on_uninstall(&self) {
+        self.inner.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.inner.on_fork_child()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::FallbackTryDropStrategyHandler;
+    use crate::drop_strategies::RateLimitDropStrategy;
+    use crate::drop_strategies::{AdHocDropStrategy, AdHocFallibleDropStrategy};
+    use crate::sampling::sampling_decision;
+    use crate::FallibleTryDropStrategy;
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+    use std::string::{String, ToString};
+    use std::time::Duration;
+    use std::vec::Vec;
+
+    type Handled = Rc<RefCell<Vec<(String, Option<SamplingDecision>)>>>;
+
+    fn recorder() -> (AdHocDropStrategy<impl Fn(anyhow::Error)>, Handled) {
+        let handled = Handled::default();
+        let h = Rc::clone(&handled);
+        let strategy = AdHocDropStrategy(move |error: anyhow::Error| {
+            h.borrow_mut()
+                .push((error.to_string(), sampling_decision(&error)))
+        });
+        (strategy, handled)
+    }
+
+    fn every(n: usize) -> NonZeroUsize {
+        NonZeroUsize::new(n).unwrap()
+    }
+
+    fn messages(handled: &Handled) -> Vec<String> {
+        handled
+            .borrow()
+            .iter()
+            .map(|(message, _)| message.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_sampling_drop_strategy() {
+        let (inner, handled) = recorder();
+        let strategy = SamplingDropStrategy::new(inner, every(3));
+
+        for index in 0..7 {
+            strategy.handle_error(anyhow::anyhow!("{index}"));
+        }
+
+        assert_eq!(messages(&handled), ["0", "3", "6"]);
+    }
+
+    /// Composes a rate limited primary handler, whose inner strategy always fails, with a sampling
+    /// fallback handler.
+    fn compose(
+        primary: impl FallibleTryDropStrategy,
+        fallback: impl TryDropStrategy,
+        errors: usize,
+    ) {
+        let handler = FallbackTryDropStrategyHandler::new(fallback, primary);
+
+        for index in 0..errors {
+            handler.handle_error(anyhow::anyhow!("{index}"))
+        }
+    }
+
+    fn failing(
+    ) -> AdHocFallibleDropStrategy<impl Fn(anyhow::Error) -> anyhow::Result<()>, anyhow::Error>
+    {
+        AdHocFallibleDropStrategy::from_result_fn(Err)
+    }
+
+    #[test]
+    fn test_fallback_honors_primary_decision() {
+        let (inner, handled) = recorder();
+        let primary = RateLimitDropStrategy::new(failing(), Duration::ZERO);
+        let fallback = SamplingDropStrategy::new(inner, every(2));
+        compose(primary, fallback, 4);
+
+        // on its own, the fallback handler would have skipped every other error
+        assert_eq!(messages(&handled), ["0", "1", "2", "3"]);
+        assert!(handled
+            .borrow()
+            .iter()
+            .all(|(_, decision)| *decision == Some(SamplingDecision::Selected)));
+    }
+
+    #[test]
+    fn test_decision_carried_to_new_error() {
+        let (inner, handled) = recorder();
+        let primary = RateLimitDropStrategy::new(
+            AdHocFallibleDropStrategy(|error: anyhow::Error| -> io::Result<()> {
+                Err(io::Error::other(error.to_string()))
+            }),
+            Duration::ZERO,
+        );
+        let fallback = SamplingDropStrategy::new(inner, every(2));
+        compose(primary, fallback, 4);
+
+        assert_eq!(messages(&handled), ["0", "1", "2", "3"]);
+    }
+
+    #[test]
+    fn test_skipped_errors_never_reach_fallback() {
+        let (inner, handled) = recorder();
+        let primary = RateLimitDropStrategy::new(failing(), Duration::from_secs(3600));
+        let fallback = SamplingDropStrategy::new(inner, every(1));
+        compose(primary, fallback, 3);
+
+        assert_eq!(messages(&handled), ["0"]);
+    }
+
+    #[test]
+    fn test_independent_sampling() {
+        let (inner, handled) = recorder();
+        let primary = RateLimitDropStrategy::new(failing(), Duration::ZERO);
+        let fallback = SamplingDropStrategy::new(inner, every(2)).with_independent_sampling();
+        compose(primary, fallback, 4);
+
+        assert_eq!(messages(&handled), ["0", "2"]);
+    }
+}
@@ -0,0 +1,344 @@
+//! Types and traits for the sequence drop strategies, which combine many drop strategies into one.
+//!
+//! These only need the `alloc` feature, so they can be used in `no_std` environments.
+
+use crate::adapters::SharedError;
+use crate::{DynFallibleTryDropStrategy, FallibleTryDropStrategy, TryDropStrategy};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::mem;
+use spin::Mutex;
+
+#[cfg(feature = "derives")]
+use core::fmt;
+
+type Strategies = Vec<Box<dyn DynFallibleTryDropStrategy + Send + Sync>>;
+
+/// Hand `error` to each of the `strategies` in order until `f` returns [`Some`]. Every strategy
+/// but the last gets a copy of the error; the last one gets the original error, unless an earlier
+/// strategy kept its copy around.
+fn share<T>(
+    error: crate::Error,
+    strategies: &Strategies,
+    mut f: impl FnMut(&(dyn DynFallibleTryDropStrategy + Send + Sync), crate::Error) -> Option<T>,
+) -> Option<T> {
+    let shared = SharedError::new(error);
+    let (last, rest) = strategies.split_last()?;
+
+    for strategy in rest {
+        if let Some(value) = f(strategy.as_ref(), shared.copy()) {
+            return Some(value);
+        }
+    }
+
+    f(last.as_ref(), shared.into_error())
+}
+
+/// A drop strategy which hands every error to all of its drop strategies, in the order they were
+/// added. A drop strategy failing doesn't stop the error from reaching the rest; the error of the
+/// first drop strategy which failed is returned.
+#[derive(Default)]
+pub struct SequenceDropStrategy {
+    strategies: Strategies,
+}
+
+impl SequenceDropStrategy {
+    /// Create a new sequence drop strategy without any drop strategies, which drops every error.
+    pub const fn new() -> Self {
+        Self {
+            strategies: Vec::new(),
+        }
+    }
+
+    /// Add a drop strategy to the end of the sequence.
+    pub fn push(
+        &mut self,
+        strategy: impl FallibleTryDropStrategy + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.strategies.push(Box::new(strategy));
+        self
+    }
+
+    /// Get how many drop strategies are in the sequence.
+    pub fn len(&self) -> usize {
+        self.strategies.len()
+    }
+
+    /// Check whether or not the sequence doesn't have any drop strategies.
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty()
+    }
+}
+
+#[cfg(feature = "derives")]
+impl fmt::Debug for SequenceDropStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SequenceDropStrategy")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl FallibleTryDropStrategy for SequenceDropStrategy {
+    type Error = crate::Error;
+
+    fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
+        let mut first_error = None;
+        share::<()>(error, &self.strategies, |strategy, error| {
+            if let Err(error) = strategy.dyn_try_handle_error(error) {
+                first_error.get_or_insert(error);
+            }
+
+            None
+        });
+
+        first_error.map_or(Ok(()), Err)
+    }
+
+    fn on_uninstall(&self) {
+        self.strategies
+            .iter()
+            .for_each(|strategy| strategy.dyn_on_uninstall())
+    }
+
+    fn on_fork_child(&self) {
+        self.strategies
+            .iter()
+            .for_each(|strategy| strategy.dyn_on_fork_child())
+    }
+}
+
+/// A drop strategy which hands an error to its drop strategies in the order they were added,
+/// stopping at the first one which succeeds. If all of them fail, the error of the last one is
+/// returned. If there aren't any, the error itself is returned.
+#[derive(Default)]
+pub struct FirstSuccessDropStrategy {
+    strategies: Strategies,
+}
+
+impl FirstSuccessDropStrategy {
+    /// Create a new first success drop strategy without any drop strategies, which fails to handle
+    /// every error.
+    pub const fn new() -> Self {
+        Self {
+            strategies: Vec::new(),
+        }
+    }
+
+    /// Add a drop strategy to try after the ones which were already added.
+    pub fn push(
+        &mut self,
+        strategy: impl FallibleTryDropStrategy + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.strategies.push(Box::new(strategy));
+        self
+    }
+
+    /// Get how many drop strategies will be tried.
+    pub fn len(&self) -> usize {
+        self.strategies.len()
+    }
+
+    /// Check whether or not there aren't any drop strategies to try.
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty()
+    }
+}
+
+#[cfg(feature = "derives")]
+impl fmt::Debug for FirstSuccessDropStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FirstSuccessDropStrategy")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl FallibleTryDropStrategy for FirstSuccessDropStrategy {
+    type Error = crate::Error;
+
+    fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
+        if self.strategies.is_empty() {
+            return Err(error);
+        }
+
+        let mut last_error = None;
+        let handled = share(error, &self.strategies, |strategy, error| {
+            match strategy.dyn_try_handle_error(error) {
+                Ok(()) => Some(()),
+                Err(error) => {
+                    last_error = Some(error);
+                    None
+                }
+            }
+        });
+
+        match (handled, last_error) {
+            (None, Some(error)) => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    fn on_uninstall(&self) {
+        self.strategies
+            .iter()
+            .for_each(|strategy| strategy.dyn_on_uninstall())
+    }
+
+    fn on_fork_child(&self) {
+        self.strategies
+            .iter()
+            .for_each(|strategy| strategy.dyn_on_fork_child())
+    }
+}
+
+/// A drop strategy which keeps every drop error it handles in a [`Vec`], in the order they were
+/// handled. Unlike the [`CollectDropStrategy`], this doesn't record when an error was reported,
+/// which means it can be used in `no_std` environments.
+///
+/// [`CollectDropStrategy`]: crate::drop_strategies::CollectDropStrategy
+#[cfg_attr(feature = "derives", derive(Debug))]
+#[derive(Default)]
+pub struct VecDropStrategy {
+    errors: Mutex<Vec<crate::Error>>,
+}
+
+impl VecDropStrategy {
+    /// Create a new vec drop strategy which hasn't kept any errors yet.
+    pub const fn new() -> Self {
+        Self {
+            errors: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Get how many errors have been kept so far.
+    pub fn len(&self) -> usize {
+        self.errors.lock().len()
+    }
+
+    /// Check whether or not no errors have been kept so far.
+    pub fn is_empty(&self) -> bool {
+        self.errors.lock().is_empty()
+    }
+
+    /// Take the errors which have been kept so far, in the order they were handled, leaving none
+    /// behind.
+    pub fn take(&self) -> Vec<crate::Error> {
+        mem::take(&mut *self.errors.lock())
+    }
+}
+
+impl TryDropStrategy for VecDropStrategy {
+    fn handle_error(&self, error: crate::Error) {
+        self.errors.lock().push(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::{NoOpDropStrategy, PanicDropStrategy};
+    use crate::test_utils::{fallible_given, FallibleDropStrategy};
+    use alloc::sync::Arc;
+    use core::error::Error;
+    use core::fmt;
+    use std::format;
+    use std::string::{String, ToString};
+
+    /// Hands its errors to the vec drop strategy it shares with the test.
+    struct Shared(Arc<VecDropStrategy>);
+
+    impl TryDropStrategy for Shared {
+        fn handle_error(&self, error: crate::Error) {
+            self.0.handle_error(error)
+        }
+    }
+
+    fn messages(strategy: &VecDropStrategy) -> Vec<String> {
+        strategy.take().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_sequence_drop_strategy() {
+        let first = Arc::new(VecDropStrategy::new());
+        let second = Arc::new(VecDropStrategy::new());
+        let mut strategy = SequenceDropStrategy::new();
+        strategy
+            .push(Shared(Arc::clone(&first)))
+            .push(FallibleDropStrategy)
+            .push(Shared(Arc::clone(&second)));
+        assert_eq!(strategy.len(), 3);
+
+        let error = strategy
+            .try_handle_error(anyhow::anyhow!("inner").context("outer"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "outer");
+        assert_eq!(format!("{error:#}"), "outer: inner");
+
+        assert_eq!(messages(&first), ["outer"]);
+        assert_eq!(messages(&second), ["outer"]);
+    }
+
+    #[test]
+    fn test_sequence_drop_strategy_last_gets_original() {
+        #[derive(Debug)]
+        struct Original;
+
+        impl fmt::Display for Original {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("original")
+            }
+        }
+
+        impl Error for Original {}
+
+        let errors = Arc::new(VecDropStrategy::new());
+        let mut strategy = SequenceDropStrategy::new();
+        strategy
+            .push(NoOpDropStrategy)
+            .push(Shared(Arc::clone(&errors)));
+        strategy
+            .try_handle_error(crate::Error::new(Original))
+            .unwrap();
+
+        let errors = errors.take();
+        assert!(errors[0].is::<Original>());
+    }
+
+    #[test]
+    fn test_first_success_drop_strategy() {
+        let errors = Arc::new(VecDropStrategy::new());
+        let mut strategy = FirstSuccessDropStrategy::new();
+        assert!(strategy.try_handle_error(anyhow::anyhow!("empty")).is_err());
+
+        strategy
+            .push(FallibleDropStrategy)
+            .push(Shared(Arc::clone(&errors)))
+            .push(PanicDropStrategy::DEFAULT);
+        strategy
+            .try_handle_error(anyhow::anyhow!("handled"))
+            .unwrap();
+        assert_eq!(messages(&errors), ["handled"]);
+
+        let mut strategy = FirstSuccessDropStrategy::new();
+        strategy
+            .push(FallibleDropStrategy)
+            .push(FallibleDropStrategy);
+        let error = strategy
+            .try_handle_error(anyhow::anyhow!("unhandled"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "unhandled");
+    }
+
+    #[test]
+    fn test_vec_drop_strategy() {
+        let strategy = Arc::new(VecDropStrategy::new());
+        drop(fallible_given(
+            Shared(Arc::clone(&strategy)),
+            PanicDropStrategy::DEFAULT,
+        ));
+        assert_eq!(strategy.len(), 1);
+        assert_eq!(messages(&strategy), ["this will always fail"]);
+        assert!(strategy.is_empty());
+    }
+}
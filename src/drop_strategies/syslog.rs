@@ -0,0 +1,100 @@
+//! Types and traits for the syslog drop strategy. This is built on top of the `syslog` crate.
+
+use crate::FallibleTryDropStrategy;
+#[cfg(feature = "derives")]
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::string::String;
+use std::sync::{Mutex, PoisonError};
+use syslog::{Formatter3164, Logger, LoggerBackend};
+
+pub use syslog::{Facility, Severity};
+
+/// A drop strategy which sends the message of an error to the system logger, which is the natural
+/// destination for drop errors in long running services on Unix.
+pub struct SyslogDropStrategy {
+    /// The severity to send the errors at.
+    pub severity: Severity,
+
+    logger: Mutex<Logger<LoggerBackend, Formatter3164>>,
+}
+
+impl SyslogDropStrategy {
+    /// Connect to the system logger through its local socket, sending the errors at the error
+    /// severity under the given facility and process name.
+    pub fn new(facility: Facility, process_name: impl Into<String>) -> io::Result<Self> {
+        syslog::unix(formatter(facility, process_name))
+            .map(Self::from_logger)
+            .map_err(into_io_error)
+    }
+
+    /// Connect to the system logger through the local socket at the given path, sending the
+    /// errors at the error severity under the given facility and process name.
+    pub fn with_path(
+        facility: Facility,
+        process_name: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> io::Result<Self> {
+        syslog::unix_custom(formatter(facility, process_name), path)
+            .map(Self::from_logger)
+            .map_err(into_io_error)
+    }
+
+    /// Set the severity to send the errors at.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    fn from_logger(logger: Logger<LoggerBackend, Formatter3164>) -> Self {
+        Self {
+            severity: Severity::LOG_ERR,
+            logger: Mutex::new(logger),
+        }
+    }
+}
+
+fn formatter(facility: Facility, process_name: impl Into<String>) -> Formatter3164 {
+    Formatter3164 {
+        facility,
+        hostname: None,
+        process: process_name.into(),
+        pid: std::process::id(),
+    }
+}
+
+fn into_io_error(error: syslog::Error) -> io::Error {
+    match error {
+        syslog::Error::Io(error) | syslog::Error::Write(error) => error,
+        error => io::Error::other(error),
+    }
+}
+
+#[cfg(feature = "derives")]
+impl fmt::Debug for SyslogDropStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SyslogDropStrategy").finish_non_exhaustive()
+    }
+}
+
+impl FallibleTryDropStrategy for SyslogDropStrategy {
+    type Error = io::Error;
+
+    fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
+        let message = crate::fmt::render(&error);
+        let mut logger = self.logger.lock().unwrap_or_else(PoisonError::into_inner);
+        let result = match self.severity {
+            Severity::LOG_EMERG => logger.emerg(message),
+            Severity::LOG_ALERT => logger.alert(message),
+            Severity::LOG_CRIT => logger.crit(message),
+            Severity::LOG_ERR => logger.err(message),
+            Severity::LOG_WARNING => logger.warning(message),
+            Severity::LOG_NOTICE => logger.notice(message),
+            Severity::LOG_INFO => logger.info(message),
+            Severity::LOG_DEBUG => logger.debug(message),
+        };
+
+        result.map_err(into_io_error)
+    }
+}
@@ -0,0 +1,109 @@
+//! Types and traits shared by the drop strategies which end the program or the thread, such as the
+//! [`PanicDropStrategy`](super::PanicDropStrategy) and the
+//! [`ExitDropStrategy`](super::ExitDropStrategy).
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+/// A shared function which compares, orders and hashes by its address, so that the types wrapping
+/// one can derive those traits. Clones compare equal to each other; two functions shared
+/// separately never compare equal, even if they're the same function.
+pub(crate) struct SharedFn<F: ?Sized>(Arc<F>);
+
+impl<F: ?Sized> SharedFn<F> {
+    pub(crate) fn new(f: Arc<F>) -> Self {
+        Self(f)
+    }
+
+    fn addr(&self) -> *const () {
+        Arc::as_ptr(&self.0) as *const ()
+    }
+}
+
+impl<F: ?Sized> Clone for SharedFn<F> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<F: ?Sized> Deref for SharedFn<F> {
+    type Target = F;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<F: ?Sized> fmt::Debug for SharedFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.addr(), f)
+    }
+}
+
+impl<F: ?Sized> PartialEq for SharedFn<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl<F: ?Sized> Eq for SharedFn<F> {}
+
+impl<F: ?Sized> PartialOrd for SharedFn<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: ?Sized> Ord for SharedFn<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+impl<F: ?Sized> Hash for SharedFn<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr().hash(state)
+    }
+}
+
+/// A callback which is run right before a terminal drop strategy panics or exits, with the error
+/// which caused it. This is the last chance to flush metrics or write an audit record, as the
+/// fallback handler never runs after a terminal drop strategy.
+///
+/// It's cheap to clone, and clones compare equal to each other; two hooks created separately never
+/// compare equal, even from the same function.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct TerminalHook(SharedFn<dyn Fn(&anyhow::Error) + Send + Sync>);
+
+impl TerminalHook {
+    /// Create a new terminal hook from a function.
+    pub fn new(f: impl Fn(&anyhow::Error) + Send + Sync + 'static) -> Self {
+        Self(SharedFn::new(Arc::new(f)))
+    }
+
+    /// Run the hook on a best-effort basis: if it panics, the panic is swallowed so that the drop
+    /// strategy can still carry on.
+    ///
+    /// # Notes
+    /// If the hook panics while the thread is already unwinding, the program aborts anyway.
+    pub fn call(&self, error: &anyhow::Error) {
+        call_hook(&*self.0, error)
+    }
+}
+
+/// Run a hook on a best-effort basis, swallowing its panic if it panics.
+pub(crate) fn call_hook(hook: &dyn Fn(&anyhow::Error), error: &anyhow::Error) {
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(error)));
+}
+
+/// Flush the drop strategies registered to be flushed, if any, right before a terminal drop
+/// strategy panics or exits.
+pub(crate) fn flush_registered() {
+    // the program is about to end, there's nothing useful to do if flushing fails
+    #[cfg(feature = "async-flush")]
+    let _ = crate::flush::flush_all();
+}
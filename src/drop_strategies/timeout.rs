@@ -0,0 +1,205 @@
+//! Types and traits for the timeout drop strategy.
+
+use crate::{FallibleTryDropStrategy, TryDropStrategy};
+use std::error::Error as StdError;
+use std::fmt;
+use std::panic;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// The error handed to the `on_timeout` drop strategy of a [`TimeoutDropStrategy`] when the inner
+/// drop strategy didn't finish in time.
+#[cfg_attr(
+    feature = "derives",
+    derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Debug)]
+pub struct TimedOut {
+    /// How long the inner drop strategy was waited for.
+    pub timeout: Duration,
+}
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the drop strategy didn't handle the error within {:?}",
+            self.timeout
+        )
+    }
+}
+
+impl StdError for TimedOut {}
+
+/// A drop strategy which bounds how long an inner drop strategy may take to handle an error, so
+/// that a misbehaving drop strategy, e.g. one which blocks on a network write, can't hang the
+/// program, for example while it's shutting down.
+///
+/// Every error is handled by the inner drop strategy on a newly spawned thread, which is waited
+/// for until the timeout runs out. If it does, a [`TimedOut`] error is handed to the `on_timeout`
+/// drop strategy instead. If the inner drop strategy panics in time, the panic is resumed on the
+/// calling thread.
+///
+/// # Caveats
+/// A thread can't be stopped from the outside, so the thread of a drop strategy which timed out is
+/// abandoned, not killed. It keeps running until the inner drop strategy returns, which may be
+/// never, so every timeout can leak a thread along with whatever the inner drop strategy holds on
+/// to. The thread also doesn't keep the program alive; if the program exits first, the error is
+/// never handled.
+#[cfg_attr(feature = "derives", derive(Debug, Clone))]
+pub struct TimeoutDropStrategy<S, F>
+where
+    S: FallibleTryDropStrategy + Send + Sync + 'static,
+    F: TryDropStrategy,
+{
+    /// The drop strategy to hand the errors to. It's shared with the threads which handle the
+    /// errors.
+    pub inner: Arc<S>,
+
+    /// The drop strategy to hand a [`TimedOut`] error to if the inner drop strategy didn't finish
+    /// in time.
+    pub on_timeout: F,
+
+    /// How long to wait for the inner drop strategy to handle an error.
+    pub timeout: Duration,
+}
+
+impl<S, F> TimeoutDropStrategy<S, F>
+where
+    S: FallibleTryDropStrategy + Send + Sync + 'static,
+    F: TryDropStrategy,
+{
+    /// Create a new timeout drop strategy which waits `timeout` for the inner drop strategy before
+    /// handing a [`TimedOut`] error to `on_timeout`.
+    pub fn new(inner: S, on_timeout: F, timeout: Duration) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            on_timeout,
+            timeout,
+        }
+    }
+}
+
+impl<S, F> FallibleTryDropStrategy for TimeoutDropStrategy<S, F>
+where
+    S: FallibleTryDropStrategy + Send + Sync + 'static,
+    S::Error: Send + 'static,
+    F: TryDropStrategy,
+{
+    type Error = S::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let inner = Arc::clone(&self.inner);
+        let spawned = thread::Builder::new()
+            .name("try-drop-timeout".into())
+            .spawn(move || {
+                // the receiver is gone if the timeout ran out
+                let _ = sender.send(inner.try_handle_error(error));
+            });
+
+        // the error is gone along with the closure, so the best that can be done is to report why
+        let handle = match spawned {
+            Ok(handle) => handle,
+            Err(error) => {
+                self.on_timeout.handle_error(error.into());
+                return Ok(());
+            }
+        };
+
+        match receiver.recv_timeout(self.timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                self.on_timeout.handle_error(anyhow::Error::new(TimedOut {
+                    timeout: self.timeout,
+                }));
+                Ok(())
+            }
+
+            // the inner drop strategy panicked, so pass the panic on as if it ran on this thread
+            Err(RecvTimeoutError::Disconnected) => match handle.join() {
+                Err(payload) => panic::resume_unwind(payload),
+                Ok(()) => unreachable!("the thread returned without sending a result"),
+            },
+        }
+    }
+
+    fn on_uninstall(&self) {
+        self.inner.on_uninstall();
+        self.on_timeout.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.inner.on_fork_child();
+        self.on_timeout.on_fork_child()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::{AdHocDropStrategy, NoOpDropStrategy, PanicDropStrategy};
+    use crate::test_utils::FallibleDropStrategy;
+    use std::string::{String, ToString};
+    use std::sync::Mutex;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_timeout_drop_strategy() {
+        let timed_out = Arc::new(Mutex::new(Vec::new()));
+        let t = Arc::clone(&timed_out);
+        let strategy = TimeoutDropStrategy::new(
+            AdHocDropStrategy(|_| thread::sleep(Duration::from_secs(5))),
+            AdHocDropStrategy(move |error: crate::Error| t.lock().unwrap().push(error)),
+            Duration::from_millis(50),
+        );
+        strategy
+            .try_handle_error(anyhow::anyhow!("this will hang"))
+            .unwrap();
+
+        let timed_out = timed_out.lock().unwrap();
+        assert_eq!(timed_out.len(), 1);
+        let error = timed_out[0]
+            .downcast_ref::<TimedOut>()
+            .expect("the fallback didn't get a timeout error");
+        assert_eq!(error.timeout, Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_timeout_drop_strategy_in_time() {
+        let handled = Arc::new(Mutex::new(Vec::<String>::new()));
+        let h = Arc::clone(&handled);
+        let strategy = TimeoutDropStrategy::new(
+            AdHocDropStrategy(move |error: crate::Error| h.lock().unwrap().push(error.to_string())),
+            PanicDropStrategy::DEFAULT,
+            Duration::from_secs(60),
+        );
+        strategy
+            .try_handle_error(anyhow::anyhow!("handled in time"))
+            .unwrap();
+        assert_eq!(*handled.lock().unwrap(), ["handled in time"]);
+
+        let strategy = TimeoutDropStrategy::new(
+            FallibleDropStrategy,
+            NoOpDropStrategy,
+            Duration::from_secs(60),
+        );
+        let error = strategy
+            .try_handle_error(anyhow::anyhow!("failed in time"))
+            .unwrap_err();
+        assert_eq!(error.to_string(), "failed in time");
+    }
+
+    #[test]
+    #[should_panic(expected = "panicked in time")]
+    fn test_timeout_drop_strategy_resumes_panics() {
+        let strategy = TimeoutDropStrategy::new(
+            PanicDropStrategy::DEFAULT,
+            NoOpDropStrategy,
+            Duration::from_secs(60),
+        );
+        let _ = strategy.try_handle_error(anyhow::anyhow!("panicked in time"));
+    }
+}
@@ -0,0 +1,295 @@
+//! Types and traits for the tracing drop strategy. This is built on top of the `tracing` and
+//! `tracing-subscriber` crates.
+
+use crate::TryDropStrategy;
+use std::borrow::Cow;
+use std::string::String;
+use std::vec::Vec;
+use tracing::span::{Attributes, Id};
+use tracing::{Level, Span, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+/// A drop strategy which emits an event with the drop error attached. The event is emitted inside
+/// of the current span, so that the drop error is correlated with the surrounding operation.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub struct TracingDropStrategy {
+    /// The level of the event.
+    pub level: Level,
+
+    /// The message of the event.
+    pub message: Cow<'static, str>,
+}
+
+impl TracingDropStrategy {
+    /// The default tracing drop strategy, which emits an event at the error level.
+    pub const DEFAULT: Self = Self::new();
+
+    /// Create a new tracing drop strategy.
+    pub const fn new() -> Self {
+        Self {
+            level: Level::ERROR,
+            message: Cow::Borrowed("error occurred when dropping an object"),
+        }
+    }
+
+    /// Set the level of the event.
+    pub const fn with_level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set the message of the event.
+    pub fn with_message(mut self, message: impl Into<Cow<'static, str>>) -> Self {
+        self.message = message.into();
+        self
+    }
+}
+
+impl Default for TracingDropStrategy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl TryDropStrategy for TracingDropStrategy {
+    fn handle_error(&self, error: crate::Error) {
+        let error = crate::fmt::render(&error);
+
+        // the level of an event has to be known at compile time
+        macro_rules! event {
+            ($level:expr) => {
+                tracing::event!($level, error = %error, "{}", self.message)
+            };
+        }
+
+        match self.level {
+            Level::TRACE => event!(Level::TRACE),
+            Level::DEBUG => event!(Level::DEBUG),
+            Level::INFO => event!(Level::INFO),
+            Level::WARN => event!(Level::WARN),
+            _ => event!(Level::ERROR),
+        }
+    }
+}
+
+/// The drop errors which occurred while a span was open, stored in the extensions of the span.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+)]
+pub struct DropErrors(pub Vec<String>);
+
+/// A drop strategy which attributes drop errors to the current span, by storing them in its
+/// [`DropErrors`] extension.
+///
+/// The extension is only available if the [`TryDropLayer`] is installed on top of a
+/// [`Registry`]. Errors which occur outside of any span, or if the extension isn't available, are
+/// redirected to the inner drop strategy.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)
+)]
+pub struct SpanExtensionDropStrategy<S: TryDropStrategy> {
+    /// The drop strategy to redirect errors to if there's no span to attribute them to.
+    pub inner: S,
+}
+
+impl<S: TryDropStrategy> SpanExtensionDropStrategy<S> {
+    /// Create a new span extension drop strategy, which redirects errors to the given drop
+    /// strategy if there's no span to attribute them to.
+    pub const fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S: TryDropStrategy> TryDropStrategy for SpanExtensionDropStrategy<S> {
+    fn handle_error(&self, error: crate::Error) {
+        let mut error = Some(error);
+
+        Span::current().with_subscriber(|(id, dispatch)| {
+            let span = dispatch
+                .downcast_ref::<Registry>()
+                .and_then(|registry| registry.span(id));
+
+            if let Some(span) = span {
+                if let Some(errors) = span.extensions_mut().get_mut::<DropErrors>() {
+                    if let Some(error) = error.take() {
+                        errors.0.push(crate::fmt::render(&error))
+                    }
+                }
+            }
+        });
+
+        if let Some(error) = error {
+            self.inner.handle_error(error)
+        }
+    }
+}
+
+/// A layer which initializes the [`DropErrors`] extension of every new span, then once the span
+/// closes, emits a summary event if any drop errors were attributed to it.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub struct TryDropLayer {
+    _private: (),
+}
+
+impl TryDropLayer {
+    /// Create a new try drop layer.
+    pub const fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl Default for TryDropLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for TryDropLayer {
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(DropErrors(Vec::new()))
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let errors = span.extensions_mut().remove::<DropErrors>();
+
+        if let Some(DropErrors(errors)) = errors.filter(|errors| !errors.0.is_empty()) {
+            tracing::event!(
+                target: "try_drop",
+                Level::WARN,
+                span = span.name(),
+                errors = ?errors,
+                "drop errors occurred in span",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::{CountingDropStrategy, NoOpDropStrategy};
+    use std::format;
+    use std::string::ToString;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::Event;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct CapturingLayer(Arc<Mutex<Vec<String>>>);
+
+    struct FieldVisitor(String);
+
+    impl Visit for FieldVisitor {
+        fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+            if !self.0.is_empty() {
+                self.0.push(' ');
+            }
+
+            self.0.push_str(&format!("{}={:?}", field.name(), value))
+        }
+    }
+
+    impl<S: Subscriber> Layer<S> for CapturingLayer {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            let mut visitor = FieldVisitor(String::new());
+            event.record(&mut visitor);
+            self.0.lock().unwrap().push(visitor.0)
+        }
+    }
+
+    #[test]
+    fn test_span_extension_drop_strategy() {
+        let events = CapturingLayer::default();
+        let subscriber = Registry::default()
+            .with(TryDropLayer::new())
+            .with(events.clone());
+        let strategy = SpanExtensionDropStrategy::new(CountingDropStrategy::new(NoOpDropStrategy));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("request");
+            span.in_scope(|| {
+                strategy.handle_error(anyhow::anyhow!("first error"));
+                strategy.handle_error(anyhow::anyhow!("second error"));
+            });
+            drop(span);
+
+            strategy.handle_error(anyhow::anyhow!("outside of a span"));
+        });
+
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            ["message=drop errors occurred in span span=\"request\" \
+                 errors=[\"first error\", \"second error\"]"]
+        );
+        assert_eq!(strategy.inner.count(), 1);
+    }
+
+    type EventRecord = (Level, Option<&'static str>, String);
+
+    #[derive(Clone, Default)]
+    struct EventsLayer(Arc<Mutex<Vec<EventRecord>>>);
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for EventsLayer {
+        fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+            let mut visitor = FieldVisitor(String::new());
+            event.record(&mut visitor);
+            self.0.lock().unwrap().push((
+                *event.metadata().level(),
+                ctx.event_span(event).map(|span| span.name()),
+                visitor.0,
+            ))
+        }
+    }
+
+    #[test]
+    fn test_tracing_drop_strategy() {
+        let events = EventsLayer::default();
+        let subscriber = Registry::default().with(events.clone());
+        let strategy = TracingDropStrategy::new()
+            .with_level(Level::WARN)
+            .with_message("failed to drop");
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("operation")
+                .in_scope(|| strategy.handle_error(anyhow::anyhow!("this will always fail")));
+        });
+
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            [(
+                Level::WARN,
+                Some("operation"),
+                "message=failed to drop error=this will always fail".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_falls_through_without_layer() {
+        let strategy = SpanExtensionDropStrategy::new(CountingDropStrategy::new(NoOpDropStrategy));
+
+        tracing::subscriber::with_default(Registry::default(), || {
+            tracing::info_span!("request")
+                .in_scope(|| strategy.handle_error(anyhow::anyhow!("no extension")));
+        });
+
+        assert_eq!(strategy.inner.count(), 1);
+    }
+}
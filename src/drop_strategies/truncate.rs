@@ -0,0 +1,203 @@
+//! Types and traits for the truncate drop strategy.
+
+use crate::FallibleTryDropStrategy;
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt;
+use std::format;
+use std::string::{String, ToString};
+
+/// The default maximum length of a rendered error message, in bytes.
+pub const DEFAULT_MAX_MESSAGE_LEN: usize = 64 * 1024;
+
+/// Truncate the message to at most `max_len` bytes, without splitting a character, then append a
+/// note of how long the message was. The message is borrowed as is if it's short enough.
+pub fn truncate_message(message: &str, max_len: usize) -> Cow<'_, str> {
+    if message.len() <= max_len {
+        return Cow::Borrowed(message);
+    }
+
+    let mut end = max_len;
+
+    while !message.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    Cow::Owned(format!(
+        "{}… (truncated, {} bytes total)",
+        &message[..end],
+        message.len()
+    ))
+}
+
+/// An error whose message was truncated by the [`TruncateDropStrategy`]. The original error can
+/// be recovered by downcasting to this type.
+pub struct TruncatedError {
+    message: String,
+    original: anyhow::Error,
+}
+
+impl TruncatedError {
+    /// Get the original, untruncated error.
+    pub fn original(&self) -> &anyhow::Error {
+        &self.original
+    }
+
+    /// Take the original, untruncated error.
+    pub fn into_original(self) -> anyhow::Error {
+        self.original
+    }
+}
+
+impl fmt::Debug for TruncatedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TruncatedError")
+            .field("message", &self.message)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for TruncatedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for TruncatedError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.original.source()
+    }
+}
+
+/// A drop strategy which truncates very long error messages before handing the error to an inner
+/// drop strategy, so that it doesn't blow up log files or notification systems.
+///
+/// Errors with a message which is too long are wrapped in a [`TruncatedError`], which keeps the
+/// original error around for the drop strategies which need it.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub struct TruncateDropStrategy<S: FallibleTryDropStrategy> {
+    /// The drop strategy to hand the errors to.
+    pub inner: S,
+
+    /// The maximum length of the error message, in bytes.
+    pub max_len: usize,
+}
+
+impl<S: FallibleTryDropStrategy> TruncateDropStrategy<S> {
+    /// Create a new truncate drop strategy which truncates messages longer than
+    /// [`DEFAULT_MAX_MESSAGE_LEN`].
+    pub const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            max_len: DEFAULT_MAX_MESSAGE_LEN,
+        }
+    }
+
+    /// Set the maximum length of the error message, in bytes.
+    pub const fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl<S: FallibleTryDropStrategy + Default> Default for TruncateDropStrategy<S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<S: FallibleTryDropStrategy> FallibleTryDropStrategy for TruncateDropStrategy<S> {
+    type Error = S::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        let message = error.to_string();
+
+        let error = match truncate_message(&message, self.max_len) {
+            Cow::Borrowed(_) => error,
+            Cow::Owned(message) => anyhow::Error::new(TruncatedError {
+                message,
+                original: error,
+            }),
+        };
+
+        self.inner.try_handle_error(error)
+    }
+
+    fn on_uninstall(&self) {
+        self.inner.on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        self.inner.on_fork_child()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::AdHocDropStrategy;
+    use std::sync::Mutex;
+    use std::vec::Vec;
+
+    #[test]
+    fn test_truncate_message() {
+        let message = "a".repeat(4 * 1024 * 1024);
+        let truncated = truncate_message(&message, DEFAULT_MAX_MESSAGE_LEN);
+        assert!(truncated.len() < DEFAULT_MAX_MESSAGE_LEN + 64);
+        assert!(truncated.ends_with("… (truncated, 4194304 bytes total)"));
+        assert!(matches!(
+            truncate_message("short", 5),
+            Cow::Borrowed("short")
+        ));
+    }
+
+    #[test]
+    fn test_truncate_message_on_char_boundary() {
+        // every 'é' is two bytes long, so the limit falls in the middle of one
+        let message = "é".repeat(1024 * 1024);
+        let truncated = truncate_message(&message, 7);
+        assert_eq!(truncated, "ééé… (truncated, 2097152 bytes total)");
+    }
+
+    #[test]
+    fn test_truncate_drop_strategy() {
+        let handled = Mutex::new(Vec::new());
+        let strategy = TruncateDropStrategy::new(AdHocDropStrategy(|error: crate::Error| {
+            handled.lock().unwrap().push(error)
+        }))
+        .with_max_len(16);
+        strategy
+            .try_handle_error(anyhow::anyhow!("{}", "x".repeat(2 * 1024 * 1024)))
+            .unwrap();
+        strategy.try_handle_error(anyhow::anyhow!("short")).unwrap();
+
+        let handled = handled.into_inner().unwrap();
+        assert_eq!(
+            handled[0].to_string(),
+            "xxxxxxxxxxxxxxxx… (truncated, 2097152 bytes total)"
+        );
+        let original = handled[0]
+            .downcast_ref::<TruncatedError>()
+            .expect("the error was not wrapped")
+            .original();
+        assert_eq!(original.to_string().len(), 2 * 1024 * 1024);
+        assert_eq!(handled[1].to_string(), "short");
+        assert!(handled[1].downcast_ref::<TruncatedError>().is_none());
+    }
+
+    #[test]
+    fn test_default() {
+        #[derive(Default)]
+        struct Ignore;
+
+        impl crate::TryDropStrategy for Ignore {
+            fn handle_error(&self, _error: crate::Error) {}
+        }
+
+        let strategy = TruncateDropStrategy::<Ignore>::default();
+        assert_eq!(strategy.max_len, DEFAULT_MAX_MESSAGE_LEN);
+    }
+}
@@ -0,0 +1,424 @@
+use crate::drop_strategies::truncate::{truncate_message, DEFAULT_MAX_MESSAGE_LEN};
+use crate::fmt::ChainLimit;
+use crate::FallibleTryDropStrategy;
+use parking_lot::Mutex;
+use std::boxed::Box;
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::io::Write;
+use std::string::{String, ToString};
+use std::thread;
+use std::vec::Vec;
+
+/// A drop error which has been formatted once, ready to be rendered in multiple formats.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub struct ErrorRecord {
+    /// The message of the error.
+    pub message: String,
+
+    /// The messages of the causes of the error, outermost first.
+    pub chain: Vec<String>,
+
+    /// The timestamp of when the error was handled, if any.
+    pub timestamp: Option<String>,
+
+    /// The name of the thread the error was handled in, if it has one.
+    pub thread: Option<String>,
+}
+
+impl ErrorRecord {
+    /// Create a record from the given error, capturing the name of the current thread.
+    pub fn new(error: &anyhow::Error, timestamp: Option<String>) -> Self {
+        Self {
+            message: crate::fmt::render(error),
+            chain: error.chain().skip(1).map(ToString::to_string).collect(),
+            timestamp,
+            thread: thread::current().name().map(ToString::to_string),
+        }
+    }
+
+    /// Create a record from the given error like [`ErrorRecord::new`], only keeping the causes
+    /// within `limit`.
+    pub fn with_chain_limit(
+        error: &anyhow::Error,
+        timestamp: Option<String>,
+        limit: ChainLimit,
+    ) -> Self {
+        Self {
+            message: crate::fmt::render(error),
+            chain: limit.causes(error),
+            timestamp,
+            thread: thread::current().name().map(ToString::to_string),
+        }
+    }
+
+    /// Truncate the message and the messages of the causes to at most `max_len` bytes each.
+    pub fn truncate(&mut self, max_len: usize) {
+        for message in core::iter::once(&mut self.message).chain(&mut self.chain) {
+            if message.len() > max_len {
+                *message = truncate_message(message, max_len).into_owned();
+            }
+        }
+    }
+
+    /// Render this record in a human readable format, ending with a newline.
+    pub fn write_pretty(&self, mut writer: impl Write) -> io::Result<()> {
+        if let Some(timestamp) = &self.timestamp {
+            write!(writer, "{} ", timestamp)?;
+        }
+
+        match &self.thread {
+            Some(thread) => write!(writer, "error in thread '{}': ", thread)?,
+            None => writer.write_all(b"error: ")?,
+        }
+
+        writeln!(writer, "{}", self.message)?;
+
+        for cause in &self.chain {
+            writeln!(writer, "    caused by: {}", cause)?;
+        }
+
+        Ok(())
+    }
+
+    /// Render this record as a single line JSON object, ending with a newline.
+    pub fn write_json(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(b"{\"message\":")?;
+        write_json_string(&mut writer, &self.message)?;
+        writer.write_all(b",\"chain\":[")?;
+
+        for (index, cause) in self.chain.iter().enumerate() {
+            if index != 0 {
+                writer.write_all(b",")?;
+            }
+
+            write_json_string(&mut writer, cause)?;
+        }
+
+        writer.write_all(b"],\"timestamp\":")?;
+        write_json_option(&mut writer, self.timestamp.as_deref())?;
+        writer.write_all(b",\"thread\":")?;
+        write_json_option(&mut writer, self.thread.as_deref())?;
+        writer.write_all(b"}\n")
+    }
+}
+
+fn write_json_option(writer: &mut impl Write, value: Option<&str>) -> io::Result<()> {
+    match value {
+        Some(value) => write_json_string(writer, value),
+        None => writer.write_all(b"null"),
+    }
+}
+
+fn write_json_string(writer: &mut impl Write, value: &str) -> io::Result<()> {
+    writer.write_all(b"\"")?;
+
+    for c in value.chars() {
+        match c {
+            '"' => writer.write_all(b"\\\"")?,
+            '\\' => writer.write_all(b"\\\\")?,
+            '\n' => writer.write_all(b"\\n")?,
+            '\r' => writer.write_all(b"\\r")?,
+            '\t' => writer.write_all(b"\\t")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", c as u32)?,
+            c => write!(writer, "{}", c)?,
+        }
+    }
+
+    writer.write_all(b"\"")
+}
+
+/// An error which occurs when the [`DualFormatWriteStrategy`] fails to write to its writers.
+#[derive(Debug)]
+pub struct DualWriteError {
+    /// The error which occurred while writing to the pretty writer, if any.
+    pub pretty: Option<io::Error>,
+
+    /// The error which occurred while writing to the JSON writer, if any.
+    pub json: Option<io::Error>,
+}
+
+impl StdError for DualWriteError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match (&self.pretty, &self.json) {
+            (Some(error), _) | (None, Some(error)) => Some(error),
+            (None, None) => None,
+        }
+    }
+}
+
+impl fmt::Display for DualWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.pretty.is_some(), self.json.is_some()) {
+            (true, true) => f.write_str("failed to write to both the pretty and the json writer"),
+            (true, false) => f.write_str("failed to write to the pretty writer"),
+            (false, true) => f.write_str("failed to write to the json writer"),
+            (false, false) => f.write_str("no writes failed"),
+        }
+    }
+}
+
+/// A drop strategy which formats an error once, then writes it both in a human readable format
+/// and as newline delimited JSON to two separate writers.
+pub struct DualFormatWriteStrategy<P: Write, J: Write> {
+    /// The writer to write the human readable format to.
+    pub pretty: Mutex<P>,
+
+    /// The writer to write the JSON format to.
+    pub json: Mutex<J>,
+
+    /// Whether or not to succeed if only one of the writes failed.
+    pub allow_partial_success: bool,
+
+    /// The function which generates the timestamp of the record.
+    pub timestamp: Option<Box<dyn Fn() -> String + Send + Sync>>,
+
+    /// The maximum length of each message in bytes, if any. Longer messages are truncated.
+    pub max_message_len: Option<usize>,
+
+    /// How many causes of each error to write, if there's any limit.
+    pub chain_limit: Option<ChainLimit>,
+}
+
+#[cfg(feature = "derives")]
+impl<P: Write + fmt::Debug, J: Write + fmt::Debug> fmt::Debug for DualFormatWriteStrategy<P, J> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DualFormatWriteStrategy")
+            .field("pretty", &self.pretty)
+            .field("json", &self.json)
+            .field("allow_partial_success", &self.allow_partial_success)
+            .field(
+                "timestamp",
+                &self
+                    .timestamp
+                    .as_ref()
+                    .map(|_| "Box<dyn Fn() -> String + Send + Sync>"),
+            )
+            .field("max_message_len", &self.max_message_len)
+            .field("chain_limit", &self.chain_limit)
+            .finish()
+    }
+}
+
+impl<P: Write, J: Write> DualFormatWriteStrategy<P, J> {
+    /// Creates a new [`DualFormatWriteStrategy`] with the given writers.
+    pub fn new(pretty: P, json: J) -> Self {
+        Self {
+            pretty: Mutex::new(pretty),
+            json: Mutex::new(json),
+            allow_partial_success: true,
+            timestamp: None,
+            max_message_len: Some(DEFAULT_MAX_MESSAGE_LEN),
+            chain_limit: Some(ChainLimit::DEFAULT),
+        }
+    }
+
+    /// Sets whether or not to succeed if only one of the writes failed.
+    pub fn allow_partial_success(&mut self, allow_partial_success: bool) -> &mut Self {
+        self.allow_partial_success = allow_partial_success;
+        self
+    }
+
+    /// Sets the function which generates the timestamp of the record.
+    pub fn with_timestamp(
+        &mut self,
+        timestamp: impl Fn() -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.timestamp = Some(Box::new(timestamp));
+        self
+    }
+
+    /// Sets the maximum length of each message in bytes, or disables truncation if `None`.
+    pub fn max_message_len(&mut self, max_message_len: Option<usize>) -> &mut Self {
+        self.max_message_len = max_message_len;
+        self
+    }
+
+    /// Sets how many causes of each error to write, or disables the limit if `None`.
+    pub fn chain_limit(&mut self, chain_limit: Option<ChainLimit>) -> &mut Self {
+        self.chain_limit = chain_limit;
+        self
+    }
+}
+
+impl<P: Write, J: Write> FallibleTryDropStrategy for DualFormatWriteStrategy<P, J> {
+    type Error = DualWriteError;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        let timestamp = self.timestamp.as_ref().map(|timestamp| timestamp());
+        let mut record = match self.chain_limit {
+            Some(limit) => ErrorRecord::with_chain_limit(&error, timestamp, limit),
+            None => ErrorRecord::new(&error, timestamp),
+        };
+
+        if let Some(max_len) = self.max_message_len {
+            record.truncate(max_len);
+        }
+
+        let mut pretty = Vec::new();
+        let mut json = Vec::new();
+        record
+            .write_pretty(&mut pretty)
+            .expect("writing to a vec should never fail");
+        record
+            .write_json(&mut json)
+            .expect("writing to a vec should never fail");
+
+        let error = DualWriteError {
+            pretty: self.pretty.lock().write_all(&pretty).err(),
+            json: self.json.lock().write_all(&json).err(),
+        };
+
+        match (&error.pretty, &error.json) {
+            (None, None) => Ok(()),
+            (Some(_), None) | (None, Some(_)) if self.allow_partial_success => Ok(()),
+            _ => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::PanicDropStrategy;
+    use crate::test_utils::{ErrorsOnDrop, Fallible};
+    use crate::PureTryDrop;
+    use anyhow::Context;
+    use std::io::Cursor;
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("write failed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_dual_format_write_strategy() {
+        let mut pretty = Cursor::new(Vec::new());
+        let mut json = Cursor::new(Vec::new());
+        let mut strategy = DualFormatWriteStrategy::new(&mut pretty, &mut json);
+        strategy.with_timestamp(|| "1970-01-01T00:00:00Z".into());
+        let error = Err::<(), _>(anyhow::anyhow!("disk \"full\""))
+            .context("failed to flush")
+            .unwrap_err();
+        strategy.try_handle_error(error).unwrap();
+        drop(strategy);
+
+        let thread = thread::current().name().unwrap().to_string();
+        assert_eq!(
+            String::from_utf8(pretty.into_inner()).unwrap(),
+            std::format!(
+                "1970-01-01T00:00:00Z error in thread '{thread}': failed to flush\n    caused by: disk \"full\"\n"
+            ),
+        );
+        assert_eq!(
+            String::from_utf8(json.into_inner()).unwrap(),
+            std::format!(
+                "{{\"message\":\"failed to flush\",\"chain\":[\"disk \\\"full\\\"\"],\"timestamp\":\"1970-01-01T00:00:00Z\",\"thread\":\"{thread}\"}}\n"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_dual_format_write_strategy_on_drop() {
+        let mut pretty = Cursor::new(Vec::new());
+        let mut json = Cursor::new(Vec::new());
+        let strategy = DualFormatWriteStrategy::new(&mut pretty, &mut json);
+        let errors =
+            ErrorsOnDrop::<Fallible, _>::given(strategy, PanicDropStrategy::DEFAULT).adapt();
+        drop(errors);
+        assert!(pretty.into_inner().ends_with(b"this will always fail\n"));
+        assert!(json.into_inner().starts_with(
+            b"{\"message\":\"this will always fail\",\"chain\":[],\"timestamp\":null,"
+        ));
+    }
+
+    #[test]
+    fn test_chain_limit() {
+        let mut pretty = Cursor::new(Vec::new());
+        let mut json = Cursor::new(Vec::new());
+        let mut strategy = DualFormatWriteStrategy::new(&mut pretty, &mut json);
+        strategy.chain_limit(Some(ChainLimit::new(1)));
+        let error = (0..3).fold(anyhow::anyhow!("connection refused"), |error, _| {
+            error.context("retrying")
+        });
+        strategy.try_handle_error(error).unwrap();
+        drop(strategy);
+
+        assert!(String::from_utf8(pretty.into_inner()).unwrap().ends_with(
+            "retrying\n    caused by: retrying (repeated 2 times)\n    caused by: … 1 more cause\n"
+        ));
+        assert!(String::from_utf8(json.into_inner())
+            .unwrap()
+            .contains("\"chain\":[\"retrying (repeated 2 times)\",\"… 1 more cause\"]"));
+    }
+
+    #[test]
+    fn test_partial_success() {
+        let mut json = Cursor::new(Vec::new());
+        let strategy = DualFormatWriteStrategy::new(FailingWriter, &mut json);
+        strategy
+            .try_handle_error(anyhow::anyhow!("this will always fail"))
+            .expect("a partial success should be allowed by default");
+        drop(strategy);
+        assert!(!json.into_inner().is_empty());
+    }
+
+    #[test]
+    fn test_partial_failure() {
+        let mut json = Cursor::new(Vec::new());
+        let mut strategy = DualFormatWriteStrategy::new(FailingWriter, &mut json);
+        strategy.allow_partial_success(false);
+        let error = strategy
+            .try_handle_error(anyhow::anyhow!("this will always fail"))
+            .unwrap_err();
+        assert!(error.pretty.is_some());
+        assert!(error.json.is_none());
+        drop(strategy);
+        assert!(!json.into_inner().is_empty(), "the json writer was skipped");
+    }
+
+    #[test]
+    fn test_both_writes_fail() {
+        let strategy = DualFormatWriteStrategy::new(FailingWriter, FailingWriter);
+        let error = strategy
+            .try_handle_error(anyhow::anyhow!("this will always fail"))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "failed to write to both the pretty and the json writer"
+        );
+    }
+
+    #[test]
+    fn test_truncates_large_messages() {
+        let mut pretty = Cursor::new(Vec::new());
+        let mut json = Cursor::new(Vec::new());
+        let mut strategy = DualFormatWriteStrategy::new(&mut pretty, &mut json);
+        strategy.max_message_len(Some(1024));
+        let error = Err::<(), _>(anyhow::Error::msg("é".repeat(2 * 1024 * 1024)))
+            .context("x".repeat(3 * 1024 * 1024))
+            .unwrap_err();
+        strategy.try_handle_error(error).unwrap();
+        drop(strategy);
+
+        let json = String::from_utf8(json.into_inner()).unwrap();
+        assert!(json.len() < 4096, "json record wasn't bounded");
+        assert!(json.contains("… (truncated, 3145728 bytes total)"));
+        assert!(json.contains("… (truncated, 4194304 bytes total)"));
+        assert!(
+            pretty.into_inner().len() < 4096,
+            "pretty record wasn't bounded"
+        );
+    }
+}
@@ -1,15 +1,47 @@
+//! Types and traits for the write drop strategy.
+
+mod dual;
 mod thread_unsafe;
 
+use crate::drop_strategies::truncate::{truncate_message, DEFAULT_MAX_MESSAGE_LEN};
+use crate::fmt::ChainLimit;
 use crate::FallibleTryDropStrategy;
 use parking_lot::Mutex;
+use std::borrow::Cow;
+use std::boxed::Box;
 use std::io;
-use std::io::Write;
-use std::string::ToString;
+use std::io::{IsTerminal, Write};
+use std::string::String;
 use std::vec::Vec;
+
+pub use dual::*;
+#[cfg(feature = "derives")]
+use std::fmt;
 pub use thread_unsafe::*;
 
+const PRELUDE_COLOR: &[u8] = b"\x1b[1;31m";
+const CHAIN_COLOR: &[u8] = b"\x1b[2m";
+const RESET_COLOR: &[u8] = b"\x1b[0m";
+
+/// Whether or not to color the output of a [`WriteDropStrategy`] with ANSI escape sequences.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Copy, Clone)]
+pub enum ColorChoice {
+    /// Color the output if the writer is a terminal. Only [`WriteDropStrategy::stderr`] and
+    /// [`WriteDropStrategy::stdout`] check this; any other writer is never colored.
+    Auto,
+
+    /// Always color the output.
+    Always,
+
+    /// Never color the output.
+    Never,
+}
+
 /// A drop strategy which writes the message of an error to a writer.
-#[cfg_attr(feature = "derives", derive(Debug))]
 pub struct WriteDropStrategy<W: Write> {
     /// The writer to write to.
     pub writer: Mutex<W>,
@@ -19,6 +51,66 @@ pub struct WriteDropStrategy<W: Write> {
 
     /// The message to add at the beginning of the message.
     pub prelude: Option<Vec<u8>>,
+
+    /// The function which generates the timestamp to add before the prelude.
+    pub timestamp: Option<Box<dyn Fn() -> String + Send + Sync>>,
+
+    /// The maximum length of the error message in bytes, if any. Longer messages are truncated.
+    pub max_message_len: Option<usize>,
+
+    /// How many causes of the error to write after its message, if any. If this is `None`, only
+    /// the message of the error itself is written.
+    pub chain: Option<ChainLimit>,
+
+    /// Whether or not to write the [`Debug`](fmt::Debug) format of the error instead of its
+    /// message, which includes all of its causes and its backtrace if one was captured. If this is
+    /// `true`, [`Self::chain`] is ignored.
+    pub debug: bool,
+
+    /// Whether or not to buffer the messages instead of writing each of them right away. Buffered
+    /// messages are written when [`Self::flush_every`] is reached, when
+    /// [`WriteDropStrategy::flush`] is called, or when this is dropped.
+    pub buffered: bool,
+
+    /// How many buffered messages to write at once, if any. If this is `None`, buffered messages
+    /// are only written when [`WriteDropStrategy::flush`] is called or when this is dropped.
+    pub flush_every: Option<usize>,
+
+    /// Whether or not to color the prelude red and bold, and the causes of the error dim.
+    pub color: ColorChoice,
+
+    buffer: Mutex<Buffer>,
+    is_terminal: bool,
+}
+
+#[derive(Default)]
+struct Buffer {
+    bytes: Vec<u8>,
+    messages: usize,
+}
+
+#[cfg(feature = "derives")]
+impl<W: Write + fmt::Debug> fmt::Debug for WriteDropStrategy<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WriteDropStrategy")
+            .field("writer", &self.writer)
+            .field("new_line", &self.new_line)
+            .field("prelude", &self.prelude)
+            .field(
+                "timestamp",
+                &self
+                    .timestamp
+                    .as_ref()
+                    .map(|_| "Box<dyn Fn() -> String + Send + Sync>"),
+            )
+            .field("max_message_len", &self.max_message_len)
+            .field("chain", &self.chain)
+            .field("debug", &self.debug)
+            .field("buffered", &self.buffered)
+            .field("flush_every", &self.flush_every)
+            .field("color", &self.color)
+            .finish_non_exhaustive()
+    }
 }
 
 impl<W: Write> WriteDropStrategy<W> {
@@ -28,6 +120,15 @@ impl<W: Write> WriteDropStrategy<W> {
             writer: Mutex::new(writer),
             new_line: true,
             prelude: None,
+            timestamp: None,
+            max_message_len: Some(DEFAULT_MAX_MESSAGE_LEN),
+            chain: None,
+            debug: false,
+            buffered: false,
+            flush_every: None,
+            color: ColorChoice::Never,
+            buffer: Mutex::new(Buffer::default()),
+            is_terminal: false,
         }
     }
 
@@ -42,6 +143,96 @@ impl<W: Write> WriteDropStrategy<W> {
         self.prelude = Some(prelude.into());
         self
     }
+
+    /// Sets the function which generates the timestamp to add before the prelude.
+    pub fn with_timestamp(
+        &mut self,
+        timestamp: impl Fn() -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.timestamp = Some(Box::new(timestamp));
+        self
+    }
+
+    /// Sets the maximum length of the error message in bytes, or disables truncation if `None`.
+    pub fn max_message_len(&mut self, max_message_len: Option<usize>) -> &mut Self {
+        self.max_message_len = max_message_len;
+        self
+    }
+
+    /// Sets how many causes of the error to write after its message, or to only write the message
+    /// of the error itself if `None`.
+    pub fn chain(&mut self, chain: Option<ChainLimit>) -> &mut Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Sets whether or not to write the [`Debug`](fmt::Debug) format of the error instead of its
+    /// message.
+    pub fn with_debug(&mut self, debug: bool) -> &mut Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Sets whether or not to buffer the messages instead of writing each of them right away.
+    pub fn buffered(&mut self, buffered: bool) -> &mut Self {
+        self.buffered = buffered;
+        self
+    }
+
+    /// Sets how many buffered messages to write at once, or to only write them when flushed if
+    /// `None`.
+    pub fn flush_every(&mut self, flush_every: Option<usize>) -> &mut Self {
+        self.flush_every = flush_every;
+        self
+    }
+
+    /// Sets whether or not to color the output with ANSI escape sequences.
+    pub fn color(&mut self, color: ColorChoice) -> &mut Self {
+        self.color = color;
+        self
+    }
+
+    fn colored(&self) -> bool {
+        match self.color {
+            ColorChoice::Auto => self.is_terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+
+    /// Write the buffered messages, if any, then flush the writer.
+    pub fn flush(&self) -> io::Result<()> {
+        let mut buffer = self.buffer.lock();
+        let mut writer = self.writer.lock();
+        Self::write_buffer(&mut buffer, &mut writer)?;
+        writer.flush()
+    }
+
+    fn write_buffer(buffer: &mut Buffer, writer: &mut W) -> io::Result<()> {
+        buffer.messages = 0;
+        let bytes = core::mem::take(&mut buffer.bytes);
+        writer.write_all(&bytes)
+    }
+}
+
+#[cfg(feature = "async-flush")]
+impl<W: Write> crate::flush::Flushable for WriteDropStrategy<W> {
+    fn flush(&self) -> io::Result<()> {
+        WriteDropStrategy::flush(self)
+    }
+}
+
+impl<W: Write> Drop for WriteDropStrategy<W> {
+    fn drop(&mut self) {
+        let buffer = self.buffer.get_mut();
+        if !self.buffered || buffer.bytes.is_empty() {
+            return;
+        }
+
+        // there's nowhere to report the error to
+        let writer = self.writer.get_mut();
+        let _ = Self::write_buffer(buffer, writer).and_then(|()| writer.flush());
+    }
 }
 
 impl WriteDropStrategy<io::Stderr> {
@@ -49,6 +240,7 @@ impl WriteDropStrategy<io::Stderr> {
     pub fn stderr() -> Self {
         let mut this = Self::new(io::stderr());
         this.new_line(true);
+        this.is_terminal = io::stderr().is_terminal();
         this
     }
 }
@@ -58,27 +250,124 @@ impl WriteDropStrategy<io::Stdout> {
     pub fn stdout() -> Self {
         let mut this = Self::new(io::stdout());
         this.new_line(true);
+        this.is_terminal = io::stdout().is_terminal();
         this
     }
 }
 
-impl<W: Write> FallibleTryDropStrategy for WriteDropStrategy<W> {
-    type Error = io::Error;
-
-    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+impl<W: Write> WriteDropStrategy<W> {
+    fn message(&self, error: anyhow::Error) -> Vec<u8> {
+        let colored = self.colored();
         let mut message = Vec::new();
 
+        if let Some(timestamp) = &self.timestamp {
+            message.extend_from_slice(timestamp().as_bytes());
+        }
+
         if let Some(prelude) = &self.prelude {
-            message.extend_from_slice(prelude);
+            if colored {
+                message.extend_from_slice(PRELUDE_COLOR);
+                message.extend_from_slice(prelude);
+                message.extend_from_slice(RESET_COLOR);
+            } else {
+                message.extend_from_slice(prelude);
+            }
         }
 
-        message.extend_from_slice(error.to_string().as_bytes());
+        let rendered = match self.chain {
+            _ if self.debug => crate::fmt::render_debug(&error),
+            Some(limit) => crate::fmt::render_chain(&error, limit),
+            None => crate::fmt::render(&error),
+        };
+        let rendered = match self.max_message_len {
+            Some(max_len) => truncate_message(&rendered, max_len),
+            None => Cow::Borrowed(rendered.as_str()),
+        };
+
+        // the causes follow the message of the error itself, unless the formatting context
+        // transformed the message beyond recognition
+        let head_len = match self.chain {
+            Some(_) if colored && !self.debug => {
+                let head = crate::fmt::render(&error);
+                rendered
+                    .starts_with(head.as_str())
+                    .then_some(head.len())
+                    .filter(|&head_len| head_len < rendered.len())
+            }
+            _ => None,
+        };
+
+        match head_len {
+            Some(head_len) => {
+                let (head, chain) = rendered.split_at(head_len);
+                message.extend_from_slice(head.as_bytes());
+                message.extend_from_slice(CHAIN_COLOR);
+                message.extend_from_slice(chain.as_bytes());
+                message.extend_from_slice(RESET_COLOR);
+            }
+            None => message.extend_from_slice(rendered.as_bytes()),
+        }
 
         if self.new_line {
             message.push(b'\n')
         }
 
-        self.writer.lock().write_all(&message)
+        message
+    }
+
+    // the buffer stays locked until it's written, so that messages are written in order
+    fn buffer_message(&self, buffer: &mut Buffer, message: &[u8]) -> io::Result<()> {
+        buffer.bytes.extend_from_slice(message);
+        buffer.messages += 1;
+
+        match self.flush_every {
+            Some(flush_every) if buffer.messages >= flush_every => {
+                let mut writer = self.writer.lock();
+                Self::write_buffer(buffer, &mut writer)?;
+                writer.flush()
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<W: Write> FallibleTryDropStrategy for WriteDropStrategy<W> {
+    type Error = io::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        let message = self.message(error);
+
+        if self.buffered {
+            self.buffer_message(&mut self.buffer.lock(), &message)
+        } else {
+            self.writer.lock().write_all(&message)
+        }
+    }
+
+    /// Handle many drop errors while locking the writer, or the buffer if this is buffered, only
+    /// once.
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = anyhow::Error>,
+        failed: &mut dyn FnMut(Self::Error),
+    ) {
+        let mut report = |result: io::Result<()>| {
+            if let Err(error) = result {
+                failed(error)
+            }
+        };
+
+        if self.buffered {
+            let mut buffer = self.buffer.lock();
+            for error in errors {
+                report(self.buffer_message(&mut buffer, &self.message(error)))
+            }
+        } else {
+            let mut writer = self.writer.lock();
+            for error in errors {
+                report(writer.write_all(&self.message(error)))
+            }
+        }
     }
 }
 
@@ -89,6 +378,7 @@ mod tests {
     use crate::test_utils::{ErrorsOnDrop, Fallible};
     use crate::PureTryDrop;
     use std::io::Cursor;
+    use std::string::ToString;
 
     #[test]
     fn test_write_drop_strategy() {
@@ -100,4 +390,211 @@ mod tests {
         drop(errors);
         assert_eq!(writer.into_inner(), b"error: this will always fail\n",)
     }
+
+    #[test]
+    fn test_write_drop_strategy_with_timestamp() {
+        let mut writer = Cursor::new(Vec::new());
+        let mut strategy = WriteDropStrategy::new(&mut writer);
+        strategy
+            .prelude("error: ")
+            .with_timestamp(|| "[1970-01-01T00:00:00Z] ".into());
+        let errors =
+            ErrorsOnDrop::<Fallible, _>::given(strategy, PanicDropStrategy::DEFAULT).adapt();
+        drop(errors);
+        let written = writer.into_inner();
+        assert!(written.starts_with(b"[1970-01-01T00:00:00Z] "));
+        assert_eq!(
+            written,
+            b"[1970-01-01T00:00:00Z] error: this will always fail\n",
+        )
+    }
+
+    #[test]
+    fn test_write_drop_strategy_with_chain() {
+        let mut writer = Cursor::new(Vec::new());
+        let mut strategy = WriteDropStrategy::new(&mut writer);
+        strategy.chain(Some(ChainLimit::new(1)));
+        let error = anyhow::anyhow!("connection refused")
+            .context("retry 0")
+            .context("retry 1")
+            .context("failed to connect");
+        strategy.try_handle_error(error).unwrap();
+        drop(strategy);
+        assert_eq!(
+            writer.into_inner(),
+            "failed to connect: retry 1: … 2 more causes\n".as_bytes(),
+        )
+    }
+
+    #[test]
+    fn test_write_drop_strategy_with_debug() {
+        let mut writer = Cursor::new(Vec::new());
+        let mut strategy = WriteDropStrategy::new(&mut writer);
+        strategy.with_debug(true).max_message_len(None);
+        let error = anyhow::anyhow!("connection refused")
+            .context("retry 0")
+            .context("failed to connect");
+        strategy.try_handle_error(error).unwrap();
+        drop(strategy);
+        let written = String::from_utf8(writer.into_inner()).unwrap();
+        assert!(written.starts_with("failed to connect\n"), "{written}");
+
+        for layer in ["retry 0", "connection refused"] {
+            assert!(
+                written.contains(layer),
+                "`{layer}` is missing from {written:?}"
+            );
+        }
+    }
+
+    fn colored_message(color: ColorChoice) -> String {
+        let mut writer = Cursor::new(Vec::new());
+        let mut strategy = WriteDropStrategy::new(&mut writer);
+        strategy
+            .prelude("error: ")
+            .chain(Some(ChainLimit::DEFAULT))
+            .color(color);
+        let error = anyhow::anyhow!("connection refused").context("failed to connect");
+        strategy.try_handle_error(error).unwrap();
+        drop(strategy);
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_write_drop_strategy_with_color() {
+        assert_eq!(
+            colored_message(ColorChoice::Always),
+            "\x1b[1;31merror: \x1b[0mfailed to connect\x1b[2m: connection refused\x1b[0m\n",
+        );
+
+        // only the standard streams are checked for a terminal
+        for color in [ColorChoice::Never, ColorChoice::Auto] {
+            assert_eq!(
+                colored_message(color),
+                "error: failed to connect: connection refused\n",
+            );
+        }
+    }
+
+    #[test]
+    fn test_write_drop_strategy_truncates_large_messages() {
+        let mut writer = Cursor::new(Vec::new());
+        let strategy = WriteDropStrategy::new(&mut writer);
+        let message = "x".repeat(4 * 1024 * 1024);
+        strategy
+            .try_handle_error(anyhow::Error::msg(message))
+            .unwrap();
+        drop(strategy);
+        let written = String::from_utf8(writer.into_inner()).unwrap();
+        assert!(written.len() < DEFAULT_MAX_MESSAGE_LEN + 64);
+        assert!(written.ends_with("… (truncated, 4194304 bytes total)\n"));
+    }
+
+    #[test]
+    fn test_buffered_write_drop_strategy() {
+        let mut strategy = WriteDropStrategy::new(Cursor::new(Vec::new()));
+        strategy.buffered(true).flush_every(Some(3));
+        let written = |strategy: &WriteDropStrategy<Cursor<Vec<u8>>>| {
+            String::from_utf8(strategy.writer.lock().get_ref().clone()).unwrap()
+        };
+
+        for message in ["a", "b"] {
+            strategy
+                .try_handle_error(anyhow::Error::msg(message))
+                .unwrap();
+        }
+
+        assert_eq!(written(&strategy), "", "wrote before the threshold");
+        strategy.try_handle_error(anyhow::Error::msg("c")).unwrap();
+        assert_eq!(written(&strategy), "a\nb\nc\n");
+
+        strategy.try_handle_error(anyhow::Error::msg("d")).unwrap();
+        assert_eq!(
+            written(&strategy),
+            "a\nb\nc\n",
+            "wrote before the threshold"
+        );
+        strategy.flush().unwrap();
+        assert_eq!(written(&strategy), "a\nb\nc\nd\n");
+    }
+
+    #[test]
+    fn test_buffered_write_drop_strategy_flushes_on_drop() {
+        let mut writer = Cursor::new(Vec::new());
+        let mut strategy = WriteDropStrategy::new(&mut writer);
+        strategy.buffered(true);
+        strategy.try_handle_error(anyhow::Error::msg("a")).unwrap();
+        drop(strategy);
+        assert_eq!(writer.into_inner(), b"a\n");
+    }
+
+    /// Counts how many times it's flushed.
+    #[derive(Default)]
+    struct CountsFlushes(usize);
+
+    impl Write for CountsFlushes {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0 += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_unbuffered_write_drop_strategy_doesnt_flush_on_drop() {
+        let mut writer = CountsFlushes::default();
+        let strategy = WriteDropStrategy::new(&mut writer);
+        strategy.try_handle_error(anyhow::Error::msg("a")).unwrap();
+        drop(strategy);
+        assert_eq!(writer.0, 0, "flushed the writer on drop");
+
+        let mut strategy = WriteDropStrategy::new(&mut writer);
+        strategy.buffered(true);
+        drop(strategy);
+        assert_eq!(
+            writer.0, 0,
+            "flushed the writer with an empty buffer on drop"
+        );
+    }
+
+    /// Fails every second write.
+    struct Flaky {
+        written: Vec<u8>,
+        writes: usize,
+    }
+
+    impl Write for Flaky {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            if self.writes.is_multiple_of(2) {
+                return Err(io::Error::other("the disk is full"));
+            }
+
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_try_handle_errors_batch() {
+        let strategy = WriteDropStrategy::new(Flaky {
+            written: Vec::new(),
+            writes: 0,
+        });
+        let mut failed = Vec::new();
+        strategy.try_handle_errors_batch(
+            &mut ["a", "b", "c"].into_iter().map(anyhow::Error::msg),
+            &mut |error| failed.push(error.to_string()),
+        );
+
+        assert_eq!(strategy.writer.lock().written, b"a\nc\n");
+        assert_eq!(failed, ["the disk is full"]);
+    }
 }
@@ -3,7 +3,6 @@ use anyhow::Error;
 use std::cell::RefCell;
 use std::io;
 use std::io::Write;
-use std::string::ToString;
 use std::vec::Vec;
 
 /// A drop strategy which writes the message of an error to a writer. While more efficient than
@@ -70,7 +69,7 @@ impl<W: Write> FallibleTryDropStrategy for ThreadUnsafeWriteDropStrategy<W> {
             message.extend_from_slice(prelude);
         }
 
-        message.extend_from_slice(error.to_string().as_bytes());
+        message.extend_from_slice(crate::fmt::render(&error).as_bytes());
 
         if self.new_line {
             message.push(b'\n')
@@ -0,0 +1,205 @@
+//! A lightweight hook which is called every time a drop error is produced, for finding out which
+//! types generate the most drop errors without routing every error through a heavy strategy.
+
+use crate::labels::{self, Label};
+use core::mem;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, Ordering};
+use std::error::Error as StdError;
+use std::sync::{Mutex, PoisonError};
+use std::vec::Vec;
+
+/// The signature of a hook. It's given the type name of the value which failed to drop, and the
+/// approximate size of the error it produced, in bytes.
+pub type Hook = fn(&'static str, usize);
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Register the hook to call every time a drop error is produced, replacing the previous one.
+pub fn set_hook(hook: Hook) {
+    HOOK.store(hook as *mut (), Ordering::Release)
+}
+
+/// Unregister the hook, if any.
+pub fn clear_hook() {
+    HOOK.store(ptr::null_mut(), Ordering::Release)
+}
+
+/// Call the registered hook, if any, with the drop error produced by a value of type `T`. This is
+/// a single atomic load if no hook is registered.
+pub(crate) fn record<T: ?Sized>(error: &anyhow::Error) {
+    let hook = HOOK.load(Ordering::Acquire);
+
+    if hook.is_null() {
+        return;
+    }
+
+    // SAFETY: the only non null pointers ever stored are `Hook`s, cast in `set_hook`.
+    let hook = unsafe { mem::transmute::<*mut (), Hook>(hook) };
+    let source: &(dyn StdError + Send + Sync + 'static) = error.as_ref();
+    hook(core::any::type_name::<T>(), mem::size_of_val(source))
+}
+
+/// How many drop errors a single type produced, as aggregated by the [`TopNReporter`].
+#[cfg_attr(feature = "derives", derive(Debug, Ord, PartialOrd, Hash))]
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct SourceStats {
+    /// The type name of the values which failed to drop.
+    pub type_name: &'static str,
+
+    /// How many drop errors the type produced.
+    pub count: usize,
+
+    /// The approximate sum of the sizes of the drop errors, in bytes.
+    pub bytes: usize,
+}
+
+/// Aggregates the drop errors produced by each type, reporting the types which produce the most.
+///
+/// Hooks are plain functions, so this is meant to be stored in a `static` which the hook records
+/// into:
+///
+/// ```rust
+/// use try_drop::error_telemetry::{self, TopNReporter};
+///
+/// static REPORTER: TopNReporter = TopNReporter::new();
+///
+/// error_telemetry::set_hook(|type_name, size| REPORTER.record(type_name, size));
+/// ```
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct TopNReporter {
+    // the labels of the type names, so that finding the stats of a type is an integer comparison
+    sources: Mutex<Vec<(Label, SourceStats)>>,
+}
+
+impl TopNReporter {
+    /// Create a new reporter which hasn't recorded any drop errors yet.
+    pub const fn new() -> Self {
+        Self {
+            sources: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a drop error of the given size, produced by the given type.
+    pub fn record(&self, type_name: &'static str, size: usize) {
+        let label = labels::intern_static(type_name);
+        let mut sources = self.sources.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match sources.iter_mut().find(|(source, _)| *source == label) {
+            Some((_, source)) => {
+                source.count += 1;
+                source.bytes += size;
+            }
+            None => sources.push((
+                label,
+                SourceStats {
+                    type_name,
+                    count: 1,
+                    bytes: size,
+                },
+            )),
+        }
+    }
+
+    /// Get the `n` types which produced the most drop errors, most first. Ties are ordered by type
+    /// name.
+    pub fn top(&self, n: usize) -> Vec<SourceStats> {
+        let mut sources: Vec<_> = self
+            .sources
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(_, source)| *source)
+            .collect();
+        sources.sort_unstable_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.type_name.cmp(b.type_name))
+        });
+        sources.truncate(n);
+        sources
+    }
+
+    /// Forget every drop error recorded so far.
+    pub fn clear(&self) {
+        self.sources
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear()
+    }
+}
+
+impl Default for TopNReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::NoOpDropStrategy;
+    use crate::PureTryDrop;
+
+    static REPORTER: TopNReporter = TopNReporter::new();
+
+    macro_rules! fails_on_drop {
+        ($($name:ident),*) => {$(
+            struct $name;
+
+            impl PureTryDrop for $name {
+                type Error = anyhow::Error;
+                type FallbackTryDropStrategy = NoOpDropStrategy;
+                type TryDropStrategy = NoOpDropStrategy;
+
+                fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+                    &NoOpDropStrategy
+                }
+
+                fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+                    &NoOpDropStrategy
+                }
+
+                unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+                    anyhow::bail!("this will always fail")
+                }
+            }
+        )*};
+    }
+
+    fails_on_drop!(Often, Rarely);
+
+    #[test]
+    fn test_top_n_reporter() {
+        // other tests may drop values at the same time, so only record the types of this test.
+        set_hook(|type_name, size| {
+            if type_name.starts_with(module_path!()) {
+                REPORTER.record(type_name, size)
+            }
+        });
+
+        for _ in 0..3 {
+            drop(Often.adapt());
+        }
+
+        drop(Rarely.adapt());
+        clear_hook();
+        drop(Rarely.adapt());
+
+        let top = REPORTER.top(2);
+        assert_eq!(
+            top.iter()
+                .map(|source| (source.type_name, source.count))
+                .collect::<Vec<_>>(),
+            [
+                (core::any::type_name::<Often>(), 3),
+                (core::any::type_name::<Rarely>(), 1),
+            ]
+        );
+        assert!(top[0].bytes > 0);
+        assert_eq!(REPORTER.top(1).len(), 1);
+
+        REPORTER.clear();
+        assert!(REPORTER.top(2).is_empty());
+    }
+}
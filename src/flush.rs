@@ -0,0 +1,377 @@
+//! Flush the drop strategies which buffer errors before the program exits, e.g. when handling a
+//! shutdown signal with [`tokio::signal`](https://docs.rs/tokio/latest/tokio/signal/index.html).
+//!
+//! Register the strategies which need flushing with [`register`] or [`register_async`], then call
+//! [`flush_all_async`] on shutdown. The panic and exit drop strategies call [`flush_all`] before
+//! they panic or exit:
+//!
+//! ```ignore
+//! let stderr = Arc::new(WriteDropStrategy::stderr());
+//! try_drop::flush::register(Arc::clone(&stderr));
+//! try_drop::install_global_handlers(stderr, PanicDropStrategy::DEFAULT);
+//!
+//! tokio::signal::ctrl_c().await?;
+//! let report = try_drop::flush_all_async(Duration::from_secs(5)).await;
+//! ```
+
+use core::cell::Cell;
+use core::future::Future;
+use core::pin::Pin;
+use std::boxed::Box;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::thread_local;
+use std::time::Duration;
+use std::vec::Vec;
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant};
+
+/// A drop strategy which buffers errors and can write them out synchronously.
+pub trait Flushable {
+    /// Write out the buffered errors.
+    fn flush(&self) -> io::Result<()>;
+}
+
+/// A drop strategy which buffers errors and can write them out asynchronously.
+///
+/// Implementors may use an `async fn`, as long as the returned future is [`Send`].
+pub trait AsyncFlushable {
+    /// Write out the buffered errors.
+    fn flush(&self) -> impl Future<Output = io::Result<()>> + Send;
+}
+
+type BoxFuture<'a> = Pin<Box<dyn Future<Output = io::Result<()>> + Send + 'a>>;
+
+/// An object safe version of [`AsyncFlushable`].
+trait DynAsyncFlushable: Send + Sync {
+    fn flush_boxed(&self) -> BoxFuture<'_>;
+}
+
+impl<T: AsyncFlushable + Send + Sync> DynAsyncFlushable for T {
+    fn flush_boxed(&self) -> BoxFuture<'_> {
+        Box::pin(AsyncFlushable::flush(self))
+    }
+}
+
+#[derive(Clone)]
+enum Entry {
+    Sync(Arc<dyn Flushable + Send + Sync>),
+    Async(Arc<dyn DynAsyncFlushable>),
+}
+
+struct Registered {
+    type_name: &'static str,
+    entry: Entry,
+}
+
+static REGISTRY: Mutex<Vec<Registered>> = Mutex::new(Vec::new());
+
+fn registry() -> std::sync::MutexGuard<'static, Vec<Registered>> {
+    // a panicking flushable can't leave the registry itself in an inconsistent state
+    REGISTRY.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+/// Register a drop strategy to be flushed by [`flush_all_async`] and [`flush_all`]. Since it can
+/// only flush synchronously, [`flush_all_async`] flushes it on the blocking thread pool of the
+/// runtime.
+///
+/// Usually the strategy is shared with the handler it's installed as through an [`Arc`].
+pub fn register<T: Flushable + Send + Sync + 'static>(flushable: Arc<T>) {
+    registry().push(Registered {
+        type_name: core::any::type_name::<T>(),
+        entry: Entry::Sync(flushable),
+    })
+}
+
+/// Register a drop strategy to be flushed asynchronously by [`flush_all_async`].
+///
+/// Usually the strategy is shared with the handler it's installed as through an [`Arc`].
+pub fn register_async<T: AsyncFlushable + Send + Sync + 'static>(flushable: Arc<T>) {
+    registry().push(Registered {
+        type_name: core::any::type_name::<T>(),
+        entry: Entry::Async(flushable),
+    })
+}
+
+/// Unregister every drop strategy registered with [`register`] or [`register_async`].
+pub fn unregister_all() {
+    registry().clear()
+}
+
+/// How flushing a drop strategy went.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub enum FlushStatus {
+    /// The drop strategy was flushed.
+    Flushed,
+
+    /// The drop strategy failed to flush.
+    Failed(io::Error),
+
+    /// The drop strategy didn't finish flushing in time. It keeps flushing in the background, but
+    /// isn't waited for.
+    TimedOut,
+
+    /// The drop strategy panicked while flushing.
+    Panicked,
+
+    /// The drop strategy can only flush asynchronously, so [`flush_all`] didn't flush it.
+    Skipped,
+}
+
+/// How flushing a single registered drop strategy went.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct FlushOutcome {
+    /// The type name of the drop strategy.
+    pub type_name: &'static str,
+
+    /// How flushing it went.
+    pub status: FlushStatus,
+}
+
+/// How flushing every registered drop strategy went, in the order they were registered in.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct FlushReport {
+    /// How flushing each drop strategy went.
+    pub outcomes: Vec<FlushOutcome>,
+}
+
+impl FlushReport {
+    /// Check whether or not every drop strategy was flushed.
+    pub fn all_flushed(&self) -> bool {
+        self.outcomes
+            .iter()
+            .all(|outcome| matches!(outcome.status, FlushStatus::Flushed))
+    }
+
+    /// Get the type names of the drop strategies which didn't finish flushing in time.
+    pub fn timed_out(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome.status, FlushStatus::TimedOut))
+            .map(|outcome| outcome.type_name)
+    }
+}
+
+/// Flush every registered drop strategy at once, waiting at most `timeout` for all of them.
+/// Asynchronous ones are spawned onto the runtime, and synchronous ones onto its blocking thread
+/// pool, so that a slow drop strategy doesn't hold up the others.
+///
+/// Drop strategies which don't finish in time are reported as [`FlushStatus::TimedOut`] and left
+/// running in the background.
+///
+/// Note that dropping a runtime waits for its blocking thread pool, so a synchronous drop strategy
+/// which timed out still holds up the runtime until it's done, unless the runtime is shut down
+/// with [`Runtime::shutdown_timeout`](tokio::runtime::Runtime::shutdown_timeout).
+///
+/// # Panics
+/// If called outside of a tokio runtime.
+pub async fn flush_all_async(timeout: Duration) -> FlushReport {
+    let deadline = Instant::now() + timeout;
+    let entries: Vec<_> = registry()
+        .iter()
+        .map(|registered| (registered.type_name, registered.entry.clone()))
+        .collect();
+    let handles: Vec<(&'static str, JoinHandle<io::Result<()>>)> = entries
+        .into_iter()
+        .map(|(type_name, entry)| {
+            let handle = match entry {
+                Entry::Sync(flushable) => tokio::task::spawn_blocking(move || flushable.flush()),
+                Entry::Async(flushable) => {
+                    tokio::spawn(async move { flushable.flush_boxed().await })
+                }
+            };
+            (type_name, handle)
+        })
+        .collect();
+
+    let mut outcomes = Vec::with_capacity(handles.len());
+    for (type_name, handle) in handles {
+        let status = match time::timeout_at(deadline, handle).await {
+            Ok(Ok(Ok(()))) => FlushStatus::Flushed,
+            Ok(Ok(Err(error))) => FlushStatus::Failed(error),
+            Ok(Err(_)) => FlushStatus::Panicked,
+            Err(_) => FlushStatus::TimedOut,
+        };
+        outcomes.push(FlushOutcome { type_name, status });
+    }
+
+    FlushReport { outcomes }
+}
+
+thread_local! {
+    static FLUSHING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Flush every drop strategy registered with [`register`] synchronously, on the current thread.
+/// The ones registered with [`register_async`] can't be flushed without a runtime, so they're
+/// reported as [`FlushStatus::Skipped`].
+///
+/// If a drop strategy which is being flushed ends up calling this again on the same thread, e.g.
+/// because it fails to drop something and the panic drop strategy handles the error, the inner
+/// call flushes nothing and returns an empty report instead of flushing the same drop strategies
+/// again.
+pub fn flush_all() -> FlushReport {
+    if FLUSHING
+        .try_with(|flushing| flushing.replace(true))
+        .unwrap_or(true)
+    {
+        return FlushReport {
+            outcomes: Vec::new(),
+        };
+    }
+
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            let _ = FLUSHING.try_with(|flushing| flushing.set(false));
+        }
+    }
+
+    let _reset = Reset;
+    let entries: Vec<_> = registry()
+        .iter()
+        .map(|registered| (registered.type_name, registered.entry.clone()))
+        .collect();
+    let outcomes = entries
+        .into_iter()
+        .map(|(type_name, entry)| {
+            let status = match entry {
+                Entry::Sync(flushable) => {
+                    match panic::catch_unwind(AssertUnwindSafe(|| flushable.flush())) {
+                        Ok(Ok(())) => FlushStatus::Flushed,
+                        Ok(Err(error)) => FlushStatus::Failed(error),
+                        Err(_) => FlushStatus::Panicked,
+                    }
+                }
+                Entry::Async(_) => FlushStatus::Skipped,
+            };
+            FlushOutcome { type_name, status }
+        })
+        .collect();
+
+    FlushReport { outcomes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Tests which register drop strategies must hold this lock, since the registry is global.
+    static REGISTRY_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    #[derive(Default)]
+    struct Fast(AtomicBool);
+
+    impl AsyncFlushable for Fast {
+        async fn flush(&self) -> io::Result<()> {
+            self.0.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    struct Slow;
+
+    impl Flushable for Slow {
+        fn flush(&self) -> io::Result<()> {
+            std::thread::sleep(Duration::from_secs(1));
+            Ok(())
+        }
+    }
+
+    struct Fails;
+
+    impl AsyncFlushable for Fails {
+        async fn flush(&self) -> io::Result<()> {
+            Err(io::Error::other("disk full"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush_all_async_times_out() {
+        let _lock = REGISTRY_LOCK.lock().await;
+        let fast = Arc::new(Fast::default());
+        register_async(Arc::clone(&fast));
+        register(Arc::new(Slow));
+
+        let report = flush_all_async(Duration::from_millis(100)).await;
+        unregister_all();
+
+        assert!(
+            fast.0.load(Ordering::Relaxed),
+            "the fast strategy wasn't flushed"
+        );
+        assert!(!report.all_flushed());
+        assert!(matches!(report.outcomes[0].status, FlushStatus::Flushed));
+        assert!(report.outcomes[0].type_name.ends_with("Fast"));
+        assert_eq!(report.timed_out().count(), 1);
+        assert!(report.outcomes[1].type_name.ends_with("Slow"));
+    }
+
+    #[derive(Default)]
+    struct Counts(std::sync::atomic::AtomicUsize);
+
+    impl Flushable for Counts {
+        fn flush(&self) -> io::Result<()> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+
+            // a drop strategy failing to drop something while flushing
+            assert!(flush_all().outcomes.is_empty(), "flushed reentrantly");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush_all() {
+        let _lock = REGISTRY_LOCK.lock().await;
+        let counts = Arc::new(Counts::default());
+        register(Arc::clone(&counts));
+        register_async(Arc::new(Fast::default()));
+
+        let report = flush_all();
+        unregister_all();
+
+        assert_eq!(counts.0.load(Ordering::Relaxed), 1);
+        assert!(matches!(report.outcomes[0].status, FlushStatus::Flushed));
+        assert!(matches!(report.outcomes[1].status, FlushStatus::Skipped));
+    }
+
+    #[cfg(feature = "ds-panic")]
+    #[tokio::test]
+    async fn test_flush_all_before_panicking() {
+        use crate::drop_strategies::PanicDropStrategy;
+        use crate::TryDropStrategy;
+
+        let _lock = REGISTRY_LOCK.lock().await;
+        let counts = Arc::new(Counts::default());
+        register(Arc::clone(&counts));
+
+        let result = std::panic::catch_unwind(|| {
+            PanicDropStrategy::DEFAULT.handle_error(anyhow::anyhow!("this will always fail"))
+        });
+        unregister_all();
+
+        assert!(result.is_err(), "the panic drop strategy didn't panic");
+        assert_eq!(counts.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_flush_all_async_failed() {
+        let _lock = REGISTRY_LOCK.lock().await;
+        register_async(Arc::new(Fails));
+
+        let report = flush_all_async(Duration::from_secs(60)).await;
+        unregister_all();
+
+        match &report.outcomes[..] {
+            [FlushOutcome {
+                status: FlushStatus::Failed(error),
+                ..
+            }] => assert_eq!(error.to_string(), "disk full"),
+            _ => panic!("the failure wasn't reported"),
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! Change how drop errors are rendered for a region of code, without replacing the installed
+//! strategies. This is much lighter than swapping out the strategy with a scope guard.
+//!
+//! The formatting context is a thread local stack of prefixes and transforms. It's consulted by
+//! the drop strategies of this crate which render drop errors into messages, such as the write,
+//! log and tracing drop strategies.
+//!
+//! # Examples
+//! ```rust
+//! let _cache = try_drop::fmt::push_prefix("[cache] ");
+//! let _lru = try_drop::fmt::push_prefix("[lru] ");
+//! let error = anyhow::anyhow!("failed to evict entry");
+//!
+//! // the prefixes are composed in the order they were pushed in
+//! assert_eq!(try_drop::fmt::render(&error), "[cache] [lru] failed to evict entry");
+//! ```
+//!
+//! Rendering the causes of a drop error can be bounded with a [`ChainLimit`], for pathologically
+//! deep chains, such as the ones built by adding context on every retry in a loop.
+
+use core::cell::RefCell;
+use core::marker::PhantomData;
+use std::borrow::Cow;
+use std::boxed::Box;
+use std::format;
+use std::string::{String, ToString};
+use std::thread_local;
+use std::vec::Vec;
+
+enum Entry {
+    Prefix(Cow<'static, str>),
+    Transform(Box<dyn Fn(&mut String)>),
+}
+
+thread_local! {
+    static STACK: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Removes the prefix or transform it was returned with from the formatting context when dropped,
+/// along with anything which was pushed after it and is still there.
+#[must_use = "the prefix or transform is removed as soon as this is dropped"]
+pub struct PrefixGuard {
+    depth: usize,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for PrefixGuard {
+    fn drop(&mut self) {
+        let _ = STACK.try_with(|stack| stack.borrow_mut().truncate(self.depth));
+    }
+}
+
+fn push(entry: Entry) -> PrefixGuard {
+    let depth = STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        stack.push(entry);
+        stack.len() - 1
+    });
+
+    PrefixGuard {
+        depth,
+        _not_send: PhantomData,
+    }
+}
+
+/// Prefix every drop error rendered on this thread with `prefix`, until the returned guard is
+/// dropped. Prefixes which are pushed while it's alive go after it.
+pub fn push_prefix(prefix: impl Into<Cow<'static, str>>) -> PrefixGuard {
+    push(Entry::Prefix(prefix.into()))
+}
+
+/// Transform every drop error rendered on this thread with `transform`, until the returned guard
+/// is dropped. Like prefixes, transforms which are pushed while it's alive are applied before it.
+///
+/// The transform must not push to or pop from the formatting context itself.
+pub fn push_transform(transform: impl Fn(&mut String) + 'static) -> PrefixGuard {
+    push(Entry::Transform(Box::new(transform)))
+}
+
+/// Apply the formatting context of this thread to the given message, innermost entry first.
+pub fn apply(message: &mut String) {
+    let _ = STACK.try_with(|stack| {
+        for entry in stack.borrow().iter().rev() {
+            match entry {
+                Entry::Prefix(prefix) => message.insert_str(0, prefix),
+                Entry::Transform(transform) => transform(message),
+            }
+        }
+    });
+}
+
+/// Render the message of the given drop error, with the formatting context of this thread applied.
+pub fn render(error: &anyhow::Error) -> String {
+    let mut message = error.to_string();
+    apply(&mut message);
+    message
+}
+
+/// Render the message of the given drop error followed by its causes within `limit`, separated by
+/// `": "` like the alternate format of [`anyhow::Error`], with the formatting context of this
+/// thread applied.
+pub fn render_chain(error: &anyhow::Error, limit: ChainLimit) -> String {
+    let mut message = error.to_string();
+
+    for cause in limit.causes(error) {
+        message.push_str(": ");
+        message.push_str(&cause);
+    }
+
+    apply(&mut message);
+    message
+}
+
+/// Render the [`Debug`](core::fmt::Debug) format of the given drop error, which for
+/// [`anyhow::Error`] includes all of its causes and its backtrace if one was captured, with the
+/// formatting context of this thread applied.
+pub fn render_debug(error: &anyhow::Error) -> String {
+    let mut message = format!("{error:?}");
+    apply(&mut message);
+    message
+}
+
+/// Get the length of the chain of the given drop error, including the error itself.
+pub fn chain_len(error: &anyhow::Error) -> usize {
+    error.chain().count()
+}
+
+/// How many causes of a drop error to render, at most.
+///
+/// The causes past the limit are summed up as `"… K more causes"`. Consecutive causes with the
+/// same message can be collapsed into one, followed by how many times it was repeated, which only
+/// counts once towards the limit.
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Copy, Clone)]
+pub struct ChainLimit {
+    /// The maximum amount of causes to render.
+    pub max_causes: usize,
+
+    /// Whether or not to collapse consecutive causes with the same message into one.
+    pub collapse_repeats: bool,
+}
+
+impl ChainLimit {
+    /// The default chain limit, which renders up to 32 causes and collapses repeated ones.
+    pub const DEFAULT: Self = Self::new(32);
+
+    /// Render up to `max_causes` causes, collapsing repeated ones.
+    pub const fn new(max_causes: usize) -> Self {
+        Self {
+            max_causes,
+            collapse_repeats: true,
+        }
+    }
+
+    /// Don't collapse consecutive causes with the same message.
+    pub const fn without_collapsing(mut self) -> Self {
+        self.collapse_repeats = false;
+        self
+    }
+
+    /// Render the messages of the causes of the given drop error within this limit, outermost
+    /// first.
+    pub fn causes(&self, error: &anyhow::Error) -> Vec<String> {
+        let mut causes = Vec::new();
+        let mut chain = error.chain().skip(1).peekable();
+
+        while causes.len() < self.max_causes {
+            let Some(cause) = chain.next() else { break };
+            let message = cause.to_string();
+            let mut repeated = 1;
+
+            if self.collapse_repeats {
+                while chain.next_if(|next| next.to_string() == message).is_some() {
+                    repeated += 1;
+                }
+            }
+
+            if repeated == 1 {
+                causes.push(message)
+            } else {
+                causes.push(format!("{message} (repeated {repeated} times)"))
+            }
+        }
+
+        match chain.count() {
+            0 => {}
+            1 => causes.push("… 1 more cause".to_string()),
+            remaining => causes.push(format!("… {remaining} more causes")),
+        }
+
+        causes
+    }
+}
+
+impl Default for ChainLimit {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_prefixes() {
+        let error = anyhow::anyhow!("this will always fail");
+        let outer = push_prefix("[cache] ");
+
+        {
+            let _inner = push_prefix("[lru] ");
+            assert_eq!(render(&error), "[cache] [lru] this will always fail");
+        }
+
+        assert_eq!(render(&error), "[cache] this will always fail");
+        drop(outer);
+        assert_eq!(render(&error), "this will always fail");
+    }
+
+    #[test]
+    fn test_transform() {
+        let error = anyhow::anyhow!("this will always fail");
+        let _prefix = push_prefix("[cache] ");
+        let _transform = push_transform(|message| message.make_ascii_uppercase());
+
+        // the transform was pushed last, so it's applied before the prefix
+        assert_eq!(render(&error), "[cache] THIS WILL ALWAYS FAIL");
+    }
+
+    /// Deep chains are dropped recursively, which overflows the default stack of test threads.
+    fn with_large_stack(f: impl FnOnce() + Send + 'static) {
+        std::thread::Builder::new()
+            .stack_size(64 * 1024 * 1024)
+            .spawn(f)
+            .unwrap()
+            .join()
+            .unwrap()
+    }
+
+    fn deep_chain(depth: usize, context: impl Fn(usize) -> String) -> anyhow::Error {
+        (0..depth).fold(anyhow::anyhow!("connection refused"), |error, index| {
+            error.context(context(index))
+        })
+    }
+
+    #[test]
+    fn test_chain_len() {
+        with_large_stack(|| {
+            assert_eq!(chain_len(&anyhow::anyhow!("connection refused")), 1);
+            assert_eq!(
+                chain_len(&deep_chain(10_000, |index| format!("retry {index}"))),
+                10_001
+            );
+        })
+    }
+
+    #[test]
+    fn test_chain_limit() {
+        with_large_stack(|| {
+            let error = deep_chain(10_000, |index| format!("retry {index}"));
+            let causes = ChainLimit::new(3).causes(&error);
+            assert_eq!(
+                causes,
+                [
+                    "retry 9998",
+                    "retry 9997",
+                    "retry 9996",
+                    "… 9997 more causes"
+                ]
+            );
+
+            let rendered = render_chain(&error, ChainLimit::DEFAULT);
+            assert!(rendered.starts_with("retry 9999: retry 9998: "));
+            assert!(rendered.ends_with(": … 9968 more causes"));
+            assert!(rendered.len() < 1024, "the rendered chain isn't bounded");
+        })
+    }
+
+    #[test]
+    fn test_chain_limit_collapses_repeats() {
+        with_large_stack(|| {
+            let error = deep_chain(10_000, |_| "retrying".to_string()).context("failed to connect");
+            assert_eq!(
+                ChainLimit::new(3).causes(&error),
+                [
+                    "retrying (repeated 10000 times)".to_string(),
+                    "connection refused".to_string()
+                ]
+            );
+            assert_eq!(
+                ChainLimit::new(2).without_collapsing().causes(&error),
+                ["retrying", "retrying", "… 9999 more causes"]
+            );
+        })
+    }
+
+    #[test]
+    fn test_render_chain() {
+        let error = deep_chain(2, |index| format!("retry {index}"));
+        let _prefix = push_prefix("[db] ");
+        assert_eq!(
+            render_chain(&error, ChainLimit::DEFAULT),
+            "[db] retry 1: retry 0: connection refused"
+        );
+    }
+
+    #[test]
+    fn test_out_of_order_drop() {
+        let error = anyhow::anyhow!("this will always fail");
+        let outer = push_prefix("[cache] ");
+        let inner = push_prefix("[lru] ");
+
+        drop(outer);
+        assert_eq!(render(&error), "this will always fail");
+        drop(inner);
+        assert_eq!(render(&error), "this will always fail");
+    }
+
+    #[cfg(feature = "ds-write")]
+    #[test]
+    fn test_write_drop_strategy() {
+        use crate::drop_strategies::WriteDropStrategy;
+        use crate::FallibleTryDropStrategy;
+        use std::io::Cursor;
+
+        let strategy = WriteDropStrategy::new(Cursor::new(Vec::new()));
+        let _cache = push_prefix("[cache] ");
+
+        {
+            let _lru = push_prefix("[lru] ");
+            strategy
+                .try_handle_error(anyhow::anyhow!("failed to evict entry"))
+                .unwrap();
+        }
+
+        strategy
+            .try_handle_error(anyhow::anyhow!("failed to flush"))
+            .unwrap();
+
+        assert_eq!(
+            strategy.writer.lock().get_ref(),
+            b"[cache] [lru] failed to evict entry\n[cache] failed to flush\n"
+        );
+    }
+}
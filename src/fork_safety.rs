@@ -0,0 +1,205 @@
+//! Reset the global handlers in the child process after `fork()`.
+//!
+//! A child process inherits the global handlers of its parent, but only the thread which forked.
+//! Drop strategies which own threads, async runtimes or file descriptors, such as the broadcast
+//! drop strategy, may then misbehave or hang in the child. After [`install`] is called, the global
+//! handlers are replaced in every child process, with the default global handlers or with the ones
+//! given to [`install_with`].
+//!
+//! The replaced handlers have their [`on_fork_child`](crate::FallibleTryDropStrategy::on_fork_child)
+//! hooks called, then are leaked rather than dropped, so that nothing which only exists in the
+//! parent process is touched.
+//!
+//! Both global handlers are locked while forking, so that no other thread can hold a guard to them
+//! in the child. This means that forking waits for other threads to finish handling drop errors,
+//! and that a drop strategy must not fork while it's handling one.
+//!
+//! # Examples
+//! ```rust
+//! try_drop::fork_safety::install().expect("failed to register the fork handlers");
+//! ```
+
+use crate::handlers::fork;
+use crate::{GlobalDynFallibleTryDropStrategy, GlobalTryDropStrategy};
+use once_cell::sync::OnceCell;
+use std::boxed::Box;
+use std::io;
+
+/// Creates the global primary handler to install in the child process. See [`install_with`] for
+/// what it may do.
+pub type PrimaryFactory = fn() -> Box<dyn GlobalDynFallibleTryDropStrategy>;
+
+/// Creates the global fallback handler to install in the child process. See [`install_with`] for
+/// what it may do.
+pub type FallbackFactory = fn() -> Box<dyn GlobalTryDropStrategy>;
+
+struct Factories {
+    primary: PrimaryFactory,
+    fallback: FallbackFactory,
+}
+
+static FACTORIES: OnceCell<Factories> = OnceCell::new();
+
+/// Reset the global handlers to the default ones in every child process, which write drop errors
+/// to standard error and panic if that fails.
+///
+/// # Errors
+/// If the fork handlers couldn't be registered.
+pub fn install() -> io::Result<()> {
+    install_with(fork::default_primary, fork::default_fallback)
+}
+
+/// Reset the global handlers to the ones `primary` and `fallback` create in every child process.
+///
+/// Only the first successful call has an effect, later calls keep the handlers of the first one.
+///
+/// The factories are called in the child process right after `fork()`, and the handlers they
+/// create are boxed, so they allocate, which the default factories of [`install`] do too. If
+/// another thread of the parent process was holding a lock of the allocator while forking, this
+/// deadlocks the child. Processes which fork while other threads may be allocating should only
+/// call async signal safe functions in the child, such as `exec`, and not use these handlers.
+///
+/// # Errors
+/// If the fork handlers couldn't be registered. The factories aren't kept then, so this can be
+/// called again.
+pub fn install_with(primary: PrimaryFactory, fallback: FallbackFactory) -> io::Result<()> {
+    FACTORIES
+        .get_or_try_init(|| {
+            // SAFETY: the handlers are `extern "C"` functions which never unwind.
+            let code = unsafe { libc::pthread_atfork(Some(prepare), Some(parent), Some(child)) };
+
+            if code != 0 {
+                return Err(io::Error::from_raw_os_error(code));
+            }
+
+            Ok(Factories { primary, fallback })
+        })
+        .map(|_| ())
+}
+
+/// Whether or not the fork handlers were installed.
+pub fn installed() -> bool {
+    FACTORIES.get().is_some()
+}
+
+extern "C" fn prepare() {
+    fork::lock()
+}
+
+extern "C" fn parent() {
+    // SAFETY: `prepare` locked the global handlers right before forking.
+    unsafe { fork::unlock() }
+}
+
+extern "C" fn child() {
+    let (primary, fallback) = match FACTORIES.get() {
+        Some(factories) => (factories.primary, factories.fallback),
+        None => (
+            fork::default_primary as PrimaryFactory,
+            fork::default_fallback as FallbackFactory,
+        ),
+    };
+
+    // a panic must not unwind out of an `extern "C"` function
+    let _ = std::panic::catch_unwind(|| {
+        // SAFETY: `prepare` locked the global handlers right before forking.
+        unsafe { fork::reset_in_child(primary(), fallback()) }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handlers::primary;
+    use crate::test_utils::GLOBAL_LOCK;
+    use crate::{FallibleTryDropStrategy, TryDropStrategy};
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use std::string::ToString;
+
+    static FORKED: AtomicBool = AtomicBool::new(false);
+    static DROPPED: AtomicBool = AtomicBool::new(false);
+
+    /// Stands in for a drop strategy which owns a runtime of the parent process.
+    struct OwnsRuntime;
+
+    impl TryDropStrategy for OwnsRuntime {
+        fn handle_error(&self, _error: anyhow::Error) {}
+
+        fn on_fork_child(&self) {
+            FORKED.store(true, Ordering::SeqCst)
+        }
+    }
+
+    impl Drop for OwnsRuntime {
+        fn drop(&mut self) {
+            DROPPED.store(true, Ordering::SeqCst)
+        }
+    }
+
+    /// The strategy which is installed in the child process.
+    struct Reset;
+
+    impl FallibleTryDropStrategy for Reset {
+        type Error = anyhow::Error;
+
+        fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+            Err(error.context("handled by the reset strategy"))
+        }
+    }
+
+    fn child_checks() -> bool {
+        let routed = primary::global::read()
+            .dyn_try_handle_error(anyhow::anyhow!("this will always fail"))
+            .map_err(|error| error.to_string())
+            == Err("handled by the reset strategy".into());
+
+        routed && FORKED.load(Ordering::SeqCst) && !DROPPED.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_fork() {
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = primary::global::take();
+        primary::global::install(OwnsRuntime);
+        install_with(|| Box::new(Reset), fork::default_fallback).unwrap();
+        assert!(installed());
+
+        // SAFETY: the child only checks the handlers, then exits without unwinding.
+        match unsafe { libc::fork() } {
+            -1 => panic!("failed to fork: {}", io::Error::last_os_error()),
+            0 => {
+                let code = match std::panic::catch_unwind(child_checks) {
+                    Ok(true) => 0,
+                    _ => 1,
+                };
+
+                // SAFETY: `_exit` doesn't run any destructors of the parent's state.
+                unsafe { libc::_exit(code) }
+            }
+            pid => {
+                let mut status = 0;
+
+                // SAFETY: `pid` is the child we just forked.
+                assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+                assert!(libc::WIFEXITED(status));
+                assert_eq!(
+                    libc::WEXITSTATUS(status),
+                    0,
+                    "the child saw the wrong handler"
+                );
+            }
+        }
+
+        // the parent keeps its handler
+        assert_eq!(
+            primary::global::read().type_name(),
+            core::any::type_name::<OwnsRuntime>()
+        );
+        assert!(!FORKED.load(Ordering::SeqCst));
+
+        match previous {
+            Some(previous) => primary::global::install_dyn(previous),
+            None => primary::global::uninstall(),
+        }
+    }
+}
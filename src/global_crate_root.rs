@@ -3,6 +3,7 @@ use crate::handlers::{
     FallbackHandler, PrimaryHandler, DEFAULT_FALLBACK_HANDLER, DEFAULT_PRIMARY_HANDLER,
 };
 use crate::prelude::*;
+use crate::RepeatableTryDrop;
 
 impl<TD: ImpureTryDrop> PureTryDrop for TD {
     type Error = TD::Error;
@@ -21,3 +22,15 @@ impl<TD: ImpureTryDrop> PureTryDrop for TD {
         TD::try_drop(self)
     }
 }
+
+impl<STD: SafeTryDrop> ImpureTryDrop for STD {
+    type Error = STD::Error;
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        SafeTryDrop::try_drop(self)
+    }
+}
+
+// SAFETY: `SafeTryDrop::try_drop` is a safe function, so calling it any number of times can't
+// cause undefined behavior.
+unsafe impl<STD: SafeTryDrop> RepeatableTryDrop for STD {}
@@ -1,17 +1,17 @@
 pub(crate) mod imports {}
 
-use crate::handlers::common::Handler;
-use crate::handlers::UninitializedError;
-use parking_lot::{
-    MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
-};
+use crate::handlers::common::{handler, Handler};
+use crate::handlers::slot::{GlobalSlot, SlotScopeGuard, SlotWriteGuard};
+use crate::handlers::{ScopeKind, UninitializedError};
+use parking_lot::MappedRwLockReadGuard;
+use std::fmt;
 use std::marker::PhantomData;
 
 pub trait GlobalDefinition: Handler {
     const UNINITIALIZED_ERROR: &'static str;
     type Global: 'static;
 
-    fn global() -> &'static RwLock<Option<Self::Global>>;
+    fn global() -> &'static GlobalSlot<Self::Global>;
 }
 
 pub trait DefaultGlobalDefinition: GlobalDefinition {
@@ -22,60 +22,103 @@ pub struct Global<T: GlobalDefinition>(PhantomData<T>);
 
 impl<T: GlobalDefinition> Global<T> {
     pub fn install_dyn(strategy: T::Global) {
-        T::global().write().replace(strategy);
+        T::global().install_dyn(strategy)
     }
 
     pub fn install(strategy: impl Into<T::Global>) {
         Self::install_dyn(strategy.into())
     }
 
-    pub fn try_read() -> Result<MappedRwLockReadGuard<'static, T::Global>, UninitializedError> {
-        let global = T::global().read();
-
-        if global.is_some() {
-            Ok(RwLockReadGuard::map(global, |global| {
-                global.as_ref().unwrap()
-            }))
+    /// The error to return if the global can't be read, either because it's uninitialized, or
+    /// because this thread is holding a write guard to it, so that reading it would deadlock.
+    fn error() -> UninitializedError {
+        if T::global().written_on_this_thread() {
+            UninitializedError::new_would_deadlock(T::KIND, ScopeKind::Global)
         } else {
-            Err(UninitializedError(()))
+            UninitializedError::new(T::KIND, ScopeKind::Global)
         }
     }
 
-    pub fn read() -> MappedRwLockReadGuard<'static, T::Global> {
-        Self::try_read().expect(T::UNINITIALIZED_ERROR)
+    pub fn try_read() -> Result<MappedRwLockReadGuard<'static, T::Global>, UninitializedError> {
+        T::global().try_read().ok_or_else(Self::error)
     }
 
-    pub fn try_write() -> Result<MappedRwLockWriteGuard<'static, T::Global>, UninitializedError> {
-        let global = T::global().write();
+    pub fn read() -> MappedRwLockReadGuard<'static, T::Global> {
+        handler::expect(T::KIND, ScopeKind::Global, Self::try_read())
+    }
 
-        if global.is_some() {
-            Ok(RwLockWriteGuard::map(global, |global| {
-                global.as_mut().unwrap()
-            }))
-        } else {
-            Err(UninitializedError(()))
-        }
+    pub fn try_write() -> Result<SlotWriteGuard<'static, T::Global>, UninitializedError> {
+        T::global().try_write().ok_or_else(Self::error)
     }
 
-    pub fn write() -> MappedRwLockWriteGuard<'static, T::Global> {
-        Self::try_write().expect(T::UNINITIALIZED_ERROR)
+    pub fn write() -> SlotWriteGuard<'static, T::Global> {
+        handler::expect(T::KIND, ScopeKind::Global, Self::try_write())
     }
 
     pub fn uninstall() {
-        *T::global().write() = None
+        T::global().uninstall()
+    }
+
+    pub fn take() -> Option<T::Global> {
+        T::global().take()
+    }
+
+    pub fn scope(strategy: impl Into<T::Global>) -> GlobalScopeGuard<T> {
+        Self::scope_dyn(strategy.into())
+    }
+
+    pub fn scope_dyn(strategy: T::Global) -> GlobalScopeGuard<T> {
+        GlobalScopeGuard::new_dyn(strategy)
+    }
+}
+
+/// Restores the previous strategy when dropped, or uninstalls the strategy if there wasn't any, but
+/// only if the strategy wasn't installed, replaced or taken by something else in the meantime; in
+/// that case, the newer strategy is left in place, like with the thread local scope guard.
+///
+/// The previous strategy is stored inside of the guard, so scopes can be nested, as long as they
+/// are dropped in reverse order.
+pub struct GlobalScopeGuard<D: GlobalDefinition>(SlotScopeGuard<'static, D::Global>);
+
+impl<D: GlobalDefinition> GlobalScopeGuard<D> {
+    pub fn new(strategy: impl Into<D::Global>) -> Self {
+        Self::new_dyn(strategy.into())
+    }
+
+    pub fn new_dyn(strategy: D::Global) -> Self {
+        Self(D::global().scope_dyn(strategy))
+    }
+}
+
+impl<D: GlobalDefinition> fmt::Debug for GlobalScopeGuard<D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GlobalScopeGuard")
+            .field("installed_previously", &self.0.installed_previously())
+            .finish()
     }
 }
 
 impl<T: DefaultGlobalDefinition> Global<T> {
+    /// Like [`Self::read_or_default`], but returns an error instead of panicking if this thread is
+    /// holding a write guard to the global.
+    pub fn try_read_or_default(
+    ) -> Result<MappedRwLockReadGuard<'static, T::Global>, UninitializedError> {
+        if T::global().written_on_this_thread() {
+            Err(UninitializedError::new_would_deadlock(
+                T::KIND,
+                ScopeKind::Global,
+            ))
+        } else {
+            Ok(Self::read_or_default())
+        }
+    }
+
     pub fn read_or_default() -> MappedRwLockReadGuard<'static, T::Global> {
-        drop(Self::write_or_default());
-        Self::read()
+        T::global().read_or_else(T::default)
     }
 
-    pub fn write_or_default() -> MappedRwLockWriteGuard<'static, T::Global> {
-        RwLockWriteGuard::map(T::global().write(), |drop_strategy| {
-            drop_strategy.get_or_insert_with(T::default)
-        })
+    pub fn write_or_default() -> SlotWriteGuard<'static, T::Global> {
+        T::global().write_or_else(T::default)
     }
 }
 
@@ -84,6 +127,7 @@ macro_rules! global_methods {
         Global = $global:ident;
         GenericStrategy = $generic_strategy:ident;
         DynStrategy = $dyn_strategy:ident;
+        ScopeGuard = $scope_guard:ident;
         feature = $feature:literal;
 
         $(#[$($install_dyn_tt:tt)*])*
@@ -107,6 +151,21 @@ macro_rules! global_methods {
         $(#[$($uninstall_tt:tt)*])*
         uninstall;
 
+        $(#[$($take_tt:tt)*])*
+        take;
+
+        $(#[$($installed_type_name_tt:tt)*])*
+        installed_type_name;
+
+        $(#[$($downcast_ref_tt:tt)*])*
+        downcast_ref;
+
+        $(#[$($scope_tt:tt)*])*
+        scope;
+
+        $(#[$($scope_dyn_tt:tt)*])*
+        scope_dyn;
+
         $(#[$($read_or_default_tt:tt)*])*
         read_or_default;
 
@@ -137,12 +196,12 @@ macro_rules! global_methods {
         }
 
         $(#[$($try_write_tt)*])*
-        pub fn try_write() -> Result<MappedRwLockWriteGuard<'static, $dyn_strategy>, UninitializedError> {
+        pub fn try_write() -> Result<SlotWriteGuard<'static, $dyn_strategy>, UninitializedError> {
             $global::try_write()
         }
 
         $(#[$($write_tt)*])*
-        pub fn write() -> MappedRwLockWriteGuard<'static, $dyn_strategy> {
+        pub fn write() -> SlotWriteGuard<'static, $dyn_strategy> {
             $global::write()
         }
 
@@ -151,6 +210,38 @@ macro_rules! global_methods {
             $global::uninstall()
         }
 
+        $(#[$($take_tt)*])*
+        pub fn take() -> Option<$dyn_strategy> {
+            $global::take()
+        }
+
+        $(#[$($installed_type_name_tt)*])*
+        pub fn installed_type_name() -> Option<&'static str> {
+            #[allow(unused_imports)]
+            use $crate::{DynFallibleTryDropStrategy as _, GlobalTryDropStrategy as _};
+
+            $global::try_read().ok().map(|strategy| (**strategy).type_name())
+        }
+
+        $(#[$($downcast_ref_tt)*])*
+        #[cfg(feature = "downcast-rs")]
+        pub fn downcast_ref<T: $generic_strategy>() -> Option<MappedRwLockReadGuard<'static, T>> {
+            MappedRwLockReadGuard::try_map($global::try_read().ok()?, |strategy| {
+                strategy.downcast_ref::<T>()
+            })
+            .ok()
+        }
+
+        $(#[$($scope_tt)*])*
+        pub fn scope(strategy: impl $generic_strategy) -> $scope_guard {
+            $global::scope(strategy)
+        }
+
+        $(#[$($scope_dyn_tt)*])*
+        pub fn scope_dyn(strategy: $dyn_strategy) -> $scope_guard {
+            $global::scope_dyn(strategy)
+        }
+
         $(#[$($read_or_default_tt)*])*
         #[cfg(feature = $feature)]
         pub fn read_or_default() -> MappedRwLockReadGuard<'static, $dyn_strategy> {
@@ -159,8 +250,97 @@ macro_rules! global_methods {
 
         $(#[$($write_or_default_tt)*])*
         #[cfg(feature = $feature)]
-        pub fn write_or_default() -> MappedRwLockWriteGuard<'static, $dyn_strategy> {
+        pub fn write_or_default() -> SlotWriteGuard<'static, $dyn_strategy> {
             $global::write_or_default()
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::drop_strategies::AdHocDropStrategy;
+    use crate::handlers::fallback::global as fallback;
+    use crate::test_utils::GLOBAL_LOCK;
+    use anyhow::anyhow;
+    use std::sync::{Arc, Mutex};
+
+    type Called = Arc<Mutex<Option<&'static str>>>;
+
+    fn strategy(name: &'static str, called: &Called) -> AdHocDropStrategy<impl Fn(crate::Error)> {
+        let called = Arc::clone(called);
+        AdHocDropStrategy(move |_| *called.lock().unwrap() = Some(name))
+    }
+
+    fn installed(called: &Called) -> Option<&'static str> {
+        *called.lock().unwrap() = None;
+        fallback::try_read().ok()?.handle_error(anyhow!("test"));
+        *called.lock().unwrap()
+    }
+
+    #[test]
+    fn test_scope_restores_previous() {
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = fallback::take();
+        let called = Called::default();
+        fallback::install(strategy("previous", &called));
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            assert_eq!(installed(&called), Some("scoped"));
+
+            {
+                let _guard = fallback::scope(strategy("nested", &called));
+                assert_eq!(installed(&called), Some("nested"));
+            }
+
+            assert_eq!(installed(&called), Some("scoped"));
+        }
+
+        assert_eq!(installed(&called), Some("previous"));
+
+        fallback::uninstall();
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            assert_eq!(installed(&called), Some("scoped"));
+        }
+
+        assert_eq!(installed(&called), None);
+
+        if let Some(previous) = previous {
+            fallback::install_dyn(previous)
+        }
+    }
+
+    #[test]
+    fn test_install_in_scope_survives() {
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = fallback::take();
+        let called = Called::default();
+        fallback::install(strategy("previous", &called));
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+
+            {
+                let _guard = fallback::scope(strategy("nested", &called));
+                fallback::install(strategy("installed", &called));
+            }
+
+            assert_eq!(installed(&called), Some("installed"));
+        }
+
+        assert_eq!(installed(&called), Some("installed"));
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            fallback::uninstall();
+        }
+
+        assert_eq!(installed(&called), None);
+
+        if let Some(previous) = previous {
+            fallback::install_dyn(previous)
+        }
+    }
+}
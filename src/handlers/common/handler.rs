@@ -3,6 +3,7 @@
 use crate::handlers::common::shim::OnUninitShim;
 use crate::handlers::common::{Global, Handler, Scope, ThreadLocal};
 use crate::handlers::on_uninit::{DoNothingOnUninit, FlagOnUninit, OnUninit, PanicOnUninit};
+use crate::handlers::{HandlerKind, ScopeKind, UninitializedError};
 use crate::{LOAD_ORDERING, STORE_ORDERING};
 use std::marker::PhantomData;
 use std::sync::atomic::AtomicBool;
@@ -12,6 +13,14 @@ pub struct CommonHandler<OU: OnUninit, S: Scope, H: Handler> {
     pub(crate) _scope: PhantomData<(S, H)>,
 }
 
+impl<OU: OnUninit, S: Scope, H: Handler> CommonHandler<OU, S, H> {
+    /// Which handler this is.
+    pub const KIND: HandlerKind = H::KIND;
+
+    /// Which scope this handler operates in.
+    pub const SCOPE: ScopeKind = S::SCOPE;
+}
+
 impl<S: Scope, H: Handler> CommonHandler<PanicOnUninit, S, H> {
     pub const PANIC_ON_UNINIT: Self = Self {
         extra_data: (),
@@ -23,6 +32,17 @@ impl<S: Scope, H: Handler> CommonHandler<PanicOnUninit, S, H> {
     }
 }
 
+impl<S: Scope, H: Handler> CommonHandler<DoNothingOnUninit, S, H> {
+    pub const DO_NOTHING_ON_UNINIT: Self = Self {
+        extra_data: (),
+        _scope: PhantomData,
+    };
+
+    pub fn on_uninit_do_nothing() -> Self {
+        Self::DO_NOTHING_ON_UNINIT
+    }
+}
+
 impl<S: Scope, H: Handler> CommonHandler<FlagOnUninit, S, H> {
     pub const FLAG_ON_UNINIT: Self = Self {
         extra_data: AtomicBool::new(false),
@@ -37,11 +57,137 @@ impl<S: Scope, H: Handler> CommonHandler<FlagOnUninit, S, H> {
         self.extra_data.load(LOAD_ORDERING)
     }
 
+    /// Clear the flag, so that [`Self::last_drop_failed`] returns `false` until the next drop
+    /// error is handled while this handler is uninitialized.
+    pub fn reset_last_drop_failed(&self) {
+        self.set_last_drop_failed(false)
+    }
+
     pub(crate) fn set_last_drop_failed(&self, value: bool) {
         self.extra_data.store(value, STORE_ORDERING)
     }
 }
 
+/// What a handler does when it's uninitialized, for [`finish`].
+#[derive(Copy, Clone)]
+pub(crate) enum Policy<'a> {
+    /// Return the uninitialized error.
+    Error,
+
+    /// Panic, unless this thread is already panicking.
+    Panic,
+
+    /// Silently ignore the error.
+    DoNothing,
+
+    /// Use the default strategy. The handler is only unavailable with this policy if reading it
+    /// would deadlock.
+    UseDefault,
+
+    /// Store whether or not the handler was uninitialized in the flag.
+    Flag(&'a AtomicBool),
+}
+
+/// Finish handling an error, given the outcome of reading the handler and handing the error to
+/// its strategy. This is shared by every handler, on uninit policy and scope, so that the trait
+/// implementations of [`CommonHandler`] are only thin shells. It's never inlined, otherwise it
+/// would be copied right back into every shell.
+#[inline(never)]
+#[track_caller]
+pub(crate) fn finish(
+    handler: HandlerKind,
+    scope: ScopeKind,
+    policy: Policy,
+    outcome: Result<anyhow::Result<()>, UninitializedError>,
+) -> anyhow::Result<()> {
+    if let Policy::Flag(last_drop_failed) = policy {
+        last_drop_failed.store(outcome.is_err(), STORE_ORDERING)
+    }
+
+    match outcome {
+        Ok(result) => result,
+        Err(uninit_error) => uninitialized(handler, scope, policy, uninit_error),
+    }
+}
+
+/// The uninitialized branch of [`finish`]. Handlers are almost always initialized, so this is kept
+/// cold.
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn uninitialized(
+    handler: HandlerKind,
+    scope: ScopeKind,
+    policy: Policy,
+    uninit_error: UninitializedError,
+) -> anyhow::Result<()> {
+    match policy {
+        Policy::DoNothing => Ok(()),
+        Policy::Panic => {
+            match std::error::Error::source(&uninit_error) {
+                Some(would_deadlock) => crate::last_resort::panic_unless_unwinding(format_args!(
+                    "the {} {} handler can't be used right now: {}",
+                    scope, handler, would_deadlock,
+                )),
+                None => crate::last_resort::panic_unless_unwinding(format_args!(
+                    "the {} {} handler is not initialized yet: {:?}",
+                    scope, handler, uninit_error,
+                )),
+            }
+
+            // we're unwinding, let the fallback handler take it from here
+            Err(uninit_error.into())
+        }
+        Policy::Error | Policy::UseDefault | Policy::Flag(_) => Err(uninit_error.into()),
+    }
+}
+
+/// Unwrap the outcome of reading a handler for the accessors which have to return what they read,
+/// going through the panic on uninit policy of [`finish`] if the handler couldn't be read.
+///
+/// Unlike [`finish`], there's nothing to carry on with if the handler couldn't be read while this
+/// thread is already unwinding, so the process is aborted right after the error is reported.
+#[track_caller]
+pub(crate) fn expect<R>(
+    handler: HandlerKind,
+    scope: ScopeKind,
+    outcome: Result<R, UninitializedError>,
+) -> R {
+    match outcome {
+        Ok(value) => value,
+        Err(uninit_error) => {
+            let _ = uninitialized(handler, scope, Policy::Panic, uninit_error);
+            std::process::abort()
+        }
+    }
+}
+
+/// Finish handling a batch of errors, given the outcome of reading the handler once and handing the
+/// batch to its strategy. If the handler was uninitialized, the batch wasn't touched, so every
+/// error is handed to `each` instead, which goes through the on uninit policy one by one.
+pub(crate) fn finish_batch(
+    policy: Policy,
+    outcome: Result<(), UninitializedError>,
+    errors: &mut dyn Iterator<Item = anyhow::Error>,
+    failed: &mut dyn FnMut(anyhow::Error),
+    mut each: impl FnMut(anyhow::Error) -> anyhow::Result<()>,
+) {
+    match outcome {
+        Ok(()) => {
+            if let Policy::Flag(last_drop_failed) = policy {
+                last_drop_failed.store(false, STORE_ORDERING)
+            }
+        }
+        Err(_) => {
+            for error in errors {
+                if let Err(error) = each(error) {
+                    failed(error)
+                }
+            }
+        }
+    }
+}
+
 pub struct CommonShimHandler<OU: OnUninitShim, H: Handler> {
     pub(crate) global: CommonHandler<FlagOnUninit, Global, H>,
     pub(crate) thread_local: CommonHandler<FlagOnUninit, ThreadLocal, H>,
@@ -82,12 +228,350 @@ impl<H: Handler> CommonShimHandler<FlagOnUninit, H> {
     pub fn on_uninit_flag() -> Self {
         Self::FLAG_ON_UNINIT
     }
+}
+
+impl<OU: OnUninitShim, H: Handler> CommonShimHandler<OU, H> {
+    /// Which handler this is.
+    pub const KIND: HandlerKind = H::KIND;
+
+    /// Which scope this handler operates in.
+    pub const SCOPE: ScopeKind = ScopeKind::Shim;
 
     pub fn last_drop_failed(&self) -> bool {
-        self.extra_data.load(LOAD_ORDERING)
+        self.thread_local.last_drop_failed() && self.global.last_drop_failed()
     }
 
-    pub(crate) fn set_last_drop_failed(&self, value: bool) {
-        self.extra_data.store(value, STORE_ORDERING)
+    /// Clear the flags of both the thread local and the global handler, so that
+    /// [`Self::last_drop_failed`] returns `false` until the next drop error is handled while both
+    /// of them are uninitialized.
+    pub fn reset_last_drop_failed(&self) {
+        self.thread_local.reset_last_drop_failed();
+        self.global.reset_last_drop_failed()
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "global",
+    feature = "thread-local",
+    feature = "ds-write",
+    feature = "ds-panic",
+    feature = "ds-noop",
+    feature = "derives"
+))]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::NoOpDropStrategy;
+    use crate::handlers::common::global::GlobalDefinition;
+    use crate::handlers::common::thread_local::ThreadLocalDefinition;
+    use crate::handlers::common::{Fallback, Primary};
+    use crate::handlers::fallback::global::GlobalFallbackHandler;
+    use crate::handlers::fallback::thread_local::ThreadLocalFallbackHandler;
+    use crate::handlers::on_uninit::ErrorOnUninit;
+    use crate::handlers::primary::global::GlobalPrimaryHandler;
+    use crate::handlers::primary::thread_local::ThreadLocalPrimaryHandler;
+    use crate::handlers::{fallback, primary, WouldDeadlockError};
+    use crate::test_utils::GLOBAL_LOCK;
+    use crate::{FallibleTryDropStrategy, TryDropStrategy};
+    use anyhow::anyhow;
+    use std::format;
+    use std::iter;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::string::String;
+    use std::vec::Vec;
+
+    fn panic_message(f: impl FnOnce()) -> String {
+        let payload = panic::catch_unwind(AssertUnwindSafe(f)).expect_err("didn't panic");
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .expect("panic payload isn't a string")
+    }
+
+    macro_rules! test_primary {
+        ($name:ident, $module:path, $handler:ident, $definition:ident, $scope:expr) => {
+            #[test]
+            fn $name() {
+                use $module as module;
+
+                let _lock = GLOBAL_LOCK.lock();
+                let previous = module::take();
+                let uninit_error = UninitializedError::new(HandlerKind::Primary, $scope);
+
+                // error on uninit
+                let error = $handler::<ErrorOnUninit>::ON_UNINIT_ERROR
+                    .try_handle_error(anyhow!("this will always fail"))
+                    .unwrap_err();
+                assert_eq!(
+                    *error.downcast_ref::<UninitializedError>().unwrap(),
+                    uninit_error
+                );
+
+                // every error of a batch goes through the on uninit policy
+                let mut failed = Vec::new();
+                $handler::<ErrorOnUninit>::ON_UNINIT_ERROR.try_handle_errors_batch(
+                    &mut (0..2).map(|_| anyhow!("this will always fail")),
+                    &mut |error| failed.push(error),
+                );
+                assert_eq!(failed.len(), 2);
+                assert!(failed.iter().all(|error| error.is::<UninitializedError>()));
+
+                // panic on uninit
+                let message = panic_message(|| {
+                    let _ = $handler::<PanicOnUninit>::PANIC_ON_UNINIT
+                        .try_handle_error(anyhow!("this will always fail"));
+                });
+                assert_eq!(
+                    message,
+                    format!(
+                        "{}: {:?}",
+                        <Primary as $definition>::UNINITIALIZED_ERROR,
+                        uninit_error
+                    )
+                );
+
+                // do nothing on uninit
+                $handler::<DoNothingOnUninit>::DO_NOTHING_ON_UNINIT
+                    .try_handle_error(anyhow!("this will always fail"))
+                    .unwrap();
+
+                // flag on uninit
+                let handler = $handler::<FlagOnUninit>::FLAG_ON_UNINIT;
+                assert!(handler
+                    .try_handle_error(anyhow!("this will always fail"))
+                    .is_err());
+                assert!(handler.last_drop_failed());
+
+                module::install(NoOpDropStrategy);
+                handler.try_handle_errors_batch(
+                    &mut iter::once(anyhow!("this will always fail")),
+                    &mut |error| panic!("failed to handle a batch: {error}"),
+                );
+                assert!(!handler.last_drop_failed());
+                handler
+                    .try_handle_error(anyhow!("this will always fail"))
+                    .unwrap();
+                assert!(!handler.last_drop_failed());
+                $handler::<ErrorOnUninit>::ON_UNINIT_ERROR
+                    .try_handle_error(anyhow!("this will always fail"))
+                    .unwrap();
+                $handler::<PanicOnUninit>::PANIC_ON_UNINIT
+                    .try_handle_error(anyhow!("this will always fail"))
+                    .unwrap();
+
+                // use default on uninit
+                module::uninstall();
+                $handler::DEFAULT
+                    .try_handle_error(anyhow!("this will always fail"))
+                    .unwrap();
+                assert!(module::take().is_some(), "the default wasn't installed");
+
+                if let Some(previous) = previous {
+                    module::install_dyn(previous)
+                }
+            }
+        };
+    }
+
+    macro_rules! test_fallback {
+        ($name:ident, $module:path, $handler:ident, $definition:ident, $scope:expr) => {
+            #[test]
+            fn $name() {
+                use $module as module;
+
+                let _lock = GLOBAL_LOCK.lock();
+                let previous = module::take();
+                let uninit_error = UninitializedError::new(HandlerKind::Fallback, $scope);
+
+                // panic on uninit
+                let message = panic_message(|| {
+                    $handler::<PanicOnUninit>::PANIC_ON_UNINIT
+                        .handle_error(anyhow!("this will always fail"))
+                });
+                assert_eq!(
+                    message,
+                    format!(
+                        "{}: {:?}",
+                        <Fallback as $definition>::UNINITIALIZED_ERROR,
+                        uninit_error
+                    )
+                );
+
+                // do nothing on uninit
+                $handler::<DoNothingOnUninit>::DO_NOTHING_ON_UNINIT
+                    .handle_error(anyhow!("this will always fail"));
+
+                // flag on uninit
+                let handler = $handler::<FlagOnUninit>::FLAG_ON_UNINIT;
+                handler.handle_error(anyhow!("this will always fail"));
+                assert!(handler.last_drop_failed());
+
+                module::install(NoOpDropStrategy);
+                handler.handle_error(anyhow!("this will always fail"));
+                assert!(!handler.last_drop_failed());
+                $handler::<PanicOnUninit>::PANIC_ON_UNINIT
+                    .handle_error(anyhow!("this will always fail"));
+
+                // use default on uninit, which is to panic with the error, except in release
+                // builds
+                module::uninstall();
+                if cfg!(any(debug_assertions, feature = "release-panic-fallback")) {
+                    let message = panic_message(|| {
+                        $handler::DEFAULT.handle_error(anyhow!("this will always fail"))
+                    });
+                    assert!(message.contains("this will always fail"), "{message}");
+                } else {
+                    $handler::DEFAULT.handle_error(anyhow!("this will always fail"));
+                }
+                assert!(module::take().is_some(), "the default wasn't installed");
+
+                if let Some(previous) = previous {
+                    module::install_dyn(previous)
+                }
+            }
+        };
+    }
+
+    test_primary!(
+        test_global_primary,
+        primary::global,
+        GlobalPrimaryHandler,
+        GlobalDefinition,
+        ScopeKind::Global
+    );
+    test_primary!(
+        test_thread_local_primary,
+        primary::thread_local,
+        ThreadLocalPrimaryHandler,
+        ThreadLocalDefinition,
+        ScopeKind::ThreadLocal
+    );
+    test_fallback!(
+        test_global_fallback,
+        fallback::global,
+        GlobalFallbackHandler,
+        GlobalDefinition,
+        ScopeKind::Global
+    );
+    test_fallback!(
+        test_thread_local_fallback,
+        fallback::thread_local,
+        ThreadLocalFallbackHandler,
+        ThreadLocalDefinition,
+        ScopeKind::ThreadLocal
+    );
+
+    /// Runs `f` on another thread, failing instead of hanging if it doesn't finish in time.
+    fn with_timeout(f: impl FnOnce() + Send + 'static) {
+        use std::sync::mpsc::{self, RecvTimeoutError};
+        use std::thread;
+        use std::time::Duration;
+
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::spawn(move || {
+            f();
+            let _ = sender.send(());
+        });
+
+        match receiver.recv_timeout(Duration::from_secs(10)) {
+            Ok(()) => {}
+            Err(RecvTimeoutError::Timeout) => panic!("deadlocked"),
+            Err(RecvTimeoutError::Disconnected) => {
+                panic::resume_unwind(thread.join().expect_err("didn't panic"))
+            }
+        }
+    }
+
+    fn would_deadlock(error: &anyhow::Error) -> bool {
+        error.root_cause().is::<WouldDeadlockError>()
+    }
+
+    #[test]
+    fn test_global_primary_would_deadlock() {
+        use crate::drop_strategies::AdHocDropStrategy;
+        use crate::test_utils::fallible_given;
+        use std::sync::{Arc, Mutex};
+
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = primary::global::take();
+        primary::global::install(NoOpDropStrategy);
+
+        with_timeout(|| {
+            let guard = primary::global::write();
+
+            // a value fails to drop while the global primary handler is being written to
+            let deadlocked = Arc::new(Mutex::new(false));
+            let d = Arc::clone(&deadlocked);
+            drop(fallible_given(
+                GlobalPrimaryHandler::<ErrorOnUninit>::ON_UNINIT_ERROR,
+                AdHocDropStrategy(move |error| *d.lock().unwrap() = would_deadlock(&error)),
+            ));
+            assert!(*deadlocked.lock().unwrap());
+
+            let flag = GlobalPrimaryHandler::<FlagOnUninit>::on_uninit_flag();
+            assert!(would_deadlock(
+                &flag
+                    .try_handle_error(anyhow!("this will always fail"))
+                    .unwrap_err()
+            ));
+            assert!(flag.last_drop_failed());
+
+            let message = panic_message(|| {
+                let _ = GlobalPrimaryHandler::<PanicOnUninit>::PANIC_ON_UNINIT
+                    .try_handle_error(anyhow!("this will always fail"));
+            });
+            assert!(message.contains("would deadlock"), "got {message}");
+
+            let error = GlobalPrimaryHandler::DEFAULT
+                .try_handle_error(anyhow!("this will always fail"))
+                .unwrap_err();
+            assert!(would_deadlock(&error));
+
+            drop(guard);
+            GlobalPrimaryHandler::<ErrorOnUninit>::ON_UNINIT_ERROR
+                .try_handle_error(anyhow!("this will always fail"))
+                .unwrap();
+        });
+
+        match previous {
+            Some(previous) => primary::global::install_dyn(previous),
+            None => primary::global::uninstall(),
+        }
+    }
+
+    #[test]
+    fn test_global_fallback_would_deadlock() {
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = fallback::global::take();
+        fallback::global::install(NoOpDropStrategy);
+
+        with_timeout(|| {
+            let guard = fallback::global::write();
+
+            let flag = GlobalFallbackHandler::<FlagOnUninit>::on_uninit_flag();
+            flag.handle_error(anyhow!("this will always fail"));
+            assert!(flag.last_drop_failed());
+
+            for message in [
+                panic_message(|| {
+                    GlobalFallbackHandler::<PanicOnUninit>::PANIC_ON_UNINIT
+                        .handle_error(anyhow!("this will always fail"))
+                }),
+                panic_message(|| {
+                    GlobalFallbackHandler::DEFAULT.handle_error(anyhow!("this will always fail"))
+                }),
+            ] {
+                assert!(message.contains("would deadlock"), "got {message}");
+            }
+
+            drop(guard);
+            flag.handle_error(anyhow!("this will always fail"));
+            assert!(!flag.last_drop_failed());
+        });
+
+        match previous {
+            Some(previous) => fallback::global::install_dyn(previous),
+            None => fallback::global::uninstall(),
+        }
     }
 }
@@ -14,19 +14,21 @@ pub mod global;
 pub mod handler;
 pub mod proxy;
 
+use crate::handlers::{HandlerKind, ScopeKind};
 use std::error::Error;
 use std::fmt;
 use std::fmt::Formatter;
 
-/// This error occurs when you attempt to use a scope guard in a nested scope.
+/// This error occurs when you attempt to nest a scope guard in an exclusive one, or to nest an
+/// exclusive scope guard in any other.
 ///
 /// # Examples
 /// ```rust
 /// {
-///     let _guard = ScopeGuard::new(PanicDropStrategy::DEFAULT));
+///     let _guard = ScopeGuard::new_exclusive(PanicDropStrategy::DEFAULT);
 ///     {
 ///         // this isn't allowed
-///         let _guard = ScopeGuard::new(PanicDropStrategy::DEFAULT));
+///         let _guard = ScopeGuard::new(PanicDropStrategy::DEFAULT);
 ///     }
 /// }
 /// ```
@@ -35,32 +37,74 @@ use std::fmt::Formatter;
     derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
 )]
 #[derive(Debug)]
-pub struct NestedScopeError(pub(crate) ());
+pub struct NestedScopeError {
+    handler: HandlerKind,
+}
+
+impl NestedScopeError {
+    pub(crate) const fn new(handler: HandlerKind) -> Self {
+        Self { handler }
+    }
+
+    /// The handler whose scope guards were nested.
+    pub const fn handler(&self) -> HandlerKind {
+        self.handler
+    }
+}
 
 impl Error for NestedScopeError {}
 
 impl fmt::Display for NestedScopeError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        f.write_str("you cannot nest scope guards")
+        write!(f, "you cannot nest {} scope guards", self.handler)
     }
 }
 
-pub trait Handler: private::Sealed {}
+pub trait Handler: private::Sealed {
+    const KIND: HandlerKind;
+}
 
 pub enum Primary {}
 impl private::Sealed for Primary {}
-impl Handler for Primary {}
+impl Handler for Primary {
+    const KIND: HandlerKind = HandlerKind::Primary;
+}
 
 pub enum Fallback {}
 impl private::Sealed for Fallback {}
-impl Handler for Fallback {}
+impl Handler for Fallback {
+    const KIND: HandlerKind = HandlerKind::Fallback;
+}
 
-pub trait Scope: private::Sealed {}
+pub trait Scope: private::Sealed {
+    const SCOPE: ScopeKind;
+}
 
 pub enum Global {}
 impl private::Sealed for Global {}
-impl Scope for Global {}
+impl Scope for Global {
+    const SCOPE: ScopeKind = ScopeKind::Global;
+}
 
 pub enum ThreadLocal {}
 impl private::Sealed for ThreadLocal {}
-impl Scope for ThreadLocal {}
+impl Scope for ThreadLocal {
+    const SCOPE: ScopeKind = ScopeKind::ThreadLocal;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handler_kinds() {
+        assert_eq!(Primary::KIND, HandlerKind::Primary);
+        assert_eq!(Fallback::KIND, HandlerKind::Fallback);
+    }
+
+    #[test]
+    fn test_scope_kinds() {
+        assert_eq!(Global::SCOPE, ScopeKind::Global);
+        assert_eq!(ThreadLocal::SCOPE, ScopeKind::ThreadLocal);
+    }
+}
@@ -58,6 +58,12 @@ impl<D: GlobalDefinition> TheGreatAbstracter<D, Global> {
 
 #[cfg(feature = "global")]
 impl<D: DefaultGlobalDefinition> TheGreatAbstracter<D, Global> {
+    pub fn try_read_or_default<R>(
+        f: impl FnOnce(&D::Global) -> R,
+    ) -> Result<R, UninitializedError> {
+        GlobalAbstracter::<D>::try_read_or_default().map(|lock| f(lock.deref()))
+    }
+
     pub fn read_or_default<R>(f: impl FnOnce(&D::Global) -> R) -> R {
         f(GlobalAbstracter::<D>::read_or_default().deref())
     }
@@ -100,6 +106,12 @@ impl<D: ThreadLocalDefinition> TheGreatAbstracter<D, ThreadLocal> {
 
 #[cfg(feature = "thread-local")]
 impl<D: DefaultThreadLocalDefinition> TheGreatAbstracter<D, ThreadLocal> {
+    pub fn try_read_or_default<R>(
+        f: impl FnOnce(&D::ThreadLocal) -> R,
+    ) -> Result<R, UninitializedError> {
+        Ok(Self::read_or_default(f))
+    }
+
     pub fn read_or_default<R>(f: impl FnOnce(&D::ThreadLocal) -> R) -> R {
         ThreadLocalAbstracter::<D>::read_or_default(f)
     }
@@ -22,9 +22,13 @@ mod use_default {
         type ExtraData = Lazy<crate::drop_strategies::WriteDropStrategy<std::io::Stderr>>;
     }
 
-    #[cfg(feature = "ds-panic")]
+    #[cfg(feature = "ds-write")]
     impl OnUninitShim for UseDefaultOnUninitShim<Fallback> {
-        type ExtraData = Lazy<crate::drop_strategies::PanicDropStrategy>;
+        type ExtraData = Lazy<
+            crate::adapters::AssertInfallibleTryDropStrategyAdapter<
+                crate::drop_strategies::WriteDropStrategy<std::io::Stderr>,
+            >,
+        >;
     }
 
     impl<H: Handler> private::Sealed for UseDefaultOnUninitShim<H> {}
@@ -1,16 +1,18 @@
 pub mod scope_guard;
+#[allow(unused_imports)]
 pub(crate) mod imports {
     pub use crate::handlers::UninitializedError;
     pub use crate::{DynFallibleTryDropStrategy, ThreadLocalFallibleTryDropStrategy};
     pub use std::boxed::Box;
 }
 
-use crate::handlers::common::thread_local::scope_guard::ScopeGuard;
-use crate::handlers::common::Handler;
-use crate::handlers::UninitializedError;
+use crate::handlers::common::thread_local::scope_guard::{Frame, ScopeGuard};
+use crate::handlers::common::{handler, Handler};
+use crate::handlers::{ScopeKind, UninitializedError};
 use std::cell::{Cell, RefCell};
 use std::marker::PhantomData;
 use std::thread::LocalKey;
+use std::vec::Vec;
 
 macro_rules! thread_local_methods {
     (
@@ -61,6 +63,12 @@ macro_rules! thread_local_methods {
 
         $(#[$($scope_dyn_meta:meta)*])*
         scope_dyn;
+
+        $(#[$($scope_exclusive_meta:meta)*])*
+        scope_exclusive;
+
+        $(#[$($scope_exclusive_dyn_meta:meta)*])*
+        scope_exclusive_dyn;
     ) => {
         #[allow(unused_imports)]
         use $crate::handlers::common::thread_local::imports::*;
@@ -136,6 +144,16 @@ macro_rules! thread_local_methods {
         pub fn scope_dyn(strategy: $dyn_strategy) -> $scope_guard {
             $thread_local::scope_dyn(strategy)
         }
+
+        $(#[$($scope_exclusive_meta)*])*
+        pub fn scope_exclusive(strategy: impl $generic_strategy) -> $scope_guard {
+            $thread_local::scope_exclusive(strategy)
+        }
+
+        $(#[$($scope_exclusive_dyn_meta)*])*
+        pub fn scope_exclusive_dyn(strategy: $dyn_strategy) -> $scope_guard {
+            $thread_local::scope_exclusive_dyn(strategy)
+        }
     };
 }
 
@@ -146,6 +164,8 @@ pub trait ThreadLocalDefinition: Handler {
 
     fn thread_local() -> &'static LocalKey<RefCell<Option<Self::ThreadLocal>>>;
     fn locked() -> &'static LocalKey<Cell<bool>>;
+    fn stack() -> &'static LocalKey<RefCell<Vec<Frame<Self::ThreadLocal>>>>;
+    fn generation() -> &'static LocalKey<Cell<u64>>;
 }
 
 pub trait DefaultThreadLocalDefinition: ThreadLocalDefinition {
@@ -156,7 +176,7 @@ pub struct ThreadLocal<T: ThreadLocalDefinition>(PhantomData<T>);
 
 impl<T: ThreadLocalDefinition> ThreadLocal<T> {
     pub fn read<R>(f: impl FnOnce(&T::ThreadLocal) -> R) -> R {
-        Self::try_read(f).expect(T::UNINITIALIZED_ERROR)
+        handler::expect(T::KIND, ScopeKind::ThreadLocal, Self::try_read(f))
     }
 
     pub fn try_read<R>(f: impl FnOnce(&T::ThreadLocal) -> R) -> Result<R, UninitializedError> {
@@ -164,12 +184,12 @@ impl<T: ThreadLocalDefinition> ThreadLocal<T> {
             cell.borrow_mut()
                 .as_ref()
                 .map(f)
-                .ok_or(UninitializedError(()))
+                .ok_or(UninitializedError::new(T::KIND, ScopeKind::ThreadLocal))
         })
     }
 
     pub fn write<R>(f: impl FnOnce(&mut T::ThreadLocal) -> R) -> R {
-        Self::try_write(f).expect(T::UNINITIALIZED_ERROR)
+        handler::expect(T::KIND, ScopeKind::ThreadLocal, Self::try_write(f))
     }
 
     pub fn try_write<R>(f: impl FnOnce(&mut T::ThreadLocal) -> R) -> Result<R, UninitializedError> {
@@ -177,7 +197,7 @@ impl<T: ThreadLocalDefinition> ThreadLocal<T> {
             cell.borrow_mut()
                 .as_mut()
                 .map(f)
-                .ok_or(UninitializedError(()))
+                .ok_or(UninitializedError::new(T::KIND, ScopeKind::ThreadLocal))
         })
     }
 
@@ -194,7 +214,9 @@ impl<T: ThreadLocalDefinition> ThreadLocal<T> {
     }
 
     pub fn take() -> Option<T::ThreadLocal> {
-        T::thread_local().with(|cell| cell.borrow_mut().take())
+        let strategy = T::thread_local().with(|cell| cell.borrow_mut().take());
+        Self::bump_generation();
+        strategy
     }
 
     pub fn replace(new: impl Into<T::ThreadLocal>) -> Option<T::ThreadLocal> {
@@ -202,7 +224,17 @@ impl<T: ThreadLocalDefinition> ThreadLocal<T> {
     }
 
     pub fn replace_dyn(new: T::ThreadLocal) -> Option<T::ThreadLocal> {
-        T::thread_local().with(|cell| cell.borrow_mut().replace(new))
+        let strategy = T::thread_local().with(|cell| cell.borrow_mut().replace(new));
+        Self::bump_generation();
+        strategy
+    }
+
+    pub fn generation() -> u64 {
+        T::generation().with(Cell::get)
+    }
+
+    fn bump_generation() {
+        T::generation().with(|cell| cell.set(cell.get().wrapping_add(1)))
     }
 
     pub fn scope(strategy: impl Into<T::ThreadLocal>) -> ScopeGuard<T> {
@@ -212,6 +244,14 @@ impl<T: ThreadLocalDefinition> ThreadLocal<T> {
     pub fn scope_dyn(strategy: T::ThreadLocal) -> ScopeGuard<T> {
         ScopeGuard::new_dyn(strategy)
     }
+
+    pub fn scope_exclusive(strategy: impl Into<T::ThreadLocal>) -> ScopeGuard<T> {
+        Self::scope_exclusive_dyn(strategy.into())
+    }
+
+    pub fn scope_exclusive_dyn(strategy: T::ThreadLocal) -> ScopeGuard<T> {
+        ScopeGuard::new_exclusive_dyn(strategy)
+    }
 }
 
 impl<T: DefaultThreadLocalDefinition> ThreadLocal<T> {
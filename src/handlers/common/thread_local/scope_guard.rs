@@ -1,9 +1,33 @@
 use crate::handlers::common::thread_local::{ThreadLocal, ThreadLocalDefinition};
 use crate::handlers::common::NestedScopeError;
-use std::{fmt, format};
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
 
+/// What a scope guard needs to restore the strategy which preceded it. These are stored in a stack
+/// per handler, one for every scope guard which is alive.
+pub struct Frame<T> {
+    previous: Option<T>,
+
+    /// The generation of the handler right after the strategy of the scope guard was installed,
+    /// or after the strategy of the scope guard nested in it was restored.
+    generation: u64,
+}
+
+/// Restores the strategy which preceded it when dropped, but only if the strategy wasn't
+/// installed, replaced or taken by something else in the meantime; in that case, the newer
+/// strategy is left in place.
+///
+/// Scope guards can be nested, in which case the innermost one wins. They should be dropped in
+/// reverse order; if an outer scope guard is dropped first, the scope guards nested in it are
+/// restored right away. Exclusive scope guards can't be nested, nor be nested in other scope
+/// guards.
 pub struct ScopeGuard<D: ThreadLocalDefinition> {
-    last_strategy: Option<D::ThreadLocal>,
+    depth: usize,
+    exclusive: bool,
+
+    // scope guards are bound to the stack of the thread they were created on
+    _definition: PhantomData<*const D>,
 }
 
 impl<D: ThreadLocalDefinition> ScopeGuard<D> {
@@ -12,7 +36,7 @@ impl<D: ThreadLocalDefinition> ScopeGuard<D> {
     }
 
     pub fn new_dyn(strategy: D::ThreadLocal) -> Self {
-        Self::try_new_dyn(strategy).expect("you cannot nest scope guards")
+        Self::try_new_dyn(strategy).expect("you cannot nest scope guards in an exclusive one")
     }
 
     pub fn try_new(strategy: impl Into<D::ThreadLocal>) -> Result<Self, NestedScopeError> {
@@ -20,31 +44,296 @@ impl<D: ThreadLocalDefinition> ScopeGuard<D> {
     }
 
     pub fn try_new_dyn(strategy: D::ThreadLocal) -> Result<Self, NestedScopeError> {
-        if D::locked().with(|cell| cell.get()) {
-            Err(NestedScopeError(()))
+        if D::locked().with(Cell::get) {
+            Err(NestedScopeError::new(D::KIND))
+        } else {
+            Ok(Self::push(strategy, false))
+        }
+    }
+
+    pub fn new_exclusive(strategy: impl Into<D::ThreadLocal>) -> Self {
+        Self::new_exclusive_dyn(strategy.into())
+    }
+
+    pub fn new_exclusive_dyn(strategy: D::ThreadLocal) -> Self {
+        Self::try_new_exclusive_dyn(strategy).expect("you cannot nest exclusive scope guards")
+    }
+
+    pub fn try_new_exclusive(
+        strategy: impl Into<D::ThreadLocal>,
+    ) -> Result<Self, NestedScopeError> {
+        Self::try_new_exclusive_dyn(strategy.into())
+    }
+
+    pub fn try_new_exclusive_dyn(strategy: D::ThreadLocal) -> Result<Self, NestedScopeError> {
+        if D::locked().with(Cell::get) || D::stack().with(|stack| !stack.borrow().is_empty()) {
+            Err(NestedScopeError::new(D::KIND))
         } else {
             D::locked().with(|cell| cell.set(true));
-            Ok(Self {
-                last_strategy: ThreadLocal::<D>::replace_dyn(strategy),
-            })
+            Ok(Self::push(strategy, true))
+        }
+    }
+
+    fn push(strategy: D::ThreadLocal, exclusive: bool) -> Self {
+        let previous = ThreadLocal::<D>::replace_dyn(strategy);
+        let frame = Frame {
+            previous,
+            generation: ThreadLocal::<D>::generation(),
+        };
+        let depth = D::stack().with(|stack| {
+            let mut stack = stack.borrow_mut();
+            stack.push(frame);
+            stack.len()
+        });
+
+        Self {
+            depth,
+            exclusive,
+            _definition: PhantomData,
         }
     }
+
+    /// Whether or not this scope guard is exclusive.
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    /// How many scope guards this one is nested in, plus one.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
 }
 
 impl<D: ThreadLocalDefinition> fmt::Debug for ScopeGuard<D> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("ScopeGuard")
-            .field("last_strategy", &format!("Option<Box<dyn {}>>", D::DYN))
+            .field("depth", &self.depth)
+            .field("exclusive", &self.exclusive)
             .finish()
     }
 }
 
 impl<D: ThreadLocalDefinition> Drop for ScopeGuard<D> {
     fn drop(&mut self) {
-        if let Some(last_strategy) = self.last_strategy.take() {
-            ThreadLocal::<D>::install_dyn(last_strategy)
+        // pops the frames of the scope guards nested in this one too, if they're still alive
+        loop {
+            let frame = D::stack().with(|stack| {
+                let mut stack = stack.borrow_mut();
+                if stack.len() >= self.depth {
+                    stack.pop()
+                } else {
+                    None
+                }
+            });
+            let Some(frame) = frame else { break };
+
+            if ThreadLocal::<D>::generation() == frame.generation {
+                match frame.previous {
+                    Some(previous) => ThreadLocal::<D>::install_dyn(previous),
+                    None => ThreadLocal::<D>::uninstall(),
+                }
+
+                // the strategy of the enclosing scope guard is back in place
+                let generation = ThreadLocal::<D>::generation();
+                D::stack().with(|stack| {
+                    if let Some(enclosing) = stack.borrow_mut().last_mut() {
+                        enclosing.generation = generation
+                    }
+                });
+            }
+        }
+
+        if self.exclusive {
+            D::locked().with(|cell| cell.set(false))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::drop_strategies::AdHocDropStrategy;
+    use crate::handlers::fallback::thread_local as fallback;
+    use anyhow::anyhow;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn strategy(
+        name: &'static str,
+        called: &Rc<Cell<Option<&'static str>>>,
+    ) -> AdHocDropStrategy<impl Fn(crate::Error)> {
+        let called = Rc::clone(called);
+        AdHocDropStrategy(move |_| called.set(Some(name)))
+    }
+
+    fn installed(called: &Rc<Cell<Option<&'static str>>>) -> Option<&'static str> {
+        called.set(None);
+        fallback::try_read(|strategy| strategy.handle_error(anyhow!("test"))).ok()?;
+        called.get()
+    }
+
+    #[test]
+    fn test_scope_restores_previous() {
+        let called = Rc::new(Cell::new(None));
+        fallback::install(strategy("previous", &called));
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            assert_eq!(installed(&called), Some("scoped"));
+        }
+
+        assert_eq!(installed(&called), Some("previous"));
+    }
+
+    #[test]
+    fn test_scope_restores_uninstalled() {
+        let called = Rc::new(Cell::new(None));
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            assert_eq!(installed(&called), Some("scoped"));
+        }
+
+        assert_eq!(installed(&called), None);
+    }
+
+    #[test]
+    fn test_replace_in_scope_survives() {
+        let called = Rc::new(Cell::new(None));
+        fallback::install(strategy("previous", &called));
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            fallback::replace(strategy("replacement", &called));
+            assert_eq!(installed(&called), Some("replacement"));
+        }
+
+        assert_eq!(installed(&called), Some("replacement"));
+    }
+
+    #[test]
+    fn test_install_in_scope_survives() {
+        let called = Rc::new(Cell::new(None));
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            fallback::install(strategy("installed", &called));
+        }
+
+        assert_eq!(installed(&called), Some("installed"));
+    }
+
+    #[test]
+    fn test_uninstall_in_scope_survives() {
+        let called = Rc::new(Cell::new(None));
+        fallback::install(strategy("previous", &called));
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            fallback::uninstall();
+        }
+
+        assert_eq!(installed(&called), None);
+    }
+
+    #[test]
+    fn test_sequential_scopes_after_replace() {
+        let called = Rc::new(Cell::new(None));
+        fallback::install(strategy("previous", &called));
+
+        {
+            let _guard = fallback::scope(strategy("first", &called));
+            fallback::replace(strategy("replacement", &called));
+        }
+
+        {
+            let _guard = fallback::scope(strategy("second", &called));
+            assert_eq!(installed(&called), Some("second"));
+        }
+
+        assert_eq!(installed(&called), Some("replacement"));
+    }
+
+    #[test]
+    fn test_nested_scopes_restore_in_order() {
+        let called = Rc::new(Cell::new(None));
+        fallback::install(strategy("previous", &called));
+
+        {
+            let outer = fallback::scope(strategy("outer", &called));
+            assert_eq!(outer.depth(), 1);
+            assert_eq!(installed(&called), Some("outer"));
+
+            {
+                let inner = fallback::scope(strategy("inner", &called));
+                assert_eq!(inner.depth(), 2);
+                assert_eq!(installed(&called), Some("inner"));
+            }
+
+            assert_eq!(installed(&called), Some("outer"));
+
+            {
+                let _inner = fallback::scope(strategy("second inner", &called));
+                assert_eq!(installed(&called), Some("second inner"));
+            }
+
+            assert_eq!(installed(&called), Some("outer"));
+        }
+
+        assert_eq!(installed(&called), Some("previous"));
+    }
+
+    #[test]
+    fn test_replace_in_nested_scope_survives() {
+        let called = Rc::new(Cell::new(None));
+        fallback::install(strategy("previous", &called));
+
+        {
+            let _outer = fallback::scope(strategy("outer", &called));
+
+            {
+                let _inner = fallback::scope(strategy("inner", &called));
+                fallback::replace(strategy("replacement", &called));
+            }
+
+            assert_eq!(installed(&called), Some("replacement"));
+        }
+
+        assert_eq!(installed(&called), Some("replacement"));
+    }
+
+    #[test]
+    fn test_outer_scope_dropped_first() {
+        let called = Rc::new(Cell::new(None));
+        fallback::install(strategy("previous", &called));
+
+        let outer = fallback::scope(strategy("outer", &called));
+        let inner = fallback::scope(strategy("inner", &called));
+        drop(outer);
+        assert_eq!(installed(&called), Some("previous"));
+        drop(inner);
+        assert_eq!(installed(&called), Some("previous"));
+    }
+
+    #[test]
+    fn test_exclusive_scope() {
+        let called = Rc::new(Cell::new(None));
+
+        {
+            let _guard = fallback::scope_exclusive(strategy("exclusive", &called));
+            assert!(fallback::ScopeGuard::try_new(strategy("nested", &called)).is_err());
+            assert!(fallback::ScopeGuard::try_new_exclusive(strategy("nested", &called)).is_err());
+            assert_eq!(installed(&called), Some("exclusive"));
+        }
+
+        {
+            let _guard = fallback::scope(strategy("scoped", &called));
+            assert!(fallback::ScopeGuard::try_new_exclusive(strategy("nested", &called)).is_err());
         }
 
-        D::locked().with(|cell| cell.set(false))
+        let guard =
+            fallback::ScopeGuard::try_new_exclusive(strategy("exclusive", &called)).unwrap();
+        assert!(guard.is_exclusive());
+        drop(guard);
+        assert_eq!(installed(&called), None);
     }
 }
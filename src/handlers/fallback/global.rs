@@ -1,17 +1,26 @@
 //! Manage the global fallback handler.
 
 use super::DefaultOnUninit;
-use crate::handlers::common::global::{Global as GenericGlobal, GlobalDefinition};
-use crate::handlers::common::handler::CommonHandler;
+use crate::handlers::common::global::{
+    Global as GenericGlobal, GlobalDefinition, GlobalScopeGuard as GenericGlobalScopeGuard,
+};
+use crate::handlers::common::handler::{finish, finish_batch, CommonHandler, Policy};
 use crate::handlers::common::Fallback;
 use crate::handlers::common::Global as GlobalScope;
 use crate::handlers::fallback::Abstracter;
-use crate::handlers::on_uninit::{FlagOnUninit, PanicOnUninit};
+use crate::handlers::on_uninit::{DoNothingOnUninit, FlagOnUninit, PanicOnUninit};
+use crate::handlers::slot::GlobalSlot;
+use crate::handlers::slot::SlotWriteGuard;
 use crate::handlers::uninit_error::UninitializedError;
-use crate::{GlobalTryDropStrategy, TryDropStrategy};
+use crate::{GlobalTryDropStrategy, InfallibleStrategy, TryDropStrategy};
 use anyhow::Error;
-use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use parking_lot::{MappedRwLockReadGuard, Mutex};
 use std::boxed::Box;
+use std::panic::{self, PanicHookInfo};
+use std::sync::Arc;
+use std::thread_local;
 
 #[cfg(feature = "ds-panic")]
 use crate::handlers::common::global::DefaultGlobalDefinition;
@@ -25,16 +34,18 @@ pub type GlobalFallbackHandler<OU = DefaultOnUninit> = CommonHandler<OU, GlobalS
 /// The default global fallback handler.
 pub static DEFAULT_GLOBAL_FALLBACK_HANDLER: GlobalFallbackHandler = GlobalFallbackHandler::DEFAULT;
 
-static FALLBACK_HANDLER: RwLock<Option<Box<dyn GlobalTryDropStrategy>>> =
-    parking_lot::const_rwlock(None);
+static FALLBACK_HANDLER: GlobalSlot<Box<dyn GlobalTryDropStrategy>> = GlobalSlot::new();
 
-impl_try_drop_strategy_for!(GlobalFallbackHandler where Scope: GlobalScope);
+impl_try_drop_strategy_for!(GlobalFallbackHandler
+where
+    Scope: GlobalScope
+);
 
 impl GlobalDefinition for Fallback {
     const UNINITIALIZED_ERROR: &'static str = "the global fallback handler is not initialized yet";
     type Global = Box<dyn GlobalTryDropStrategy>;
 
-    fn global() -> &'static RwLock<Option<Self::Global>> {
+    fn global() -> &'static GlobalSlot<Self::Global> {
         &FALLBACK_HANDLER
     }
 }
@@ -42,7 +53,7 @@ impl GlobalDefinition for Fallback {
 #[cfg(feature = "ds-panic")]
 impl DefaultGlobalDefinition for Fallback {
     fn default() -> Self::Global {
-        Box::new(crate::drop_strategies::PanicDropStrategy::DEFAULT)
+        Box::new(super::DEFAULT_STRATEGY)
     }
 }
 
@@ -55,10 +66,15 @@ impl<T: GlobalTryDropStrategy> From<T> for Box<dyn GlobalTryDropStrategy> {
 type Global = GenericGlobal<Fallback>;
 type BoxDynGlobalTryDropStrategy = Box<dyn GlobalTryDropStrategy>;
 
+/// A scope guard for the global fallback handler. This sets the global fallback handler to the one
+/// specified for the duration of the scope.
+pub type GlobalScopeGuard = GenericGlobalScopeGuard<Fallback>;
+
 global_methods! {
     Global = Global;
     GenericStrategy = GlobalTryDropStrategy;
     DynStrategy = BoxDynGlobalTryDropStrategy;
+    ScopeGuard = GlobalScopeGuard;
     feature = "ds-panic";
 
     /// Install a new global fallback handler. Must be a dynamic trait object.
@@ -94,6 +110,30 @@ global_methods! {
     /// Uninstall the current global fallback handler.
     uninstall;
 
+    /// Take the current global fallback handler, if there is any initialized.
+    take;
+
+    /// Get the type name of the concrete strategy installed as the global fallback handler, if
+    /// there is any initialized. This is useful to confirm which strategy is actually installed.
+    installed_type_name;
+
+    /// Get a reference to the concrete strategy installed as the global fallback handler, if there
+    /// is any initialized and it's of type `T`. This is useful to e.g. read back the errors which a
+    /// strategy installed globally has collected.
+    downcast_ref;
+
+    /// Sets the global fallback handler to the specified one for the duration of the scope, then
+    /// restores the previous one.
+    ///
+    /// For more information, see the [`GlobalScopeGuard`] type.
+    scope;
+
+    /// Sets the global fallback handler to the specified one for the duration of the scope, then
+    /// restores the previous one. Must be a dynamic trait object.
+    ///
+    /// For more information, see the [`GlobalScopeGuard`] type.
+    scope_dyn;
+
     /// Get a reference to the global fallback handler.
     ///
     /// If the global fallback handler is not initialized yet, it is initialized with the default
@@ -106,3 +146,174 @@ global_methods! {
     /// one.
     write_or_default;
 }
+
+/// Install a new global fallback handler, which must be audited to never fail.
+///
+/// Unlike [`install`], this only accepts try drop strategies which implement
+/// [`InfallibleStrategy`].
+pub fn install_checked(strategy: impl InfallibleStrategy + GlobalTryDropStrategy) {
+    install(strategy)
+}
+
+type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+struct PanicHookState {
+    previous: Option<Arc<PanicHook>>,
+    installed: usize,
+}
+
+static PANIC_HOOK: Mutex<PanicHookState> = parking_lot::const_mutex(PanicHookState {
+    previous: None,
+    installed: 0,
+});
+
+thread_local! {
+    static FORWARDING_PANIC: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Uninstalls the panic hook installed by [`install_panic_hook`] when dropped, once every other
+/// guard returned by it is dropped too.
+#[must_use = "the panic hook is uninstalled as soon as this is dropped"]
+#[derive(Debug)]
+pub struct PanicHookGuard {
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for PanicHookGuard {
+    fn drop(&mut self) {
+        let mut state = PANIC_HOOK.lock();
+        state.installed -= 1;
+
+        // the panic hook can't be changed while panicking, so it's left installed
+        if state.installed > 0 || std::thread::panicking() {
+            return;
+        }
+
+        // drops our hook, along with its reference to the previous one
+        drop(panic::take_hook());
+
+        if let Some(previous) = state.previous.take() {
+            match Arc::try_unwrap(previous) {
+                Ok(previous) => panic::set_hook(previous),
+                Err(previous) => panic::set_hook(Box::new(move |info| previous(info))),
+            }
+        }
+    }
+}
+
+fn forward_panic(info: &PanicHookInfo<'_>) {
+    if FORWARDING_PANIC.with(|forwarding| forwarding.replace(true)) {
+        return;
+    }
+
+    let message = crate::adapters::panic_message(info.payload());
+    let error = match info.location() {
+        Some(location) => anyhow::anyhow!("panicked at {location}: {message}"),
+        None => anyhow::anyhow!("panicked: {message}"),
+    };
+
+    if let Ok(strategy) = try_read() {
+        strategy.handle_error(error)
+    }
+
+    FORWARDING_PANIC.with(|forwarding| forwarding.set(false))
+}
+
+/// Install a panic hook which forwards every panic as an error to the global fallback handler, if
+/// there is any initialized, before running the previous panic hook. This gives a single place
+/// which both drop errors and panics end up at, e.g. when the primary handler is a
+/// [`PanicDropStrategy`](crate::drop_strategies::PanicDropStrategy).
+///
+/// Installing it again while it's installed does nothing more. The previous panic hook is
+/// restored when every returned guard is dropped.
+///
+/// # Notes
+/// The global fallback handler must not panic itself, otherwise the program aborts, as it's
+/// called from within the panic hook.
+pub fn install_panic_hook() -> PanicHookGuard {
+    let mut state = PANIC_HOOK.lock();
+
+    if state.installed == 0 {
+        let previous = Arc::new(panic::take_hook());
+        let p = Arc::clone(&previous);
+        panic::set_hook(Box::new(move |info| {
+            forward_panic(info);
+            p(info)
+        }));
+        state.previous = Some(previous);
+    }
+
+    state.installed += 1;
+    PanicHookGuard {
+        _not_send: PhantomData,
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "ds-collect", feature = "downcast-rs"))]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::CollectDropStrategy;
+    use crate::test_utils::GLOBAL_LOCK;
+    use std::string::ToString;
+
+    fn panics_collected() -> usize {
+        downcast_ref::<CollectDropStrategy>()
+            .expect("the collect drop strategy isn't installed")
+            .errors()
+            .iter()
+            .filter(|error| {
+                error
+                    .to_string()
+                    .contains("the connection pool was poisoned")
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_install_panic_hook() {
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = take();
+        install(CollectDropStrategy::new());
+
+        {
+            let _guard = install_panic_hook();
+            let _again = install_panic_hook();
+            let _ = panic::catch_unwind(|| panic!("the connection pool was poisoned"));
+        }
+
+        assert_eq!(
+            panics_collected(),
+            1,
+            "the panic wasn't forwarded exactly once"
+        );
+
+        let _ = panic::catch_unwind(|| panic!("the connection pool was poisoned"));
+        assert_eq!(
+            panics_collected(),
+            1,
+            "the panic was forwarded after the guards were dropped"
+        );
+
+        match previous {
+            Some(previous) => install_dyn(previous),
+            None => uninstall(),
+        }
+    }
+
+    #[cfg(feature = "ds-panic")]
+    #[test]
+    fn test_default_strategy() {
+        let strategy = <Fallback as DefaultGlobalDefinition>::default();
+        let expected = if cfg!(any(debug_assertions, feature = "release-panic-fallback")) {
+            "PanicDropStrategy"
+        } else {
+            "ReportDropStrategy"
+        };
+        assert!(
+            strategy.type_name().ends_with(expected),
+            "the default strategy is {}, not {expected}",
+            strategy.type_name()
+        );
+    }
+}
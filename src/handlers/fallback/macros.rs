@@ -1,27 +1,101 @@
 macro_rules! impl_try_drop_strategy_for {
     ($handler:ident where Scope: $scope:ident) => {
         impl TryDropStrategy for $handler<PanicOnUninit> {
+            #[inline]
             fn handle_error(&self, error: crate::Error) {
-                Abstracter::<$scope>::read(|strategy| strategy.handle_error(error))
+                let _ = finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::Panic,
+                    Abstracter::<$scope>::try_read(|strategy| Ok(strategy.handle_error(error))),
+                );
+            }
+
+            #[inline]
+            fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = Error>) {
+                let outcome =
+                    Abstracter::<$scope>::try_read(|strategy| strategy.handle_errors_batch(errors));
+                finish_batch(Policy::Panic, outcome, errors, &mut |_| (), |error| {
+                    self.handle_error(error);
+                    Ok(())
+                })
             }
         }
 
-        #[cfg(feature = "ds-write")]
+        #[cfg(feature = "ds-panic")]
         impl TryDropStrategy for $handler<UseDefaultOnUninit> {
+            #[inline]
+            fn handle_error(&self, error: Error) {
+                // the default strategy can't be installed if it would deadlock, so panic instead
+                let _ = finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::Panic,
+                    Abstracter::<$scope>::try_read_or_default(|strategy| {
+                        Ok(strategy.handle_error(error))
+                    }),
+                );
+            }
+
+            #[inline]
+            fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = Error>) {
+                let outcome = Abstracter::<$scope>::try_read_or_default(|strategy| {
+                    strategy.handle_errors_batch(errors)
+                });
+                finish_batch(Policy::Panic, outcome, errors, &mut |_| (), |error| {
+                    self.handle_error(error);
+                    Ok(())
+                })
+            }
+        }
+
+        impl TryDropStrategy for $handler<DoNothingOnUninit> {
+            #[inline]
             fn handle_error(&self, error: Error) {
-                Abstracter::<$scope>::read_or_default(|strategy| strategy.handle_error(error))
+                let _ = finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::DoNothing,
+                    Abstracter::<$scope>::try_read(|strategy| Ok(strategy.handle_error(error))),
+                );
+            }
+
+            #[inline]
+            fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = Error>) {
+                let outcome =
+                    Abstracter::<$scope>::try_read(|strategy| strategy.handle_errors_batch(errors));
+                finish_batch(Policy::DoNothing, outcome, errors, &mut |_| (), |error| {
+                    self.handle_error(error);
+                    Ok(())
+                })
             }
         }
 
         impl TryDropStrategy for $handler<FlagOnUninit> {
+            #[inline]
             fn handle_error(&self, error: Error) {
-                if let Err(UninitializedError(())) =
-                    Abstracter::<$scope>::try_read(|strategy| strategy.handle_error(error))
-                {
-                    self.set_last_drop_failed(true)
-                } else {
-                    self.set_last_drop_failed(false)
-                }
+                let _ = finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::Flag(&self.extra_data),
+                    Abstracter::<$scope>::try_read(|strategy| Ok(strategy.handle_error(error))),
+                );
+            }
+
+            #[inline]
+            fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = Error>) {
+                let outcome =
+                    Abstracter::<$scope>::try_read(|strategy| strategy.handle_errors_batch(errors));
+                finish_batch(
+                    Policy::Flag(&self.extra_data),
+                    outcome,
+                    errors,
+                    &mut |_| (),
+                    |error| {
+                        self.handle_error(error);
+                        Ok(())
+                    },
+                )
             }
         }
     };
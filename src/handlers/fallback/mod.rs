@@ -12,10 +12,6 @@ pub mod thread_local;
 #[cfg(all(feature = "global", feature = "thread-local"))]
 pub mod shim;
 
-mod private {
-    pub trait Sealed {}
-}
-
 use crate::handlers::common::handler::CommonHandler;
 use crate::handlers::common::proxy::TheGreatAbstracter;
 use crate::handlers::common::{Fallback, Scope};
@@ -31,6 +27,42 @@ pub type DefaultOnUninit = crate::handlers::on_uninit::UseDefaultOnUninit;
 
 type Abstracter<S> = TheGreatAbstracter<Fallback, S>;
 
+// The strategy the fallback handlers use when they're not initialized. Panicking is loud, which is
+// what's wanted during development, but in release builds it turns a failed drop into a panic in
+// drop, so there the errors are written to standard error instead, unless the
+// `release-panic-fallback` feature is enabled.
+#[cfg(all(
+    feature = "ds-panic",
+    any(debug_assertions, feature = "release-panic-fallback")
+))]
+const DEFAULT_STRATEGY: crate::drop_strategies::PanicDropStrategy =
+    crate::drop_strategies::PanicDropStrategy::DEFAULT;
+
+#[cfg(all(
+    feature = "ds-panic",
+    not(any(debug_assertions, feature = "release-panic-fallback"))
+))]
+const DEFAULT_STRATEGY: ReportDropStrategy = ReportDropStrategy;
+
+/// A try drop strategy which reports every error to standard error. Unlike the write drop
+/// strategy, this is always available, and never fails, since failing to write is ignored.
+#[cfg(all(
+    feature = "ds-panic",
+    not(any(debug_assertions, feature = "release-panic-fallback"))
+))]
+#[derive(Debug, Clone, Copy)]
+struct ReportDropStrategy;
+
+#[cfg(all(
+    feature = "ds-panic",
+    not(any(debug_assertions, feature = "release-panic-fallback"))
+))]
+impl crate::TryDropStrategy for ReportDropStrategy {
+    fn handle_error(&self, error: crate::Error) {
+        crate::last_resort::report(format_args!("{error:#}"))
+    }
+}
+
 impl<S: Scope> CommonHandler<DefaultOnUninit, S, Fallback> {
     pub const DEFAULT: Self = Self {
         extra_data: (),
@@ -1,14 +1,16 @@
 //! Manage the shim fallback handler.
 
-#[cfg(feature = "ds-panic")]
+#[cfg(feature = "ds-write")]
 mod imp {
     use super::ShimFallbackHandler;
-    use crate::drop_strategies::PanicDropStrategy;
+    use crate::adapters::AssertInfallibleTryDropStrategyAdapter;
+    use crate::drop_strategies::WriteDropStrategy;
     use crate::handlers::common::handler::CommonHandler;
     use crate::handlers::common::shim::UseDefaultOnUninitShim;
     use crate::handlers::common::Fallback;
     use crate::TryDropStrategy;
     use once_cell::sync::Lazy;
+    use std::io;
 
     /// The default thing to do when both the primary and fallback handlers are uninitialized,
     /// that is to use the inner cache to handle the error instead.
@@ -16,19 +18,26 @@ mod imp {
 
     impl ShimFallbackHandler<UseDefaultOnUninitShim<Fallback>> {
         /// See [`Self::use_default_on_uninit`].
+        #[allow(clippy::declare_interior_mutable_const)]
         pub const USE_DEFAULT_ON_UNINIT: Self = Self {
             global: CommonHandler::FLAG_ON_UNINIT,
             thread_local: CommonHandler::FLAG_ON_UNINIT,
-            extra_data: Lazy::new(|| PanicDropStrategy::DEFAULT),
+            extra_data: Lazy::new(|| {
+                let mut strategy = WriteDropStrategy::stderr();
+                strategy
+                    .prelude("error: ")
+                    .color(crate::drop_strategies::write::ColorChoice::Auto);
+                AssertInfallibleTryDropStrategyAdapter::new(strategy)
+            }),
         };
 
         /// If both the primary and fallback handlers are uninitialized, use the inner cache to
-        /// handle the error instead.
+        /// handle the error instead. The inner cache writes the error to standard error.
         pub const fn use_default_on_uninit() -> Self {
             Self::USE_DEFAULT_ON_UNINIT
         }
 
-        fn cache(&self) -> &PanicDropStrategy {
+        fn cache(&self) -> &AssertInfallibleTryDropStrategyAdapter<WriteDropStrategy<io::Stderr>> {
             &self.extra_data
         }
     }
@@ -40,11 +49,19 @@ mod imp {
 
     impl TryDropStrategy for ShimFallbackHandler<UseDefaultOnUninitShim<Fallback>> {
         fn handle_error(&self, error: crate::Error) {
-            self.on_all_uninit(error, |error| self.cache().handle_error(error.into()))
+            self.on_all_uninit(error, |error| {
+                let error = error.into();
+                let _guard = crate::tier::enter(&error, crate::tier::HandlingTier::LastResort);
+                self.cache().handle_error(error)
+            })
+        }
+
+        fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = crate::Error>) {
+            self.on_all_uninit_batch(errors, |error| self.handle_error(error))
         }
     }
 }
-#[cfg(not(feature = "ds-panic"))]
+#[cfg(not(feature = "ds-write"))]
 mod imp {
     use super::ShimFallbackHandler;
     use crate::handlers::on_uninit::PanicOnUninit;
@@ -59,16 +76,22 @@ mod imp {
     }
 }
 
+use super::Abstracter;
 use crate::adapters::ArcError;
-use crate::handlers::common::handler::CommonShimHandler;
+use crate::handlers::common::handler::{finish_batch, CommonShimHandler, Policy};
 use crate::handlers::common::shim::OnUninitShim;
-use crate::handlers::common::Fallback;
+use crate::handlers::common::{Fallback, Global, ThreadLocal};
 use crate::handlers::on_uninit::{DoNothingOnUninit, FlagOnUninit, PanicOnUninit};
 use crate::TryDropStrategy;
 pub use imp::DefaultOnUninit;
 
 /// A fallback handler which uses both the global and thread-local scopes, with the thread-local
 /// scope taking precedence.
+///
+/// Whether or not both scopes were uninitialized on the last handled error can be checked with
+/// `last_drop_failed`, regardless of what to do on uninit. Note that there is no
+/// [`ErrorOnUninit`](crate::handlers::on_uninit::ErrorOnUninit) variant, as the fallback handler
+/// cannot fail.
 pub type ShimFallbackHandler<OU = DefaultOnUninit> = CommonShimHandler<OU, Fallback>;
 
 /// The default shim fallback handler.
@@ -88,27 +111,219 @@ impl<OU: OnUninitShim> ShimFallbackHandler<OU> {
             }
         }
     }
+
+    /// Hand the batch to the thread-local fallback handler, or to the global one if it's
+    /// uninitialized, reading either of them only once. If both are uninitialized, every error is
+    /// handed to `each` instead.
+    fn on_all_uninit_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = crate::Error>,
+        mut each: impl FnMut(anyhow::Error),
+    ) {
+        let outcome =
+            Abstracter::<ThreadLocal>::try_read(|strategy| strategy.handle_errors_batch(errors));
+        self.thread_local.set_last_drop_failed(outcome.is_err());
+
+        let outcome = outcome.or_else(|_| {
+            let outcome =
+                Abstracter::<Global>::try_read(|strategy| strategy.handle_errors_batch(errors));
+            self.global.set_last_drop_failed(outcome.is_err());
+            outcome
+        });
+
+        finish_batch(Policy::DoNothing, outcome, errors, &mut |_| (), |error| {
+            each(error);
+            Ok(())
+        })
+    }
 }
 
 impl TryDropStrategy for ShimFallbackHandler<PanicOnUninit> {
     fn handle_error(&self, error: crate::Error) {
         self.on_all_uninit(
             error,
-            |error| panic!("neither the fallback thread local nor the fallback global handlers are initialized (but here's the drop error anyway: {error})")
+            |error| crate::last_resort::panic_unless_unwinding(format_args!("neither the fallback thread local nor the fallback global handlers are initialized (but here's the drop error anyway: {error})"))
         )
     }
+
+    fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = crate::Error>) {
+        self.on_all_uninit_batch(errors, |error| self.handle_error(error))
+    }
 }
 
 impl TryDropStrategy for ShimFallbackHandler<DoNothingOnUninit> {
     fn handle_error(&self, error: crate::Error) {
         self.on_all_uninit(error, |_| ())
     }
+
+    fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = crate::Error>) {
+        self.on_all_uninit_batch(errors, |error| self.handle_error(error))
+    }
 }
 
 impl TryDropStrategy for ShimFallbackHandler<FlagOnUninit> {
     fn handle_error(&self, error: crate::Error) {
-        let mut last_drop_failed = false;
-        self.on_all_uninit(error, |_| last_drop_failed = true);
-        self.set_last_drop_failed(last_drop_failed);
+        self.on_all_uninit(error, |_| ())
+    }
+
+    fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = crate::Error>) {
+        self.on_all_uninit_batch(errors, |error| self.handle_error(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::AdHocDropStrategy;
+    use crate::handlers::fallback;
+    use crate::test_utils::GLOBAL_LOCK;
+    use anyhow::anyhow;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    struct Layers {
+        thread_local: Rc<Cell<bool>>,
+        global: Arc<AtomicBool>,
+    }
+
+    impl Layers {
+        fn install(thread_local: bool, global: bool) -> Self {
+            fallback::thread_local::uninstall();
+            fallback::global::uninstall();
+
+            let layers = Self {
+                thread_local: Rc::new(Cell::new(false)),
+                global: Arc::new(AtomicBool::new(false)),
+            };
+
+            if thread_local {
+                let tl = Rc::clone(&layers.thread_local);
+                fallback::thread_local::install(AdHocDropStrategy(move |_| tl.set(true)));
+            }
+
+            if global {
+                let g = Arc::clone(&layers.global);
+                fallback::global::install(AdHocDropStrategy(move |_| {
+                    g.store(true, Ordering::SeqCst)
+                }));
+            }
+
+            layers
+        }
+    }
+
+    fn test<OU: OnUninitShim>(handler: ShimFallbackHandler<OU>)
+    where
+        ShimFallbackHandler<OU>: TryDropStrategy,
+    {
+        for (thread_local, global) in [(false, false), (true, false), (false, true), (true, true)] {
+            let layers = Layers::install(thread_local, global);
+            handler.handle_error(anyhow!("test"));
+
+            assert_eq!(
+                layers.thread_local.get(),
+                thread_local,
+                "thread local layer should only be used if installed"
+            );
+            assert_eq!(
+                layers.global.load(Ordering::SeqCst),
+                global && !thread_local,
+                "global layer should only be used if installed and the thread local layer isn't"
+            );
+            assert_eq!(
+                handler.last_drop_failed(),
+                !thread_local && !global,
+                "last drop should only fail if both layers are uninstalled"
+            );
+        }
+
+        fallback::global::uninstall();
+    }
+
+    #[test]
+    fn test_do_nothing_on_uninit() {
+        let _lock = GLOBAL_LOCK.lock();
+        test(ShimFallbackHandler::on_uninit_do_nothing());
+    }
+
+    #[test]
+    fn test_flag_on_uninit() {
+        let _lock = GLOBAL_LOCK.lock();
+        test(ShimFallbackHandler::on_uninit_flag());
+    }
+
+    #[test]
+    fn test_reset_last_drop_failed() {
+        let _lock = GLOBAL_LOCK.lock();
+        let handler = ShimFallbackHandler::on_uninit_flag();
+        let _layers = Layers::install(false, false);
+        handler.handle_error(anyhow!("test"));
+        assert!(handler.last_drop_failed());
+
+        handler.reset_last_drop_failed();
+        assert!(
+            !handler.last_drop_failed(),
+            "the flag is still set after resetting it"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "ds-write")]
+    fn test_use_default_on_uninit() {
+        let _lock = GLOBAL_LOCK.lock();
+        test(ShimFallbackHandler::use_default_on_uninit());
+    }
+
+    #[test]
+    fn test_panic_on_uninit_when_installed() {
+        let _lock = GLOBAL_LOCK.lock();
+        let handler = ShimFallbackHandler::on_uninit_panic();
+
+        for (thread_local, global) in [(true, false), (false, true), (true, true)] {
+            let _layers = Layers::install(thread_local, global);
+            handler.handle_error(anyhow!("test"));
+            assert!(
+                !handler.last_drop_failed(),
+                "last drop failed but a layer is installed"
+            );
+        }
+
+        fallback::global::uninstall();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "neither the fallback thread local nor the fallback global handlers are initialized"
+    )]
+    fn test_panic_on_uninit() {
+        let _lock = GLOBAL_LOCK.lock();
+        let _layers = Layers::install(false, false);
+        ShimFallbackHandler::on_uninit_panic().handle_error(anyhow!("test"))
+    }
+
+    #[test]
+    #[cfg(feature = "ds-write")]
+    fn test_last_drop_failed_from_static() {
+        let _lock = GLOBAL_LOCK.lock();
+        let _layers = Layers::install(false, false);
+        DEFAULT_SHIM_FALLBACK_HANDLER.handle_error(anyhow!("test"));
+        assert!(DEFAULT_SHIM_FALLBACK_HANDLER.last_drop_failed());
+
+        let _layers = Layers::install(true, false);
+        DEFAULT_SHIM_FALLBACK_HANDLER.handle_error(anyhow!("test"));
+        assert!(!DEFAULT_SHIM_FALLBACK_HANDLER.last_drop_failed());
+    }
+
+    #[test]
+    fn test_handler_kinds() {
+        use crate::handlers::{HandlerKind, ScopeKind};
+
+        assert_eq!(
+            ShimFallbackHandler::<PanicOnUninit>::KIND,
+            HandlerKind::Fallback
+        );
+        assert_eq!(ShimFallbackHandler::<PanicOnUninit>::SCOPE, ScopeKind::Shim);
     }
 }
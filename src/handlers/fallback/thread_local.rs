@@ -1,13 +1,14 @@
 //! Manage the thread local fallback handler.
 use super::{Abstracter, DefaultOnUninit};
-use crate::handlers::common::handler::CommonHandler;
-use crate::handlers::common::thread_local::scope_guard::ScopeGuard as GenericScopeGuard;
+use crate::handlers::common::handler::{finish, finish_batch, CommonHandler, Policy};
+use crate::handlers::common::thread_local::scope_guard::{Frame, ScopeGuard as GenericScopeGuard};
 use crate::handlers::common::thread_local::{
     ThreadLocal as GenericThreadLocal, ThreadLocalDefinition,
 };
 use crate::handlers::common::{Fallback, ThreadLocal as ThreadLocalScope};
-use crate::handlers::on_uninit::{FlagOnUninit, PanicOnUninit};
+use crate::handlers::on_uninit::{DoNothingOnUninit, FlagOnUninit, PanicOnUninit};
 use crate::handlers::uninit_error::UninitializedError;
+use crate::InfallibleStrategy;
 use crate::ThreadLocalTryDropStrategy;
 use crate::TryDropStrategy;
 use anyhow::Error;
@@ -15,6 +16,7 @@ use std::boxed::Box;
 use std::cell::{Cell, RefCell};
 use std::thread::LocalKey;
 use std::thread_local;
+use std::vec::Vec;
 
 #[cfg(feature = "ds-panic")]
 use crate::handlers::common::thread_local::DefaultThreadLocalDefinition;
@@ -30,11 +32,16 @@ pub type ThreadLocalFallbackHandler<OU = DefaultOnUninit> =
 pub static DEFAULT_THREAD_LOCAL_FALLBACK_HANDLER: ThreadLocalFallbackHandler =
     ThreadLocalFallbackHandler::DEFAULT;
 
-impl_try_drop_strategy_for!(ThreadLocalFallbackHandler where Scope: ThreadLocalScope);
+impl_try_drop_strategy_for!(ThreadLocalFallbackHandler
+where
+    Scope: ThreadLocalScope
+);
 
 thread_local! {
     static FALLBACK_HANDLER: RefCell<Option<Box<dyn ThreadLocalTryDropStrategy>>> = RefCell::new(None);
-    static LOCKED: Cell<bool> = Cell::new(false);
+    static LOCKED: Cell<bool> = const { Cell::new(false) };
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+    static STACK: RefCell<Vec<Frame<Box<dyn ThreadLocalTryDropStrategy>>>> = const { RefCell::new(Vec::new()) };
 }
 
 impl ThreadLocalDefinition for Fallback {
@@ -50,12 +57,20 @@ impl ThreadLocalDefinition for Fallback {
     fn locked() -> &'static LocalKey<Cell<bool>> {
         &LOCKED
     }
+
+    fn stack() -> &'static LocalKey<RefCell<Vec<Frame<Self::ThreadLocal>>>> {
+        &STACK
+    }
+
+    fn generation() -> &'static LocalKey<Cell<u64>> {
+        &GENERATION
+    }
 }
 
 #[cfg(feature = "ds-panic")]
 impl DefaultThreadLocalDefinition for Fallback {
     fn default() -> Self::ThreadLocal {
-        Box::new(crate::drop_strategies::PanicDropStrategy::DEFAULT)
+        Box::new(super::DEFAULT_STRATEGY)
     }
 }
 
@@ -145,17 +160,42 @@ thread_local_methods! {
     /// Sets the fallback thread local handler to the specified one for the duration of the scope.
     /// Must be a dynamic trait object.
     scope_dyn;
+
+    /// Sets the fallback thread local handler to the specified one for the duration of the scope,
+    /// forbidding any other scope to be nested in it.
+    ///
+    /// # Panics
+    /// If this is nested in another scope, this function will panic.
+    scope_exclusive;
+
+    /// Sets the fallback thread local handler to the specified one for the duration of the scope,
+    /// forbidding any other scope to be nested in it. Must be a dynamic trait object.
+    ///
+    /// # Panics
+    /// If this is nested in another scope, this function will panic.
+    scope_exclusive_dyn;
+}
+
+/// Install a new fallback thread local handler, which must be audited to never fail.
+///
+/// Unlike [`install`], this only accepts try drop strategies which implement
+/// [`InfallibleStrategy`].
+pub fn install_checked(strategy: impl InfallibleStrategy + ThreadLocalTryDropStrategy) {
+    install(strategy)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::drop_strategies::{AdHocDropStrategy, AdHocFallibleDropStrategy, IntoAdHocDropStrategy, NoOpDropStrategy};
-    use crate::handlers::{fallback, primary};
+    use crate::adapters::AssumeInfallibleAdapter;
+    use crate::drop_strategies::{IntoAdHocDropStrategy, NoOpDropStrategy};
+    use crate::handlers::on_uninit::PanicOnUninit;
+    use crate::handlers::{primary, HandlerKind, ScopeKind};
     use crate::test_utils::{ErrorsOnDrop, Fallible, FallibleDropStrategy};
     use crate::PureTryDrop;
     use anyhow::anyhow;
     use std::rc::Rc;
+    use std::string::ToString;
 
     #[test]
     #[should_panic]
@@ -180,6 +220,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reset_last_drop_failed() {
+        let handler = ThreadLocalFallbackHandler::on_uninit_flag();
+        handler.handle_error(anyhow!("test"));
+        assert!(handler.last_drop_failed());
+        handler.reset_last_drop_failed();
+        assert!(
+            !handler.last_drop_failed(),
+            "the flag is still set after resetting it"
+        );
+    }
+
     #[test]
     fn test_install() {
         let installed = Rc::new(RefCell::new(false));
@@ -202,9 +254,28 @@ mod tests {
         assert!(*installed.borrow(), "install_dyn didn't install");
     }
 
+    #[test]
+    fn test_install_checked() {
+        install_checked(NoOpDropStrategy);
+        try_read(|_| ()).expect("install_checked didn't install");
+    }
+
+    #[test]
+    fn test_install_checked_assumed_infallible() {
+        let installed = Rc::new(RefCell::new(false));
+        let i = Rc::clone(&installed);
+        let strategy = (move |_| *i.borrow_mut() = true).into_drop_strategy();
+
+        // SAFETY: the closure only sets a flag, which never fails.
+        install_checked(unsafe { AssumeInfallibleAdapter::new(strategy) });
+        primary::thread_local::install(FallibleDropStrategy);
+        drop(ErrorsOnDrop::<Fallible, _>::not_given().adapt());
+        assert!(*installed.borrow(), "install_checked didn't install");
+    }
+
     #[test]
     #[should_panic(
-        expected = "the thread local fallback handler is not initialized yet: UninitializedError(())"
+        expected = "the thread local fallback handler is not initialized yet: UninitializedError { handler: Fallback, scope: ThreadLocal }"
     )]
     fn test_read_panics_on_uninit() {
         read(|_| panic!("did not panic on uninit"))
@@ -215,6 +286,29 @@ mod tests {
         try_read(|_| panic!("did not error on uninit")).expect_err("did not error on uninit");
     }
 
+    #[test]
+    fn test_uninit_error_kinds() {
+        let error = try_read(|_| ()).expect_err("did not error on uninit");
+        assert_eq!(error.handler(), HandlerKind::Fallback);
+        assert_eq!(error.scope(), ScopeKind::ThreadLocal);
+        assert_eq!(
+            error.to_string(),
+            "the thread local fallback drop strategy is not initialized yet"
+        );
+    }
+
+    #[test]
+    fn test_handler_kinds() {
+        assert_eq!(
+            ThreadLocalFallbackHandler::<PanicOnUninit>::KIND,
+            HandlerKind::Fallback
+        );
+        assert_eq!(
+            ThreadLocalFallbackHandler::<PanicOnUninit>::SCOPE,
+            ScopeKind::ThreadLocal
+        );
+    }
+
     #[test]
     fn test_read_or_default() {
         let mut executed = false;
@@ -224,7 +318,7 @@ mod tests {
 
     #[test]
     #[should_panic(
-        expected = "the thread local fallback handler is not initialized yet: UninitializedError(())"
+        expected = "the thread local fallback handler is not initialized yet: UninitializedError { handler: Fallback, scope: ThreadLocal }"
     )]
     fn test_write_panics_on_uninit() {
         write(|_| panic!("did not panic on uninit"))
@@ -242,4 +336,45 @@ mod tests {
         assert!(executed, "read_or_default didn't execute");
     }
     // todo: test uninstall, take, replace, replace_dyn, scope, scope_dyn
+
+    #[cfg(feature = "ds-panic")]
+    #[test]
+    fn test_default_strategy() {
+        let strategy = <Fallback as DefaultThreadLocalDefinition>::default();
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            strategy.handle_error(anyhow!("the connection pool was closed"))
+        }))
+        .is_err();
+        assert_eq!(
+            panicked,
+            cfg!(any(debug_assertions, feature = "release-panic-fallback")),
+            "the default strategy panicked in the wrong profile"
+        );
+    }
+
+    #[test]
+    fn test_install_fn_pointers() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static PRIMARY: AtomicUsize = AtomicUsize::new(0);
+        static FALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+        fn primary(error: crate::Error) -> Result<(), crate::Error> {
+            PRIMARY.fetch_add(1, Ordering::Relaxed);
+            Err(error)
+        }
+
+        fn fallback(error: crate::Error) {
+            assert_eq!(error.to_string(), "this will always fail");
+            FALLBACK.fetch_add(1, Ordering::Relaxed);
+        }
+
+        crate::with_thread_local_handlers(
+            primary as fn(_) -> Result<(), crate::Error>,
+            fallback as fn(_),
+            || drop(ErrorsOnDrop::<Fallible, _>::not_given().adapt()),
+        );
+        assert_eq!(PRIMARY.load(Ordering::Relaxed), 1);
+        assert_eq!(FALLBACK.load(Ordering::Relaxed), 1);
+    }
 }
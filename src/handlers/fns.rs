@@ -1,12 +1,24 @@
-use super::{fallback, primary};
+use super::{fallback, primary, DEFAULT_FALLBACK_HANDLER, DEFAULT_PRIMARY_HANDLER};
+use crate::tier::HandlingTier;
+use crate::{FallibleTryDropStrategy, TryDropStrategy};
 use std::boxed::Box;
+use std::vec::Vec;
 
 #[cfg(feature = "global")]
 use crate::{GlobalDynFallibleTryDropStrategy, GlobalTryDropStrategy};
 
+#[cfg(feature = "global")]
+use super::common::{global::GlobalDefinition, Fallback, Primary};
+
 #[cfg(feature = "thread-local")]
 use crate::{ThreadLocalFallibleTryDropStrategy, ThreadLocalTryDropStrategy};
 
+#[cfg(all(feature = "thread-local", feature = "ds-collect"))]
+use crate::{adapters::ArcError, drop_strategies::CollectDropStrategy};
+
+#[cfg(all(feature = "thread-local", feature = "ds-collect"))]
+use std::sync::Arc;
+
 /// This installs the primary and fallback global handlers.
 #[cfg(feature = "global")]
 pub fn install_global_handlers(
@@ -26,6 +38,42 @@ pub fn install_global_handlers_dyn(
     fallback::global::install_dyn(fallback);
 }
 
+/// This installs the primary and fallback global handlers, runs `f`, then restores the previous
+/// ones, even if `f` panics. Unlike [`primary::global::scope`] and [`fallback::global::scope`],
+/// there's no guard which could be dropped too early or kept alive for too long.
+#[cfg(feature = "global")]
+pub fn with_global_handlers<R>(
+    primary: impl GlobalDynFallibleTryDropStrategy,
+    fallback: impl GlobalTryDropStrategy,
+    f: impl FnOnce() -> R,
+) -> R {
+    let _primary = primary::global::scope(primary);
+    let _fallback = fallback::global::scope(fallback);
+    f()
+}
+
+/// This installs the primary global handler, runs `f`, then restores the previous one, even if `f`
+/// panics.
+#[cfg(feature = "global")]
+pub fn with_global_primary_handler<R>(
+    strategy: impl GlobalDynFallibleTryDropStrategy,
+    f: impl FnOnce() -> R,
+) -> R {
+    let _guard = primary::global::scope(strategy);
+    f()
+}
+
+/// This installs the fallback global handler, runs `f`, then restores the previous one, even if
+/// `f` panics.
+#[cfg(feature = "global")]
+pub fn with_global_fallback_handler<R>(
+    strategy: impl GlobalTryDropStrategy,
+    f: impl FnOnce() -> R,
+) -> R {
+    let _guard = fallback::global::scope(strategy);
+    f()
+}
+
 /// This uninstalls the primary and fallback global handlers.
 #[cfg(feature = "global")]
 pub fn uninstall_globally() {
@@ -33,6 +81,59 @@ pub fn uninstall_globally() {
     fallback::global::uninstall();
 }
 
+/// What [`ensure_installed_once`] did.
+#[cfg(feature = "global")]
+#[cfg_attr(
+    feature = "derives",
+    derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+pub enum EnsureInstalled {
+    /// At least one of the global handlers wasn't installed, so it was installed now.
+    InstalledNow,
+
+    /// Both global handlers were already installed, and at least one of them by the application.
+    AlreadyConfigured,
+
+    /// Both global handlers were already installed by an earlier call to
+    /// [`ensure_installed_once`], e.g. by another library.
+    PreviouslyLibraryDefault,
+}
+
+/// This installs the primary and fallback global handlers which the factories create, but only
+/// the ones which aren't installed yet. This is meant for libraries, which need some handlers to
+/// be installed before they create adapted values, but shouldn't override the handlers of the
+/// application.
+///
+/// The handlers installed by this are library defaults; the application can still replace them
+/// with [`install_global_handlers`] at any time. If many threads call this at once, each handler
+/// is only installed once, and each factory is only called if its handler is installed.
+///
+/// Each factory is called while its handler is locked for writing, so it must not drop values
+/// whose drop errors are handled by the global handlers. See
+/// [`GlobalSlot::install_provisionally_if_empty`](crate::handlers::slot::GlobalSlot::install_provisionally_if_empty).
+#[cfg(feature = "global")]
+pub fn ensure_installed_once<P, F>(
+    primary_factory: impl FnOnce() -> P,
+    fallback_factory: impl FnOnce() -> F,
+) -> EnsureInstalled
+where
+    P: GlobalDynFallibleTryDropStrategy,
+    F: GlobalTryDropStrategy,
+{
+    let primary_slot = <Primary as GlobalDefinition>::global();
+    let fallback_slot = <Fallback as GlobalDefinition>::global();
+    let primary = primary_slot.install_provisionally_if_empty(|| Box::new(primary_factory()));
+    let fallback = fallback_slot.install_provisionally_if_empty(|| Box::new(fallback_factory()));
+
+    if primary || fallback {
+        EnsureInstalled::InstalledNow
+    } else if primary_slot.is_provisional() && fallback_slot.is_provisional() {
+        EnsureInstalled::PreviouslyLibraryDefault
+    } else {
+        EnsureInstalled::AlreadyConfigured
+    }
+}
+
 /// This installs the primary and fallback thread local handlers.
 #[cfg(feature = "thread-local")]
 pub fn install_thread_local_handlers(
@@ -81,9 +182,629 @@ pub fn install_thread_local_handlers_for_this_scope_dyn(
     )
 }
 
+/// This installs the primary and fallback thread local handlers, runs `f`, then restores the
+/// previous ones, even if `f` panics. Unlike [`install_thread_local_handlers_for_this_scope`],
+/// there's no guard which could be dropped too early or kept alive for too long.
+#[cfg(feature = "thread-local")]
+pub fn with_thread_local_handlers<R>(
+    primary: impl ThreadLocalFallibleTryDropStrategy,
+    fallback: impl ThreadLocalTryDropStrategy,
+    f: impl FnOnce() -> R,
+) -> R {
+    let _guards = install_thread_local_handlers_for_this_scope(primary, fallback);
+    f()
+}
+
+/// This installs the primary thread local handler, runs `f`, then restores the previous one, even
+/// if `f` panics.
+#[cfg(feature = "thread-local")]
+pub fn with_thread_local_primary_handler<R>(
+    strategy: impl ThreadLocalFallibleTryDropStrategy,
+    f: impl FnOnce() -> R,
+) -> R {
+    let _guard = primary::thread_local::scope(strategy);
+    f()
+}
+
+/// This installs the fallback thread local handler, runs `f`, then restores the previous one,
+/// even if `f` panics.
+#[cfg(feature = "thread-local")]
+pub fn with_thread_local_fallback_handler<R>(
+    strategy: impl ThreadLocalTryDropStrategy,
+    f: impl FnOnce() -> R,
+) -> R {
+    let _guard = fallback::thread_local::scope(strategy);
+    f()
+}
+
+/// Shares a collector between the thread local primary handler and [`with_collecting_handlers`].
+#[cfg(all(feature = "thread-local", feature = "ds-collect"))]
+struct SharedCollector(Arc<CollectDropStrategy>);
+
+#[cfg(all(feature = "thread-local", feature = "ds-collect"))]
+impl TryDropStrategy for SharedCollector {
+    fn handle_error(&self, error: crate::Error) {
+        self.0.handle_error(error)
+    }
+}
+
+/// Get the error out of `error` if nothing else shares it. The shim primary handler shares each
+/// error between the thread local and global primary handlers as an [`ArcError`], so that's
+/// unwrapped as well.
+#[cfg(all(feature = "thread-local", feature = "ds-collect"))]
+fn unwrap_shared(error: ArcError) -> crate::Error {
+    match Arc::try_unwrap(error.0) {
+        Ok(error) => match error.downcast::<ArcError>() {
+            Ok(shared) => unwrap_shared(shared),
+            Err(error) => error,
+        },
+        Err(error) => anyhow::Error::new(ArcError(error)),
+    }
+}
+
+/// This installs a [`CollectDropStrategy`] as the primary thread local handler, runs `f`, then
+/// restores the previous one and returns what `f` returned along with the errors which were
+/// collected in the meantime, in the order they were handled.
+///
+/// Only errors of values dropped on this thread are collected. If `f` panics, the previous handler
+/// is still restored, but the collected errors are lost.
+#[cfg(all(feature = "thread-local", feature = "ds-collect"))]
+pub fn with_collecting_handlers<R>(f: impl FnOnce() -> R) -> (R, Vec<crate::Error>) {
+    let collector = Arc::new(CollectDropStrategy::new());
+    let result = with_thread_local_primary_handler(SharedCollector(Arc::clone(&collector)), f);
+    let errors = collector
+        .take_all()
+        .errors
+        .into_iter()
+        .map(|collected| unwrap_shared(collected.error))
+        .collect();
+    (result, errors)
+}
+
 /// This uninstalls the primary and fallback thread local handlers.
 #[cfg(feature = "thread-local")]
 pub fn uninstall_for_thread() {
     primary::thread_local::uninstall();
     fallback::thread_local::uninstall();
 }
+
+/// The handlers which were removed by [`uninstall_all_ordered`]. Pass this to [`reinstall`] to
+/// restore them.
+#[cfg_attr(feature = "derives", derive(Default))]
+pub struct RemovedHandlers {
+    /// The thread local primary handler, if it was installed.
+    #[cfg(feature = "thread-local")]
+    pub thread_local_primary: Option<Box<dyn ThreadLocalFallibleTryDropStrategy>>,
+
+    /// The thread local fallback handler, if it was installed.
+    #[cfg(feature = "thread-local")]
+    pub thread_local_fallback: Option<Box<dyn ThreadLocalTryDropStrategy>>,
+
+    /// The global primary handler, if it was installed.
+    #[cfg(feature = "global")]
+    pub global_primary: Option<Box<dyn GlobalDynFallibleTryDropStrategy>>,
+
+    /// The global fallback handler, if it was installed.
+    #[cfg(feature = "global")]
+    pub global_fallback: Option<Box<dyn GlobalTryDropStrategy>>,
+}
+
+/// This uninstalls every handler, one by one, in the following order:
+///
+/// 1. the thread local primary handler
+/// 2. the thread local fallback handler
+/// 3. the global primary handler
+/// 4. the global fallback handler
+///
+/// Thread local handlers shadow the global ones, so they're uninstalled first, and a primary
+/// handler may still redirect errors to its fallback handler, so it's uninstalled before it. Each
+/// handler has its `on_uninstall` hook called right after it's been uninstalled, while no lock is
+/// held, so a hook which handles errors of its own reaches the handlers which are still
+/// installed instead of deadlocking.
+///
+/// The global primary handler is drained with [`primary::global::begin_drain_default`] rather
+/// than uninstalled, if the `ds-write` feature is enabled. Errors of values which are dropped on
+/// other threads in the meantime are then written to standard error instead of hitting their on
+/// uninit policy. The replacement is only uninstalled once every handler has been finalized.
+///
+/// The removed handlers are returned, so that they can be finalized explicitly or restored with
+/// [`reinstall`].
+pub fn uninstall_all_ordered() -> RemovedHandlers {
+    #[cfg(feature = "thread-local")]
+    let thread_local_primary = primary::thread_local::take();
+
+    #[cfg(feature = "thread-local")]
+    if let Some(strategy) = &thread_local_primary {
+        strategy.dyn_on_uninstall()
+    }
+
+    #[cfg(feature = "thread-local")]
+    let thread_local_fallback = fallback::thread_local::take();
+
+    #[cfg(feature = "thread-local")]
+    if let Some(strategy) = &thread_local_fallback {
+        strategy.on_uninstall()
+    }
+
+    #[cfg(all(feature = "global", feature = "ds-write"))]
+    let global_primary = primary::global::begin_drain_default();
+
+    #[cfg(all(feature = "global", not(feature = "ds-write")))]
+    let global_primary = primary::global::take();
+
+    #[cfg(feature = "global")]
+    if let Some(strategy) = &global_primary {
+        strategy.dyn_on_uninstall()
+    }
+
+    #[cfg(feature = "global")]
+    let global_fallback = fallback::global::take();
+
+    #[cfg(feature = "global")]
+    if let Some(strategy) = &global_fallback {
+        strategy.on_uninstall()
+    }
+
+    #[cfg(all(feature = "global", feature = "ds-write"))]
+    primary::global::uninstall();
+
+    RemovedHandlers {
+        #[cfg(feature = "thread-local")]
+        thread_local_primary,
+
+        #[cfg(feature = "thread-local")]
+        thread_local_fallback,
+
+        #[cfg(feature = "global")]
+        global_primary,
+
+        #[cfg(feature = "global")]
+        global_fallback,
+    }
+}
+
+/// This restores the handlers which were removed by [`uninstall_all_ordered`], in the reverse
+/// order they were uninstalled in. Handlers which weren't installed before are uninstalled.
+pub fn reinstall(previous: RemovedHandlers) {
+    #[cfg(feature = "global")]
+    match previous.global_fallback {
+        Some(strategy) => fallback::global::install_dyn(strategy),
+        None => fallback::global::uninstall(),
+    }
+
+    #[cfg(feature = "global")]
+    match previous.global_primary {
+        Some(strategy) => primary::global::install_dyn(strategy),
+        None => primary::global::uninstall(),
+    }
+
+    #[cfg(feature = "thread-local")]
+    match previous.thread_local_fallback {
+        Some(strategy) => fallback::thread_local::install_dyn(strategy),
+        None => fallback::thread_local::uninstall(),
+    }
+
+    #[cfg(feature = "thread-local")]
+    match previous.thread_local_primary {
+        Some(strategy) => primary::thread_local::install_dyn(strategy),
+        None => primary::thread_local::uninstall(),
+    }
+}
+
+/// This handles many drop errors at once with the default primary and fallback handlers, e.g. the
+/// ones returned by [`try_drop_all`](crate::batch::try_drop_all).
+///
+/// Unlike handing the errors to the handlers one by one, each handler is only read once for the
+/// whole batch, and strategies which override
+/// [`FallibleTryDropStrategy::try_handle_errors_batch`] can handle all of them at once. Errors
+/// which the primary handler fails to handle are handed to the fallback handler, which is only
+/// read if there are any.
+pub fn handle_errors(errors: impl IntoIterator<Item = anyhow::Error>) {
+    let _scope = crate::sampling::enter_batch();
+    let mut failed = Vec::new();
+    DEFAULT_PRIMARY_HANDLER
+        .try_handle_errors_batch(&mut errors.into_iter(), &mut |error| failed.push(error));
+
+    if failed.is_empty() {
+        return;
+    }
+
+    let _guards: Vec<_> = failed
+        .iter()
+        .map(|error| crate::tier::enter(error, HandlingTier::Fallback))
+        .collect();
+    DEFAULT_FALLBACK_HANDLER.handle_errors_batch(&mut failed.into_iter())
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "global", feature = "thread-local"))]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::PanicDropStrategy;
+    use crate::test_utils::GLOBAL_LOCK;
+    use crate::TryDropStrategy;
+    use std::string::{String, ToString};
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    /// Buffers errors until it's uninstalled, then flushes them into the sink.
+    struct BufferingDropStrategy {
+        name: &'static str,
+        buffer: Mutex<Vec<String>>,
+        sink: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl BufferingDropStrategy {
+        fn new(name: &'static str, sink: &Arc<Mutex<Vec<String>>>) -> Self {
+            Self {
+                name,
+                buffer: Mutex::new(Vec::new()),
+                sink: Arc::clone(sink),
+            }
+        }
+    }
+
+    impl TryDropStrategy for BufferingDropStrategy {
+        fn handle_error(&self, error: crate::Error) {
+            self.buffer
+                .lock()
+                .unwrap()
+                .push(std::format!("{}: {}", self.name, error))
+        }
+
+        fn on_uninstall(&self) {
+            let buffered = core::mem::take(&mut *self.buffer.lock().unwrap());
+            self.sink.lock().unwrap().extend(buffered)
+        }
+    }
+
+    #[test]
+    fn test_uninstall_all_ordered() {
+        let _lock = GLOBAL_LOCK.lock();
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        install_thread_local_handlers(
+            BufferingDropStrategy::new("thread local primary", &sink),
+            BufferingDropStrategy::new("thread local fallback", &sink),
+        );
+        install_global_handlers(
+            BufferingDropStrategy::new("global primary", &sink),
+            BufferingDropStrategy::new("global fallback", &sink),
+        );
+
+        primary::thread_local::read(|strategy| {
+            strategy
+                .dyn_try_handle_error(anyhow::anyhow!("error"))
+                .unwrap()
+        });
+        fallback::thread_local::read(|strategy| strategy.handle_error(anyhow::anyhow!("error")));
+        primary::global::read()
+            .dyn_try_handle_error(anyhow::anyhow!("error"))
+            .unwrap();
+        fallback::global::read().handle_error(anyhow::anyhow!("error"));
+
+        let removed = uninstall_all_ordered();
+        assert!(primary::thread_local::try_read(|_| ()).is_err());
+        assert!(fallback::thread_local::try_read(|_| ()).is_err());
+        assert!(primary::global::try_read().is_err());
+        assert!(fallback::global::try_read().is_err());
+        assert_eq!(
+            *sink.lock().unwrap(),
+            [
+                "thread local primary: error",
+                "thread local fallback: error",
+                "global primary: error",
+                "global fallback: error",
+            ]
+            .map(ToString::to_string),
+            "a buffered error was lost",
+        );
+
+        reinstall(removed);
+        assert!(primary::thread_local::try_read(|_| ()).is_ok());
+        assert!(fallback::thread_local::try_read(|_| ()).is_ok());
+        assert!(primary::global::try_read().is_ok());
+        assert!(fallback::global::try_read().is_ok());
+
+        drop(uninstall_all_ordered());
+    }
+
+    #[test]
+    fn test_reinstall_uninstalls_missing_handlers() {
+        let _lock = GLOBAL_LOCK.lock();
+        let removed = uninstall_all_ordered();
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        install_thread_local_handlers(
+            BufferingDropStrategy::new("thread local primary", &sink),
+            BufferingDropStrategy::new("thread local fallback", &sink),
+        );
+
+        reinstall(removed);
+        assert!(primary::thread_local::try_read(|_| ()).is_err());
+        assert!(fallback::thread_local::try_read(|_| ()).is_err());
+    }
+
+    #[test]
+    fn test_installed_type_name() {
+        let _lock = GLOBAL_LOCK.lock();
+        let removed = uninstall_all_ordered();
+        assert_eq!(primary::global::installed_type_name(), None);
+        assert_eq!(fallback::global::installed_type_name(), None);
+
+        install_global_handlers(PanicDropStrategy::DEFAULT, PanicDropStrategy::DEFAULT);
+        let primary = primary::global::installed_type_name().expect("primary not installed");
+        let fallback = fallback::global::installed_type_name().expect("fallback not installed");
+        assert!(primary.contains("PanicDropStrategy"), "got {primary}");
+        assert!(fallback.contains("PanicDropStrategy"), "got {fallback}");
+
+        reinstall(removed);
+    }
+
+    #[test]
+    fn test_ensure_installed_once_races() {
+        use crate::drop_strategies::NoOpDropStrategy;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Barrier;
+        use std::thread;
+
+        const THREADS: usize = 8;
+
+        let _lock = GLOBAL_LOCK.lock();
+        let removed = uninstall_all_ordered();
+        let factory_calls = AtomicUsize::new(0);
+        let barrier = Barrier::new(THREADS);
+
+        let outcomes: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        ensure_installed_once(
+                            || {
+                                factory_calls.fetch_add(1, Ordering::Relaxed);
+                                NoOpDropStrategy
+                            },
+                            || {
+                                factory_calls.fetch_add(1, Ordering::Relaxed);
+                                NoOpDropStrategy
+                            },
+                        )
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        assert_eq!(
+            factory_calls.load(Ordering::Relaxed),
+            2,
+            "a handler was installed more than once"
+        );
+        let installed_now = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome, EnsureInstalled::InstalledNow))
+            .count();
+        let library_default = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome, EnsureInstalled::PreviouslyLibraryDefault))
+            .count();
+        assert!(
+            (1..=2).contains(&installed_now),
+            "{installed_now} threads installed a handler"
+        );
+        assert_eq!(installed_now + library_default, THREADS);
+
+        reinstall(removed);
+    }
+
+    #[test]
+    fn test_application_overrides_library_default() {
+        use crate::drop_strategies::NoOpDropStrategy;
+
+        let _lock = GLOBAL_LOCK.lock();
+        let removed = uninstall_all_ordered();
+
+        let library = || NoOpDropStrategy;
+        assert!(matches!(
+            ensure_installed_once(library, library),
+            EnsureInstalled::InstalledNow
+        ));
+        assert!(matches!(
+            ensure_installed_once(library, library),
+            EnsureInstalled::PreviouslyLibraryDefault
+        ));
+
+        install_global_handlers(PanicDropStrategy::DEFAULT, PanicDropStrategy::DEFAULT);
+        assert!(matches!(
+            ensure_installed_once(library, library),
+            EnsureInstalled::AlreadyConfigured
+        ));
+        let primary = primary::global::installed_type_name().unwrap();
+        assert!(
+            primary.contains("PanicDropStrategy"),
+            "the library default replaced the application's handler: {primary}"
+        );
+
+        // a library default only fills in the handlers which are missing
+        fallback::global::uninstall();
+        assert!(matches!(
+            ensure_installed_once(library, library),
+            EnsureInstalled::InstalledNow
+        ));
+        assert!(primary::global::installed_type_name()
+            .unwrap()
+            .contains("PanicDropStrategy"));
+        assert!(fallback::global::installed_type_name()
+            .unwrap()
+            .contains("NoOpDropStrategy"));
+        assert!(matches!(
+            ensure_installed_once(library, library),
+            EnsureInstalled::AlreadyConfigured
+        ));
+
+        reinstall(removed);
+    }
+
+    /// Fails to handle errors whose message is odd, counting how many batches it was handed.
+    struct FailsOnOdd {
+        handled: Arc<Mutex<Vec<String>>>,
+        batches: Arc<Mutex<usize>>,
+    }
+
+    impl FallibleTryDropStrategy for FailsOnOdd {
+        type Error = crate::Error;
+
+        fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
+            let odd = error.to_string().parse::<usize>().unwrap() % 2 == 1;
+            self.handled.lock().unwrap().push(error.to_string());
+            if odd {
+                Err(error)
+            } else {
+                Ok(())
+            }
+        }
+
+        fn try_handle_errors_batch(
+            &self,
+            errors: &mut dyn Iterator<Item = crate::Error>,
+            failed: &mut dyn FnMut(Self::Error),
+        ) {
+            *self.batches.lock().unwrap() += 1;
+            for error in errors {
+                if let Err(error) = self.try_handle_error(error) {
+                    failed(error)
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_handle_errors() {
+        use crate::drop_strategies::AdHocDropStrategy;
+        use crate::tier::{self, HandlingTier};
+
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let batches = Arc::new(Mutex::new(0));
+        let fallback = Arc::new(Mutex::new(Vec::new()));
+        let f = Arc::clone(&fallback);
+        let _guards = install_thread_local_handlers_for_this_scope(
+            FailsOnOdd {
+                handled: Arc::clone(&handled),
+                batches: Arc::clone(&batches),
+            },
+            AdHocDropStrategy(move |error| {
+                assert_eq!(tier::current_tier(&error), HandlingTier::Fallback);
+                f.lock().unwrap().push(error.to_string())
+            }),
+        );
+
+        handle_errors((0..5).map(|index| anyhow::anyhow!("{index}")));
+
+        assert_eq!(*batches.lock().unwrap(), 1, "the batch was split up");
+        assert_eq!(
+            *handled.lock().unwrap(),
+            ["0", "1", "2", "3", "4"].map(ToString::to_string)
+        );
+        assert_eq!(
+            *fallback.lock().unwrap(),
+            ["1", "3"].map(ToString::to_string),
+            "only the errors which failed should reach the fallback handler",
+        );
+    }
+
+    #[test]
+    fn test_install_boxed_strategies() {
+        use crate::drop_strategies::AdHocDropStrategy;
+        use std::boxed::Box;
+
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let fallback = Arc::new(Mutex::new(Vec::new()));
+        let f = Arc::clone(&fallback);
+        let primary: Box<dyn FallibleTryDropStrategy<Error = crate::Error>> =
+            Box::new(FailsOnOdd {
+                handled: Arc::clone(&handled),
+                batches: Arc::new(Mutex::new(0)),
+            });
+        let fallbacks: Vec<Box<dyn TryDropStrategy>> = std::vec![
+            Box::new(PanicDropStrategy::DEFAULT),
+            Box::new(AdHocDropStrategy(move |error: crate::Error| {
+                f.lock().unwrap().push(error.to_string())
+            })),
+        ];
+
+        install_thread_local_handlers(primary, fallbacks.into_iter().last().unwrap());
+        handle_errors((0..3).map(|index| anyhow::anyhow!("{index}")));
+        uninstall_for_thread();
+
+        assert_eq!(
+            *handled.lock().unwrap(),
+            ["0", "1", "2"].map(ToString::to_string)
+        );
+        assert_eq!(*fallback.lock().unwrap(), ["1"].map(ToString::to_string));
+    }
+
+    #[test]
+    fn test_with_handlers_restores_on_panic() {
+        use crate::drop_strategies::NoOpDropStrategy;
+        use std::panic::{self, AssertUnwindSafe};
+
+        let _lock = GLOBAL_LOCK.lock();
+        let removed = uninstall_all_ordered();
+        install_global_handlers(PanicDropStrategy::DEFAULT, PanicDropStrategy::DEFAULT);
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            with_global_handlers(NoOpDropStrategy, NoOpDropStrategy, || {
+                with_thread_local_handlers(NoOpDropStrategy, NoOpDropStrategy, || {
+                    assert!(primary::global::installed_type_name()
+                        .unwrap()
+                        .contains("NoOpDropStrategy"));
+                    assert!(primary::thread_local::try_read(|_| ()).is_ok());
+                    panic!("closure panicked")
+                })
+            })
+        }));
+        assert!(result.is_err());
+
+        assert!(primary::thread_local::try_read(|_| ()).is_err());
+        assert!(fallback::thread_local::try_read(|_| ()).is_err());
+        assert!(primary::global::installed_type_name()
+            .unwrap()
+            .contains("PanicDropStrategy"));
+        assert!(fallback::global::installed_type_name()
+            .unwrap()
+            .contains("PanicDropStrategy"));
+
+        let value = with_global_fallback_handler(NoOpDropStrategy, || {
+            with_thread_local_primary_handler(NoOpDropStrategy, || 42)
+        });
+        assert_eq!(value, 42);
+        assert!(primary::thread_local::try_read(|_| ()).is_err());
+        assert!(fallback::global::installed_type_name()
+            .unwrap()
+            .contains("PanicDropStrategy"));
+
+        reinstall(removed);
+    }
+
+    #[cfg(feature = "ds-collect")]
+    #[test]
+    fn test_with_collecting_handlers() {
+        use crate::on_drop::on_drop;
+        use std::io;
+
+        let ((), errors) = with_collecting_handlers(|| {
+            drop(on_drop(|| Err(io::Error::other("first"))));
+            drop(on_drop(|| Ok::<_, io::Error>(())));
+            drop(on_drop(|| Err(io::Error::other("second"))));
+        });
+        let messages: Vec<_> = errors.iter().map(ToString::to_string).collect();
+        assert_eq!(messages, ["first", "second"]);
+        assert!(
+            errors[0].downcast_ref::<io::Error>().is_some(),
+            "the collected error was rewrapped"
+        );
+        assert!(primary::thread_local::try_read(|_| ()).is_err());
+    }
+}
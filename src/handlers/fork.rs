@@ -0,0 +1,54 @@
+//! Keep the global handlers consistent across `fork()`. See [`crate::fork_safety`].
+
+use crate::handlers::common::global::{DefaultGlobalDefinition, GlobalDefinition};
+use crate::handlers::common::{Fallback, Primary};
+use crate::{GlobalDynFallibleTryDropStrategy, GlobalTryDropStrategy};
+use std::boxed::Box;
+
+/// Lock both global handlers, so that no other thread holds a guard to them while forking.
+pub(crate) fn lock() {
+    Primary::global().lock_for_fork();
+    Fallback::global().lock_for_fork();
+}
+
+/// Unlock both global handlers in the parent process.
+///
+/// # Safety
+/// [`lock`] must have been called right before forking.
+pub(crate) unsafe fn unlock() {
+    Fallback::global().unlock_after_fork();
+    Primary::global().unlock_after_fork();
+}
+
+/// Unlock both global handlers in the child process, then replace them. The replaced handlers
+/// have their `on_fork_child` hooks called, then are leaked, as dropping them could wait for
+/// threads or touch resources which only exist in the parent process.
+///
+/// # Safety
+/// [`lock`] must have been called right before forking.
+pub(crate) unsafe fn reset_in_child(
+    primary: Box<dyn GlobalDynFallibleTryDropStrategy>,
+    fallback: Box<dyn GlobalTryDropStrategy>,
+) {
+    unlock();
+
+    if let Some(previous) = Primary::global().replace(primary) {
+        previous.dyn_on_fork_child();
+        core::mem::forget(previous)
+    }
+
+    if let Some(previous) = Fallback::global().replace(fallback) {
+        previous.on_fork_child();
+        core::mem::forget(previous)
+    }
+}
+
+/// The default global primary handler.
+pub(crate) fn default_primary() -> Box<dyn GlobalDynFallibleTryDropStrategy> {
+    Primary::default()
+}
+
+/// The default global fallback handler.
+pub(crate) fn default_fallback() -> Box<dyn GlobalTryDropStrategy> {
+    Fallback::default()
+}
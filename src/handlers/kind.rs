@@ -0,0 +1,47 @@
+use core::fmt;
+
+/// Identifies a handler at runtime.
+#[cfg_attr(feature = "derives", derive(Ord, PartialOrd, Hash))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum HandlerKind {
+    /// The primary handler.
+    Primary,
+
+    /// The fallback handler.
+    Fallback,
+}
+
+impl fmt::Display for HandlerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Primary => "primary",
+            Self::Fallback => "fallback",
+        })
+    }
+}
+
+/// Identifies the scope of a handler at runtime.
+#[cfg_attr(feature = "derives", derive(Ord, PartialOrd, Hash))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ScopeKind {
+    /// The handler is shared across all threads.
+    Global,
+
+    /// The handler is local to the current thread.
+    ThreadLocal,
+
+    /// The handler tries the thread local scope first, then the global scope.
+    Shim,
+}
+
+impl fmt::Display for ScopeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Global => "global",
+            Self::ThreadLocal => "thread local",
+            Self::Shim => "shim",
+        })
+    }
+}
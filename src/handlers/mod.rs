@@ -5,8 +5,15 @@ mod common;
 
 pub mod fallback;
 pub(crate) mod fns;
+
+#[cfg(all(unix, feature = "fork-safety"))]
+pub(crate) mod fork;
+mod kind;
 pub mod primary;
 
+#[cfg(feature = "global")]
+pub mod slot;
+
 #[cfg(any(feature = "global", feature = "thread-local"))]
 pub mod on_uninit;
 
@@ -14,9 +21,10 @@ pub mod on_uninit;
 mod uninit_error;
 
 #[cfg(any(feature = "global", feature = "thread-local"))]
-pub use uninit_error::UninitializedError;
+pub use uninit_error::{UninitializedError, WouldDeadlockError};
 
 pub use fns::*;
+pub use kind::{HandlerKind, ScopeKind};
 
 #[cfg(all(feature = "global", not(feature = "thread-local")))]
 pub use primary::global::GlobalPrimaryHandler as PrimaryHandler;
@@ -1,19 +1,27 @@
 //! Manage the primary global handler.
 
-use crate::handlers::common::global::{Global as GenericGlobal, GlobalDefinition};
-use crate::handlers::common::handler::CommonHandler;
+use crate::handlers::common::global::{
+    Global as GenericGlobal, GlobalDefinition, GlobalScopeGuard as GenericGlobalScopeGuard,
+};
+use crate::handlers::common::handler::{finish, finish_batch, CommonHandler, Policy};
 use crate::handlers::common::{Global as GlobalScope, Primary};
-use crate::handlers::on_uninit::{ErrorOnUninit, FlagOnUninit, PanicOnUninit};
+use crate::handlers::on_uninit::{DoNothingOnUninit, ErrorOnUninit, FlagOnUninit, PanicOnUninit};
 use crate::handlers::primary::{Abstracter, DefaultOnUninit};
 use crate::handlers::uninit_error::UninitializedError;
+use crate::tier::HandlingTier;
 use crate::{FallibleTryDropStrategy, GlobalDynFallibleTryDropStrategy};
 
 #[cfg(feature = "ds-write")]
 use crate::handlers::common::global::DefaultGlobalDefinition;
 
-use parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock};
+use crate::handlers::slot::GlobalSlot;
+use crate::handlers::slot::SlotWriteGuard;
+use core::fmt;
+use parking_lot::MappedRwLockReadGuard;
 use std::boxed::Box;
-use std::convert;
+use std::env;
+use std::error::Error;
+use std::string::String;
 
 #[cfg(feature = "ds-write")]
 use crate::handlers::on_uninit::UseDefaultOnUninit;
@@ -26,18 +34,16 @@ pub static DEFAULT_GLOBAL_PRIMARY_HANDLER: GlobalPrimaryHandler = GlobalPrimaryH
 
 impl_fallible_try_drop_strategy_for!(GlobalPrimaryHandler
 where
-    Scope: GlobalScope,
-    Definition: GlobalDefinition
+    Scope: GlobalScope
 );
 
-static PRIMARY_HANDLER: RwLock<Option<Box<dyn GlobalDynFallibleTryDropStrategy>>> =
-    parking_lot::const_rwlock(None);
+static PRIMARY_HANDLER: GlobalSlot<Box<dyn GlobalDynFallibleTryDropStrategy>> = GlobalSlot::new();
 
 impl GlobalDefinition for Primary {
     const UNINITIALIZED_ERROR: &'static str = "the global primary handler is not initialized yet";
     type Global = Box<dyn GlobalDynFallibleTryDropStrategy>;
 
-    fn global() -> &'static RwLock<Option<Self::Global>> {
+    fn global() -> &'static GlobalSlot<Self::Global> {
         &PRIMARY_HANDLER
     }
 }
@@ -46,7 +52,9 @@ impl GlobalDefinition for Primary {
 impl DefaultGlobalDefinition for Primary {
     fn default() -> Self::Global {
         let mut strategy = crate::drop_strategies::WriteDropStrategy::stderr();
-        strategy.prelude("error: ");
+        strategy
+            .prelude("error: ")
+            .color(crate::drop_strategies::write::ColorChoice::Auto);
         Box::new(strategy)
     }
 }
@@ -64,10 +72,15 @@ type Global = GenericGlobal<Primary>;
 /// A handy type alias to `Box<dyn GlobalDynFallibleTryDropStrategy>`.
 pub type BoxDynGlobalFallibleTryDropStrategy = Box<dyn GlobalDynFallibleTryDropStrategy>;
 
+/// A scope guard for the global primary handler. This sets the global primary handler to the one
+/// specified for the duration of the scope.
+pub type GlobalScopeGuard = GenericGlobalScopeGuard<Primary>;
+
 global_methods! {
     Global = Global;
     GenericStrategy = GlobalDynFallibleTryDropStrategy;
     DynStrategy = BoxDynGlobalFallibleTryDropStrategy;
+    ScopeGuard = GlobalScopeGuard;
     feature = "ds-write";
 
     /// Set the global primary handler. Must be a dynamic trait object.
@@ -101,8 +114,36 @@ global_methods! {
     write;
 
     /// Uninstall the global primary handler.
+    ///
+    /// Values which are dropped on other threads in the meantime will have their errors handled
+    /// according to their on uninit policy, which is often to panic. During shutdown, consider
+    /// [`begin_drain`] instead.
     uninstall;
 
+    /// Take the global primary handler, if there is any initialized.
+    take;
+
+    /// Get the type name of the concrete strategy installed as the global primary handler, if
+    /// there is any initialized. This is useful to confirm which strategy is actually installed.
+    installed_type_name;
+
+    /// Get a reference to the concrete strategy installed as the global primary handler, if there
+    /// is any initialized and it's of type `T`. This is useful to e.g. read back the errors which a
+    /// strategy installed globally has collected.
+    downcast_ref;
+
+    /// Sets the global primary handler to the specified one for the duration of the scope, then
+    /// restores the previous one.
+    ///
+    /// For more information, see the [`GlobalScopeGuard`] type.
+    scope;
+
+    /// Sets the global primary handler to the specified one for the duration of the scope, then
+    /// restores the previous one. Must be a dynamic trait object.
+    ///
+    /// For more information, see the [`GlobalScopeGuard`] type.
+    scope_dyn;
+
     /// Get a reference to the global primary handler.
     ///
     /// If the global primary handler is not initialized yet, it is initialized with the default
@@ -115,3 +156,347 @@ global_methods! {
     /// value.
     write_or_default;
 }
+
+/// Begin draining the global primary handler: atomically swap in the given replacement, returning
+/// the old handler, if there was any, so that it can be finalized.
+///
+/// Unlike [`uninstall`] followed by [`install`], there's no window in which the global primary
+/// handler is uninitialized, so values which are still being dropped on other threads, e.g. during
+/// shutdown, have their errors handled by the replacement instead of by their on uninit policy.
+pub fn begin_drain(
+    replacement: impl GlobalDynFallibleTryDropStrategy,
+) -> Option<BoxDynGlobalFallibleTryDropStrategy> {
+    begin_drain_dyn(Box::new(replacement))
+}
+
+/// Begin draining the global primary handler, swapping in the given replacement. Must be a dynamic
+/// trait object. See [`begin_drain`].
+pub fn begin_drain_dyn(
+    replacement: BoxDynGlobalFallibleTryDropStrategy,
+) -> Option<BoxDynGlobalFallibleTryDropStrategy> {
+    Primary::global().replace(replacement)
+}
+
+/// Begin draining the global primary handler, swapping in the default one, which writes the errors
+/// to standard error. See [`begin_drain`].
+#[cfg(feature = "ds-write")]
+pub fn begin_drain_default() -> Option<BoxDynGlobalFallibleTryDropStrategy> {
+    begin_drain_dyn(<Primary as DefaultGlobalDefinition>::default())
+}
+
+/// Hand the error to the global primary handler, and to the global fallback handler if the
+/// primary one fails to handle it, returning an error if either of them is needed but isn't
+/// initialized yet, instead of applying an on uninit policy like [`DEFAULT_GLOBAL_PRIMARY_HANDLER`]
+/// does, which may panic.
+///
+/// # Errors
+/// If the global primary handler isn't initialized yet, or if it failed to handle the error and
+/// the global fallback handler isn't initialized yet. The drop error is lost in either case.
+pub fn try_handle_error(error: anyhow::Error) -> Result<(), UninitializedError> {
+    let scope = crate::sampling::enter(&error);
+    let result = {
+        let strategy = try_read()?;
+        let _guard = crate::tier::enter(&error, HandlingTier::Primary);
+        strategy.dyn_try_handle_error(error)
+    };
+
+    if let Err(error) = result {
+        scope.carry_to(&error);
+        let fallback = crate::handlers::fallback::global::try_read()?;
+        let _guard = crate::tier::enter(&error, HandlingTier::Fallback);
+        fallback.handle_error(error)
+    }
+
+    Ok(())
+}
+
+/// The environment variable [`install_from_env`] reads the drop strategy from.
+pub const ENV_VAR: &str = "TRY_DROP_STRATEGY";
+
+/// The names of the drop strategies [`install_from_name`] and [`install_from_env`] recognize.
+pub const ENV_STRATEGIES: &[&str] = &["panic", "abort", "exit", "noop", "stderr"];
+
+/// The name of the drop strategy given to [`install_from_name`], or the one in the
+/// `TRY_DROP_STRATEGY` environment variable, isn't one which is recognized, or it wasn't compiled
+/// in. Contains the name.
+#[cfg_attr(feature = "derives", derive(Ord, PartialOrd, Hash))]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnknownStrategyError(pub String);
+
+impl fmt::Display for UnknownStrategyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown drop strategy `{}`, expected one of: {}",
+            self.0,
+            ENV_STRATEGIES.join(", ")
+        )
+    }
+}
+
+impl Error for UnknownStrategyError {}
+
+/// Create the drop strategy with the given name, as recognized by [`install_from_name`], if it was
+/// compiled in.
+fn strategy_from_name(name: &str) -> Option<BoxDynGlobalFallibleTryDropStrategy> {
+    match name {
+        #[cfg(feature = "ds-panic")]
+        "panic" => Some(Box::new(crate::drop_strategies::PanicDropStrategy::DEFAULT)),
+        #[cfg(feature = "ds-abort")]
+        "abort" => Some(Box::new(crate::drop_strategies::AbortDropStrategy)),
+        #[cfg(feature = "ds-exit")]
+        "exit" => Some(Box::new(crate::drop_strategies::ExitDropStrategy::DEFAULT)),
+        #[cfg(feature = "ds-noop")]
+        "noop" => Some(Box::new(crate::drop_strategies::NoOpDropStrategy)),
+        #[cfg(feature = "ds-write")]
+        "stderr" => Some(<Primary as DefaultGlobalDefinition>::default()),
+        _ => None,
+    }
+}
+
+/// Install the drop strategy with the given name as the global primary handler, ignoring
+/// surrounding whitespace. The recognized names are:
+///
+/// - `panic`: the [`PanicDropStrategy`](crate::drop_strategies::PanicDropStrategy).
+/// - `abort`: the [`AbortDropStrategy`](crate::drop_strategies::AbortDropStrategy).
+/// - `exit`: the [`ExitDropStrategy`](crate::drop_strategies::ExitDropStrategy), exiting with
+///   code 1.
+/// - `noop`: the [`NoOpDropStrategy`](crate::drop_strategies::NoOpDropStrategy).
+/// - `stderr`: the default global primary handler, which writes the errors to standard error.
+///
+/// # Errors
+/// If the name isn't recognized, or the drop strategy it names wasn't compiled in, nothing is
+/// installed and an error is returned.
+pub fn install_from_name(name: &str) -> Result<(), UnknownStrategyError> {
+    let strategy =
+        strategy_from_name(name.trim()).ok_or_else(|| UnknownStrategyError(name.into()))?;
+    install_dyn(strategy);
+    Ok(())
+}
+
+/// Install the drop strategy named in the `TRY_DROP_STRATEGY` environment variable as the global
+/// primary handler with [`install_from_name`], if it's set, so that it can be chosen per
+/// deployment rather than in code.
+///
+/// # Errors
+/// If the name isn't recognized, or the drop strategy it names wasn't compiled in, nothing is
+/// installed and an error is returned.
+pub fn install_from_env() -> Result<(), UnknownStrategyError> {
+    match env::var(ENV_VAR) {
+        Ok(name) => install_from_name(&name),
+        Err(env::VarError::NotPresent) => Ok(()),
+        Err(env::VarError::NotUnicode(name)) => {
+            Err(UnknownStrategyError(name.to_string_lossy().into()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::{AdHocDropStrategy, NoOpDropStrategy};
+    use crate::test_utils::GLOBAL_LOCK;
+    use crate::PureTryDrop;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::string::ToString;
+    use std::sync::Arc;
+    use std::thread;
+    use std::vec::Vec;
+
+    static UNINIT: AtomicUsize = AtomicUsize::new(0);
+
+    /// Counts the errors which the primary handler failed to handle because it wasn't initialized.
+    static COUNT_UNINIT: AdHocDropStrategy<fn(crate::Error)> = AdHocDropStrategy(|error| {
+        if error.is::<UninitializedError>() {
+            UNINIT.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    struct Fails;
+
+    impl PureTryDrop for Fails {
+        type Error = anyhow::Error;
+        type FallbackTryDropStrategy = AdHocDropStrategy<fn(crate::Error)>;
+        type TryDropStrategy = GlobalPrimaryHandler<ErrorOnUninit>;
+
+        fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+            &COUNT_UNINIT
+        }
+
+        fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+            &GlobalPrimaryHandler::ON_UNINIT_ERROR
+        }
+
+        unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+            anyhow::bail!("this will always fail")
+        }
+    }
+
+    #[test]
+    fn test_reset_last_drop_failed() {
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = take();
+        let handler = GlobalPrimaryHandler::on_uninit_flag();
+        assert!(handler.try_handle_error(anyhow::anyhow!("test")).is_err());
+        assert!(handler.last_drop_failed());
+
+        handler.reset_last_drop_failed();
+        assert!(
+            !handler.last_drop_failed(),
+            "the flag is still set after resetting it"
+        );
+
+        if let Some(previous) = previous {
+            install_dyn(previous)
+        }
+    }
+
+    #[test]
+    fn test_begin_drain() {
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = take();
+        let handled = Arc::new(AtomicUsize::new(0));
+        let h = Arc::clone(&handled);
+        install(AdHocDropStrategy(move |_| {
+            h.fetch_add(1, Ordering::Relaxed);
+        }));
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let stop = Arc::clone(&stop);
+                thread::spawn(move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        drop(Fails.adapt())
+                    }
+                })
+            })
+            .collect();
+
+        while handled.load(Ordering::Relaxed) < 1000 {
+            thread::yield_now()
+        }
+
+        let drained = begin_drain(NoOpDropStrategy);
+        assert!(drained.is_some(), "the old handler wasn't returned");
+
+        // the old handler can be finalized while the threads keep on dropping
+        drop(drained);
+        let handled = handled.load(Ordering::Relaxed);
+        for _ in 0..1000 {
+            drop(Fails.adapt())
+        }
+
+        stop.store(true, Ordering::Relaxed);
+        threads
+            .into_iter()
+            .for_each(|thread| thread.join().unwrap());
+
+        assert!(handled >= 1000);
+        assert_eq!(UNINIT.load(Ordering::Relaxed), 0);
+
+        match previous {
+            Some(previous) => install_dyn(previous),
+            None => uninstall(),
+        }
+    }
+
+    #[cfg(all(feature = "downcast-rs", feature = "ds-collect"))]
+    #[test]
+    fn test_downcast_ref() {
+        use crate::drop_strategies::CollectDropStrategy;
+        use std::string::ToString;
+
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = take();
+        install(CollectDropStrategy::new());
+        read()
+            .dyn_try_handle_error(anyhow::anyhow!("this will always fail"))
+            .unwrap();
+
+        assert!(downcast_ref::<NoOpDropStrategy>().is_none());
+        let errors = downcast_ref::<CollectDropStrategy>()
+            .expect("the installed strategy wasn't downcast")
+            .errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "this will always fail");
+
+        uninstall();
+        assert!(downcast_ref::<CollectDropStrategy>().is_none());
+
+        if let Some(previous) = previous {
+            install_dyn(previous)
+        }
+    }
+
+    #[test]
+    #[cfg(all(
+        feature = "ds-panic",
+        feature = "ds-abort",
+        feature = "ds-exit",
+        feature = "ds-noop",
+        feature = "ds-write"
+    ))]
+    fn test_install_from_name() {
+        let _lock = GLOBAL_LOCK.lock();
+        let previous = take();
+
+        let expected = [
+            ("panic", "PanicDropStrategy"),
+            ("abort", "AbortDropStrategy"),
+            ("exit", "ExitDropStrategy"),
+            ("noop", "NoOpDropStrategy"),
+            (" stderr\n", "WriteDropStrategy<std::io::stdio::Stderr>"),
+        ];
+        assert_eq!(expected.len(), ENV_STRATEGIES.len());
+
+        for (name, type_name) in expected {
+            assert_eq!(install_from_name(name), Ok(()), "failed to install {name}");
+            let installed = installed_type_name().unwrap();
+            assert!(
+                installed.ends_with(type_name),
+                "installed {installed} for {name}"
+            );
+        }
+
+        uninstall();
+        let error = install_from_name("syslog").unwrap_err();
+        assert_eq!(error, UnknownStrategyError("syslog".into()));
+        assert!(error.to_string().contains("expected one of: panic, abort"));
+        assert_eq!(installed_type_name(), None, "installed an unknown strategy");
+
+        if let Some(previous) = previous {
+            install_dyn(previous)
+        }
+    }
+
+    #[test]
+    fn test_try_handle_error() {
+        use crate::handlers::fallback;
+        use crate::test_utils::FallibleDropStrategy;
+
+        let _lock = GLOBAL_LOCK.lock();
+        let removed = crate::handlers::uninstall_all_ordered();
+        assert!(try_handle_error(anyhow::anyhow!("uninstalled")).is_err());
+
+        install(NoOpDropStrategy);
+        assert!(try_handle_error(anyhow::anyhow!("installed")).is_ok());
+
+        install(FallibleDropStrategy);
+        assert!(
+            try_handle_error(anyhow::anyhow!("no fallback")).is_err(),
+            "the fallback handler wasn't needed"
+        );
+
+        let handled = Arc::new(AtomicUsize::new(0));
+        let h = Arc::clone(&handled);
+        fallback::global::install(AdHocDropStrategy(move |error: crate::Error| {
+            assert_eq!(error.to_string(), "failed over");
+            h.fetch_add(1, Ordering::Relaxed);
+        }));
+        assert!(try_handle_error(anyhow::anyhow!("failed over")).is_ok());
+        assert_eq!(handled.load(Ordering::Relaxed), 1);
+
+        crate::handlers::reinstall(removed);
+    }
+}
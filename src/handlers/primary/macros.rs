@@ -1,21 +1,58 @@
 macro_rules! impl_fallible_try_drop_strategy_for {
-    ($handler:ident where Scope: $scope:ident, Definition: $definition:ident) => {
+    ($handler:ident where Scope: $scope:ident) => {
         impl FallibleTryDropStrategy for $handler<ErrorOnUninit> {
             type Error = anyhow::Error;
 
+            #[inline]
             fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
-                Abstracter::<$scope>::try_read(|strategy| strategy.dyn_try_handle_error(error))
-                    .map_err(Into::into)
-                    .and_then(convert::identity)
+                finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::Error,
+                    Abstracter::<$scope>::try_read(|strategy| strategy.dyn_try_handle_error(error)),
+                )
+            }
+
+            #[inline]
+            fn try_handle_errors_batch(
+                &self,
+                errors: &mut dyn Iterator<Item = crate::Error>,
+                failed: &mut dyn FnMut(Self::Error),
+            ) {
+                let outcome = Abstracter::<$scope>::try_read(|strategy| {
+                    strategy.dyn_try_handle_errors_batch(errors, failed)
+                });
+                finish_batch(Policy::Error, outcome, errors, failed, |error| {
+                    self.try_handle_error(error)
+                })
             }
         }
 
         impl FallibleTryDropStrategy for $handler<PanicOnUninit> {
             type Error = anyhow::Error;
 
+            #[inline]
             fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
-                Abstracter::<$scope>::try_read(|strategy| strategy.dyn_try_handle_error(error))
-                    .expect(<Primary as $definition>::UNINITIALIZED_ERROR)
+                finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::Panic,
+                    Abstracter::<$scope>::try_read(|strategy| strategy.dyn_try_handle_error(error)),
+                )
+            }
+
+            #[inline]
+            fn try_handle_errors_batch(
+                &self,
+                errors: &mut dyn Iterator<Item = crate::Error>,
+                failed: &mut dyn FnMut(Self::Error),
+            ) {
+                let outcome = Abstracter::<$scope>::try_read(|strategy| {
+                    strategy.dyn_try_handle_errors_batch(errors, failed)
+                });
+                finish_batch(Policy::Panic, outcome, errors, failed, |error| {
+                    self.try_handle_error(error)
+                })
             }
         }
 
@@ -23,9 +60,57 @@ macro_rules! impl_fallible_try_drop_strategy_for {
         impl FallibleTryDropStrategy for $handler<UseDefaultOnUninit> {
             type Error = anyhow::Error;
 
+            #[inline]
             fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
-                Abstracter::<$scope>::read_or_default(|strategy| {
-                    strategy.dyn_try_handle_error(error)
+                finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::UseDefault,
+                    Abstracter::<$scope>::try_read_or_default(|strategy| {
+                        strategy.dyn_try_handle_error(error)
+                    }),
+                )
+            }
+
+            #[inline]
+            fn try_handle_errors_batch(
+                &self,
+                errors: &mut dyn Iterator<Item = crate::Error>,
+                failed: &mut dyn FnMut(Self::Error),
+            ) {
+                let outcome = Abstracter::<$scope>::try_read_or_default(|strategy| {
+                    strategy.dyn_try_handle_errors_batch(errors, failed)
+                });
+                finish_batch(Policy::UseDefault, outcome, errors, failed, |error| {
+                    self.try_handle_error(error)
+                })
+            }
+        }
+
+        impl FallibleTryDropStrategy for $handler<DoNothingOnUninit> {
+            type Error = anyhow::Error;
+
+            #[inline]
+            fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
+                finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::DoNothing,
+                    Abstracter::<$scope>::try_read(|strategy| strategy.dyn_try_handle_error(error)),
+                )
+            }
+
+            #[inline]
+            fn try_handle_errors_batch(
+                &self,
+                errors: &mut dyn Iterator<Item = crate::Error>,
+                failed: &mut dyn FnMut(Self::Error),
+            ) {
+                let outcome = Abstracter::<$scope>::try_read(|strategy| {
+                    strategy.dyn_try_handle_errors_batch(errors, failed)
+                });
+                finish_batch(Policy::DoNothing, outcome, errors, failed, |error| {
+                    self.try_handle_error(error)
                 })
             }
         }
@@ -33,15 +118,32 @@ macro_rules! impl_fallible_try_drop_strategy_for {
         impl FallibleTryDropStrategy for $handler<FlagOnUninit> {
             type Error = anyhow::Error;
 
+            #[inline]
             fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
-                let (last_drop_failed, ret) =
-                    match Abstracter::<$scope>::try_read(|s| s.dyn_try_handle_error(error)) {
-                        Ok(Ok(())) => (false, Ok(())),
-                        Ok(Err(error)) => (false, Err(error)),
-                        Err(error) => (true, Err(error.into())),
-                    };
-                self.set_last_drop_failed(last_drop_failed);
-                ret
+                finish(
+                    Self::KIND,
+                    Self::SCOPE,
+                    Policy::Flag(&self.extra_data),
+                    Abstracter::<$scope>::try_read(|strategy| strategy.dyn_try_handle_error(error)),
+                )
+            }
+
+            #[inline]
+            fn try_handle_errors_batch(
+                &self,
+                errors: &mut dyn Iterator<Item = crate::Error>,
+                failed: &mut dyn FnMut(Self::Error),
+            ) {
+                let outcome = Abstracter::<$scope>::try_read(|strategy| {
+                    strategy.dyn_try_handle_errors_batch(errors, failed)
+                });
+                finish_batch(
+                    Policy::Flag(&self.extra_data),
+                    outcome,
+                    errors,
+                    failed,
+                    |error| self.try_handle_error(error),
+                )
             }
         }
     };
@@ -29,7 +29,9 @@ mod imp {
             thread_local: CommonHandler::FLAG_ON_UNINIT,
             extra_data: Lazy::new(|| {
                 let mut strategy = WriteDropStrategy::stderr();
-                strategy.prelude("error: ");
+                strategy
+                    .prelude("error: ")
+                    .color(crate::drop_strategies::write::ColorChoice::Auto);
                 strategy
             }),
         };
@@ -55,6 +57,14 @@ mod imp {
                     .map_err(Into::into)
             })
         }
+
+        fn try_handle_errors_batch(
+            &self,
+            errors: &mut dyn Iterator<Item = crate::Error>,
+            failed: &mut dyn FnMut(Self::Error),
+        ) {
+            self.on_all_uninit_batch(errors, failed, |error| self.try_handle_error(error))
+        }
     }
 }
 
@@ -72,10 +82,11 @@ mod imp {
     }
 }
 
+use super::Abstracter;
 use crate::adapters::ArcError;
-use crate::handlers::common::handler::CommonShimHandler;
+use crate::handlers::common::handler::{finish_batch, CommonShimHandler, Policy};
 use crate::handlers::common::shim::OnUninitShim;
-use crate::handlers::common::Primary;
+use crate::handlers::common::{Global, Primary, ThreadLocal};
 use crate::handlers::on_uninit::{DoNothingOnUninit, ErrorOnUninit, FlagOnUninit, PanicOnUninit};
 use crate::FallibleTryDropStrategy;
 pub use imp::DefaultOnUninit;
@@ -110,6 +121,31 @@ impl<OU: OnUninitShim> ShimPrimaryHandler<OU> {
             Err(error) => Err(error),
         }
     }
+
+    /// Hand the batch to the thread-local primary handler, or to the global one if it's
+    /// uninitialized, reading either of them only once. If both are uninitialized, every error is
+    /// handed to `each` instead.
+    fn on_all_uninit_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = crate::Error>,
+        failed: &mut dyn FnMut(anyhow::Error),
+        each: impl FnMut(anyhow::Error) -> anyhow::Result<()>,
+    ) {
+        let outcome = Abstracter::<ThreadLocal>::try_read(|strategy| {
+            strategy.dyn_try_handle_errors_batch(errors, failed)
+        });
+        self.thread_local.set_last_drop_failed(outcome.is_err());
+
+        let outcome = outcome.or_else(|_| {
+            let outcome = Abstracter::<Global>::try_read(|strategy| {
+                strategy.dyn_try_handle_errors_batch(errors, failed)
+            });
+            self.global.set_last_drop_failed(outcome.is_err());
+            outcome
+        });
+
+        finish_batch(Policy::DoNothing, outcome, errors, failed, each)
+    }
 }
 
 impl FallibleTryDropStrategy for ShimPrimaryHandler<ErrorOnUninit> {
@@ -118,6 +154,14 @@ impl FallibleTryDropStrategy for ShimPrimaryHandler<ErrorOnUninit> {
     fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
         self.on_all_uninit(error, |uninit_error, _| Err(uninit_error))
     }
+
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = crate::Error>,
+        failed: &mut dyn FnMut(Self::Error),
+    ) {
+        self.on_all_uninit_batch(errors, failed, |error| self.try_handle_error(error))
+    }
 }
 
 impl FallibleTryDropStrategy for ShimPrimaryHandler<PanicOnUninit> {
@@ -126,9 +170,20 @@ impl FallibleTryDropStrategy for ShimPrimaryHandler<PanicOnUninit> {
     fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
         self.on_all_uninit(
             error,
-            |_, error| panic!("neither the thread local nor the global primary handlers are initialized (but here's the drop error anyway: {error})")
+            |_, error| {
+                crate::last_resort::panic_unless_unwinding(format_args!("neither the thread local nor the global primary handlers are initialized (but here's the drop error anyway: {error})"));
+                Ok(())
+            }
         )
     }
+
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = crate::Error>,
+        failed: &mut dyn FnMut(Self::Error),
+    ) {
+        self.on_all_uninit_batch(errors, failed, |error| self.try_handle_error(error))
+    }
 }
 
 impl FallibleTryDropStrategy for ShimPrimaryHandler<DoNothingOnUninit> {
@@ -137,20 +192,28 @@ impl FallibleTryDropStrategy for ShimPrimaryHandler<DoNothingOnUninit> {
     fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
         self.on_all_uninit(error, |_, _| Ok(()))
     }
+
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = crate::Error>,
+        failed: &mut dyn FnMut(Self::Error),
+    ) {
+        self.on_all_uninit_batch(errors, failed, |error| self.try_handle_error(error))
+    }
 }
 
 impl FallibleTryDropStrategy for ShimPrimaryHandler<FlagOnUninit> {
     type Error = anyhow::Error;
 
     fn try_handle_error(&self, error: crate::Error) -> Result<(), Self::Error> {
-        let mut last_drop_failed = false;
-        let result = self.on_all_uninit(error, |uninit_error, _| {
-            last_drop_failed = true;
-            Err(uninit_error)
-        });
-
-        self.set_last_drop_failed(last_drop_failed);
+        self.on_all_uninit(error, |uninit_error, _| Err(uninit_error))
+    }
 
-        result
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = crate::Error>,
+        failed: &mut dyn FnMut(Self::Error),
+    ) {
+        self.on_all_uninit_batch(errors, failed, |error| self.try_handle_error(error))
     }
 }
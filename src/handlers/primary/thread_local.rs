@@ -1,21 +1,22 @@
 //! Manage the thread local primary handler.
 
 use super::{Abstracter, DefaultOnUninit};
-use crate::handlers::common::handler::CommonHandler;
+use crate::handlers::common::handler::{finish, finish_batch, CommonHandler, Policy};
 use crate::handlers::common::thread_local::{
-    scope_guard::ScopeGuard as GenericScopeGuard, ThreadLocal as GenericThreadLocal,
-    ThreadLocalDefinition,
+    scope_guard::{Frame, ScopeGuard as GenericScopeGuard},
+    ThreadLocal as GenericThreadLocal, ThreadLocalDefinition,
 };
 use crate::handlers::common::Primary;
 use crate::handlers::common::ThreadLocal as ThreadLocalScope;
-use crate::handlers::on_uninit::{ErrorOnUninit, FlagOnUninit, PanicOnUninit};
+use crate::handlers::on_uninit::{DoNothingOnUninit, ErrorOnUninit, FlagOnUninit, PanicOnUninit};
 use crate::handlers::uninit_error::UninitializedError;
 use crate::FallibleTryDropStrategy;
 use std::boxed::Box;
 use std::cell::{Cell, RefCell};
 
 use std::thread::LocalKey;
-use std::{convert, thread_local};
+use std::thread_local;
+use std::vec::Vec;
 
 #[cfg(feature = "ds-write")]
 use crate::handlers::common::thread_local::DefaultThreadLocalDefinition;
@@ -33,13 +34,14 @@ pub static DEFAULT_THREAD_LOCAL_PRIMARY_HANDLER: ThreadLocalPrimaryHandler =
 
 impl_fallible_try_drop_strategy_for!(ThreadLocalPrimaryHandler
 where
-    Scope: ThreadLocalScope,
-    Definition: ThreadLocalDefinition
+    Scope: ThreadLocalScope
 );
 
 thread_local! {
     static PRIMARY_HANDLER: RefCell<Option<Box<dyn ThreadLocalFallibleTryDropStrategy>>> = RefCell::new(None);
-    static LOCKED: Cell<bool> = Cell::new(false);
+    static LOCKED: Cell<bool> = const { Cell::new(false) };
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+    static STACK: RefCell<Vec<Frame<Box<dyn ThreadLocalFallibleTryDropStrategy>>>> = const { RefCell::new(Vec::new()) };
 }
 
 impl ThreadLocalDefinition for Primary {
@@ -55,13 +57,23 @@ impl ThreadLocalDefinition for Primary {
     fn locked() -> &'static LocalKey<Cell<bool>> {
         &LOCKED
     }
+
+    fn stack() -> &'static LocalKey<RefCell<Vec<Frame<Self::ThreadLocal>>>> {
+        &STACK
+    }
+
+    fn generation() -> &'static LocalKey<Cell<u64>> {
+        &GENERATION
+    }
 }
 
 #[cfg(feature = "ds-write")]
 impl DefaultThreadLocalDefinition for Primary {
     fn default() -> Self::ThreadLocal {
         let mut strategy = crate::drop_strategies::WriteDropStrategy::stderr();
-        strategy.prelude("error: ");
+        strategy
+            .prelude("error: ")
+            .color(crate::drop_strategies::write::ColorChoice::Auto);
         Box::new(strategy)
     }
 }
@@ -154,4 +166,18 @@ thread_local_methods! {
     /// Sets the thread local primary handler to the given one for the duration of the given scope.
     /// For more advanced usage, see the [`ScopeGuard`] type. Must be a dynamic trait object.
     scope_dyn;
+
+    /// Sets the thread local primary handler to the given one for the duration of the given scope,
+    /// forbidding any other scope to be nested in it.
+    ///
+    /// # Panics
+    /// If this is nested in another scope, this function will panic.
+    scope_exclusive;
+
+    /// Sets the thread local primary handler to the given one for the duration of the given scope,
+    /// forbidding any other scope to be nested in it. Must be a dynamic trait object.
+    ///
+    /// # Panics
+    /// If this is nested in another scope, this function will panic.
+    scope_exclusive_dyn;
 }
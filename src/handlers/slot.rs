@@ -0,0 +1,577 @@
+//! Declare your own global slots for drop strategies, independent of the primary and fallback
+//! handlers, for example for an extra "audit" channel.
+//!
+//! ```rust
+//! use try_drop::handlers::slot::{GlobalSlot, SlotForwardStrategy};
+//! use try_drop::GlobalDynFallibleTryDropStrategy;
+//!
+//! static AUDIT: GlobalSlot<Box<dyn GlobalDynFallibleTryDropStrategy>> = GlobalSlot::new();
+//!
+//! AUDIT.install(try_drop::drop_strategies::NoOpDropStrategy);
+//! let forward = SlotForwardStrategy(&AUDIT);
+//! ```
+
+use crate::{FallibleTryDropStrategy, GlobalDynFallibleTryDropStrategy};
+use core::cell::RefCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use parking_lot::{
+    MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+use std::boxed::Box;
+use std::fmt;
+use std::thread_local;
+use std::vec::Vec;
+
+const UNINITIALIZED_ERROR: &str = "the global slot is not initialized yet";
+const WOULD_DEADLOCK_ERROR: &str =
+    "using the global slot would deadlock, since this thread is holding a write guard to it";
+
+/// Where the generations of every slot come from, so that a generation is never reused, even by
+/// another slot.
+static GENERATIONS: AtomicUsize = AtomicUsize::new(1);
+
+thread_local! {
+    /// The addresses of the slots which this thread is holding a write guard to.
+    static WRITING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A slot which stores a value, usually a drop strategy, which can be shared across all threads.
+/// This is what the global primary and fallback handlers are stored in.
+pub struct GlobalSlot<T>(RwLock<Option<T>>, AtomicBool, AtomicUsize);
+
+impl<T> GlobalSlot<T> {
+    /// Create a new, empty slot. This is a `const fn`, so it can be used to declare a `static`.
+    pub const fn new() -> Self {
+        Self(
+            parking_lot::const_rwlock(None),
+            AtomicBool::new(false),
+            AtomicUsize::new(0),
+        )
+    }
+
+    /// Give this slot a new generation, since its value was installed, replaced or taken. This
+    /// must be called while this slot is locked for writing.
+    fn bump_generation(&self) -> usize {
+        let generation = GENERATIONS.fetch_add(1, Ordering::Relaxed);
+        self.2.store(generation, Ordering::Relaxed);
+        generation
+    }
+
+    /// Install a new value into this slot, replacing the previous one.
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn install_dyn(&self, value: T) {
+        self.replace(value);
+    }
+
+    /// Install a new value into this slot, replacing the previous one.
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn install(&self, value: impl Into<T>) {
+        self.install_dyn(value.into())
+    }
+
+    /// Install a new value into this slot, returning the previous one if there was any.
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn replace(&self, value: T) -> Option<T> {
+        let mut slot = self.lock();
+        self.1.store(false, Ordering::Relaxed);
+        self.bump_generation();
+        slot.replace(value)
+    }
+
+    /// Install the value `value` returns, but only if this slot is empty, marking it as
+    /// provisional until something else is installed into this slot. Returns whether or not it was
+    /// installed.
+    ///
+    /// This is race free: if many threads call this at once, exactly one of them installs its
+    /// value, and `value` is only called by that thread.
+    ///
+    /// `value` is called while this slot is locked for writing, so it must not use this slot, e.g.
+    /// by dropping a value whose drop errors are handled by it. If it does, reading from this slot
+    /// fails like it does while holding a [`SlotWriteGuard`], instead of deadlocking.
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn install_provisionally_if_empty(&self, value: impl FnOnce() -> T) -> bool {
+        assert!(!self.written_on_this_thread(), "{}", WOULD_DEADLOCK_ERROR);
+
+        if self.0.read().is_some() {
+            return false;
+        }
+
+        // another thread may have installed a value since the check above
+        let mut slot = self.0.write();
+
+        if slot.is_some() {
+            return false;
+        }
+
+        let writing = Writing::new(self);
+        *slot = Some(value());
+        drop(writing);
+        self.1.store(true, Ordering::Relaxed);
+        self.bump_generation();
+        true
+    }
+
+    /// Whether or not the value in this slot was installed by
+    /// [`Self::install_provisionally_if_empty`], and nothing has been installed into this slot
+    /// since.
+    pub fn is_provisional(&self) -> bool {
+        let slot = self.0.read();
+        slot.is_some() && self.1.load(Ordering::Relaxed)
+    }
+
+    /// Lock this slot for writing, panicking instead of deadlocking if the current thread is
+    /// holding a write guard to it.
+    fn lock(&self) -> RwLockWriteGuard<'_, Option<T>> {
+        assert!(!self.written_on_this_thread(), "{}", WOULD_DEADLOCK_ERROR);
+        self.0.write()
+    }
+
+    fn address(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Whether or not the current thread is holding a write guard to this slot. If it is, reading
+    /// from or writing to this slot on the current thread would deadlock.
+    pub fn written_on_this_thread(&self) -> bool {
+        let address = self.address();
+        WRITING
+            .try_with(|writing| writing.borrow().contains(&address))
+            .unwrap_or(false)
+    }
+
+    /// Try to get a reference to the value in this slot. Returns `None` if the slot is empty, or
+    /// if the current thread is holding a write guard to it, as reading it would deadlock.
+    pub fn try_read(&self) -> Option<MappedRwLockReadGuard<'_, T>> {
+        if self.written_on_this_thread() {
+            return None;
+        }
+
+        RwLockReadGuard::try_map(self.0.read(), Option::as_ref).ok()
+    }
+
+    /// Get a reference to the value in this slot.
+    ///
+    /// # Panics
+    /// If the slot is empty, or if the current thread is holding a write guard to it, this will
+    /// panic.
+    pub fn read(&self) -> MappedRwLockReadGuard<'_, T> {
+        self.try_read()
+            .unwrap_or_else(|| panic!("{}", self.error()))
+    }
+
+    /// Try to get a mutable reference to the value in this slot. Returns `None` if the slot is
+    /// empty, or if the current thread is already holding a write guard to it.
+    pub fn try_write(&self) -> Option<SlotWriteGuard<'_, T>> {
+        if self.written_on_this_thread() {
+            return None;
+        }
+
+        RwLockWriteGuard::try_map(self.0.write(), Option::as_mut)
+            .ok()
+            .map(|guard| SlotWriteGuard::new(self, guard))
+    }
+
+    /// Get a mutable reference to the value in this slot.
+    ///
+    /// # Panics
+    /// If the slot is empty, or if the current thread is already holding a write guard to it,
+    /// this will panic.
+    pub fn write(&self) -> SlotWriteGuard<'_, T> {
+        self.try_write()
+            .unwrap_or_else(|| panic!("{}", self.error()))
+    }
+
+    fn error(&self) -> &'static str {
+        if self.written_on_this_thread() {
+            WOULD_DEADLOCK_ERROR
+        } else {
+            UNINITIALIZED_ERROR
+        }
+    }
+
+    /// Lock this slot for writing until [`Self::unlock_after_fork`] is called, so that no other
+    /// thread holds a guard to it while forking.
+    #[cfg(feature = "fork-safety")]
+    pub(crate) fn lock_for_fork(&self) {
+        core::mem::forget(self.0.write())
+    }
+
+    /// Unlock this slot after forking, in either the parent or the child process.
+    ///
+    /// # Safety
+    /// [`Self::lock_for_fork`] must have been called right before forking.
+    #[cfg(feature = "fork-safety")]
+    pub(crate) unsafe fn unlock_after_fork(&self) {
+        self.0.force_unlock_write()
+    }
+
+    /// Empty this slot.
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn uninstall(&self) {
+        drop(self.take())
+    }
+
+    /// Take the value out of this slot, if there is any.
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn take(&self) -> Option<T> {
+        let mut slot = self.lock();
+        self.1.store(false, Ordering::Relaxed);
+        self.bump_generation();
+        slot.take()
+    }
+
+    /// Install a new value into this slot for the duration of the scope. See [`SlotScopeGuard`].
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn scope(&self, value: impl Into<T>) -> SlotScopeGuard<'_, T> {
+        self.scope_dyn(value.into())
+    }
+
+    /// Install a new value into this slot for the duration of the scope. See [`SlotScopeGuard`].
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn scope_dyn(&self, value: T) -> SlotScopeGuard<'_, T> {
+        let mut slot = self.lock();
+        self.1.store(false, Ordering::Relaxed);
+        let last_generation = self.2.load(Ordering::Relaxed);
+        let generation = self.bump_generation();
+        SlotScopeGuard {
+            slot: self,
+            last_value: slot.replace(value),
+            last_generation,
+            generation,
+        }
+    }
+
+    /// Get a reference to the value in this slot, installing the one `default` returns if the slot
+    /// is empty.
+    ///
+    /// # Panics
+    /// If the current thread is holding a write guard to this slot, this will panic.
+    pub fn read_or_else(&self, default: impl FnOnce() -> T) -> MappedRwLockReadGuard<'_, T> {
+        let mut slot = self.lock();
+        slot.get_or_insert_with(default);
+
+        // downgrading makes sure that nothing can uninstall it before we read it
+        RwLockReadGuard::map(RwLockWriteGuard::downgrade(slot), |slot| {
+            slot.as_ref().unwrap()
+        })
+    }
+
+    /// Get a mutable reference to the value in this slot, installing the one `default` returns if
+    /// the slot is empty.
+    ///
+    /// # Panics
+    /// If the current thread is already holding a write guard to this slot, this will panic.
+    pub fn write_or_else(&self, default: impl FnOnce() -> T) -> SlotWriteGuard<'_, T> {
+        let guard = RwLockWriteGuard::map(self.lock(), |slot| slot.get_or_insert_with(default));
+        SlotWriteGuard::new(self, guard)
+    }
+}
+
+impl<T> Default for GlobalSlot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for GlobalSlot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("GlobalSlot")
+            .field("installed", &self.0.read().is_some())
+            .finish()
+    }
+}
+
+/// Marks a slot as being written to by the current thread until this is dropped.
+struct Writing(usize);
+
+impl Writing {
+    fn new<T>(slot: &GlobalSlot<T>) -> Self {
+        let address = slot.address();
+        let _ = WRITING.try_with(|writing| writing.borrow_mut().push(address));
+        Self(address)
+    }
+}
+
+impl Drop for Writing {
+    fn drop(&mut self) {
+        let _ = WRITING.try_with(|writing| {
+            let mut writing = writing.borrow_mut();
+
+            if let Some(index) = writing.iter().rposition(|address| *address == self.0) {
+                writing.remove(index);
+            }
+        });
+    }
+}
+
+/// A mutable reference to the value in a slot. While this is alive, reading from or writing to the
+/// slot on the same thread fails instead of deadlocking.
+pub struct SlotWriteGuard<'a, T> {
+    guard: MappedRwLockWriteGuard<'a, T>,
+    _writing: Writing,
+}
+
+impl<'a, T> SlotWriteGuard<'a, T> {
+    fn new(slot: &'a GlobalSlot<T>, guard: MappedRwLockWriteGuard<'a, T>) -> Self {
+        Self {
+            guard,
+            _writing: Writing::new(slot),
+        }
+    }
+}
+
+impl<T> Deref for SlotWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<T> DerefMut for SlotWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SlotWriteGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.guard, f)
+    }
+}
+
+/// Restores the previous value of a slot when dropped, or empties the slot if there wasn't any,
+/// but only if the slot wasn't installed into, replaced or taken by something else in the
+/// meantime; in that case, the newer value is left in place.
+///
+/// The previous value is stored inside of the guard, so scopes can be nested, as long as they are
+/// dropped in reverse order.
+///
+/// # Panics
+/// If the current thread is holding a write guard to the slot when this is dropped, this will
+/// panic.
+pub struct SlotScopeGuard<'a, T> {
+    slot: &'a GlobalSlot<T>,
+    last_value: Option<T>,
+
+    /// The generation of the slot before the value of this guard was installed, which it's put
+    /// back to when the previous value is restored, so that an enclosing guard still restores its
+    /// own previous value.
+    last_generation: usize,
+
+    /// The generation of the slot right after the value of this guard was installed.
+    generation: usize,
+}
+
+impl<T> SlotScopeGuard<'_, T> {
+    /// Whether or not there was a value in the slot before this scope.
+    pub fn installed_previously(&self) -> bool {
+        self.last_value.is_some()
+    }
+}
+
+impl<T> fmt::Debug for SlotScopeGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SlotScopeGuard")
+            .field("installed_previously", &self.installed_previously())
+            .finish()
+    }
+}
+
+impl<T> Drop for SlotScopeGuard<'_, T> {
+    fn drop(&mut self) {
+        let mut slot = self.slot.lock();
+
+        if self.slot.2.load(Ordering::Relaxed) == self.generation {
+            self.slot.1.store(false, Ordering::Relaxed);
+            self.slot.2.store(self.last_generation, Ordering::Relaxed);
+            *slot = self.last_value.take();
+        }
+    }
+}
+
+/// A drop strategy which forwards every drop error to the drop strategy installed in a slot.
+///
+/// If the slot is empty, the drop error is returned back with some context.
+#[cfg_attr(feature = "derives", derive(Debug, Copy, Clone))]
+pub struct SlotForwardStrategy<'a>(pub &'a GlobalSlot<Box<dyn GlobalDynFallibleTryDropStrategy>>);
+
+impl FallibleTryDropStrategy for SlotForwardStrategy<'_> {
+    type Error = anyhow::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        match self.0.try_read() {
+            Some(strategy) => strategy.dyn_try_handle_error(error),
+            None => Err(error.context(UNINITIALIZED_ERROR)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::AdHocFallibleDropStrategy;
+    use anyhow::anyhow;
+    use std::string::{String, ToString};
+    use std::sync::Mutex;
+    use std::vec::Vec;
+
+    static AUDIT: GlobalSlot<Box<dyn GlobalDynFallibleTryDropStrategy>> = GlobalSlot::new();
+    static AUDITED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    fn audit(prefix: &'static str) -> Box<dyn GlobalDynFallibleTryDropStrategy> {
+        Box::new(AdHocFallibleDropStrategy(move |error: anyhow::Error| {
+            AUDITED
+                .lock()
+                .unwrap()
+                .push(std::format!("{prefix}: {error}"));
+            Ok::<_, anyhow::Error>(())
+        }))
+    }
+
+    #[test]
+    fn test_custom_slot() {
+        let forward = SlotForwardStrategy(&AUDIT);
+        let error = forward.try_handle_error(anyhow!("too early")).unwrap_err();
+        assert_eq!(error.to_string(), UNINITIALIZED_ERROR);
+        assert_eq!(error.root_cause().to_string(), "too early");
+
+        AUDIT.install_dyn(audit("audit"));
+
+        // fans the error out to both the local log and the audit slot.
+        let local = Mutex::new(Vec::new());
+        let fan_out = AdHocFallibleDropStrategy(|error: anyhow::Error| {
+            local.lock().unwrap().push(error.to_string());
+            forward.try_handle_error(error)
+        });
+        fan_out.try_handle_error(anyhow!("first")).unwrap();
+
+        {
+            let guard = AUDIT.scope_dyn(audit("scoped"));
+            assert!(guard.installed_previously());
+            fan_out.try_handle_error(anyhow!("second")).unwrap();
+        }
+
+        fan_out.try_handle_error(anyhow!("third")).unwrap();
+        assert!(AUDIT.take().is_some());
+        assert!(AUDIT.try_read().is_none());
+
+        assert_eq!(*local.lock().unwrap(), ["first", "second", "third"]);
+        assert_eq!(
+            *AUDITED.lock().unwrap(),
+            ["audit: first", "scoped: second", "audit: third"]
+        );
+    }
+
+    #[test]
+    fn test_scope_leaves_newer_value() {
+        let slot = GlobalSlot::<usize>::new();
+        slot.install(1usize);
+
+        {
+            let _outer = slot.scope(2usize);
+
+            {
+                let _inner = slot.scope(3usize);
+                assert_eq!(*slot.read(), 3);
+            }
+
+            assert_eq!(*slot.read(), 2);
+            slot.install(4usize);
+        }
+
+        assert_eq!(*slot.read(), 4);
+
+        {
+            let _guard = slot.scope(5usize);
+            assert!(slot.take().is_some());
+        }
+
+        assert!(slot.try_read().is_none());
+    }
+
+    #[test]
+    fn test_install_provisionally_if_empty() {
+        let slot = GlobalSlot::<usize>::new();
+        assert!(slot.install_provisionally_if_empty(|| 1));
+        assert!(!slot.install_provisionally_if_empty(|| unreachable!()));
+        assert_eq!(*slot.read(), 1);
+        assert!(slot.is_provisional());
+
+        slot.install(2usize);
+        assert!(!slot.is_provisional());
+        assert!(!slot.install_provisionally_if_empty(|| unreachable!()));
+        assert_eq!(*slot.read(), 2);
+
+        slot.uninstall();
+        assert!(!slot.is_provisional());
+    }
+
+    #[test]
+    fn test_install_provisionally_if_empty_reentrant() {
+        let slot = GlobalSlot::<usize>::new();
+        assert!(slot.install_provisionally_if_empty(|| {
+            assert!(
+                slot.try_read().is_none(),
+                "read the slot while installing into it"
+            );
+            1
+        }));
+        assert_eq!(*slot.read(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "using the global slot would deadlock")]
+    fn test_install_provisionally_if_empty_while_writing() {
+        let slot = GlobalSlot::<usize>::new();
+        slot.install(1usize);
+        let _guard = slot.write();
+        slot.install_provisionally_if_empty(|| 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "using the global slot would deadlock")]
+    fn test_replace_while_writing() {
+        let slot = GlobalSlot::<usize>::new();
+        slot.install(1usize);
+        let _guard = slot.write();
+        slot.replace(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "using the global slot would deadlock")]
+    fn test_take_while_writing() {
+        let slot = GlobalSlot::<usize>::new();
+        slot.install(1usize);
+        let _guard = slot.write();
+        slot.take();
+    }
+
+    #[test]
+    fn test_read_or_else() {
+        let slot = GlobalSlot::<usize>::new();
+        assert_eq!(*slot.read_or_else(|| 1), 1);
+        assert_eq!(*slot.read_or_else(|| 2), 1);
+        *slot.write() += 1;
+        assert_eq!(*slot.read(), 2);
+        slot.uninstall();
+        assert!(slot.try_write().is_none());
+    }
+}
@@ -1,18 +1,124 @@
+use crate::handlers::{HandlerKind, ScopeKind};
 use std::error::Error;
 use std::fmt;
 
 /// This error occurs when an attempt to get a drop strategy is made before it is initialized.
+///
+/// It also occurs when the drop strategy can't be read because the current thread is writing to
+/// it, which would otherwise deadlock. In that case, its source is a [`WouldDeadlockError`].
 #[cfg_attr(
     feature = "derives",
     derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
 )]
-#[derive(Debug)]
-pub struct UninitializedError(pub(crate) ());
+pub struct UninitializedError {
+    handler: HandlerKind,
+    scope: ScopeKind,
+    would_deadlock: Option<WouldDeadlockError>,
+}
+
+impl UninitializedError {
+    pub(crate) const fn new(handler: HandlerKind, scope: ScopeKind) -> Self {
+        Self {
+            handler,
+            scope,
+            would_deadlock: None,
+        }
+    }
+
+    #[cfg(feature = "global")]
+    pub(crate) const fn new_would_deadlock(handler: HandlerKind, scope: ScopeKind) -> Self {
+        Self {
+            handler,
+            scope,
+            would_deadlock: Some(WouldDeadlockError { handler, scope }),
+        }
+    }
+
+    /// The handler which was not initialized.
+    pub const fn handler(&self) -> HandlerKind {
+        self.handler
+    }
+
+    /// The scope of the handler which was not initialized.
+    pub const fn scope(&self) -> ScopeKind {
+        self.scope
+    }
+}
+
+impl fmt::Debug for UninitializedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("UninitializedError");
+        debug
+            .field("handler", &self.handler)
+            .field("scope", &self.scope);
+
+        if let Some(would_deadlock) = &self.would_deadlock {
+            debug.field("would_deadlock", would_deadlock);
+        }
 
-impl Error for UninitializedError {}
+        debug.finish()
+    }
+}
+
+impl Error for UninitializedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.would_deadlock
+            .as_ref()
+            .map(|error| error as &(dyn Error + 'static))
+    }
+}
 
 impl fmt::Display for UninitializedError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_str("the drop strategy is not initialized yet")
+        match self.would_deadlock {
+            Some(_) => write!(
+                f,
+                "the {} {} drop strategy can't be used right now",
+                self.scope, self.handler
+            ),
+            None => write!(
+                f,
+                "the {} {} drop strategy is not initialized yet",
+                self.scope, self.handler
+            ),
+        }
+    }
+}
+
+/// This error occurs when an attempt to get a global drop strategy is made on a thread which is
+/// holding a write guard to it, e.g. because a value failed to drop while the drop strategy was
+/// being modified. Reading it would deadlock.
+#[cfg_attr(
+    feature = "derives",
+    derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Debug)]
+pub struct WouldDeadlockError {
+    handler: HandlerKind,
+    scope: ScopeKind,
+}
+
+impl WouldDeadlockError {
+    /// The handler which would deadlock.
+    pub const fn handler(&self) -> HandlerKind {
+        self.handler
+    }
+
+    /// The scope of the handler which would deadlock.
+    pub const fn scope(&self) -> ScopeKind {
+        self.scope
+    }
+}
+
+impl Error for WouldDeadlockError {}
+
+impl fmt::Display for WouldDeadlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "using the {} {} drop strategy would deadlock, since this thread is holding a write \
+             guard to it",
+            self.scope, self.handler
+        )
     }
 }
@@ -0,0 +1,226 @@
+//! Interning of label strings, so that they can be compared and stored as a small [`Copy`] index
+//! instead of hashing and cloning strings.
+
+use core::cell::RefCell;
+use core::fmt;
+use core::hash::{BuildHasherDefault, Hasher};
+use std::boxed::Box;
+use std::collections::HashMap;
+use std::string::ToString;
+use std::sync::{OnceLock, PoisonError, RwLock};
+use std::thread_local;
+use std::vec::Vec;
+
+/// An interned string. Two labels are equal if and only if the strings they were interned from are
+/// equal.
+///
+/// Labels are created by [`intern`], and stay valid for the rest of the program.
+#[cfg_attr(feature = "derives", derive(Ord, PartialOrd))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Label(u32);
+
+impl Label {
+    /// Get the string this label was interned from.
+    pub fn as_str(self) -> &'static str {
+        arena()
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .strings[self.0 as usize]
+    }
+
+    /// Get the index of this label in the global arena.
+    pub const fn index(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Debug for Label {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Label").field(&self.as_str()).finish()
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Default)]
+struct Arena {
+    strings: Vec<&'static str>,
+    labels: HashMap<&'static str, Label>,
+}
+
+fn arena() -> &'static RwLock<Arena> {
+    static ARENA: OnceLock<RwLock<Arena>> = OnceLock::new();
+    ARENA.get_or_init(Default::default)
+}
+
+/// Intern the given string, returning the same [`Label`] every time it's called with an equal
+/// string.
+///
+/// The arena is append only, so every distinct string interned is kept around for the rest of the
+/// program. Only intern strings which come from a small set, such as type names or categories.
+pub fn intern(string: &str) -> Label {
+    let arena = arena();
+
+    if let Some(label) = arena
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .labels
+        .get(string)
+    {
+        return *label;
+    }
+
+    let mut arena = arena.write().unwrap_or_else(PoisonError::into_inner);
+
+    // another thread may have interned the same string between releasing the read lock and
+    // acquiring the write lock.
+    if let Some(label) = arena.labels.get(string) {
+        return *label;
+    }
+
+    let index = u32::try_from(arena.strings.len()).expect("too many labels interned");
+    let string: &'static str = Box::leak(string.to_string().into_boxed_str());
+    let label = Label(index);
+    arena.strings.push(string);
+    arena.labels.insert(string, label);
+    label
+}
+
+/// Hashes the addresses of the strings cached by [`intern_static`]. They can't be picked by an
+/// attacker, so they don't need the DoS resistance of the default hasher.
+#[derive(Default)]
+struct AddressHasher(u64);
+
+impl Hasher for AddressHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.write_u64(u64::from(*byte))
+        }
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64)
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = (self.0.rotate_left(5) ^ value).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95)
+    }
+}
+
+type BuildAddressHasher = BuildHasherDefault<AddressHasher>;
+
+/// Like [`intern`], but remembers the label of `string` by its address on the current thread, so
+/// that interning the same `&'static str` again, such as a type name, only hashes its address
+/// instead of the whole string.
+pub fn intern_static(string: &'static str) -> Label {
+    thread_local! {
+        static CACHE: RefCell<HashMap<(usize, usize), Label, BuildAddressHasher>> =
+            RefCell::new(HashMap::default());
+    }
+
+    let key = (string.as_ptr() as usize, string.len());
+    CACHE
+        .try_with(|cache| {
+            if let Some(label) = cache.borrow().get(&key) {
+                return *label;
+            }
+
+            let label = intern(string);
+            cache.borrow_mut().insert(key, label);
+            label
+        })
+        .unwrap_or_else(|_| intern(string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::format;
+    use std::string::String;
+    use std::sync::Barrier;
+    use std::thread;
+
+    #[test]
+    fn test_intern_is_idempotent() {
+        let label = intern("labels::test_intern_is_idempotent");
+        assert_eq!(
+            label,
+            intern(&String::from("labels::test_intern_is_idempotent"))
+        );
+        assert_eq!(label.as_str(), "labels::test_intern_is_idempotent");
+        assert_eq!(
+            label.to_string(),
+            "labels::test_intern_is_idempotent",
+            "display should write the interned string"
+        );
+    }
+
+    #[test]
+    fn test_distinct_strings_are_distinct_labels() {
+        let a = intern("labels::test_distinct_strings_are_distinct_labels::a");
+        let b = intern("labels::test_distinct_strings_are_distinct_labels::b");
+        assert_ne!(a, b);
+        assert_ne!(a.index(), b.index());
+    }
+
+    #[test]
+    fn test_intern_static() {
+        let string = "labels::test_intern_static";
+        let label = intern_static(string);
+        assert_eq!(label, intern_static(string));
+        assert_eq!(label, intern(&String::from(string)));
+    }
+
+    #[test]
+    fn test_concurrent_interning() {
+        const THREADS: usize = 8;
+        const STRINGS: usize = 64;
+
+        let barrier = Barrier::new(THREADS);
+        let results: Vec<Vec<Label>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        (0..STRINGS)
+                            .map(|index| {
+                                intern(&format!("labels::test_concurrent_interning::{index}"))
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        for labels in &results[1..] {
+            assert_eq!(
+                labels, &results[0],
+                "threads disagree on the interned labels"
+            );
+        }
+
+        for (index, label) in results[0].iter().enumerate() {
+            assert_eq!(
+                label.as_str(),
+                format!("labels::test_concurrent_interning::{index}")
+            );
+        }
+
+        let mut indices: Vec<_> = results[0].iter().map(|label| label.index()).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        assert_eq!(indices.len(), STRINGS, "distinct strings shared a label");
+    }
+}
@@ -0,0 +1,21 @@
+//! What to do with a drop error when there's nothing left which can handle it.
+
+use std::fmt;
+use std::io::{self, Write};
+use std::thread;
+
+/// Report the message to standard error. If even that fails, the message is lost.
+pub(crate) fn report(message: fmt::Arguments) {
+    let _ = writeln!(io::stderr(), "error: {}", message);
+}
+
+/// Panic with the message, unless this thread is already panicking. Panicking while unwinding
+/// aborts the whole process, so in that case the message is reported to standard error instead.
+#[track_caller]
+pub(crate) fn panic_unless_unwinding(message: fmt::Arguments) {
+    if thread::panicking() {
+        report(message)
+    } else {
+        panic!("{}", message)
+    }
+}
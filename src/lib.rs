@@ -11,6 +11,9 @@ extern crate shrinkwraprs;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod prelude;
 
 pub mod drop_strategies;
@@ -27,6 +30,9 @@ mod global_crate_root;
 #[cfg(any(feature = "global", feature = "thread-local"))]
 pub use global_crate_root::*;
 
+#[cfg(feature = "async-flush")]
+pub use flush::flush_all_async;
+
 #[cfg(not(any(feature = "global", feature = "thread-local")))]
 pub use self::PureTryDrop as TryDrop;
 
@@ -36,12 +42,63 @@ pub use self::ImpureTryDrop as TryDrop;
 #[cfg(any(feature = "__tests", test))]
 pub mod test_utils;
 
+#[cfg(any(feature = "test-utils", test))]
+pub mod verify;
+
 #[cfg(any(feature = "global", feature = "thread-local"))]
 pub mod handlers;
 
 pub mod adapters;
 
-use adapters::DropAdapter;
+#[cfg(feature = "std")]
+pub mod batch;
+
+#[cfg(feature = "std")]
+pub mod debugging;
+
+#[cfg(feature = "std")]
+pub mod drop_context;
+
+#[cfg(any(feature = "error-telemetry", test))]
+pub mod error_telemetry;
+
+#[cfg(feature = "async-flush")]
+pub mod flush;
+
+#[cfg(feature = "std")]
+pub mod fmt;
+
+#[cfg(all(unix, feature = "fork-safety"))]
+pub mod fork_safety;
+
+#[cfg(feature = "std")]
+pub mod labels;
+
+#[cfg(feature = "std")]
+mod last_resort;
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+pub mod on_drop;
+
+#[cfg(feature = "replay")]
+pub mod replay;
+
+#[cfg(feature = "std")]
+pub mod sampling;
+
+#[cfg(feature = "std")]
+pub mod suppress;
+
+#[cfg(feature = "std")]
+pub mod tier;
+
+use adapters::{DropAdapter, MapErrDropAdapter, SealedDropAdapter};
+
+#[cfg(feature = "std")]
+use adapters::{DebugSnapshotDropAdapter, PanicToErrorAdapter};
+
+#[cfg(any(feature = "global", feature = "thread-local"))]
+use adapters::WithStrategies;
 
 #[allow(dead_code)]
 const LOAD_ORDERING: Ordering = Ordering::Acquire;
@@ -59,6 +116,35 @@ const STORE_ORDERING: Ordering = Ordering::Release;
 /// to be run, you need to put your type in a [`DropAdapter`].
 ///
 /// An easier way to make your type droppable is to call [`PureTryDrop::adapt`] on it.
+///
+/// # Examples
+/// ```rust
+/// use try_drop::drop_strategies::{DefaultNoOp, DefaultPanic};
+/// use try_drop::PureTryDrop;
+///
+/// struct Connection;
+///
+/// impl PureTryDrop for Connection {
+///     type Error = try_drop::Error;
+///     type FallbackTryDropStrategy = DefaultPanic;
+///     type TryDropStrategy = DefaultNoOp;
+///
+///     fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+///         &DefaultPanic
+///     }
+///
+///     fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+///         &DefaultNoOp
+///     }
+///
+///     unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+///         anyhow::bail!("failed to close the connection")
+///     }
+/// }
+///
+/// // the error is handled by `DefaultNoOp`, which ignores it
+/// drop(Connection.adapt());
+/// ```
 pub trait PureTryDrop {
     /// The type of the error that may occur during drop.
     type Error: Into<anyhow::Error>;
@@ -117,6 +203,9 @@ pub trait PureTryDrop {
     ///
     /// This is because [`Self`] implicitly get copied.
     /// <sup><i>I may or may not have spent a large amount of time trying to get rid of this "soundness hole".</i></sup>
+    ///
+    /// To rule this out, use [`PureTryDrop::adapt_sealed`] instead, which doesn't give out access to
+    /// the inner value.
     fn adapt(self) -> DropAdapter<Self>
     where
         Self: Sized,
@@ -124,6 +213,49 @@ pub trait PureTryDrop {
         DropAdapter(self)
     }
 
+    /// Adapts this type to take advantage of the specified try drop strategies, without giving
+    /// out access to it afterwards. See [`SealedDropAdapter`] for why this is recommended for
+    /// types which implement [`Copy`].
+    fn adapt_sealed(self) -> SealedDropAdapter<Self>
+    where
+        Self: Sized,
+    {
+        SealedDropAdapter::new(self)
+    }
+
+    /// Adapts this type to take advantage of the specified try drop strategies, mapping the drop
+    /// error with `f` before it reaches them. This is useful to add context to the drop error, for
+    /// example with [`anyhow::Error::context`].
+    fn adapt_map_err<F>(self, f: F) -> MapErrDropAdapter<Self, F>
+    where
+        Self: Sized,
+        F: Fn(anyhow::Error) -> anyhow::Error,
+    {
+        MapErrDropAdapter::new(self, f)
+    }
+
+    /// Adapts this type to take advantage of the specified try drop strategies, attaching a
+    /// snapshot of its [`Debug`](core::fmt::Debug) representation to the drop error. This is
+    /// opt-in, since formatting the value may be expensive or leak sensitive data. See
+    /// [`DebugSnapshotDropAdapter`] for more information.
+    #[cfg(feature = "std")]
+    fn adapt_with_debug_snapshot(self) -> DebugSnapshotDropAdapter<Self>
+    where
+        Self: Sized + core::fmt::Debug,
+    {
+        DebugSnapshotDropAdapter::new(self)
+    }
+
+    /// Adapts this type to take advantage of the specified try drop strategies, turning panics in
+    /// its destructor into drop errors. See [`PanicToErrorAdapter`] for more information.
+    #[cfg(feature = "std")]
+    fn adapt_catching(self) -> DropAdapter<PanicToErrorAdapter<Self>>
+    where
+        Self: Sized,
+    {
+        DropAdapter(PanicToErrorAdapter::new(self))
+    }
+
     /// Execute the fallible destructor for this type. This function is unsafe because if this is
     /// called outside of a [`Drop::drop`] context, once the scope of the object implementing trait
     /// ends, this function will be called twice, potentially resulting in a double-free.
@@ -162,6 +294,69 @@ pub trait ImpureTryDrop {
     /// safe to call multiple times. If the `unsafe` seems ugly to you, you can use
     /// [`RepeatableTryDrop::safe_try_drop`].
     unsafe fn try_drop(&mut self) -> Result<(), Self::Error>;
+
+    /// Use the given try drop strategies for this value instead of the primary and fallback
+    /// handlers, without having to implement [`PureTryDrop`] for it.
+    ///
+    /// The result still has to be adapted, see [`WithStrategies`].
+    fn with_strategies<S, F>(
+        self,
+        try_drop_strategy: S,
+        fallback_try_drop_strategy: F,
+    ) -> WithStrategies<Self, S, F>
+    where
+        Self: Sized,
+        S: FallibleTryDropStrategy,
+        F: TryDropStrategy,
+    {
+        WithStrategies::new(self, try_drop_strategy, fallback_try_drop_strategy)
+    }
+}
+
+/// A trait for types which can be dropped, but which may fail to do so, and whose fallible
+/// destructor is harmless to call early or more than once, e.g. flushing a buffer. Unlike
+/// [`PureTryDrop`] and [`ImpureTryDrop`], implementing this trait doesn't require writing any
+/// `unsafe`, which makes it usable from crates with `#![forbid(unsafe_code)]`.
+///
+/// With the `global` or `thread-local` features, every type which implements this trait also
+/// implements [`ImpureTryDrop`] and [`RepeatableTryDrop`], so it can be adapted to use the
+/// handlers. To use specific try drop strategies instead, wrap it in a
+/// [`WithStrategies`](adapters::WithStrategies).
+///
+/// # Requirements
+/// [`SafeTryDrop::try_drop`] may be called while the value is still in use, and may be called
+/// again afterwards. Since it's a safe function, doing so can never cause undefined behavior, but
+/// it should leave the value in a state where it's still usable, e.g. by making the following
+/// calls a no-op.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "global")] {
+/// use try_drop::{PureTryDrop, SafeTryDrop};
+///
+/// struct Buffer(Vec<u8>);
+///
+/// impl SafeTryDrop for Buffer {
+///     type Error = std::io::Error;
+///
+///     fn try_drop(&mut self) -> Result<(), Self::Error> {
+///         // a second flush writes nothing
+///         std::io::Write::write_all(&mut std::io::sink(), &self.0)?;
+///         self.0.clear();
+///         Ok(())
+///     }
+/// }
+///
+/// // errors are handled by the primary and fallback handlers
+/// drop(Buffer(b"hello".to_vec()).adapt());
+/// # }
+/// ```
+pub trait SafeTryDrop {
+    /// The type of the error that may occur during drop.
+    type Error: Into<anyhow::Error>;
+
+    /// Execute the fallible destructor for this type. See the requirements on [`SafeTryDrop`].
+    fn try_drop(&mut self) -> Result<(), Self::Error>;
 }
 
 /// A trait which signifies a try drop strategy which can fail.
@@ -171,20 +366,114 @@ pub trait FallibleTryDropStrategy {
 
     /// Try and handle a drop error.
     fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error>;
+
+    /// Try and handle many drop errors at once, handing the error of every drop error which
+    /// couldn't be handled to `failed`. By default, this hands each of them to
+    /// [`FallibleTryDropStrategy::try_handle_error`]; strategies which can do better, e.g. by
+    /// locking a writer once for the whole batch, can override it.
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = anyhow::Error>,
+        failed: &mut dyn FnMut(Self::Error),
+    ) {
+        for error in errors {
+            if let Err(error) = self.try_handle_error(error) {
+                failed(error)
+            }
+        }
+    }
+
+    /// Called after this try drop strategy was uninstalled as a handler by
+    /// `handlers::uninstall_all_ordered`, e.g. to flush buffered errors. Uninstalling, replacing or
+    /// taking a single handler doesn't call this, as the try drop strategy may still be in use.
+    /// Does nothing by default.
+    fn on_uninstall(&self) {}
+
+    /// Called in the child process after `fork()` if this try drop strategy was installed as a
+    /// global handler and `fork_safety::install` was called, right before it's replaced and leaked.
+    /// Only the thread which forked exists in the child, so this must not wait for other threads.
+    /// Does nothing by default.
+    fn on_fork_child(&self) {}
 }
 
 /// A trait which signifies a try drop strategy which can fail. Can be dynamically dispatched.
 pub trait DynFallibleTryDropStrategy {
     /// Try to handle the drop error.
     fn dyn_try_handle_error(&self, error: anyhow::Error) -> anyhow::Result<()>;
+
+    /// Try to handle many drop errors at once. See
+    /// [`FallibleTryDropStrategy::try_handle_errors_batch`].
+    fn dyn_try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = anyhow::Error>,
+        failed: &mut dyn FnMut(anyhow::Error),
+    );
+
+    /// Called after this try drop strategy was uninstalled as a handler. See
+    /// [`FallibleTryDropStrategy::on_uninstall`]. Does nothing by default.
+    fn dyn_on_uninstall(&self) {}
+
+    /// Called in the child process after `fork()`. See [`FallibleTryDropStrategy::on_fork_child`].
+    /// Does nothing by default.
+    fn dyn_on_fork_child(&self) {}
+
+    /// Get the type name of the concrete try drop strategy, even behind a trait object. This is
+    /// intended for diagnostics only.
+    fn type_name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
 }
 
 impl<T: FallibleTryDropStrategy> DynFallibleTryDropStrategy for T {
     fn dyn_try_handle_error(&self, error: anyhow::Error) -> anyhow::Result<()> {
         self.try_handle_error(error).map_err(Into::into)
     }
+
+    fn dyn_try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = anyhow::Error>,
+        failed: &mut dyn FnMut(anyhow::Error),
+    ) {
+        self.try_handle_errors_batch(errors, &mut |error| failed(error.into()))
+    }
+
+    fn dyn_on_uninstall(&self) {
+        self.on_uninstall()
+    }
+
+    fn dyn_on_fork_child(&self) {
+        self.on_fork_child()
+    }
 }
 
+/// Chaining methods for types which implement [`FallibleTryDropStrategy`].
+pub trait FallibleTryDropStrategyExt: FallibleTryDropStrategy + Sized {
+    /// Hand the errors this try drop strategy fails to handle to `fallback`. This is the same as
+    /// creating a [`FallbackTryDropStrategyHandler`] from the two.
+    ///
+    /// [`FallbackTryDropStrategyHandler`]: adapters::FallbackTryDropStrategyHandler
+    fn or_else<F: TryDropStrategy>(
+        self,
+        fallback: F,
+    ) -> adapters::FallbackTryDropStrategyHandler<F, Self> {
+        adapters::FallbackTryDropStrategyHandler::new(fallback, self)
+    }
+
+    /// Hand the error to `next` as well, but only if this try drop strategy handled it
+    /// successfully. See [`AndThenTryDropStrategyHandler`] for more information.
+    ///
+    /// [`AndThenTryDropStrategyHandler`]: adapters::AndThenTryDropStrategyHandler
+    #[cfg(feature = "alloc")]
+    fn and_then<S: FallibleTryDropStrategy>(
+        self,
+        next: S,
+    ) -> adapters::AndThenTryDropStrategyHandler<Self, S> {
+        adapters::AndThenTryDropStrategyHandler::new(self, next)
+    }
+}
+
+impl<T: FallibleTryDropStrategy> FallibleTryDropStrategyExt for T {}
+
 /// A trait which signifies a try drop strategy which can fail, can be dynamically dispatched, and
 /// can be used as the global try drop strategy.
 #[cfg(feature = "global")]
@@ -220,8 +509,39 @@ impl<T: DynFallibleTryDropStrategy + 'static> ThreadLocalFallibleTryDropStrategy
 pub trait TryDropStrategy {
     /// Handle the drop error.
     fn handle_error(&self, error: anyhow::Error);
+
+    /// Handle many drop errors at once. By default, this hands each of them to
+    /// [`TryDropStrategy::handle_error`]; strategies which can do better can override it.
+    fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = anyhow::Error>) {
+        errors.for_each(|error| self.handle_error(error))
+    }
+
+    /// Called after this try drop strategy was uninstalled as a handler by
+    /// `handlers::uninstall_all_ordered`, e.g. to flush buffered errors. Uninstalling, replacing or
+    /// taking a single handler doesn't call this, as the try drop strategy may still be in use.
+    /// Does nothing by default.
+    fn on_uninstall(&self) {}
+
+    /// Called in the child process after `fork()` if this try drop strategy was installed as a
+    /// global handler and `fork_safety::install` was called, right before it's replaced and leaked.
+    /// Only the thread which forked exists in the child, so this must not wait for other threads.
+    /// Does nothing by default.
+    fn on_fork_child(&self) {}
 }
 
+/// A marker trait which signifies a try drop strategy which is audited to never fail, meaning it
+/// handles every error without reporting it somewhere which can fail, such as the network.
+///
+/// This is required by the `install_checked` functions of the fallback handlers, which are a
+/// stricter, opt-in alternative to their `install` functions.
+///
+/// # Safety
+/// The implementor must guarantee that [`TryDropStrategy::handle_error`] always either returns
+/// normally, panics, or terminates the program. To assume this for a strategy which is defined in
+/// another crate, such as an ad-hoc strategy over a closure which is known to be total, use the
+/// [`AssumeInfallibleAdapter`](adapters::AssumeInfallibleAdapter).
+pub unsafe trait InfallibleStrategy: TryDropStrategy {}
+
 impl<TDS: TryDropStrategy> FallibleTryDropStrategy for TDS {
     type Error = Infallible;
 
@@ -229,19 +549,128 @@ impl<TDS: TryDropStrategy> FallibleTryDropStrategy for TDS {
         self.handle_error(error);
         Ok(())
     }
+
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = anyhow::Error>,
+        _failed: &mut dyn FnMut(Self::Error),
+    ) {
+        self.handle_errors_batch(errors)
+    }
+
+    fn on_uninstall(&self) {
+        TryDropStrategy::on_uninstall(self)
+    }
+
+    fn on_fork_child(&self) {
+        TryDropStrategy::on_fork_child(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Into<anyhow::Error>> FallibleTryDropStrategy
+    for std::boxed::Box<dyn FallibleTryDropStrategy<Error = E>>
+{
+    type Error = E;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        (**self).try_handle_error(error)
+    }
+
+    fn try_handle_errors_batch(
+        &self,
+        errors: &mut dyn Iterator<Item = anyhow::Error>,
+        failed: &mut dyn FnMut(Self::Error),
+    ) {
+        (**self).try_handle_errors_batch(errors, failed)
+    }
+
+    fn on_uninstall(&self) {
+        (**self).on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        (**self).on_fork_child()
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryDropStrategy for std::boxed::Box<dyn TryDropStrategy> {
+    fn handle_error(&self, error: anyhow::Error) {
+        (**self).handle_error(error)
+    }
+
+    fn handle_errors_batch(&self, errors: &mut dyn Iterator<Item = anyhow::Error>) {
+        (**self).handle_errors_batch(errors)
+    }
+
+    fn on_uninstall(&self) {
+        (**self).on_uninstall()
+    }
+
+    fn on_fork_child(&self) {
+        (**self).on_fork_child()
+    }
+}
+
+impl TryDropStrategy for fn(anyhow::Error) {
+    fn handle_error(&self, error: anyhow::Error) {
+        self(error)
+    }
+}
+
+impl<E: Into<anyhow::Error>> FallibleTryDropStrategy for fn(anyhow::Error) -> Result<(), E> {
+    type Error = E;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        self(error)
+    }
+}
+
+/// A trait which signifies a try drop strategy whose error handling is asynchronous. This can
+/// never fail.
+///
+/// This can't be used as a try drop strategy directly; use a bridge in
+/// [`drop_strategies::async_strategy`] to adapt it to a [`TryDropStrategy`] instead.
+///
+/// Implementors may use an `async fn`, as long as the returned future is [`Send`]:
+///
+/// ```ignore
+/// impl AsyncTryDropStrategy for Reporter {
+///     async fn handle_error(&self, error: try_drop::Error) {
+///         self.client.report(error).await
+///     }
+/// }
+/// ```
+#[cfg(feature = "async-strategy")]
+pub trait AsyncTryDropStrategy {
+    /// Handle the drop error asynchronously.
+    fn handle_error(&self, error: anyhow::Error) -> impl core::future::Future<Output = ()> + Send;
 }
 
 /// A trait which signifies a try drop strategy which can be used as the primary or fallback
 /// handler.
 #[cfg(feature = "global")]
 #[cfg(not(feature = "downcast-rs"))]
-pub trait GlobalTryDropStrategy: ThreadSafe + TryDropStrategy {}
+pub trait GlobalTryDropStrategy: ThreadSafe + TryDropStrategy {
+    /// Get the type name of the concrete try drop strategy, even behind a trait object. This is
+    /// intended for diagnostics only.
+    fn type_name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+}
 
 /// A trait which signifies a try drop strategy which can be used as the primary or fallback
 /// handler. Can be downcast.
 #[cfg(feature = "global")]
 #[cfg(feature = "downcast-rs")]
-pub trait GlobalTryDropStrategy: ThreadSafe + downcast_rs::DowncastSync + TryDropStrategy {}
+pub trait GlobalTryDropStrategy: ThreadSafe + downcast_rs::DowncastSync + TryDropStrategy {
+    /// Get the type name of the concrete try drop strategy, even behind a trait object. This is
+    /// intended for diagnostics only.
+    fn type_name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+}
 
 #[cfg(feature = "global")]
 #[cfg(feature = "downcast-rs")]
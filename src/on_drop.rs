@@ -0,0 +1,98 @@
+//! Run a fallible cleanup function when a value goes out of scope, like a scope guard whose
+//! cleanup can fail.
+
+use crate::adapters::DropAdapter;
+use crate::{ImpureTryDrop, PureTryDrop};
+use core::marker::PhantomData;
+
+/// A value which calls a fallible function when it's dropped, handing the error to the primary and
+/// fallback handlers if it fails. Usually created and adapted at once with [`on_drop`].
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct OnDrop<F, E>
+where
+    F: FnMut() -> Result<(), E>,
+    E: Into<anyhow::Error>,
+{
+    /// The function to call.
+    pub f: F,
+    _error: PhantomData<fn() -> E>,
+}
+
+impl<F, E> OnDrop<F, E>
+where
+    F: FnMut() -> Result<(), E>,
+    E: Into<anyhow::Error>,
+{
+    /// Create a new value which calls the given function when it's dropped. It still has to be
+    /// adapted for that to happen; see [`on_drop`] for doing both at once.
+    pub const fn new(f: F) -> Self {
+        Self {
+            f,
+            _error: PhantomData,
+        }
+    }
+}
+
+impl<F, E> ImpureTryDrop for OnDrop<F, E>
+where
+    F: FnMut() -> Result<(), E>,
+    E: Into<anyhow::Error>,
+{
+    type Error = E;
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        (self.f)()
+    }
+}
+
+/// Call the given function once the returned value is dropped. If it fails, the error is handed
+/// to the primary and fallback handlers.
+///
+/// # Examples
+/// ```rust
+/// use std::fs;
+/// use try_drop::on_drop::on_drop;
+///
+/// let _guard = on_drop(|| fs::remove_file("/tmp/try-drop-on-drop-example.lock"));
+/// // ...
+/// // removing the file failed since it was never created, so the primary handler reports it here
+/// ```
+pub fn on_drop<F, E>(f: F) -> DropAdapter<OnDrop<F, E>>
+where
+    F: FnMut() -> Result<(), E>,
+    E: Into<anyhow::Error>,
+{
+    OnDrop::new(f).adapt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::drop_strategies::{AdHocDropStrategy, PanicDropStrategy};
+    use crate::test_utils::GLOBAL_LOCK;
+    use std::io;
+    use std::string::{String, ToString};
+    use std::sync::{Arc, Mutex};
+    use std::vec::Vec;
+
+    #[test]
+    fn test_on_drop() {
+        let _lock = GLOBAL_LOCK.lock();
+        let errors = Arc::new(Mutex::new(Vec::<String>::new()));
+        let e = Arc::clone(&errors);
+        let strategy =
+            AdHocDropStrategy(move |error: crate::Error| e.lock().unwrap().push(error.to_string()));
+        crate::install_global_handlers(strategy, PanicDropStrategy::DEFAULT);
+
+        let mut calls = 0;
+        drop(on_drop(|| {
+            calls += 1;
+            Err(io::Error::new(io::ErrorKind::NotFound, "lock file is gone"))
+        }));
+        assert_eq!(calls, 1);
+        assert_eq!(*errors.lock().unwrap(), ["lock file is gone"]);
+
+        drop(on_drop(|| Ok::<_, io::Error>(())));
+        assert_eq!(errors.lock().unwrap().len(), 1);
+    }
+}
@@ -1,8 +1,8 @@
-//! Most commonly used traits.
+//! Most commonly used traits, along with the most commonly used drop strategies in [`strategies`].
 
 pub use crate::{
-    DynFallibleTryDropStrategy, FallibleTryDropStrategy, PureTryDrop, ThreadSafe, TryDrop,
-    TryDropStrategy,
+    DynFallibleTryDropStrategy, FallibleTryDropStrategy, FallibleTryDropStrategyExt, PureTryDrop,
+    SafeTryDrop, ThreadSafe, TryDrop, TryDropStrategy,
 };
 
 #[cfg(feature = "global")]
@@ -10,3 +10,43 @@ pub use crate::{GlobalDynFallibleTryDropStrategy, GlobalTryDropStrategy};
 
 #[cfg(any(feature = "global", feature = "thread-local"))]
 pub use crate::ImpureTryDrop;
+
+#[cfg(any(
+    feature = "ds-abort",
+    feature = "ds-exit",
+    feature = "ds-noop",
+    feature = "ds-panic",
+    feature = "ds-write",
+    feature = "ds-adhoc",
+    feature = "ds-adhoc-mut"
+))]
+pub use self::strategies::*;
+
+/// Most commonly used drop strategies, each available if its feature is enabled.
+pub mod strategies {
+    #[cfg(feature = "ds-abort")]
+    pub use crate::drop_strategies::AbortDropStrategy;
+
+    #[cfg(feature = "ds-exit")]
+    pub use crate::drop_strategies::ExitDropStrategy;
+
+    #[cfg(feature = "ds-noop")]
+    pub use crate::drop_strategies::NoOpDropStrategy;
+
+    #[cfg(feature = "ds-panic")]
+    pub use crate::drop_strategies::PanicDropStrategy;
+
+    #[cfg(feature = "ds-write")]
+    pub use crate::drop_strategies::WriteDropStrategy;
+
+    #[cfg(feature = "ds-adhoc")]
+    pub use crate::drop_strategies::{
+        AdHocDropStrategy, AdHocFallibleDropStrategy, IntoAdHocDropStrategy,
+        IntoAdHocFallibleDropStrategy,
+    };
+
+    // the conversion traits of the mutable ad-hoc strategies are left out, as their methods have
+    // the same names as the ones above and every `Fn` is also an `FnMut`.
+    #[cfg(feature = "ds-adhoc-mut")]
+    pub use crate::drop_strategies::{AdHocMutDropStrategy, AdHocMutFallibleDropStrategy};
+}
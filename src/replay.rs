@@ -0,0 +1,485 @@
+//! Persist drop errors to disk and load them back later, e.g. to analyze the drop errors of a run
+//! which crashed.
+//!
+//! The [`PersistDropStrategy`] appends every drop error it handles to a file as an [`ReplayRecord`],
+//! one JSON object per line (NDJSON), which [`ReplayRecord::load_ndjson`] reads back. Records are
+//! lossy: only what can be written down is kept, such as the messages of the error and its causes,
+//! not the errors themselves.
+//!
+//! # Examples
+//! ```rust
+//! use try_drop::replay::{ReplayRecord, FsyncPolicy, PersistDropStrategy};
+//! use try_drop::FallibleTryDropStrategy;
+//!
+//! let path = std::env::temp_dir().join("try-drop-replay-example.ndjson");
+//! # let _ = std::fs::remove_file(&path);
+//! let strategy = PersistDropStrategy::open(&path, FsyncPolicy::Always)?;
+//! strategy.try_handle_error(anyhow::anyhow!("failed to flush the buffer"))?;
+//!
+//! // ...after the crash...
+//! let records = ReplayRecord::load_ndjson(&path)?;
+//! assert_eq!(records[0].message, "failed to flush the buffer");
+//! # std::fs::remove_file(&path)?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use crate::adapters::SerializableError;
+use crate::drop_context::{DropContext, ErrorCategory};
+use crate::FallibleTryDropStrategy;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::string::{String, ToString};
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+use std::vec::Vec;
+use std::{format, vec};
+
+/// A drop error written down, as persisted by the [`PersistDropStrategy`].
+///
+/// This is serialized with a version, `"v"`, which is [`ReplayRecord::VERSION`] for the records
+/// written by this version of the crate. Fields which are added later are ignored when records are
+/// loaded by an older version.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ReplayRecord {
+    /// The version of the schema of the record.
+    #[serde(rename = "v")]
+    pub version: u32,
+
+    /// When the error was handled.
+    pub timestamp: SystemTime,
+
+    /// The type name of the value which failed to drop, if it's known. See
+    /// [`DropContext::type_name`].
+    pub type_name: Option<String>,
+
+    /// What kind of error it was.
+    pub category: ReplayCategory,
+
+    /// The message of the error, with the formatting context of the thread it was handled in
+    /// applied. See [`crate::fmt`].
+    pub message: String,
+
+    /// The messages of the causes of the error, outermost first.
+    pub chain: Vec<String>,
+
+    /// A hash of the type name, message and chain, which is the same for errors which look the
+    /// same, across runs. This is useful to group the records of an error which occurred many
+    /// times.
+    pub fingerprint: u64,
+}
+
+impl ReplayRecord {
+    /// The version of the schema of the records written by this version of the crate.
+    pub const VERSION: u32 = 1;
+
+    /// Write down the given drop error, which should be the one being handled right now, as it is
+    /// right now.
+    pub fn new(error: &anyhow::Error) -> Self {
+        let context = DropContext::of(error);
+        let type_name = context.type_name.map(ToString::to_string);
+        let serializable = SerializableError(error);
+        let mut message = serializable.message();
+        crate::fmt::apply(&mut message);
+        let chain: Vec<_> = serializable.chain().collect();
+
+        Self {
+            version: Self::VERSION,
+            timestamp: SystemTime::now(),
+            fingerprint: fingerprint(type_name.as_deref(), &message, &chain),
+            type_name,
+            category: ReplayCategory::from(context.category),
+            message,
+            chain,
+        }
+    }
+
+    /// Load the records in the NDJSON file at the given path, as written by the
+    /// [`PersistDropStrategy`], in the order they were written. A final line which was only
+    /// partially written, e.g. because the program crashed while writing it, is ignored.
+    ///
+    /// # Errors
+    /// If the file can't be read, or if any other line isn't a record, an error is returned.
+    pub fn load_ndjson(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        let contents = fs::read_to_string(path)?;
+        let mut lines: Vec<_> = contents.split('\n').collect();
+        let partial = lines.pop().filter(|line| !line.trim().is_empty());
+        let mut records = Vec::with_capacity(lines.len());
+
+        for (index, line) in lines.into_iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record = serde_json::from_str(line).map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {} isn't an error record: {error}", index + 1),
+                )
+            })?;
+            records.push(record);
+        }
+
+        if let Some(record) = partial.and_then(|line| serde_json::from_str(line).ok()) {
+            records.push(record)
+        }
+
+        Ok(records)
+    }
+}
+
+impl From<&anyhow::Error> for ReplayRecord {
+    fn from(error: &anyhow::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+/// What kind of error a [`ReplayRecord`] is, as it's persisted. See [`ErrorCategory`].
+///
+/// Unlike [`ErrorCategory`], this is serialized the same way regardless of the version of the
+/// crate or of Rust and of the enabled features, e.g. as `{"kind":"io","io_kind":"broken_pipe"}`
+/// in JSON. Categories added later are loaded as [`ReplayCategory::Other`] by older versions.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReplayCategory {
+    /// An I/O error. See [`ReplayCategory::io_kind`].
+    Io {
+        /// The kind of the I/O error in snake case, e.g. `broken_pipe`, or `other` if it's a kind
+        /// this version of the crate doesn't know the name of.
+        io_kind: String,
+    },
+
+    /// A handler wasn't initialized, so the error couldn't be handled by it.
+    Uninitialized,
+
+    /// Any other error.
+    #[serde(other)]
+    Other,
+}
+
+/// The names the kinds of I/O errors are persisted as. These must never change.
+const IO_KINDS: &[(io::ErrorKind, &str)] = &[
+    (io::ErrorKind::NotFound, "not_found"),
+    (io::ErrorKind::PermissionDenied, "permission_denied"),
+    (io::ErrorKind::ConnectionRefused, "connection_refused"),
+    (io::ErrorKind::ConnectionReset, "connection_reset"),
+    (io::ErrorKind::HostUnreachable, "host_unreachable"),
+    (io::ErrorKind::NetworkUnreachable, "network_unreachable"),
+    (io::ErrorKind::ConnectionAborted, "connection_aborted"),
+    (io::ErrorKind::NotConnected, "not_connected"),
+    (io::ErrorKind::AddrInUse, "addr_in_use"),
+    (io::ErrorKind::AddrNotAvailable, "addr_not_available"),
+    (io::ErrorKind::NetworkDown, "network_down"),
+    (io::ErrorKind::BrokenPipe, "broken_pipe"),
+    (io::ErrorKind::AlreadyExists, "already_exists"),
+    (io::ErrorKind::WouldBlock, "would_block"),
+    (io::ErrorKind::NotADirectory, "not_a_directory"),
+    (io::ErrorKind::IsADirectory, "is_a_directory"),
+    (io::ErrorKind::DirectoryNotEmpty, "directory_not_empty"),
+    (io::ErrorKind::ReadOnlyFilesystem, "read_only_filesystem"),
+    (
+        io::ErrorKind::StaleNetworkFileHandle,
+        "stale_network_file_handle",
+    ),
+    (io::ErrorKind::InvalidInput, "invalid_input"),
+    (io::ErrorKind::InvalidData, "invalid_data"),
+    (io::ErrorKind::TimedOut, "timed_out"),
+    (io::ErrorKind::WriteZero, "write_zero"),
+    (io::ErrorKind::StorageFull, "storage_full"),
+    (io::ErrorKind::NotSeekable, "not_seekable"),
+    (io::ErrorKind::QuotaExceeded, "quota_exceeded"),
+    (io::ErrorKind::FileTooLarge, "file_too_large"),
+    (io::ErrorKind::ResourceBusy, "resource_busy"),
+    (io::ErrorKind::ExecutableFileBusy, "executable_file_busy"),
+    (io::ErrorKind::Deadlock, "deadlock"),
+    (io::ErrorKind::CrossesDevices, "crosses_devices"),
+    (io::ErrorKind::TooManyLinks, "too_many_links"),
+    (io::ErrorKind::InvalidFilename, "invalid_filename"),
+    (io::ErrorKind::ArgumentListTooLong, "argument_list_too_long"),
+    (io::ErrorKind::Interrupted, "interrupted"),
+    (io::ErrorKind::Unsupported, "unsupported"),
+    (io::ErrorKind::UnexpectedEof, "unexpected_eof"),
+    (io::ErrorKind::OutOfMemory, "out_of_memory"),
+    (io::ErrorKind::Other, "other"),
+];
+
+impl ReplayCategory {
+    /// The kind of the I/O error, if this is an I/O error of a kind this version of the crate
+    /// knows the name of.
+    pub fn io_kind(&self) -> Option<io::ErrorKind> {
+        match self {
+            Self::Io { io_kind } => IO_KINDS
+                .iter()
+                .find(|(_, name)| name == io_kind)
+                .map(|(kind, _)| *kind),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorCategory> for ReplayCategory {
+    fn from(category: ErrorCategory) -> Self {
+        match category {
+            ErrorCategory::Io(kind) => {
+                let name = IO_KINDS
+                    .iter()
+                    .find(|(known, _)| *known == kind)
+                    .map_or("other", |(_, name)| name);
+                Self::Io {
+                    io_kind: name.to_string(),
+                }
+            }
+            #[cfg(any(feature = "global", feature = "thread-local"))]
+            ErrorCategory::Uninitialized => Self::Uninitialized,
+            ErrorCategory::Other => Self::Other,
+        }
+    }
+}
+
+/// FNV-1a, since unlike the hashers of the standard library, it's guaranteed to stay the same
+/// across runs and versions.
+fn fingerprint(type_name: Option<&str>, message: &str, chain: &[String]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let parts = type_name
+        .into_iter()
+        .chain([message])
+        .chain(chain.iter().map(String::as_str));
+    let mut hash = OFFSET;
+
+    for part in parts {
+        // separate the parts, so that moving text between them changes the hash
+        for byte in part.bytes().chain([0]) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+
+    hash
+}
+
+/// When the [`PersistDropStrategy`] flushes the records it wrote all the way to the disk, so that
+/// they survive the machine crashing as well.
+#[cfg_attr(feature = "derives", derive(Ord, PartialOrd, Hash))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FsyncPolicy {
+    /// Leave it up to the operating system. The records still survive the program crashing.
+    Never,
+
+    /// After every record.
+    Always,
+
+    /// After every given number of records.
+    Every(NonZeroUsize),
+}
+
+#[cfg_attr(feature = "derives", derive(Debug))]
+struct Persisted {
+    file: File,
+    unsynced: usize,
+}
+
+/// A drop strategy which appends every drop error it handles to a file as an [`ReplayRecord`], one
+/// JSON object per line, which can be loaded back with [`ReplayRecord::load_ndjson`].
+///
+/// Every record is written at once to a file opened in append mode, so as long as the operating
+/// system writes it at once too, the records of several strategies, or several processes,
+/// appending to the same file don't interleave.
+#[cfg_attr(feature = "derives", derive(Debug))]
+pub struct PersistDropStrategy {
+    persisted: Mutex<Persisted>,
+
+    /// When to flush the records all the way to the disk.
+    pub fsync: FsyncPolicy,
+}
+
+impl PersistDropStrategy {
+    /// Open the file at the given path to append records to, creating it if it doesn't exist.
+    ///
+    /// # Errors
+    /// If the file can't be opened, an error is returned.
+    pub fn open(path: impl AsRef<Path>, fsync: FsyncPolicy) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(file, fsync))
+    }
+
+    /// Append records to the given file, which should be opened in append mode.
+    pub const fn new(file: File, fsync: FsyncPolicy) -> Self {
+        Self {
+            persisted: Mutex::new(Persisted { file, unsynced: 0 }),
+            fsync,
+        }
+    }
+}
+
+impl FallibleTryDropStrategy for PersistDropStrategy {
+    type Error = io::Error;
+
+    fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+        let mut line = vec![];
+        serde_json::to_writer(&mut line, &ReplayRecord::new(&error))?;
+        line.push(b'\n');
+
+        let mut persisted = self
+            .persisted
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        persisted.file.write_all(&line)?;
+        persisted.unsynced += 1;
+
+        let sync = match self.fsync {
+            FsyncPolicy::Never => false,
+            FsyncPolicy::Always => true,
+            FsyncPolicy::Every(records) => persisted.unsynced >= records.get(),
+        };
+
+        if sync {
+            persisted.file.sync_data()?;
+            persisted.unsynced = 0;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+    use std::path::PathBuf;
+
+    /// A path in the temporary directory which is removed when this is dropped.
+    struct TempPath(PathBuf);
+
+    impl TempPath {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "try-drop-replay-{name}-{}.ndjson",
+                std::process::id()
+            ));
+            let _ = fs::remove_file(&path);
+            Self(path)
+        }
+    }
+
+    impl Drop for TempPath {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = TempPath::new("round-trip");
+        let strategy = PersistDropStrategy::open(&path.0, FsyncPolicy::Always).unwrap();
+        let unusual = "quotes \" and \\ backslashes,\nnew lines,\ttabs, \u{0} nul, é and 🦀";
+        let error = Err::<(), _>(io::Error::new(io::ErrorKind::BrokenPipe, unusual))
+            .context("failed to flush the buffer")
+            .context("failed to close the connection")
+            .unwrap_err();
+        let expected = ReplayRecord::new(&error);
+        strategy.try_handle_error(error).unwrap();
+        strategy
+            .try_handle_error(anyhow::anyhow!("no causes"))
+            .unwrap();
+
+        let records = ReplayRecord::load_ndjson(&path.0).unwrap();
+        assert_eq!(records.len(), 2);
+
+        let record = &records[0];
+        assert_eq!(record.version, ReplayRecord::VERSION);
+        assert_eq!(record.type_name, None);
+        assert_eq!(record.category.io_kind(), Some(io::ErrorKind::BrokenPipe));
+        assert_eq!(records[1].category, ReplayCategory::Other);
+        assert_eq!(record.message, "failed to close the connection");
+        assert_eq!(record.chain, ["failed to flush the buffer", unusual]);
+        assert_eq!(record.fingerprint, expected.fingerprint);
+
+        assert_eq!(records[1].message, "no causes");
+        assert!(records[1].chain.is_empty());
+        assert_ne!(records[1].fingerprint, record.fingerprint);
+    }
+
+    #[test]
+    fn test_serialized_records_round_trip() {
+        let path = TempPath::new("serialized");
+        let record = ReplayRecord::new(&anyhow::anyhow!("a\nb").context("c"));
+        let mut contents = serde_json::to_string(&record).unwrap();
+        assert!(contents.starts_with(r#"{"v":1,"#), "{contents}");
+        assert!(!contents.contains('\n'), "a record spans multiple lines");
+        contents.push('\n');
+        fs::write(&path.0, contents).unwrap();
+
+        assert_eq!(ReplayRecord::load_ndjson(&path.0).unwrap(), [record]);
+    }
+
+    #[test]
+    fn test_category_serialization() {
+        let category = ReplayCategory::from(ErrorCategory::Io(io::ErrorKind::BrokenPipe));
+        assert_eq!(
+            serde_json::to_string(&category).unwrap(),
+            r#"{"kind":"io","io_kind":"broken_pipe"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ReplayCategory::Other).unwrap(),
+            r#"{"kind":"other"}"#
+        );
+
+        for (kind, _) in IO_KINDS {
+            assert_eq!(
+                ReplayCategory::from(ErrorCategory::Io(*kind)).io_kind(),
+                Some(*kind)
+            );
+        }
+    }
+
+    #[test]
+    fn test_forward_compat() {
+        let path = TempPath::new("forward-compat");
+        fs::write(
+            &path.0,
+            concat!(
+                r#"{"v":2,"timestamp":{"secs_since_epoch":1,"nanos_since_epoch":2},"#,
+                r#""type_name":"app::Connection","category":{"kind":"quic","stream":4},"#,
+                r#""message":"reset","#,
+                r#""chain":[],"fingerprint":3,"severity":"high","tags":{"region":"eu"}}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let records = ReplayRecord::load_ndjson(&path.0).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].version, 2);
+        assert_eq!(records[0].type_name.as_deref(), Some("app::Connection"));
+        assert_eq!(records[0].category, ReplayCategory::Other);
+        assert_eq!(records[0].message, "reset");
+        assert_eq!(records[0].fingerprint, 3);
+    }
+
+    #[test]
+    fn test_load_ndjson_partial_line() {
+        let path = TempPath::new("partial");
+        let strategy =
+            PersistDropStrategy::open(&path.0, FsyncPolicy::Every(NonZeroUsize::new(2).unwrap()))
+                .unwrap();
+        strategy
+            .try_handle_error(anyhow::anyhow!("written"))
+            .unwrap();
+        drop(strategy);
+
+        let mut file = OpenOptions::new().append(true).open(&path.0).unwrap();
+        file.write_all(br#"{"v":1,"timestamp":"#).unwrap();
+
+        let records = ReplayRecord::load_ndjson(&path.0).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "written");
+
+        file.write_all(b"\n{}\n").unwrap();
+        let error = ReplayRecord::load_ndjson(&path.0).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
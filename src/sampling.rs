@@ -0,0 +1,162 @@
+//! Share the decision of whether or not to handle a drop error between the sampling layers it goes
+//! through, so that e.g. a sampling fallback handler doesn't skip an error which the sampling
+//! primary handler has already selected.
+//!
+//! Like the [tier](crate::tier) of error handling, the decision is kept in a side table instead of
+//! on the error itself, so the error can still be downcast as usual. It's only kept while the
+//! error is being handled through the handler machinery of this crate, and it's carried over to
+//! the error the primary try drop strategy fails with when that one is handed to the fallback try
+//! drop strategy.
+
+use crate::tier;
+use core::cell::{Cell, RefCell};
+use std::thread_local;
+use std::vec::Vec;
+
+/// The decision a sampling layer made for a drop error.
+#[cfg_attr(feature = "derives", derive(Ord, PartialOrd, Hash))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SamplingDecision {
+    /// The error was selected to be handled.
+    Selected,
+
+    /// The error was skipped, and shouldn't be handled.
+    Skipped,
+}
+
+thread_local! {
+    static DECISIONS: RefCell<Vec<(usize, SamplingDecision)>> = const { RefCell::new(Vec::new()) };
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+fn decision_at(address: usize) -> Option<SamplingDecision> {
+    DECISIONS
+        .try_with(|decisions| {
+            decisions
+                .borrow()
+                .iter()
+                .rev()
+                .find(|(marked, _)| *marked == address)
+                .map(|(_, decision)| *decision)
+        })
+        .ok()
+        .flatten()
+}
+
+fn record(address: usize, decision: SamplingDecision) {
+    if DEPTH.try_with(Cell::get).unwrap_or(0) == 0 {
+        return;
+    }
+
+    let _ = DECISIONS.try_with(|decisions| {
+        let mut decisions = decisions.borrow_mut();
+        match decisions
+            .iter_mut()
+            .rev()
+            .find(|(marked, _)| *marked == address)
+        {
+            Some((_, recorded)) => *recorded = decision,
+            None => decisions.push((address, decision)),
+        }
+    });
+}
+
+/// Get the decision a sampling layer made for the given drop error, if any did.
+pub fn sampling_decision(error: &anyhow::Error) -> Option<SamplingDecision> {
+    decision_at(tier::address(error))
+}
+
+/// Record the given decision for the drop error, replacing the one which was already recorded, if
+/// any.
+///
+/// The decision is only recorded while the error is being handled through the handler machinery of
+/// this crate, and forgotten once it's handled. If a try drop strategy is called directly, this
+/// does nothing.
+pub fn mark(error: &anyhow::Error, decision: SamplingDecision) {
+    record(tier::address(error), decision)
+}
+
+/// Keeps the decisions made for a drop error until this is dropped.
+pub(crate) struct SamplingScope {
+    len: usize,
+    origin: Option<usize>,
+}
+
+impl SamplingScope {
+    /// Carry the decision made for the error this scope was entered with over to the error the
+    /// primary try drop strategy failed with.
+    pub(crate) fn carry_to(&self, error: &anyhow::Error) {
+        if let Some(decision) = self.origin.and_then(decision_at) {
+            record(tier::address(error), decision)
+        }
+    }
+}
+
+impl Drop for SamplingScope {
+    fn drop(&mut self) {
+        let _ = DECISIONS.try_with(|decisions| decisions.borrow_mut().truncate(self.len));
+        let _ = DEPTH.try_with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+fn enter_with(origin: Option<usize>) -> SamplingScope {
+    let _ = DEPTH.try_with(|depth| depth.set(depth.get() + 1));
+    SamplingScope {
+        len: DECISIONS
+            .try_with(|decisions| decisions.borrow().len())
+            .unwrap_or(0),
+        origin,
+    }
+}
+
+/// Keep the decisions made while handling the given drop error, for as long as the returned scope
+/// lives.
+pub(crate) fn enter(error: &anyhow::Error) -> SamplingScope {
+    enter_with(Some(tier::address(error)))
+}
+
+/// Keep the decisions made while handling a batch of drop errors, for as long as the returned
+/// scope lives.
+pub(crate) fn enter_batch() -> SamplingScope {
+    enter_with(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn test_mark() {
+        let error = anyhow::anyhow!("this will always fail");
+        mark(&error, SamplingDecision::Selected);
+        assert_eq!(
+            sampling_decision(&error),
+            None,
+            "the decision was recorded outside of a scope"
+        );
+
+        let scope = enter(&error);
+        mark(&error, SamplingDecision::Skipped);
+        assert_eq!(sampling_decision(&error), Some(SamplingDecision::Skipped));
+        mark(&error, SamplingDecision::Selected);
+        assert_eq!(sampling_decision(&error), Some(SamplingDecision::Selected));
+        assert_eq!(error.to_string(), "this will always fail");
+
+        let failed = anyhow::anyhow!("the primary handler failed");
+        scope.carry_to(&failed);
+        assert_eq!(sampling_decision(&failed), Some(SamplingDecision::Selected));
+
+        drop(scope);
+        assert_eq!(sampling_decision(&error), None);
+        assert_eq!(sampling_decision(&failed), None);
+    }
+
+    #[test]
+    fn test_downcast_after_mark() {
+        let error: anyhow::Error = std::io::Error::other("this will always fail").into();
+        let _scope = enter(&error);
+        mark(&error, SamplingDecision::Selected);
+        assert!(error.downcast_ref::<std::io::Error>().is_some());
+    }
+}
@@ -0,0 +1,379 @@
+//! Never report some categories of drop errors, such as closed pipes, no matter which drop
+//! strategies are used.
+//!
+//! A [`SuppressionList`] is a set of [`Matcher`]s, which is installed once for the whole program,
+//! usually at startup. Every drop error is checked against it right before it's handed to the drop
+//! strategies, and is dropped if any matcher matches it. How many drop errors were suppressed is
+//! kept track of with [`suppressed_count`].
+//!
+//! The suppression list can also be loaded from the `TRY_DROP_SUPPRESS` environment variable with
+//! [`init`], see [`SuppressionList::parse`] for its format.
+//!
+//! # Examples
+//! ```rust
+//! use std::io;
+//! use try_drop::suppress::{self, Matcher, SuppressionList};
+//!
+//! suppress::install(
+//!     SuppressionList::new()
+//!         .suppress(Matcher::Kind(io::ErrorKind::BrokenPipe))
+//!         .suppress(Matcher::Kind(io::ErrorKind::NotConnected)),
+//! );
+//! ```
+
+use core::fmt;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::borrow::Cow;
+use std::env;
+use std::error::Error as StdError;
+use std::io;
+use std::string::{String, ToString};
+use std::sync::{PoisonError, RwLock};
+use std::vec::Vec;
+
+/// The environment variable [`init`] loads the suppression list from.
+pub const ENV_VAR: &str = "TRY_DROP_SUPPRESS";
+
+/// Matches a category of drop errors. A drop error matches if any error in its chain, including
+/// itself, matches.
+#[cfg_attr(feature = "derives", derive(Debug, Clone))]
+pub enum Matcher {
+    /// Matches [`io::Error`]s of this kind.
+    Kind(io::ErrorKind),
+
+    /// Matches errors of a specific type. Created by [`Matcher::of_type`].
+    Type {
+        /// The name of the type.
+        name: &'static str,
+
+        /// Whether or not the error is of the type.
+        is: fn(&(dyn StdError + 'static)) -> bool,
+    },
+
+    /// Matches errors whose message contains this string.
+    Substring(Cow<'static, str>),
+}
+
+impl Matcher {
+    /// Match errors of type `T`.
+    pub fn of_type<T: StdError + 'static>() -> Self {
+        Self::Type {
+            name: core::any::type_name::<T>(),
+            is: |error| error.is::<T>(),
+        }
+    }
+
+    /// Match errors whose message contains `substring`.
+    pub fn substring(substring: impl Into<Cow<'static, str>>) -> Self {
+        Self::Substring(substring.into())
+    }
+
+    fn matches_one(&self, error: &(dyn StdError + 'static)) -> bool {
+        match self {
+            Self::Kind(kind) => error
+                .downcast_ref::<io::Error>()
+                .is_some_and(|error| error.kind() == *kind),
+            Self::Type { is, .. } => is(error),
+            Self::Substring(substring) => error.to_string().contains(&**substring),
+        }
+    }
+
+    /// Check whether or not this matches the given drop error.
+    pub fn matches(&self, error: &anyhow::Error) -> bool {
+        error.chain().any(|error| self.matches_one(error))
+    }
+}
+
+/// A set of [`Matcher`]s. A drop error is suppressed if any of them match it.
+#[cfg_attr(feature = "derives", derive(Debug, Clone))]
+#[derive(Default)]
+pub struct SuppressionList {
+    matchers: Vec<Matcher>,
+}
+
+impl SuppressionList {
+    /// Create an empty suppression list, which doesn't suppress anything.
+    pub const fn new() -> Self {
+        Self {
+            matchers: Vec::new(),
+        }
+    }
+
+    /// Suppress drop errors which `matcher` matches, too.
+    pub fn suppress(mut self, matcher: Matcher) -> Self {
+        self.matchers.push(matcher);
+        self
+    }
+
+    /// Get the matchers of this suppression list.
+    pub fn matchers(&self) -> &[Matcher] {
+        &self.matchers
+    }
+
+    /// Check whether or not the given drop error should be suppressed.
+    pub fn matches(&self, error: &anyhow::Error) -> bool {
+        self.matchers.iter().any(|matcher| matcher.matches(error))
+    }
+
+    /// Parse a suppression list from a comma separated list of matchers. Whitespace around them is
+    /// ignored. Each matcher is either:
+    ///
+    /// * the name of an [`io::ErrorKind`] in snake case, such as `broken_pipe`, or
+    /// * `contains:` followed by a string the message of the error contains.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use try_drop::suppress::SuppressionList;
+    ///
+    /// let list = SuppressionList::parse("broken_pipe, not_connected, contains:shutting down")?;
+    /// assert_eq!(list.matchers().len(), 3);
+    /// # Ok::<(), try_drop::suppress::ParseError>(())
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let mut list = Self::new();
+
+        for matcher in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let matcher = match matcher.strip_prefix("contains:") {
+                Some(substring) => Matcher::substring(substring.to_string()),
+                None => parse_kind(matcher)
+                    .map(Matcher::Kind)
+                    .ok_or_else(|| ParseError(matcher.to_string()))?,
+            };
+            list = list.suppress(matcher);
+        }
+
+        Ok(list)
+    }
+}
+
+fn parse_kind(s: &str) -> Option<io::ErrorKind> {
+    use io::ErrorKind::*;
+
+    Some(match s {
+        "not_found" => NotFound,
+        "permission_denied" => PermissionDenied,
+        "connection_refused" => ConnectionRefused,
+        "connection_reset" => ConnectionReset,
+        "connection_aborted" => ConnectionAborted,
+        "not_connected" => NotConnected,
+        "addr_in_use" => AddrInUse,
+        "addr_not_available" => AddrNotAvailable,
+        "broken_pipe" => BrokenPipe,
+        "already_exists" => AlreadyExists,
+        "would_block" => WouldBlock,
+        "invalid_input" => InvalidInput,
+        "invalid_data" => InvalidData,
+        "timed_out" => TimedOut,
+        "write_zero" => WriteZero,
+        "interrupted" => Interrupted,
+        "unsupported" => Unsupported,
+        "unexpected_eof" => UnexpectedEof,
+        "out_of_memory" => OutOfMemory,
+        "other" => Other,
+        _ => return None,
+    })
+}
+
+/// The error returned when a suppression list can't be parsed, with the matcher which isn't
+/// recognized.
+#[cfg_attr(
+    feature = "derives",
+    derive(Clone, Ord, PartialOrd, Eq, PartialEq, Hash)
+)]
+#[derive(Debug)]
+pub struct ParseError(pub String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown drop error matcher `{}`", self.0)
+    }
+}
+
+impl StdError for ParseError {}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+static LIST: RwLock<SuppressionList> = RwLock::new(SuppressionList::new());
+static SUPPRESSED: AtomicUsize = AtomicUsize::new(0);
+
+/// Install the suppression list for the whole program, replacing the previous one.
+pub fn install(list: SuppressionList) {
+    let installed = !list.matchers.is_empty();
+    *LIST.write().unwrap_or_else(PoisonError::into_inner) = list;
+    INSTALLED.store(installed, Ordering::Release);
+}
+
+/// Uninstall the suppression list, so that no drop errors are suppressed anymore.
+pub fn uninstall() {
+    install(SuppressionList::new())
+}
+
+/// Install the suppression list in the `TRY_DROP_SUPPRESS` environment variable, if it's set.
+///
+/// # Errors
+/// If the environment variable can't be parsed, nothing is installed and the error is returned.
+pub fn init() -> Result<(), ParseError> {
+    match env::var(ENV_VAR) {
+        Ok(list) => {
+            install(SuppressionList::parse(&list)?);
+            Ok(())
+        }
+        Err(env::VarError::NotPresent) => Ok(()),
+        Err(env::VarError::NotUnicode(list)) => Err(ParseError(list.to_string_lossy().into())),
+    }
+}
+
+/// Get how many drop errors were suppressed so far.
+pub fn suppressed_count() -> usize {
+    SUPPRESSED.load(Ordering::Relaxed)
+}
+
+/// Check whether or not the given drop error should be suppressed, counting it if so. This is
+/// cheap if no suppression list is installed.
+pub(crate) fn is_suppressed(error: &anyhow::Error) -> bool {
+    if !INSTALLED.load(Ordering::Acquire) {
+        return false;
+    }
+
+    let suppressed = LIST
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .matches(error);
+
+    if suppressed {
+        SUPPRESSED.fetch_add(1, Ordering::Relaxed);
+    }
+
+    suppressed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::GLOBAL_LOCK;
+    use anyhow::Context;
+
+    #[derive(Debug)]
+    struct PoolClosed;
+
+    impl fmt::Display for PoolClosed {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("the pool was closed")
+        }
+    }
+
+    impl StdError for PoolClosed {}
+
+    fn broken_pipe() -> anyhow::Error {
+        anyhow::Error::new(io::Error::from(io::ErrorKind::BrokenPipe)).context("failed to flush")
+    }
+
+    #[test]
+    fn test_kind() {
+        let matcher = Matcher::Kind(io::ErrorKind::BrokenPipe);
+        assert!(matcher.matches(&broken_pipe()));
+        assert!(!matcher.matches(&io::Error::from(io::ErrorKind::NotFound).into()));
+        assert!(!matcher.matches(&anyhow::anyhow!("broken pipe")));
+    }
+
+    #[test]
+    fn test_type() {
+        let matcher = Matcher::of_type::<PoolClosed>();
+        assert!(matcher.matches(&PoolClosed.into()));
+        assert!(matcher.matches(
+            &Err::<(), _>(PoolClosed)
+                .context("failed to return connection")
+                .unwrap_err()
+        ));
+        assert!(!matcher.matches(&anyhow::anyhow!("the pool was closed")));
+    }
+
+    #[test]
+    fn test_substring() {
+        let matcher = Matcher::substring("shutting down");
+        assert!(matcher.matches(&anyhow::anyhow!("the server is shutting down")));
+        assert!(matcher
+            .matches(&anyhow::anyhow!("the server is shutting down").context("failed to close")));
+        assert!(!matcher.matches(&anyhow::anyhow!("the server is up")));
+    }
+
+    #[test]
+    fn test_parse() {
+        let list =
+            SuppressionList::parse(" broken_pipe,not_connected , contains:shutting down,").unwrap();
+        assert_eq!(list.matchers().len(), 3);
+        assert!(list.matches(&broken_pipe()));
+        assert!(list.matches(&io::Error::from(io::ErrorKind::NotConnected).into()));
+        assert!(list.matches(&anyhow::anyhow!("the server is shutting down")));
+        assert!(!list.matches(&io::Error::from(io::ErrorKind::NotFound).into()));
+
+        assert!(SuppressionList::parse("").unwrap().matchers().is_empty());
+        match SuppressionList::parse("broken_pipe,brokenpipe") {
+            Err(error) => assert_eq!(error.0, "brokenpipe"),
+            Ok(_) => panic!("an unknown matcher was accepted"),
+        }
+    }
+
+    #[test]
+    fn test_init() {
+        let _lock = GLOBAL_LOCK.lock();
+        env::set_var(ENV_VAR, "invalid_matcher");
+        assert!(init().is_err());
+        assert!(!INSTALLED.load(Ordering::Acquire));
+
+        env::set_var(ENV_VAR, "contains:the suppressed init test failed");
+        init().unwrap();
+        env::remove_var(ENV_VAR);
+
+        let before = suppressed_count();
+        assert!(is_suppressed(&anyhow::anyhow!(
+            "the suppressed init test failed"
+        )));
+        assert!(!is_suppressed(&anyhow::anyhow!("the init test failed")));
+        assert_eq!(suppressed_count(), before + 1);
+        uninstall();
+    }
+
+    #[cfg(feature = "ds-collect")]
+    #[test]
+    fn test_suppressed_before_strategies() {
+        use crate::adapters::{TryDropStrategyRef, WithStrategies};
+        use crate::drop_strategies::{CollectDropStrategy, NoOpDropStrategy};
+        use crate::{ImpureTryDrop, PureTryDrop};
+
+        struct Fails(bool);
+
+        impl ImpureTryDrop for Fails {
+            type Error = anyhow::Error;
+
+            unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+                if self.0 {
+                    Err(PoolClosed.into())
+                } else {
+                    anyhow::bail!("the connection was reset")
+                }
+            }
+        }
+
+        let _lock = GLOBAL_LOCK.lock();
+        install(SuppressionList::new().suppress(Matcher::of_type::<PoolClosed>()));
+        let strategy = CollectDropStrategy::new();
+        let before = suppressed_count();
+
+        for suppressed in [true, false] {
+            drop(
+                WithStrategies::new(
+                    Fails(suppressed),
+                    TryDropStrategyRef(&strategy),
+                    NoOpDropStrategy,
+                )
+                .adapt(),
+            );
+        }
+
+        uninstall();
+        let errors = strategy.errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "the connection was reset");
+        assert_eq!(suppressed_count(), before + 1);
+    }
+}
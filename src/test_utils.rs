@@ -6,6 +6,11 @@ use std::marker::PhantomData;
 use std::println;
 use anyhow::Error;
 
+/// Tests which touch the global handlers must hold this lock, otherwise the test results will be
+/// inconsistent.
+#[cfg(feature = "global")]
+pub static GLOBAL_LOCK: parking_lot::Mutex<()> = parking_lot::const_mutex(());
+
 mod private {
     pub trait Sealed {}
 }
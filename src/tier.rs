@@ -0,0 +1,127 @@
+//! Find out which tier of error handling a drop error is currently going through, for strategies
+//! which are used in more than one tier and need to avoid doing the same side effect twice.
+
+use core::cell::RefCell;
+use core::fmt;
+use std::error::Error as StdError;
+use std::thread_local;
+use std::vec::Vec;
+
+/// A tier of error handling a drop error can go through.
+#[cfg_attr(feature = "derives", derive(Ord, PartialOrd, Hash))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum HandlingTier {
+    /// The error is being handled by the primary try drop strategy.
+    Primary,
+
+    /// The primary try drop strategy failed, and the error it failed with is being handled by the
+    /// fallback try drop strategy.
+    Fallback,
+
+    /// No handler was installed, so the error is being handled by the built-in strategy of a shim
+    /// handler.
+    LastResort,
+}
+
+impl fmt::Display for HandlingTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Primary => "primary",
+            Self::Fallback => "fallback",
+            Self::LastResort => "last resort",
+        })
+    }
+}
+
+thread_local! {
+    static TIERS: RefCell<Vec<(usize, HandlingTier)>> = const { RefCell::new(Vec::new()) };
+}
+
+// the error is boxed by anyhow, so this stays the same even if the error is moved around.
+pub(crate) fn address(error: &anyhow::Error) -> usize {
+    let error: &(dyn StdError + Send + Sync + 'static) = error.as_ref();
+    error as *const (dyn StdError + Send + Sync) as *const () as usize
+}
+
+/// Get the tier of error handling the given drop error is currently going through.
+///
+/// If the error isn't being handled through the handler machinery of this crate, e.g. if a try
+/// drop strategy was called directly, [`HandlingTier::Primary`] is returned.
+pub fn current_tier(error: &anyhow::Error) -> HandlingTier {
+    let address = address(error);
+    TIERS
+        .try_with(|tiers| {
+            tiers
+                .borrow()
+                .iter()
+                .rev()
+                .find(|(tagged, _)| *tagged == address)
+                .map(|(_, tier)| *tier)
+        })
+        .ok()
+        .flatten()
+        .unwrap_or(HandlingTier::Primary)
+}
+
+/// Tags the error with a tier of error handling until this is dropped.
+pub(crate) struct TierGuard(());
+
+impl Drop for TierGuard {
+    fn drop(&mut self) {
+        let _ = TIERS.try_with(|tiers| tiers.borrow_mut().pop());
+    }
+}
+
+/// Tag the error with the given tier of error handling, for as long as the returned guard lives.
+pub(crate) fn enter(error: &anyhow::Error, tier: HandlingTier) -> TierGuard {
+    let address = address(error);
+    let _ = TIERS.try_with(|tiers| tiers.borrow_mut().push((address, tier)));
+    TierGuard(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::FallbackTryDropStrategyHandler;
+    use crate::drop_strategies::AdHocDropStrategy;
+    use crate::{FallibleTryDropStrategy, TryDropStrategy};
+    use std::rc::Rc;
+    use std::vec;
+
+    struct RecordsTiers(Rc<RefCell<Vec<HandlingTier>>>);
+
+    impl FallibleTryDropStrategy for RecordsTiers {
+        type Error = anyhow::Error;
+
+        fn try_handle_error(&self, error: anyhow::Error) -> Result<(), Self::Error> {
+            self.0.borrow_mut().push(current_tier(&error));
+            Err(error)
+        }
+    }
+
+    #[test]
+    fn test_current_tier() {
+        let tiers = Rc::new(RefCell::new(Vec::new()));
+        let t = Rc::clone(&tiers);
+        let handler = FallbackTryDropStrategyHandler::new(
+            AdHocDropStrategy(move |error| t.borrow_mut().push(current_tier(&error))),
+            RecordsTiers(Rc::clone(&tiers)),
+        );
+        handler.handle_error(anyhow::anyhow!("this will always fail"));
+        assert_eq!(
+            *tiers.borrow(),
+            vec![HandlingTier::Primary, HandlingTier::Fallback]
+        );
+    }
+
+    #[test]
+    fn test_current_tier_outside_of_handler() {
+        let error = anyhow::anyhow!("this will always fail");
+        let guard = enter(&error, HandlingTier::LastResort);
+        let moved = error;
+        assert_eq!(current_tier(&moved), HandlingTier::LastResort);
+        drop(guard);
+        assert_eq!(current_tier(&moved), HandlingTier::Primary);
+    }
+}
@@ -0,0 +1,180 @@
+//! Check that [`RepeatableTryDrop`] implementations uphold their contract, e.g. in the tests of a
+//! crate which implements it.
+
+use crate::{DropAdapter, RepeatableTryDrop};
+
+/// What [`verify_repeatable`] found wrong with a [`RepeatableTryDrop`] implementation.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ViolationKind {
+    /// Calling `try_drop` panicked, even though panics weren't allowed.
+    Panicked(std::string::String),
+
+    /// Calling `try_drop` failed after an earlier call had succeeded.
+    ErrorAfterSuccess,
+}
+
+/// A violation of the [`RepeatableTryDrop`] contract, along with how to reproduce it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RepeatableViolation {
+    /// The iteration the violation happened in, starting from zero.
+    pub iteration: usize,
+
+    /// The call to `try_drop` which violated the contract, starting from zero. The last call of an
+    /// iteration is the drop of the [`DropAdapter`].
+    pub call: usize,
+
+    /// The seed the verification was run with.
+    pub seed: u64,
+
+    /// What went wrong.
+    pub kind: ViolationKind,
+}
+
+impl core::fmt::Display for RepeatableViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "iteration {} (seed {:#x}), call {}: ",
+            self.iteration, self.seed, self.call
+        )?;
+
+        match &self.kind {
+            ViolationKind::Panicked(message) => write!(f, "try drop panicked: {message}"),
+            ViolationKind::ErrorAfterSuccess => f.write_str("try drop failed after it succeeded"),
+        }
+    }
+}
+
+impl std::error::Error for RepeatableViolation {}
+
+/// Checks that a [`RepeatableTryDrop`] implementation upholds its contract, by calling
+/// [`RepeatableTryDrop::safe_try_drop`] a random amount of times on fresh values, then dropping
+/// them through a [`DropAdapter`]. See [`verify_repeatable`].
+///
+/// The random amounts are generated from a seed, so a violation can be reproduced by running the
+/// verification again with the seed it reports.
+#[derive(Debug, Copy, Clone)]
+pub struct RepeatableVerifier {
+    /// How many values to create and drop.
+    pub iterations: usize,
+
+    /// The seed to generate the amount of calls with.
+    pub seed: u64,
+
+    /// The most times to call `try_drop` on a value before dropping it.
+    pub max_calls: usize,
+
+    /// Whether or not `try_drop` may panic, e.g. because of a double drop policy.
+    pub allow_panics: bool,
+}
+
+impl RepeatableVerifier {
+    /// The seed used unless another one is given.
+    pub const DEFAULT_SEED: u64 = 0x0074_7279_6472_6f70;
+
+    /// Create a new verifier which creates and drops `iterations` values, and doesn't allow
+    /// panics.
+    pub const fn new(iterations: usize) -> Self {
+        Self {
+            iterations,
+            seed: Self::DEFAULT_SEED,
+            max_calls: 8,
+            allow_panics: false,
+        }
+    }
+
+    /// Sets the seed to generate the amount of calls with.
+    pub const fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Sets the most times to call `try_drop` on a value before dropping it.
+    pub const fn max_calls(mut self, max_calls: usize) -> Self {
+        self.max_calls = max_calls;
+        self
+    }
+
+    /// Sets whether or not `try_drop` may panic.
+    pub const fn allow_panics(mut self, allow_panics: bool) -> Self {
+        self.allow_panics = allow_panics;
+        self
+    }
+
+    /// Run the verification on the values `make` creates, returning the first violation found.
+    pub fn verify<T: RepeatableTryDrop>(
+        &self,
+        mut make: impl FnMut() -> T,
+    ) -> Result<(), RepeatableViolation> {
+        use rand::{Rng, SeedableRng};
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(self.seed);
+        let violation = |iteration, call, kind| RepeatableViolation {
+            iteration,
+            call,
+            seed: self.seed,
+            kind,
+        };
+
+        for iteration in 0..self.iterations {
+            let calls = rng.gen_range(0..=self.max_calls);
+            let mut value = make();
+            let mut succeeded = false;
+            let mut panicked = false;
+
+            for call in 0..calls {
+                match panic::catch_unwind(AssertUnwindSafe(|| value.safe_try_drop())) {
+                    Ok(Ok(())) => succeeded = true,
+                    Ok(Err(_)) if succeeded => {
+                        return Err(violation(iteration, call, ViolationKind::ErrorAfterSuccess))
+                    }
+                    Ok(Err(_)) => {}
+                    Err(payload) if self.allow_panics => {
+                        drop(payload);
+                        panicked = true;
+                        break;
+                    }
+                    Err(payload) => {
+                        let message = crate::adapters::panic_message(&*payload).into();
+                        return Err(violation(iteration, call, ViolationKind::Panicked(message)));
+                    }
+                }
+            }
+
+            // a value which panicked may be in any state, so only check that dropping it doesn't
+            // panic if it didn't
+            let dropped = panic::catch_unwind(AssertUnwindSafe(|| drop(DropAdapter(value))));
+            if let Err(payload) = dropped {
+                if !self.allow_panics && !panicked {
+                    let message = crate::adapters::panic_message(&*payload).into();
+                    return Err(violation(
+                        iteration,
+                        calls,
+                        ViolationKind::Panicked(message),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check that the [`RepeatableTryDrop`] implementation of `T` upholds its contract over
+/// `iterations` default values, without panicking. Use a [`RepeatableVerifier`] for more control.
+///
+/// # Examples
+/// ```ignore
+/// #[test]
+/// fn connection_is_repeatable() {
+///     if let Err(violation) = try_drop::verify::verify_repeatable::<Connection>(256) {
+///         panic!("{violation}")
+///     }
+/// }
+/// ```
+pub fn verify_repeatable<T: RepeatableTryDrop + Default>(
+    iterations: usize,
+) -> Result<(), RepeatableViolation> {
+    RepeatableVerifier::new(iterations).verify(T::default)
+}
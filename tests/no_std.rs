@@ -0,0 +1,52 @@
+//! Uses the sequence drop strategies from a `no_std` crate. Building the library itself without
+//! `std` is covered by `cargo build --no-default-features --features ds-sequence`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::ToString;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use try_drop::drop_strategies::{FirstSuccessDropStrategy, SequenceDropStrategy, VecDropStrategy};
+use try_drop::{FallibleTryDropStrategy, TryDropStrategy};
+
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+struct Count;
+
+impl TryDropStrategy for Count {
+    fn handle_error(&self, _error: try_drop::Error) {
+        HANDLED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct Fails;
+
+impl FallibleTryDropStrategy for Fails {
+    type Error = try_drop::Error;
+
+    fn try_handle_error(&self, error: try_drop::Error) -> Result<(), Self::Error> {
+        Err(error)
+    }
+}
+
+#[test]
+fn test_no_std() {
+    let mut sequence = SequenceDropStrategy::new();
+    sequence.push(Count).push(Count);
+    sequence
+        .try_handle_error(anyhow::anyhow!("sequence"))
+        .unwrap();
+    assert_eq!(HANDLED.load(Ordering::Relaxed), 2);
+
+    let mut first_success = FirstSuccessDropStrategy::new();
+    first_success.push(Fails).push(Count);
+    first_success
+        .try_handle_error(anyhow::anyhow!("first success"))
+        .unwrap();
+    assert_eq!(HANDLED.load(Ordering::Relaxed), 3);
+
+    let errors = VecDropStrategy::new();
+    errors.handle_error(anyhow::anyhow!("collected"));
+    assert_eq!(errors.take()[0].to_string(), "collected");
+}
@@ -0,0 +1,47 @@
+//! Drops a value with drop strategies which are all imported through the prelude alone.
+
+use std::cell::RefCell;
+use try_drop::prelude::*;
+
+struct Fails<F: Fn(try_drop::Error)> {
+    try_drop_strategy: AdHocDropStrategy<F>,
+    fallback_try_drop_strategy: PanicDropStrategy,
+}
+
+impl<F: Fn(try_drop::Error)> PureTryDrop for Fails<F> {
+    type Error = try_drop::Error;
+    type FallbackTryDropStrategy = PanicDropStrategy;
+    type TryDropStrategy = AdHocDropStrategy<F>;
+
+    fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+        &self.fallback_try_drop_strategy
+    }
+
+    fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+        &self.try_drop_strategy
+    }
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        anyhow::bail!("failed to close connection")
+    }
+}
+
+#[test]
+fn test_prelude_strategies() {
+    let errors = RefCell::new(Vec::new());
+    let value = Fails {
+        try_drop_strategy: (|error: try_drop::Error| errors.borrow_mut().push(error.to_string()))
+            .into_drop_strategy(),
+        fallback_try_drop_strategy: PanicDropStrategy::DEFAULT,
+    };
+    drop(value.adapt());
+    assert_eq!(*errors.borrow(), ["failed to close connection"]);
+
+    // these only have to be nameable through the prelude.
+    let _ = (
+        NoOpDropStrategy,
+        AbortDropStrategy,
+        ExitDropStrategy::DEFAULT,
+        WriteDropStrategy::stderr(),
+    );
+}
@@ -0,0 +1,70 @@
+//! Implements `SafeTryDrop` from a crate which forbids unsafe code, then drops it through the
+//! handlers and through specific try drop strategies.
+
+#![forbid(unsafe_code)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use try_drop::adapters::WithStrategies;
+use try_drop::drop_strategies::{AdHocDropStrategy, PanicDropStrategy};
+use try_drop::{PureTryDrop, RepeatableTryDrop, SafeTryDrop};
+
+const MESSAGE: &str = "failed to flush";
+
+/// Fails to flush its pending bytes, then forgets them, so that flushing again does nothing.
+struct Pending(usize);
+
+impl SafeTryDrop for Pending {
+    type Error = try_drop::Error;
+
+    fn try_drop(&mut self) -> Result<(), Self::Error> {
+        match std::mem::take(&mut self.0) {
+            0 => Ok(()),
+            _ => anyhow::bail!(MESSAGE),
+        }
+    }
+}
+
+type Errors = Rc<RefCell<Vec<String>>>;
+
+fn recorder() -> (AdHocDropStrategy<impl Fn(try_drop::Error)>, Errors) {
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let e = Rc::clone(&errors);
+    let strategy =
+        AdHocDropStrategy(move |error: try_drop::Error| e.borrow_mut().push(error.to_string()));
+    (strategy, errors)
+}
+
+#[test]
+fn test_drop_through_handlers() {
+    let (strategy, errors) = recorder();
+    try_drop::install_thread_local_handlers(strategy, PanicDropStrategy::DEFAULT);
+    drop(Pending(1).adapt());
+    drop(Pending(0).adapt());
+    try_drop::uninstall_for_thread();
+
+    assert_eq!(*errors.borrow(), [MESSAGE]);
+}
+
+#[test]
+fn test_drop_with_strategies() {
+    let (strategy, errors) = recorder();
+    drop(WithStrategies::new(Pending(1), strategy, PanicDropStrategy::DEFAULT).adapt());
+
+    assert_eq!(*errors.borrow(), [MESSAGE]);
+}
+
+#[test]
+fn test_repeatable() {
+    let mut value = Pending(1);
+    assert!(value.safe_try_drop().is_err());
+    assert!(value.safe_try_drop().is_ok());
+
+    // flushing early through the adapter means the drop itself has nothing left to report
+    let (strategy, errors) = recorder();
+    let mut value = WithStrategies::new(Pending(1), strategy, PanicDropStrategy::DEFAULT).adapt();
+    assert!(value.safe_try_drop().is_err());
+    drop(value);
+
+    assert!(errors.borrow().is_empty());
+}
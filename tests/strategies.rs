@@ -0,0 +1,199 @@
+//! Installs each of the built-in drop strategies through the public install functions, then checks
+//! what can be observed from the outside after dropping a value which fails to drop.
+
+use std::env;
+use std::fs::{self, File};
+use std::panic::{self, AssertUnwindSafe};
+use std::process::{Command, Output};
+use try_drop::drop_strategies::broadcast::OkIfAlone;
+use try_drop::drop_strategies::{
+    AbortDropStrategy, BroadcastDropStrategy, ExitDropStrategy, NoOpDropStrategy,
+    PanicDropStrategy, WriteDropStrategy,
+};
+use try_drop::{ImpureTryDrop, PureTryDrop};
+
+const CHILD: &str = "TRY_DROP_STRATEGIES_CHILD";
+const MESSAGE: &str = "failed to close connection";
+
+struct Fails;
+
+impl ImpureTryDrop for Fails {
+    type Error = try_drop::Error;
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        anyhow::bail!(MESSAGE)
+    }
+}
+
+/// Runs `scenario` in a child process, returning its output.
+fn run_in_child(test: &str, scenario: impl FnOnce()) -> Option<Output> {
+    if env::var_os(CHILD).is_some() {
+        scenario();
+        return None;
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+
+    Some(output)
+}
+
+#[test]
+fn test_write() {
+    let path = env::temp_dir().join(format!("try-drop-test-write-{}", std::process::id()));
+    try_drop::install_thread_local_handlers(
+        WriteDropStrategy::new(File::create(&path).unwrap()),
+        PanicDropStrategy::DEFAULT,
+    );
+    drop(Fails.adapt());
+    try_drop::uninstall_for_thread();
+
+    let written = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(written, format!("{MESSAGE}\n"));
+}
+
+#[test]
+fn test_write_stderr() {
+    if let Some(output) = run_in_child("test_write_stderr", || {
+        try_drop::install_global_handlers(WriteDropStrategy::stderr(), PanicDropStrategy::DEFAULT);
+        drop(Fails.adapt());
+    }) {
+        assert!(output.status.success(), "{}", output.status);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(MESSAGE), "{stderr}");
+    }
+}
+
+#[test]
+fn test_panic() {
+    try_drop::install_thread_local_handlers(PanicDropStrategy::DEFAULT, NoOpDropStrategy);
+    let payload = panic::catch_unwind(AssertUnwindSafe(|| drop(Fails.adapt())))
+        .expect_err("the panic drop strategy didn't panic");
+    try_drop::uninstall_for_thread();
+
+    let message = payload
+        .downcast_ref::<String>()
+        .expect("panic payload is not a string");
+    assert!(message.contains(MESSAGE), "{message}");
+}
+
+#[test]
+fn test_noop() {
+    try_drop::install_thread_local_handlers(NoOpDropStrategy, PanicDropStrategy::DEFAULT);
+    drop(Fails.adapt());
+    try_drop::uninstall_for_thread();
+}
+
+#[test]
+fn test_broadcast() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let _guard = runtime.enter();
+    let (strategy, mut receiver) = BroadcastDropStrategy::<OkIfAlone>::new(16);
+    let mut subscriber = strategy.subscribe();
+    try_drop::install_thread_local_handlers(strategy, PanicDropStrategy::DEFAULT);
+    drop(Fails.adapt());
+    drop(Fails.adapt());
+    try_drop::uninstall_for_thread();
+
+    for receiver in [&mut receiver, &mut subscriber] {
+        let messages: Vec<_> = receiver
+            .drain()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(messages, [MESSAGE, MESSAGE]);
+    }
+}
+
+#[test]
+fn test_exit() {
+    if let Some(output) = run_in_child("test_exit", || {
+        try_drop::install_global_handlers(ExitDropStrategy::new(3), PanicDropStrategy::DEFAULT);
+        drop(Fails.adapt());
+        unreachable!("the exit drop strategy didn't exit");
+    }) {
+        assert_eq!(output.status.code(), Some(3), "{}", output.status);
+    }
+}
+
+#[test]
+fn test_exit_before_terminal() {
+    if let Some(output) = run_in_child("test_exit_before_terminal", || {
+        let strategy =
+            ExitDropStrategy::new(3).with_before_terminal(|error| println!("before exit: {error}"));
+        try_drop::install_global_handlers(strategy, PanicDropStrategy::DEFAULT);
+        drop(Fails.adapt());
+        unreachable!("the exit drop strategy didn't exit");
+    }) {
+        assert_eq!(output.status.code(), Some(3), "{}", output.status);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(
+            stdout.contains(&format!("before exit: {MESSAGE}")),
+            "the hook didn't run before exiting: {stdout}"
+        );
+    }
+}
+
+#[test]
+fn test_abort() {
+    if let Some(output) = run_in_child("test_abort", || {
+        try_drop::install_global_handlers(AbortDropStrategy, PanicDropStrategy::DEFAULT);
+        drop(Fails.adapt());
+        unreachable!("the abort drop strategy didn't abort");
+    }) {
+        assert!(!output.status.success(), "{}", output.status);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            assert_eq!(output.status.signal(), Some(6), "{}", output.status);
+        }
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "ds-syslog"))]
+#[test]
+fn test_syslog() {
+    use std::os::unix::net::UnixDatagram;
+    use try_drop::drop_strategies::syslog::{Facility, Severity};
+    use try_drop::drop_strategies::SyslogDropStrategy;
+
+    let path = env::temp_dir().join(format!("try-drop-test-syslog-{}", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let socket = UnixDatagram::bind(&path).unwrap();
+    let mut buffer = [0; 1024];
+    let mut received = || {
+        let len = socket.recv(&mut buffer).unwrap();
+        String::from_utf8_lossy(&buffer[..len]).into_owned()
+    };
+
+    let strategy = SyslogDropStrategy::with_path(Facility::LOG_USER, "try-drop", &path).unwrap();
+    try_drop::install_thread_local_handlers(strategy, PanicDropStrategy::DEFAULT);
+    drop(Fails.adapt());
+
+    // LOG_USER | LOG_ERR
+    let message = received();
+    assert!(message.starts_with("<11>"), "{message}");
+    assert!(message.contains("try-drop["), "{message}");
+    assert!(message.ends_with(MESSAGE), "{message}");
+
+    let strategy = SyslogDropStrategy::with_path(Facility::LOG_DAEMON, "try-drop", &path)
+        .unwrap()
+        .with_severity(Severity::LOG_WARNING);
+    try_drop::install_thread_local_handlers(strategy, PanicDropStrategy::DEFAULT);
+    drop(Fails.adapt());
+
+    // LOG_DAEMON | LOG_WARNING
+    let message = received();
+    assert!(message.starts_with("<28>"), "{message}");
+
+    try_drop::uninstall_for_thread();
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,8 @@
+use try_drop::adapters::AssertInfallibleTryDropStrategyAdapter;
+use try_drop::drop_strategies::WriteDropStrategy;
+use try_drop::handlers::fallback;
+
+fn main() {
+    let strategy = AssertInfallibleTryDropStrategyAdapter::new(WriteDropStrategy::stderr());
+    fallback::global::install_checked(strategy);
+}
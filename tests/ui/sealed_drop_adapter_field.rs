@@ -0,0 +1,28 @@
+use try_drop::drop_strategies::NoOpDropStrategy;
+use try_drop::PureTryDrop;
+
+#[derive(Copy, Clone)]
+struct T;
+
+impl PureTryDrop for T {
+    type Error = try_drop::Error;
+    type FallbackTryDropStrategy = NoOpDropStrategy;
+    type TryDropStrategy = NoOpDropStrategy;
+
+    fn fallback_try_drop_strategy(&self) -> &Self::FallbackTryDropStrategy {
+        &NoOpDropStrategy
+    }
+
+    fn try_drop_strategy(&self) -> &Self::TryDropStrategy {
+        &NoOpDropStrategy
+    }
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+fn main() {
+    let adapter = T.adapt_sealed();
+    let _copied: T = adapter.0;
+}
@@ -0,0 +1,72 @@
+//! A drop error which occurs while unwinding must not take the process down when it goes through
+//! the default primary and fallback handlers, whether or not they're initialized.
+
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::Command;
+use try_drop::drop_strategies::AdHocFallibleDropStrategy;
+use try_drop::handlers::primary;
+use try_drop::{ImpureTryDrop, PureTryDrop};
+
+const CHILD: &str = "TRY_DROP_UNWINDING_CHILD";
+
+struct Fails;
+
+impl ImpureTryDrop for Fails {
+    type Error = try_drop::Error;
+
+    unsafe fn try_drop(&mut self) -> Result<(), Self::Error> {
+        anyhow::bail!("dropped while unwinding")
+    }
+}
+
+/// Runs `scenario` in a child process, returning its standard error if it exited successfully.
+fn run_in_child(test: &str, scenario: impl FnOnce()) -> Option<String> {
+    if env::var_os(CHILD).is_some() {
+        scenario();
+        return None;
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args([test, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(
+        output.status.success(),
+        "the child process was taken down: {}\n{stderr}",
+        output.status
+    );
+
+    Some(stderr)
+}
+
+fn drop_while_unwinding() {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _fails = Fails.adapt();
+        panic!("unwinding")
+    }));
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_uninitialized_default_handlers_while_unwinding() {
+    if let Some(stderr) = run_in_child(
+        "test_uninitialized_default_handlers_while_unwinding",
+        drop_while_unwinding,
+    ) {
+        assert!(stderr.contains("dropped while unwinding"), "{stderr}");
+    }
+}
+
+#[test]
+fn test_default_fallback_handler_while_unwinding() {
+    if let Some(stderr) = run_in_child("test_default_fallback_handler_while_unwinding", || {
+        primary::thread_local::install(AdHocFallibleDropStrategy(Err::<(), _>));
+        drop_while_unwinding();
+        primary::thread_local::uninstall();
+    }) {
+        assert!(stderr.contains("dropped while unwinding"), "{stderr}");
+    }
+}